@@ -0,0 +1,47 @@
+// Searches the index written next to the site by the built-in builder
+(function () {
+  const input = document.getElementById("site-search-input");
+  const results = document.getElementById("site-search-results");
+  const root = document.body.dataset.root || "";
+  const docs = (window.searchIndex && window.searchIndex.docs) || [];
+
+  function search(query) {
+    const terms = query.toLowerCase().split(/\s+/).filter(Boolean);
+    if (terms.length === 0) {
+      return [];
+    }
+    return docs
+      .map((doc) => {
+        const title = doc.title.toLowerCase();
+        const text = doc.text.toLowerCase();
+        let score = 0;
+        for (const term of terms) {
+          if (title.includes(term)) {
+            score += 10;
+          } else if (text.includes(term)) {
+            score += 1;
+          } else {
+            return null;
+          }
+        }
+        return { doc, score };
+      })
+      .filter(Boolean)
+      .sort((a, b) => b.score - a.score)
+      .slice(0, 20);
+  }
+
+  input.addEventListener("input", () => {
+    results.replaceChildren();
+    const matches = search(input.value);
+    for (const { doc } of matches) {
+      const link = document.createElement("a");
+      link.href = root + doc.location;
+      link.textContent = doc.title;
+      const item = document.createElement("li");
+      item.appendChild(link);
+      results.appendChild(item);
+    }
+    results.hidden = matches.length === 0;
+  });
+})();
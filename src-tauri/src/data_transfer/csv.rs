@@ -0,0 +1,104 @@
+// Minimal RFC 4180 reader and writer. Spreadsheet exports quote fields that
+// contain commas, quotes or line breaks and double any quotes inside them.
+
+pub struct CsvRecord {
+    // Line the record starts on, counting from 1
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+pub fn parse_csv(content: &str) -> Result<Vec<CsvRecord>, (usize, String)> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut field_started = false;
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut chars = content.chars().peekable();
+    while let Some(character) = chars.next() {
+        if in_quotes {
+            match character {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(character);
+                }
+                _ => field.push(character),
+            }
+            continue;
+        }
+
+        match character {
+            '"' if !field_started => {
+                in_quotes = true;
+                field_started = true;
+            }
+            '"' => {
+                return Err((
+                    line,
+                    "Unexpected quote inside an unquoted field".to_string(),
+                ))
+            }
+            ',' => {
+                fields.push(std::mem::take(&mut field));
+                field_started = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                fields.push(std::mem::take(&mut field));
+                push_record(&mut records, record_line, std::mem::take(&mut fields));
+                field_started = false;
+                line += 1;
+                record_line = line;
+            }
+            _ => {
+                field.push(character);
+                field_started = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err((record_line, "Quoted field is never closed".to_string()));
+    }
+    if field_started || !fields.is_empty() {
+        fields.push(field);
+        push_record(&mut records, record_line, fields);
+    }
+
+    Ok(records)
+}
+
+fn push_record(records: &mut Vec<CsvRecord>, line: usize, fields: Vec<String>) {
+    // Blank lines, usually a trailing newline, are not records
+    if fields.len() == 1 && fields[0].is_empty() {
+        return;
+    }
+    records.push(CsvRecord { line, fields });
+}
+
+pub fn write_csv(rows: &[Vec<String>]) -> String {
+    let mut content = String::new();
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|field| escape_field(field)).collect();
+        content.push_str(&fields.join(","));
+        content.push('\n');
+    }
+    content
+}
+
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
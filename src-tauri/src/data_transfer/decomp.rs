@@ -0,0 +1,938 @@
+// Importer for pret decompilation projects like pokeemerald and pokefirered.
+// Species data is read straight from the C headers of a local checkout, so
+// only the initializers the importer knows about are looked at.
+use std::{collections::HashMap, fs, path::Path};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use tauri::AppHandle;
+
+use crate::{
+    data_transfer::randomizer_log::{get_wiki_lookup, normalize_name, UnmatchedEntry, WikiLookup},
+    database::{
+        repository::{apply_species_updates, get_movesets_by_pokemon_ids, get_pokemon_by_ids},
+        session::get_wiki_session,
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::capitalize_and_remove_hyphens,
+    logger::{self, LogLevel},
+    structs::{
+        pokemon_structs::{DBPokemon, Evolution, PokemonMove, SpeciesUpdate, Stats},
+        table_models::FieldChange,
+    },
+};
+
+pub const SPECIES_INFO: &str = "species_info.h";
+pub const LEVEL_UP_LEARNSETS: &str = "level_up_learnsets.h";
+pub const LEVEL_UP_LEARNSET_POINTERS: &str = "level_up_learnset_pointers.h";
+pub const TMHM_LEARNSETS: &str = "tmhm_learnsets.h";
+pub const EVOLUTION: &str = "evolution.h";
+
+// The headers live in src/data/pokemon, but the folder itself can be picked too
+const DECOMP_DATA_DIRECTORIES: [&str; 2] = ["src/data/pokemon", ""];
+
+#[derive(Debug, Clone, Default)]
+pub struct DecompData {
+    // Keyed by the wiki form of the species constant, SPECIES_MR_MIME is mr-mime
+    pub species: IndexMap<String, DecompSpecies>,
+    // Species missing from evolution.h don't evolve, but only if it was read
+    pub has_evolutions: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DecompSpecies {
+    pub types: Vec<String>,
+    pub abilities: Vec<String>,
+    pub stats: Option<Stats>,
+    // Name of the learnset array when species_info.h points to it
+    pub level_up_learnset: Option<String>,
+    // Pairs of level and move
+    pub level_up_moves: Option<Vec<(u32, String)>>,
+    pub machine_moves: Option<Vec<String>>,
+    pub evolutions: Vec<DecompEvolution>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecompEvolution {
+    pub method: String,
+    pub parameter: String,
+    pub target: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpeciesDiff {
+    pub pokemon_id: u32,
+    pub name: String,
+    pub fields: Vec<FieldChange>,
+    pub moves_added: Vec<String>,
+    pub moves_removed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DecompImportPreview {
+    pub files_read: Vec<String>,
+    pub species: Vec<SpeciesDiff>,
+    pub unchanged: usize,
+    pub unmatched: Vec<UnmatchedEntry>,
+}
+
+#[tauri::command]
+pub async fn preview_decomp_import(
+    wiki_name: &str,
+    decomp_path: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<DecompImportPreview> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match build_decomp_preview(decomp_path, &session.pool).await {
+        Ok((preview, _)) => Ok(preview),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+// Recomputes the preview from the checkout as it is now, then applies the
+// changes of the species picked from it
+#[tauri::command]
+pub async fn import_decomp_species(
+    wiki_name: &str,
+    decomp_path: &str,
+    pokemon_ids: Vec<u32>,
+    app_handle: AppHandle,
+) -> WikiGenResult<DecompImportPreview> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    let (mut preview, updates) = match build_decomp_preview(decomp_path, &session.pool).await {
+        Ok(preview) => preview,
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+    preview
+        .species
+        .retain(|species_diff| pokemon_ids.contains(&species_diff.pokemon_id));
+    let updates: Vec<SpeciesUpdate> = updates
+        .into_iter()
+        .filter(|update| {
+            preview
+                .species
+                .iter()
+                .any(|species_diff| species_diff.pokemon_id == update.pokemon_id)
+        })
+        .collect();
+
+    if let Err(err) = apply_species_updates(&session.pool, &updates, &[]).await {
+        logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+        return Err(err);
+    }
+
+    Ok(preview)
+}
+
+async fn build_decomp_preview(
+    decomp_path: &str,
+    conn: &Pool<Sqlite>,
+) -> WikiGenResult<(DecompImportPreview, Vec<SpeciesUpdate>)> {
+    let (decomp_data, files_read) = read_decomp_checkout(Path::new(decomp_path))?;
+    let lookup = get_wiki_lookup(conn).await?;
+
+    let mut unmatched = Vec::new();
+    let updates = resolve_decomp_species(&decomp_data, &lookup, &mut unmatched);
+
+    let pokemon_ids: Vec<usize> = updates
+        .iter()
+        .map(|update| update.pokemon_id as usize)
+        .collect();
+    let current_pokemon = get_pokemon_by_ids(conn, &pokemon_ids).await?;
+    let current_movesets = get_movesets_by_pokemon_ids(conn, &pokemon_ids).await?;
+    let move_names = lookup
+        .moves
+        .iter()
+        .map(|(name, id)| (*id, name.clone()))
+        .collect();
+
+    let species = diff_species_updates(&updates, &current_pokemon, &current_movesets, &move_names);
+    let preview = DecompImportPreview {
+        files_read,
+        unchanged: updates.len() - species.len(),
+        species,
+        unmatched,
+    };
+    Ok((preview, updates))
+}
+
+fn read_decomp_checkout(checkout_path: &Path) -> WikiGenResult<(DecompData, Vec<String>)> {
+    let mut files_read = Vec::new();
+    let mut read_header = |file_name: &str| -> WikiGenResult<Option<String>> {
+        let file_path = DECOMP_DATA_DIRECTORIES
+            .iter()
+            .map(|directory| checkout_path.join(directory).join(file_name))
+            .find(|file_path| file_path.is_file());
+        let Some(file_path) = file_path else {
+            return Ok(None);
+        };
+
+        match fs::read_to_string(&file_path) {
+            Ok(content) => {
+                files_read.push(file_name.to_string());
+                Ok(Some(content))
+            }
+            Err(err) => Err(WikiGenError::io(
+                format!("Failed to read {}: {}", file_name, err),
+                file_path.display(),
+            )),
+        }
+    };
+
+    let mut decomp_data = DecompData::default();
+    if let Some(content) = read_header(SPECIES_INFO)? {
+        read_species_info(&content, &mut decomp_data);
+    }
+    let learnset_pointers = read_header(LEVEL_UP_LEARNSET_POINTERS)?;
+    if let Some(content) = read_header(LEVEL_UP_LEARNSETS)? {
+        read_level_up_learnsets(&content, learnset_pointers.as_deref(), &mut decomp_data);
+    }
+    if let Some(content) = read_header(TMHM_LEARNSETS)? {
+        read_tmhm_learnsets(&content, &mut decomp_data);
+    }
+    if let Some(content) = read_header(EVOLUTION)? {
+        read_evolutions(&content, &mut decomp_data);
+    }
+
+    if decomp_data.species.is_empty() {
+        return Err(WikiGenError::io(
+            format!(
+                "No species data found, expected {} in src/data/pokemon",
+                SPECIES_INFO
+            ),
+            checkout_path.display(),
+        ));
+    }
+    Ok((decomp_data, files_read))
+}
+
+pub fn read_species_info(content: &str, decomp_data: &mut DecompData) {
+    for (species, initializer) in designated_entries(&strip_comments(content), "SPECIES_") {
+        if species == "NONE" {
+            continue;
+        }
+        let fields = designated_fields(&initializer);
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.as_str())
+        };
+
+        let entry = decomp_data
+            .species
+            .entry(normalize_name(&species))
+            .or_default();
+
+        // Older projects use type1 and type2, newer ones a types array
+        let types = ["types", "type1", "type2"]
+            .iter()
+            .filter_map(|name| field(name))
+            .flat_map(|value| constants(value, "TYPE_"));
+        for _type in types {
+            if !entry.types.contains(&_type) {
+                entry.types.push(_type);
+            }
+        }
+        for ability in field("abilities")
+            .map(|value| constants(value, "ABILITY_"))
+            .unwrap_or_default()
+        {
+            if ability != "none" && !entry.abilities.contains(&ability) {
+                entry.abilities.push(ability);
+            }
+        }
+
+        let stat = |name: &str| field(name).and_then(|value| value.parse::<u32>().ok());
+        entry.stats = match (
+            stat("baseHP"),
+            stat("baseAttack"),
+            stat("baseDefense"),
+            stat("baseSpAttack"),
+            stat("baseSpDefense"),
+            stat("baseSpeed"),
+        ) {
+            (
+                Some(hp),
+                Some(attack),
+                Some(defense),
+                Some(sp_attack),
+                Some(sp_defense),
+                Some(speed),
+            ) => Some(Stats {
+                hp,
+                attack,
+                defense,
+                sp_attack,
+                sp_defense,
+                speed,
+            }),
+            _ => None,
+        };
+        entry.level_up_learnset = field("levelUpLearnset").map(str::to_string);
+    }
+}
+
+// Learnset arrays are tied to their species by the pointers header or by
+// species_info.h, and otherwise by their name, sMrMimeLevelUpLearnset is mr-mime
+pub fn read_level_up_learnsets(
+    content: &str,
+    learnset_pointers: Option<&str>,
+    decomp_data: &mut DecompData,
+) {
+    let mut learnset_species: HashMap<String, String> = decomp_data
+        .species
+        .iter()
+        .filter_map(|(name, species)| {
+            species
+                .level_up_learnset
+                .clone()
+                .map(|learnset| (learnset, name.clone()))
+        })
+        .collect();
+    if let Some(learnset_pointers) = learnset_pointers {
+        for (species, learnset) in
+            designated_entries(&strip_comments(learnset_pointers), "SPECIES_")
+        {
+            learnset_species.insert(learnset, normalize_name(&species));
+        }
+    }
+
+    let content = strip_comments(content);
+    let mut rest = content.as_str();
+    while let Some(start) = rest.find("[]") {
+        let learnset = rest[..start]
+            .rsplit(|character: char| !is_identifier_character(character))
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        rest = &rest[start + 2..];
+        let Some(initializer) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let length = initializer_length(initializer);
+        let moves = level_up_moves(&initializer[..length]);
+        rest = &initializer[length..];
+
+        let species = match learnset_species.get(&learnset) {
+            Some(species) => species.clone(),
+            None => match learnset
+                .strip_prefix('s')
+                .and_then(|name| name.strip_suffix("LevelUpLearnset"))
+            {
+                Some(name) => camel_case_name(name),
+                None => continue,
+            },
+        };
+        decomp_data
+            .species
+            .entry(species)
+            .or_default()
+            .level_up_moves = Some(moves);
+    }
+}
+
+// Reads every LEVEL_UP_MOVE(5, MOVE_GROWL) of a learnset
+fn level_up_moves(initializer: &str) -> Vec<(u32, String)> {
+    let mut moves = Vec::new();
+    let mut rest = initializer;
+    while let Some(start) = rest.find("LEVEL_UP_MOVE(") {
+        rest = &rest[start + "LEVEL_UP_MOVE(".len()..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        if let Some((level, move_name)) = rest[..end].split_once(',') {
+            let move_name = constants(move_name, "MOVE_");
+            if let (Ok(level), Some(move_name)) = (level.trim().parse::<u32>(), move_name.first()) {
+                moves.push((level, move_name.clone()));
+            }
+        }
+        rest = &rest[end..];
+    }
+    moves
+}
+
+pub fn read_tmhm_learnsets(content: &str, decomp_data: &mut DecompData) {
+    for (species, initializer) in designated_entries(&strip_comments(content), "SPECIES_") {
+        if species == "NONE" {
+            continue;
+        }
+
+        // Machines are written as TMHM(TM06_TOXIC)
+        let mut machine_moves = Vec::new();
+        for identifier in identifiers(&initializer) {
+            let Some((machine, move_name)) = identifier.split_once('_') else {
+                continue;
+            };
+            let is_machine = (machine.starts_with("TM") || machine.starts_with("HM"))
+                && machine.len() > 2
+                && machine[2..]
+                    .chars()
+                    .all(|character| character.is_ascii_digit());
+            let move_name = normalize_name(move_name);
+            if is_machine && !machine_moves.contains(&move_name) {
+                machine_moves.push(move_name);
+            }
+        }
+
+        decomp_data
+            .species
+            .entry(normalize_name(&species))
+            .or_default()
+            .machine_moves = Some(machine_moves);
+    }
+}
+
+pub fn read_evolutions(content: &str, decomp_data: &mut DecompData) {
+    decomp_data.has_evolutions = true;
+
+    for (species, initializer) in designated_entries(&strip_comments(content), "SPECIES_") {
+        if species == "NONE" {
+            continue;
+        }
+
+        // {{EVO_LEVEL, 16, SPECIES_IVYSAUR}, {EVO_ITEM, ITEM_MOON_STONE, SPECIES_CLEFABLE}}
+        let evolutions = split_top_level(strip_braces(&initializer))
+            .into_iter()
+            .filter_map(|evolution| {
+                let parts: Vec<&str> = split_top_level(strip_braces(evolution))
+                    .into_iter()
+                    .map(str::trim)
+                    .collect();
+                let target = parts.last()?.strip_prefix("SPECIES_")?;
+                if parts.len() < 3 {
+                    return None;
+                }
+                Some(DecompEvolution {
+                    method: parts[0].to_string(),
+                    parameter: parts[1].to_string(),
+                    target: normalize_name(target),
+                })
+            })
+            .collect();
+
+        decomp_data
+            .species
+            .entry(normalize_name(&species))
+            .or_default()
+            .evolutions = evolutions;
+    }
+}
+
+// Turns what was read from the headers into one update per wiki pokemon
+pub fn resolve_decomp_species(
+    decomp_data: &DecompData,
+    lookup: &WikiLookup,
+    unmatched: &mut Vec<UnmatchedEntry>,
+) -> Vec<SpeciesUpdate> {
+    let mut updates = Vec::new();
+
+    for (name, species) in &decomp_data.species {
+        let Some((id, _, _)) = lookup.pokemon.get(name) else {
+            unmatched.push(UnmatchedEntry::new(
+                "Species",
+                name,
+                "No pokemon with this name in the wiki",
+            ));
+            continue;
+        };
+        let mut update = SpeciesUpdate {
+            pokemon_id: *id,
+            stats: species.stats.clone(),
+            ..Default::default()
+        };
+
+        if !species.types.is_empty() {
+            update.types = Some(species.types.join(","));
+        }
+        let mut abilities = Vec::new();
+        for ability in &species.abilities {
+            if lookup.abilities.contains(ability) {
+                abilities.push(ability.as_str());
+            } else {
+                unmatched.push(UnmatchedEntry::new(
+                    SPECIES_INFO,
+                    ability,
+                    &format!("Ability of {} is not in the wiki", name),
+                ));
+            }
+        }
+        if !abilities.is_empty() {
+            update.abilities = Some(abilities.join(","));
+        }
+
+        if let Some(moves) = &species.level_up_moves {
+            let mut level_up_moves: Vec<(u32, u32)> = Vec::new();
+            for (level, move_name) in moves {
+                match lookup.moves.get(move_name) {
+                    // A move learned at several levels keeps the first one
+                    Some(move_id) => {
+                        if !level_up_moves.iter().any(|(id, _)| id == move_id) {
+                            level_up_moves.push((*move_id, *level));
+                        }
+                    }
+                    None => unmatched.push(UnmatchedEntry::new(
+                        LEVEL_UP_LEARNSETS,
+                        move_name,
+                        &format!("Move learned by {} is not in the wiki", name),
+                    )),
+                }
+            }
+            update.level_up_moves = Some(level_up_moves);
+        }
+        if let Some(moves) = &species.machine_moves {
+            let mut machine_moves = Vec::new();
+            for move_name in moves {
+                match lookup.moves.get(move_name) {
+                    Some(move_id) => machine_moves.push(*move_id),
+                    None => unmatched.push(UnmatchedEntry::new(
+                        TMHM_LEARNSETS,
+                        move_name,
+                        &format!("Machine move of {} is not in the wiki", name),
+                    )),
+                }
+            }
+            update.machine_moves = Some(machine_moves);
+        }
+
+        if decomp_data.has_evolutions {
+            for evolution in &species.evolutions {
+                if !lookup.pokemon.contains_key(&evolution.target) {
+                    unmatched.push(UnmatchedEntry::new(
+                        EVOLUTION,
+                        &evolution.target,
+                        &format!("Evolution of {} is not in the wiki", name),
+                    ));
+                }
+            }
+            update.evolution = Some(evolution_columns(&species.evolutions));
+        }
+
+        updates.push(update);
+    }
+
+    updates
+}
+
+// The wiki keeps a single evolution per pokemon, so anything other than one
+// level or item evolution is described in the note instead
+pub fn evolution_columns(evolutions: &[DecompEvolution]) -> Evolution {
+    let mut columns = Evolution {
+        method: "no_change".to_string(),
+        level: None,
+        item: None,
+        other: None,
+        evolves_into: None,
+    };
+    if evolutions.is_empty() {
+        return columns;
+    }
+
+    columns.evolves_into = Some(
+        evolutions
+            .iter()
+            .map(|evolution| evolution.target.clone())
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+    if let [evolution] = evolutions {
+        let level = evolution.parameter.parse::<u32>().ok();
+        let item = evolution
+            .parameter
+            .strip_prefix("ITEM_")
+            .map(normalize_name);
+        match (evolution.method.as_str(), level, item) {
+            ("EVO_LEVEL", Some(level), _) => {
+                columns.method = "level_up".to_string();
+                columns.level = Some(level);
+                return columns;
+            }
+            ("EVO_ITEM", _, Some(item)) => {
+                columns.method = "item".to_string();
+                columns.item = Some(item);
+                return columns;
+            }
+            _ => {}
+        }
+    }
+
+    let notes: Vec<String> = evolutions
+        .iter()
+        .map(|evolution| {
+            let description = evolution_description(evolution);
+            if evolutions.len() > 1 {
+                format!(
+                    "{} into {}",
+                    description,
+                    capitalize_and_remove_hyphens(&evolution.target)
+                )
+            } else {
+                description
+            }
+        })
+        .collect();
+    columns.method = "other".to_string();
+    columns.other = Some(notes.join(", "));
+    columns
+}
+
+// EVO_TRADE_ITEM with ITEM_METAL_COAT reads as "Trade Item Metal Coat"
+fn evolution_description(evolution: &DecompEvolution) -> String {
+    let method = evolution
+        .method
+        .strip_prefix("EVO_")
+        .unwrap_or(&evolution.method);
+    let description = capitalize_and_remove_hyphens(&normalize_name(method));
+
+    let parameter = ["ITEM_", "MOVE_", "SPECIES_", "TYPE_", "MAPSEC_"]
+        .iter()
+        .find_map(|prefix| evolution.parameter.strip_prefix(prefix))
+        .map(|constant| capitalize_and_remove_hyphens(&normalize_name(constant)))
+        .unwrap_or_else(|| evolution.parameter.clone());
+    if parameter.is_empty() || parameter == "0" {
+        description
+    } else {
+        format!("{} {}", description, parameter)
+    }
+}
+
+// Compares each update with the wiki, leaving out pokemon it wouldn't change
+pub fn diff_species_updates(
+    updates: &[SpeciesUpdate],
+    current_pokemon: &[DBPokemon],
+    current_movesets: &[PokemonMove],
+    move_names: &HashMap<u32, String>,
+) -> Vec<SpeciesDiff> {
+    let mut species_diffs = Vec::new();
+
+    for update in updates {
+        let Some(pokemon) = current_pokemon
+            .iter()
+            .find(|pokemon| pokemon.id == update.pokemon_id)
+        else {
+            continue;
+        };
+
+        let mut fields = Vec::new();
+        let mut compare = |column: &str, old: Value, new: Value| {
+            if old != new {
+                fields.push(FieldChange {
+                    column: column.to_string(),
+                    old,
+                    new,
+                });
+            }
+        };
+        if let Some(types) = &update.types {
+            compare(
+                "types",
+                Value::from(pokemon.types.as_str()),
+                Value::from(types.as_str()),
+            );
+        }
+        if let Some(abilities) = &update.abilities {
+            compare(
+                "abilities",
+                Value::from(pokemon.abilities.as_str()),
+                Value::from(abilities.as_str()),
+            );
+        }
+        if let Some(stats) = &update.stats {
+            for (column, old, new) in [
+                ("hp", pokemon.hp, stats.hp),
+                ("attack", pokemon.attack, stats.attack),
+                ("defense", pokemon.defense, stats.defense),
+                ("sp_attack", pokemon.sp_attack, stats.sp_attack),
+                ("sp_defense", pokemon.sp_defense, stats.sp_defense),
+                ("speed", pokemon.speed, stats.speed),
+            ] {
+                compare(column, Value::from(old), Value::from(new));
+            }
+        }
+        if let Some(evolution) = &update.evolution {
+            compare(
+                "evolution_method",
+                Value::from(pokemon.evolution_method.as_str()),
+                Value::from(evolution.method.as_str()),
+            );
+            compare(
+                "evolution_level",
+                Value::from(pokemon.evolution_level),
+                Value::from(evolution.level),
+            );
+            compare(
+                "evolution_item",
+                Value::from(pokemon.evolution_item.clone()),
+                Value::from(evolution.item.clone()),
+            );
+            compare(
+                "evolution_other",
+                Value::from(pokemon.evolution_other.clone()),
+                Value::from(evolution.other.clone()),
+            );
+            compare(
+                "evolves_into",
+                Value::from(pokemon.evolves_into.clone()),
+                Value::from(evolution.evolves_into.clone()),
+            );
+        }
+
+        let mut moves_added = Vec::new();
+        let mut moves_removed = Vec::new();
+        let current_moves = |learn_method: &str| -> Vec<(u32, Option<u32>)> {
+            current_movesets
+                .iter()
+                .filter(|pokemon_move| {
+                    pokemon_move.pokemon == update.pokemon_id
+                        && pokemon_move.learn_method == learn_method
+                })
+                .map(|pokemon_move| (pokemon_move.move_id, pokemon_move.level_learned))
+                .collect()
+        };
+        if let Some(level_up_moves) = &update.level_up_moves {
+            let new_moves: Vec<(u32, Option<u32>)> = level_up_moves
+                .iter()
+                .map(|(move_id, level)| (*move_id, Some(*level)))
+                .collect();
+            diff_moves(
+                &current_moves("level-up"),
+                &new_moves,
+                move_names,
+                &mut moves_added,
+                &mut moves_removed,
+            );
+        }
+        if let Some(machine_moves) = &update.machine_moves {
+            // Machine moves have no level, older wikis store them as 0
+            let old_moves: Vec<(u32, Option<u32>)> = current_moves("machine")
+                .into_iter()
+                .map(|(move_id, _)| (move_id, None))
+                .collect();
+            let new_moves: Vec<(u32, Option<u32>)> = machine_moves
+                .iter()
+                .map(|move_id| (*move_id, None))
+                .collect();
+            diff_moves(
+                &old_moves,
+                &new_moves,
+                move_names,
+                &mut moves_added,
+                &mut moves_removed,
+            );
+        }
+
+        if !fields.is_empty() || !moves_added.is_empty() || !moves_removed.is_empty() {
+            species_diffs.push(SpeciesDiff {
+                pokemon_id: update.pokemon_id,
+                name: pokemon.name.clone(),
+                fields,
+                moves_added,
+                moves_removed,
+            });
+        }
+    }
+
+    species_diffs
+}
+
+fn diff_moves(
+    old_moves: &[(u32, Option<u32>)],
+    new_moves: &[(u32, Option<u32>)],
+    move_names: &HashMap<u32, String>,
+    moves_added: &mut Vec<String>,
+    moves_removed: &mut Vec<String>,
+) {
+    let describe = |(move_id, level): &(u32, Option<u32>)| {
+        let move_name = move_names
+            .get(move_id)
+            .cloned()
+            .unwrap_or_else(|| move_id.to_string());
+        match level {
+            Some(level) => format!("{} (level {})", move_name, level),
+            None => format!("{} (machine)", move_name),
+        }
+    };
+
+    for new_move in new_moves {
+        if !old_moves.contains(new_move) {
+            moves_added.push(describe(new_move));
+        }
+    }
+    for old_move in old_moves {
+        if !new_moves.contains(old_move) {
+            moves_removed.push(describe(old_move));
+        }
+    }
+}
+
+// Drops comments and preprocessor lines. Both sides of an #if are kept, and
+// since fields keep their first value the first side wins.
+fn strip_comments(content: &str) -> String {
+    let mut stripped = String::new();
+    let mut characters = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(character) = characters.next() {
+        if in_string {
+            stripped.push(character);
+            if character == '\\' {
+                if let Some(escaped) = characters.next() {
+                    stripped.push(escaped);
+                }
+            } else if character == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match character {
+            '"' => {
+                in_string = true;
+                stripped.push(character);
+            }
+            '/' if characters.peek() == Some(&'/') => {
+                while characters.next_if(|next| *next != '\n').is_some() {}
+            }
+            '/' if characters.peek() == Some(&'*') => {
+                characters.next();
+                let mut previous = ' ';
+                for next in characters.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => stripped.push(character),
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut in_directive = false;
+    for line in stripped.lines() {
+        if in_directive || line.trim_start().starts_with('#') {
+            // Multi-line macros end every line but the last with a backslash
+            in_directive = line.trim_end().ends_with('\\');
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+// Reads array entries like "[SPECIES_BULBASAUR] = { ... }," as the constant
+// without its prefix and the initializer
+fn designated_entries(content: &str, prefix: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let pattern = format!("[{}", prefix);
+    let mut rest = content;
+    while let Some(start) = rest.find(&pattern) {
+        rest = &rest[start + pattern.len()..];
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let constant = rest[..end].trim().to_string();
+        rest = &rest[end + 1..];
+        let Some(initializer) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+
+        let length = initializer_length(initializer);
+        entries.push((constant, initializer[..length].trim().to_string()));
+        rest = &initializer[length..];
+    }
+    entries
+}
+
+// Length of an initializer up to the comma, semicolon or closing brace that ends it
+fn initializer_length(text: &str) -> usize {
+    let mut depth = 0;
+    for (index, character) in text.char_indices() {
+        match character {
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => {
+                if depth == 0 {
+                    return index;
+                }
+                depth -= 1;
+            }
+            ',' | ';' if depth == 0 => return index,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+// Fields of a struct initializer, like ("baseHP", "45") for ".baseHP = 45"
+fn designated_fields(initializer: &str) -> Vec<(String, String)> {
+    split_top_level(strip_braces(initializer))
+        .into_iter()
+        .filter_map(|field| {
+            let (name, value) = field.trim().strip_prefix('.')?.split_once('=')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while !rest.trim().is_empty() {
+        let length = initializer_length(rest);
+        parts.push(&rest[..length]);
+        if length >= rest.len() {
+            break;
+        }
+        rest = &rest[length + 1..];
+    }
+    parts
+}
+
+fn strip_braces(text: &str) -> &str {
+    let text = text.trim();
+    text.strip_prefix('{')
+        .and_then(|text| text.strip_suffix('}'))
+        .unwrap_or(text)
+}
+
+fn is_identifier_character(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_'
+}
+
+fn identifiers(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|character: char| !is_identifier_character(character))
+        .filter(|identifier| !identifier.is_empty())
+}
+
+// Every constant with the prefix in wiki form, TYPE_GRASS becomes grass
+fn constants(text: &str, prefix: &str) -> Vec<String> {
+    identifiers(text)
+        .filter_map(|identifier| identifier.strip_prefix(prefix))
+        .map(normalize_name)
+        .collect()
+}
+
+fn camel_case_name(name: &str) -> String {
+    let mut hyphenated = String::new();
+    let mut previous: Option<char> = None;
+    for character in name.chars() {
+        if character.is_ascii_uppercase()
+            && previous
+                .is_some_and(|previous| previous.is_ascii_lowercase() || previous.is_ascii_digit())
+        {
+            hyphenated.push('-');
+        }
+        hyphenated.push(character);
+        previous = Some(character);
+    }
+    normalize_name(&hyphenated)
+}
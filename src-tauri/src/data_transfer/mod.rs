@@ -0,0 +1,4 @@
+pub mod csv;
+pub mod decomp;
+pub mod randomizer_log;
+pub mod tabular;
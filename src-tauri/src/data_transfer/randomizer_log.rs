@@ -0,0 +1,908 @@
+// Importer for the log files written by the Universal Pokemon Randomizer.
+// The log is split into sections headed by lines like "--Wild Pokemon--",
+// and every section the wiki can use is read on its own.
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tauri::AppHandle;
+
+use crate::{
+    database::{
+        get_routes,
+        repository::{
+            apply_species_updates, get_db_abilities, get_items, get_move_names, get_pokemon_names,
+        },
+        session::get_wiki_session,
+        update_routes,
+    },
+    errors::{WikiGenError, WikiGenResult},
+    logger::{self, LogLevel},
+    page_generators::game_routes::{Routes, TrainerPokemon, WildEncounter},
+    structs::pokemon_structs::{SpeciesUpdate, Stats},
+};
+
+// Encounter methods as they appear at the end of a wild set's name, and the
+// encounter area they become in the wiki
+const ENCOUNTER_AREA_SUFFIXES: [(&str, &str); 10] = [
+    ("grass/cave", "grass"),
+    ("doubles grass", "grass"),
+    ("shaking spots", "shaking-spots"),
+    ("surfing", "surfing"),
+    ("rock smash", "rock-smash"),
+    ("old rod", "old-rod"),
+    ("good rod", "good-rod"),
+    ("super rod", "super-rod"),
+    ("fishing", "fishing"),
+    ("headbutt", "headbutt"),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct RandomizerLog {
+    pub pokemon: Vec<RandomizedPokemon>,
+    pub movesets: Vec<RandomizedMoveset>,
+    // Pairs of machine, like tm01, and the move it teaches
+    pub machines: Vec<(String, String)>,
+    pub machine_compatibility: Vec<(String, Vec<String>)>,
+    pub trainers: Vec<RandomizedTrainer>,
+    pub encounter_sets: Vec<RandomizedEncounterSet>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RandomizedPokemon {
+    pub name: String,
+    pub types: Vec<String>,
+    pub stats: Option<Stats>,
+    pub abilities: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RandomizedMoveset {
+    pub pokemon: String,
+    // Pairs of level and move
+    pub moves: Vec<(u32, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RandomizedTrainer {
+    // Name before the randomizer renamed the trainer, if it did
+    pub original_name: String,
+    pub name: String,
+    pub team: Vec<RandomizedTrainerPokemon>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RandomizedTrainerPokemon {
+    pub name: String,
+    pub level: usize,
+    pub item: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RandomizedEncounterSet {
+    pub location: String,
+    pub area: Option<String>,
+    pub encounters: Vec<RandomizedEncounter>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RandomizedEncounter {
+    pub name: String,
+    pub min_level: usize,
+    pub max_level: usize,
+}
+
+// Names as they are stored in the wiki, used to match the log's entries
+#[derive(Debug, Clone, Default)]
+pub struct WikiLookup {
+    // Pokemon name to id, dex number and types
+    pub pokemon: HashMap<String, (u32, u32, String)>,
+    pub moves: HashMap<String, u32>,
+    pub abilities: HashSet<String>,
+    pub items: HashSet<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UnmatchedEntry {
+    pub section: String,
+    pub entry: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RandomizerImportReport {
+    pub pokemon_updated: usize,
+    pub machines_updated: usize,
+    pub trainers_updated: usize,
+    pub encounter_areas_updated: usize,
+    pub unmatched: Vec<UnmatchedEntry>,
+}
+
+impl UnmatchedEntry {
+    pub fn new(section: &str, entry: &str, reason: &str) -> Self {
+        UnmatchedEntry {
+            section: section.to_string(),
+            entry: entry.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl RandomizerImportReport {
+    fn add_unmatched(&mut self, section: &str, entry: &str, reason: &str) {
+        self.unmatched
+            .push(UnmatchedEntry::new(section, entry, reason));
+    }
+}
+
+#[tauri::command]
+pub async fn import_randomizer_log(
+    wiki_name: &str,
+    log_path: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<RandomizerImportReport> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let log_path = PathBuf::from(log_path);
+
+    let content = match fs::read_to_string(&log_path) {
+        Ok(content) => content,
+        Err(err) => {
+            return Err(WikiGenError::io(
+                format!("Failed to read randomizer log: {}", err),
+                log_path.display(),
+            ));
+        }
+    };
+    let log = parse_randomizer_log(&content);
+
+    let lookup = match get_wiki_lookup(&session.pool).await {
+        Ok(lookup) => lookup,
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    let mut report = RandomizerImportReport::default();
+    let (updates, machines) = resolve_species_updates(&log, &lookup, &mut report);
+
+    let routes_file_path = session.wiki_path.join("data").join("routes.json");
+    let mut routes = get_routes(&routes_file_path)?;
+    apply_log_to_routes(&log, &lookup, &updates, &mut routes, &mut report);
+
+    // Routes are only written once the database changes are committed, so a
+    // failed import leaves both untouched
+    if let Err(err) = apply_species_updates(&session.pool, &updates, &machines).await {
+        logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+        return Err(err);
+    }
+    update_routes(wiki_name, &session.base_path, &routes)?;
+
+    Ok(report)
+}
+
+pub async fn get_wiki_lookup(conn: &Pool<Sqlite>) -> WikiGenResult<WikiLookup> {
+    let pokemon = get_pokemon_names(conn)
+        .await?
+        .into_iter()
+        .map(|(id, dex_number, name, types)| (name, (id, dex_number, types)))
+        .collect();
+    let moves = get_move_names(conn)
+        .await?
+        .into_iter()
+        .map(|(id, name)| (name, id))
+        .collect();
+    let abilities = get_db_abilities(conn)
+        .await?
+        .into_iter()
+        .map(|ability| ability.name)
+        .collect();
+    let items = get_items(conn)
+        .await?
+        .into_iter()
+        .map(|item| item.name)
+        .collect();
+
+    Ok(WikiLookup {
+        pokemon,
+        moves,
+        abilities,
+        items,
+    })
+}
+
+// Turns a name from the log, like "MR. MIME" or "Vine Whip", into the
+// lowercase hyphenated form the wiki stores
+pub fn normalize_name(name: &str) -> String {
+    let name = name
+        .trim()
+        .to_lowercase()
+        .replace('♀', "-f")
+        .replace('♂', "-m")
+        .replace('é', "e");
+
+    let mut normalized = String::new();
+    for character in name.chars() {
+        match character {
+            '.' | '\'' | '’' | ':' => {}
+            _ if character.is_alphanumeric() => normalized.push(character),
+            _ => {
+                if !normalized.is_empty() && !normalized.ends_with('-') {
+                    normalized.push('-');
+                }
+            }
+        }
+    }
+
+    normalized.trim_end_matches('-').to_string()
+}
+
+pub fn parse_randomizer_log(content: &str) -> RandomizerLog {
+    let mut log = RandomizerLog::default();
+    let mut section = String::new();
+    let mut stats_header: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.len() > 4 && trimmed.starts_with("--") && trimmed.ends_with("--") {
+            section = trimmed.trim_matches('-').trim().to_lowercase();
+            stats_header.clear();
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if section.contains("base stats") {
+            read_stats_line(trimmed, &mut stats_header, &mut log);
+        } else if section.contains("movesets") {
+            read_moveset_line(trimmed, &mut log);
+        } else if section.contains("compatibility") {
+            read_compatibility_line(trimmed, &mut log);
+        } else if section == "tm moves" || section == "tms" {
+            read_machine_line(trimmed, &mut log);
+        } else if section.contains("trainer") {
+            read_trainer_line(trimmed, &mut log);
+        } else if section.contains("wild") {
+            read_wild_line(trimmed, &mut log);
+        }
+    }
+
+    log
+}
+
+fn read_stats_line(line: &str, header: &mut Vec<String>, log: &mut RandomizerLog) {
+    let cells: Vec<&str> = line.split('|').map(str::trim).collect();
+    if cells.len() < 3 {
+        return;
+    }
+    if cells[0].eq_ignore_ascii_case("num") {
+        *header = cells.iter().map(|cell| cell.to_uppercase()).collect();
+        return;
+    }
+    if header.is_empty() {
+        return;
+    }
+
+    let cell = |names: &[&str]| {
+        header
+            .iter()
+            .position(|column| names.iter().any(|name| *name == column.as_str()))
+            .and_then(|index| cells.get(index).copied())
+    };
+    let stat = |names: &[&str]| cell(names).and_then(|value| value.parse::<u32>().ok());
+
+    let Some(name) = cell(&["NAME"]) else {
+        return;
+    };
+    let types = cell(&["TYPE", "TYPES"])
+        .map(|types| {
+            types
+                .split('/')
+                .map(normalize_name)
+                .filter(|_type| !_type.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    let stats = match (
+        stat(&["HP"]),
+        stat(&["ATK", "ATTACK"]),
+        stat(&["DEF", "DEFENSE"]),
+        stat(&["SATK", "SPATK", "SPA", "SPEC"]),
+        stat(&["SDEF", "SPDEF", "SPEC"]),
+        stat(&["SPD", "SPE", "SPEED"]),
+    ) {
+        (Some(hp), Some(attack), Some(defense), Some(sp_attack), Some(sp_defense), Some(speed)) => {
+            Some(Stats {
+                hp,
+                attack,
+                defense,
+                sp_attack,
+                sp_defense,
+                speed,
+            })
+        }
+        _ => None,
+    };
+    let abilities = header
+        .iter()
+        .zip(cells.iter())
+        .filter(|(column, _)| column.starts_with("ABILITY"))
+        .map(|(_, ability)| normalize_name(ability))
+        .filter(|ability| !ability.is_empty())
+        .collect();
+
+    log.pokemon.push(RandomizedPokemon {
+        name: normalize_name(name),
+        types,
+        stats,
+        abilities,
+    });
+}
+
+// Splits "001 BULBASAUR" into the species name, ignoring the dex number
+fn species_name(text: &str) -> Option<String> {
+    let text = text.trim();
+    let name_start = text.find(|character: char| !character.is_ascii_digit())?;
+    if name_start == 0 {
+        return None;
+    }
+    let name = normalize_name(&text[name_start..]);
+    (!name.is_empty()).then_some(name)
+}
+
+fn read_moveset_line(line: &str, log: &mut RandomizerLog) {
+    let lowercase = line.to_lowercase();
+    if lowercase.starts_with("level") {
+        // "Level 4 : Growl" under the species it belongs to
+        let Some((level, move_name)) = line.split_once(':') else {
+            return;
+        };
+        let level = level
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+            .parse::<u32>();
+        if let (Ok(level), Some(moveset)) = (level, log.movesets.last_mut()) {
+            moveset.moves.push((level, normalize_name(move_name)));
+        }
+        return;
+    }
+
+    let (species, rest) = match line.split_once("->") {
+        Some((species, rest)) => (species, Some(rest)),
+        None => (line, None),
+    };
+    let Some(pokemon) = species_name(species) else {
+        return;
+    };
+
+    let mut moves = Vec::new();
+    // Older logs list the whole moveset on the species line, newer ones only the evolution
+    if let Some(rest) = rest.filter(|rest| rest.contains(" at level")) {
+        for entry in rest.split(',') {
+            match entry.split_once(" at level") {
+                Some((move_name, level)) => {
+                    if let Ok(level) = level.trim().parse::<u32>() {
+                        moves.push((level, normalize_name(move_name)));
+                    }
+                }
+                None => moves.push((1, normalize_name(entry))),
+            }
+        }
+    }
+
+    log.movesets.push(RandomizedMoveset { pokemon, moves });
+}
+
+fn machine_code(text: &str) -> Option<String> {
+    let code = text.trim().to_lowercase();
+    let is_machine = (code.starts_with("tm") || code.starts_with("hm"))
+        && code.len() > 2
+        && code[2..]
+            .chars()
+            .all(|character| character.is_ascii_digit());
+    is_machine.then_some(code)
+}
+
+fn read_machine_line(line: &str, log: &mut RandomizerLog) {
+    let Some((code, move_name)) = line.split_once(char::is_whitespace) else {
+        return;
+    };
+    let Some(machine) = machine_code(code) else {
+        return;
+    };
+    let move_name = normalize_name(move_name.trim().trim_start_matches('-'));
+    if !move_name.is_empty() {
+        log.machines.push((machine, move_name));
+    }
+}
+
+fn read_compatibility_line(line: &str, log: &mut RandomizerLog) {
+    let mut cells = line.split('|');
+    let Some(pokemon) = cells.next().and_then(species_name) else {
+        return;
+    };
+    let machines = cells
+        .filter_map(|cell| cell.split_whitespace().next().and_then(machine_code))
+        .collect();
+    log.machine_compatibility.push((pokemon, machines));
+}
+
+fn read_trainer_line(line: &str, log: &mut RandomizerLog) {
+    if !line.starts_with('#') {
+        return;
+    }
+    let (Some(open), Some(close)) = (line.find('('), line.find(')')) else {
+        return;
+    };
+    if close < open {
+        return;
+    }
+
+    let names = &line[open + 1..close];
+    let (original_name, name) = match names.split_once("=>") {
+        Some((original_name, name)) => (original_name.trim(), name.trim()),
+        None => (names.trim(), names.trim()),
+    };
+    let Some((_, team)) = line[close..].split_once(" - ") else {
+        return;
+    };
+
+    let team = team
+        .split(',')
+        .filter_map(|entry| {
+            let (pokemon, level) = entry.trim().rsplit_once(" Lv")?;
+            let level = level.trim_start_matches('.').trim().parse::<usize>().ok()?;
+            let (pokemon, item) = pokemon.split_once('@').unwrap_or((pokemon, ""));
+            Some(RandomizedTrainerPokemon {
+                name: normalize_name(pokemon),
+                level,
+                item: normalize_name(item),
+            })
+        })
+        .collect();
+
+    log.trainers.push(RandomizedTrainer {
+        original_name: original_name.to_string(),
+        name: name.to_string(),
+        team,
+    });
+}
+
+fn read_wild_line(line: &str, log: &mut RandomizerLog) {
+    if let Some(set) = line.strip_prefix("Set #") {
+        // "Set #1 - Route 1 Grass/Cave (rate=21)", sometimes followed by the encounters
+        let Some((_, set)) = set.split_once(" - ") else {
+            return;
+        };
+        let (name, encounters) = match set.split_once(" (rate") {
+            Some((name, rest)) => (
+                name,
+                rest.split_once(')')
+                    .and_then(|(_, rest)| rest.split_once(" - "))
+                    .map(|(_, encounters)| encounters),
+            ),
+            None => match set.split_once(" - ") {
+                Some((name, encounters)) => (name, Some(encounters)),
+                None => (set, None),
+            },
+        };
+
+        let (location, area) = split_encounter_area(name);
+        let mut encounter_set = RandomizedEncounterSet {
+            location,
+            area,
+            encounters: Vec::new(),
+        };
+        if let Some(encounters) = encounters {
+            encounter_set.encounters = encounters.split(',').filter_map(parse_encounter).collect();
+        }
+        log.encounter_sets.push(encounter_set);
+        return;
+    }
+
+    // Newer logs put every encounter of the set on its own line
+    if let (Some(encounter), Some(encounter_set)) =
+        (parse_encounter(line), log.encounter_sets.last_mut())
+    {
+        encounter_set.encounters.push(encounter);
+    }
+}
+
+fn split_encounter_area(name: &str) -> (String, Option<String>) {
+    let lowercase = name.trim().to_lowercase();
+    for (suffix, area) in ENCOUNTER_AREA_SUFFIXES {
+        if let Some(location) = lowercase.strip_suffix(suffix) {
+            let location = name
+                .trim()
+                .get(..location.len())
+                .unwrap_or(location)
+                .trim()
+                .to_string();
+            return (location, Some(area.to_string()));
+        }
+    }
+    (name.trim().to_string(), None)
+}
+
+// Reads "PIDGEY Lv2-5" and ignores anything after the levels, like stats
+fn parse_encounter(text: &str) -> Option<RandomizedEncounter> {
+    let (name, levels) = text.trim().split_once(" Lv")?;
+    let levels = levels.trim_start_matches('.').split_whitespace().next()?;
+    let (min_level, max_level) = match levels.split_once('-') {
+        Some((min_level, max_level)) => (min_level.parse().ok()?, max_level.parse().ok()?),
+        None => {
+            let level = levels.parse().ok()?;
+            (level, level)
+        }
+    };
+
+    let name = normalize_name(name);
+    if name.is_empty() {
+        return None;
+    }
+    Some(RandomizedEncounter {
+        name,
+        min_level,
+        max_level,
+    })
+}
+
+// Gathers everything the log says about each species into one update per
+// pokemon, along with the machines that were reassigned
+pub fn resolve_species_updates(
+    log: &RandomizerLog,
+    lookup: &WikiLookup,
+    report: &mut RandomizerImportReport,
+) -> (Vec<SpeciesUpdate>, Vec<(String, u32)>) {
+    let mut updates: IndexMap<u32, SpeciesUpdate> = IndexMap::new();
+
+    for pokemon in &log.pokemon {
+        let Some(update) = species_update(&mut updates, lookup, &pokemon.name, "Pokemon", report)
+        else {
+            continue;
+        };
+        if !pokemon.types.is_empty() {
+            update.types = Some(pokemon.types.join(","));
+        }
+        if pokemon.stats.is_some() {
+            update.stats = pokemon.stats.clone();
+        }
+
+        let mut abilities = Vec::new();
+        for ability in &pokemon.abilities {
+            if !lookup.abilities.contains(ability) {
+                report.add_unmatched(
+                    "Abilities",
+                    ability,
+                    &format!("Ability of {} is not in the wiki", pokemon.name),
+                );
+            } else if !abilities.contains(ability) {
+                abilities.push(ability.clone());
+            }
+        }
+        if !abilities.is_empty() {
+            update.abilities = Some(abilities.join(","));
+        }
+    }
+
+    for moveset in &log.movesets {
+        if moveset.moves.is_empty() {
+            continue;
+        }
+        let Some(update) =
+            species_update(&mut updates, lookup, &moveset.pokemon, "Movesets", report)
+        else {
+            continue;
+        };
+        let mut level_up_moves = Vec::new();
+        for (level, move_name) in &moveset.moves {
+            match lookup.moves.get(move_name) {
+                Some(move_id) => level_up_moves.push((*move_id, *level)),
+                None => report.add_unmatched(
+                    "Movesets",
+                    move_name,
+                    &format!("Move learned by {} is not in the wiki", moveset.pokemon),
+                ),
+            }
+        }
+        update.level_up_moves = Some(level_up_moves);
+    }
+
+    let mut machines = Vec::new();
+    let mut machine_moves: HashMap<&str, u32> = HashMap::new();
+    for (machine, move_name) in &log.machines {
+        match lookup.moves.get(move_name) {
+            Some(move_id) => {
+                machines.push((machine.clone(), *move_id));
+                machine_moves.insert(machine, *move_id);
+            }
+            None => report.add_unmatched(
+                "TM Moves",
+                move_name,
+                &format!(
+                    "Move taught by {} is not in the wiki",
+                    machine.to_uppercase()
+                ),
+            ),
+        }
+    }
+
+    // Without the TM list the log doesn't say which move each machine teaches
+    let machine_compatibility = if machine_moves.is_empty() {
+        &[][..]
+    } else {
+        &log.machine_compatibility[..]
+    };
+    for (pokemon, compatible_machines) in machine_compatibility {
+        let Some(update) =
+            species_update(&mut updates, lookup, pokemon, "TM Compatibility", report)
+        else {
+            continue;
+        };
+        update.machine_moves = Some(
+            compatible_machines
+                .iter()
+                .filter_map(|machine| machine_moves.get(machine.as_str()).copied())
+                .collect(),
+        );
+    }
+
+    report.pokemon_updated = updates.len();
+    report.machines_updated = machines.len();
+    (updates.into_values().collect(), machines)
+}
+
+fn species_update<'a>(
+    updates: &'a mut IndexMap<u32, SpeciesUpdate>,
+    lookup: &WikiLookup,
+    pokemon: &str,
+    section: &str,
+    report: &mut RandomizerImportReport,
+) -> Option<&'a mut SpeciesUpdate> {
+    let Some((id, _, _)) = lookup.pokemon.get(pokemon) else {
+        report.add_unmatched(section, pokemon, "No pokemon with this name in the wiki");
+        return None;
+    };
+
+    Some(updates.entry(*id).or_insert_with(|| SpeciesUpdate {
+        pokemon_id: *id,
+        ..Default::default()
+    }))
+}
+
+// Replaces the teams of known trainers and the wild encounters of known
+// routes. Trainers are matched by name in route order, so the first
+// "Youngster" in the log goes to the first Youngster in the wiki.
+pub fn apply_log_to_routes(
+    log: &RandomizerLog,
+    lookup: &WikiLookup,
+    updates: &[SpeciesUpdate],
+    routes: &mut Routes,
+    report: &mut RandomizerImportReport,
+) {
+    let updated_types: HashMap<u32, &String> = updates
+        .iter()
+        .filter_map(|update| {
+            update
+                .types
+                .as_ref()
+                .map(|types| (update.pokemon_id, types))
+        })
+        .collect();
+    let pokemon_entry = |name: &str| {
+        lookup.pokemon.get(name).map(|(id, dex_number, types)| {
+            let types = updated_types.get(id).copied().unwrap_or(types);
+            (
+                *dex_number as usize,
+                types.split(',').map(str::to_string).collect::<Vec<_>>(),
+            )
+        })
+    };
+
+    let trainer_slots: Vec<(String, String)> = routes
+        .routes
+        .iter()
+        .flat_map(|(route_name, route_properties)| {
+            route_properties
+                .trainers
+                .keys()
+                .map(move |trainer_name| (route_name.clone(), trainer_name.clone()))
+        })
+        .collect();
+    let mut used_slots = HashSet::new();
+
+    for trainer in &log.trainers {
+        let slot = trainer_slots
+            .iter()
+            .enumerate()
+            .position(|(slot, (_, trainer_name))| {
+                !used_slots.contains(&slot)
+                    && (trainer_name_matches(trainer_name, &trainer.original_name)
+                        || trainer_name_matches(trainer_name, &trainer.name))
+            });
+        let Some(slot) = slot else {
+            report.add_unmatched(
+                "Trainers",
+                &trainer.name,
+                "No trainer with this name in any route",
+            );
+            continue;
+        };
+        used_slots.insert(slot);
+
+        let (route_name, trainer_name) = &trainer_slots[slot];
+        let Some(trainer_info) = routes
+            .routes
+            .get_mut(route_name)
+            .and_then(|route_properties| route_properties.trainers.get_mut(trainer_name))
+        else {
+            continue;
+        };
+
+        // The log only has species, level and item. The rest is kept from the
+        // pokemon in the same slot, moves and ability only if the species stayed.
+        let previous_team = std::mem::take(&mut trainer_info.pokemon_team);
+        let mut pokemon_team = Vec::new();
+        for (index, pokemon) in trainer.team.iter().enumerate() {
+            let Some((dex_number, types)) = pokemon_entry(&pokemon.name) else {
+                report.add_unmatched(
+                    "Trainers",
+                    &pokemon.name,
+                    &format!("Pokemon of {} is not in the wiki", trainer.name),
+                );
+                continue;
+            };
+            let item = if pokemon.item.is_empty() || lookup.items.contains(&pokemon.item) {
+                pokemon.item.clone()
+            } else {
+                report.add_unmatched(
+                    "Trainers",
+                    &pokemon.item,
+                    &format!(
+                        "Item held by {}'s {} is not in the wiki",
+                        trainer.name, pokemon.name
+                    ),
+                );
+                String::new()
+            };
+
+            let previous_pokemon = previous_team.get(index);
+            let same_species = previous_pokemon
+                .map(|previous_pokemon| previous_pokemon.name == pokemon.name)
+                .unwrap_or(false);
+            let (moves, ability) = match (previous_pokemon, same_species) {
+                (Some(previous_pokemon), true) => (
+                    previous_pokemon.moves.clone(),
+                    previous_pokemon.ability.clone(),
+                ),
+                _ => (Vec::new(), String::new()),
+            };
+
+            pokemon_team.push(TrainerPokemon {
+                id: dex_number,
+                unique_id: format!("{}_{}_{}", dex_number, index, 1000 + trainer_info.position),
+                types,
+                name: pokemon.name.clone(),
+                level: pokemon.level,
+                moves,
+                item,
+                nature: previous_pokemon
+                    .map(|previous_pokemon| previous_pokemon.nature.clone())
+                    .unwrap_or_default(),
+                ability,
+                trainer_versions: previous_pokemon
+                    .map(|previous_pokemon| previous_pokemon.trainer_versions.clone())
+                    .unwrap_or_default(),
+            });
+        }
+        trainer_info.pokemon_team = pokemon_team;
+        report.trainers_updated += 1;
+    }
+
+    // Sets for the same route and area, like day and night, are merged
+    let route_names: HashMap<String, String> = routes
+        .routes
+        .keys()
+        .map(|route_name| (normalize_name(route_name), route_name.clone()))
+        .collect();
+    let mut route_areas: IndexMap<(String, String), Vec<&RandomizedEncounter>> = IndexMap::new();
+    for encounter_set in &log.encounter_sets {
+        let Some(area) = &encounter_set.area else {
+            report.add_unmatched(
+                "Wild Pokemon",
+                &encounter_set.location,
+                "Unknown encounter method",
+            );
+            continue;
+        };
+        let Some(route_name) = route_names.get(&normalize_name(&encounter_set.location)) else {
+            report.add_unmatched(
+                "Wild Pokemon",
+                &encounter_set.location,
+                "No route with this name in the wiki",
+            );
+            continue;
+        };
+        route_areas
+            .entry((route_name.clone(), area.clone()))
+            .or_default()
+            .extend(encounter_set.encounters.iter());
+    }
+
+    for ((route_name, area), encounters) in route_areas {
+        if !routes.encounter_areas.contains(&area) {
+            routes.encounter_areas.push(area.clone());
+        }
+        let Some(route_properties) = routes.routes.get_mut(&route_name) else {
+            continue;
+        };
+
+        // The log doesn't include encounter rates, so each pokemon's share
+        // of the area's slots is used instead
+        let mut slots: IndexMap<&str, usize> = IndexMap::new();
+        for encounter in &encounters {
+            *slots.entry(encounter.name.as_str()).or_default() += 1;
+        }
+
+        let mut wild_encounters = Vec::new();
+        for (name, slot_count) in slots {
+            let Some((dex_number, _)) = pokemon_entry(name) else {
+                report.add_unmatched(
+                    "Wild Pokemon",
+                    name,
+                    &format!("Pokemon found on {} is not in the wiki", route_name),
+                );
+                continue;
+            };
+            wild_encounters.push(WildEncounter {
+                id: dex_number,
+                name: name.to_string(),
+                encounter_rate: (slot_count * 100 / encounters.len()) as f32,
+                encounter_area: area.clone(),
+                route: route_name.clone(),
+                route_variant: "default".to_string(),
+                special_note: String::new(),
+            });
+        }
+
+        route_properties.wild_encounters.retain(|encounter| {
+            encounter.encounter_area != area || encounter.route_variant != "default"
+        });
+        route_properties.wild_encounters.extend(wild_encounters);
+
+        let min_level = encounters.iter().map(|encounter| encounter.min_level).min();
+        let max_level = encounters.iter().map(|encounter| encounter.max_level).max();
+        if let (Some(min_level), Some(max_level)) = (min_level, max_level) {
+            let levels = if min_level == max_level {
+                min_level.to_string()
+            } else {
+                format!("{}-{}", min_level, max_level)
+            };
+            route_properties
+                .wild_encounter_area_levels
+                .insert(area.clone(), levels);
+        }
+        report.encounter_areas_updated += 1;
+    }
+}
+
+// Trainer names in the log usually carry their class, like "LEADER BROCK",
+// while the wiki may only use "Brock"
+fn trainer_name_matches(wiki_name: &str, log_name: &str) -> bool {
+    let wiki_name = normalize_name(wiki_name);
+    let log_name = normalize_name(log_name);
+    if wiki_name.is_empty() || log_name.is_empty() {
+        return false;
+    }
+
+    wiki_name == log_name
+        || log_name.ends_with(&format!("-{}", wiki_name))
+        || wiki_name.ends_with(&format!("-{}", log_name))
+}
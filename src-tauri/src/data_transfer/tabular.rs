@@ -0,0 +1,585 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use tauri::AppHandle;
+
+use crate::{
+    data_transfer::csv::{parse_csv, write_csv},
+    database::{
+        repository::{apply_table_changes, get_table_ids, get_table_rows},
+        session::get_wiki_session,
+    },
+    errors::{WikiGenError, WikiGenResult},
+    logger::{self, LogLevel},
+    structs::table_models::{
+        table_spec, BlankValue, ColumnKind, FieldChange, ImportedRow, ImportedTable, RowChange,
+        RowChangeKind, RowError, TableColumn, TableFormat, TableImportPreview, TableRow, TableSpec,
+    },
+};
+
+#[tauri::command]
+pub async fn export_table(
+    wiki_name: &str,
+    table_name: &str,
+    format: TableFormat,
+    export_path: Option<String>,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let spec = get_table_spec(table_name)?;
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    let rows = match get_table_rows(&session.pool, spec).await {
+        Ok(rows) => rows,
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    let content = match format {
+        TableFormat::Csv => table_rows_to_csv(spec, &rows),
+        TableFormat::Json => match serde_json::to_string_pretty(&rows) {
+            Ok(content) => content,
+            Err(err) => return Err(format!("Failed to serialize {}: {}", spec.name, err).into()),
+        },
+    };
+
+    let export_path = match export_path {
+        Some(export_path) => PathBuf::from(export_path),
+        None => session.base_path.join("exports").join(format!(
+            "{}_{}.{}",
+            wiki_name,
+            spec.name,
+            format.extension()
+        )),
+    };
+    if let Some(export_directory) = export_path.parent() {
+        fs::create_dir_all(export_directory)?;
+    }
+    if let Err(err) = fs::write(&export_path, content) {
+        return Err(WikiGenError::io(
+            format!("Failed to write {} export: {}", spec.name, err),
+            export_path.display(),
+        ));
+    }
+
+    Ok(export_path.display().to_string())
+}
+
+#[tauri::command]
+pub async fn preview_table_import(
+    wiki_name: &str,
+    table_name: &str,
+    import_path: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<TableImportPreview> {
+    let spec = get_table_spec(table_name)?;
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    let preview = build_table_preview(spec, import_path, &session.pool).await;
+    if let Err(err) = &preview {
+        logger::write_log(&session.wiki_path, LogLevel::Error, err);
+    }
+    preview
+}
+
+// Recomputes the preview so the file is validated again as it is now,
+// then applies it only if every row is valid
+#[tauri::command]
+pub async fn import_table(
+    wiki_name: &str,
+    table_name: &str,
+    import_path: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<TableImportPreview> {
+    let spec = get_table_spec(table_name)?;
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    let preview = build_table_preview(spec, import_path, &session.pool).await?;
+    if !preview.errors.is_empty() {
+        return Err(format!(
+            "{} has {} invalid rows, nothing was imported",
+            import_path,
+            preview.errors.len()
+        )
+        .into());
+    }
+
+    if let Err(err) = apply_table_changes(&session.pool, spec, &preview.changes).await {
+        logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+        return Err(err);
+    }
+
+    Ok(preview)
+}
+
+fn get_table_spec(table_name: &str) -> WikiGenResult<&'static TableSpec> {
+    match table_spec(table_name) {
+        Some(spec) => Ok(spec),
+        None => Err(format!("{} can't be imported or exported", table_name).into()),
+    }
+}
+
+async fn build_table_preview(
+    spec: &TableSpec,
+    import_path: &str,
+    conn: &Pool<Sqlite>,
+) -> WikiGenResult<TableImportPreview> {
+    let import_path = PathBuf::from(import_path);
+    let format = import_path
+        .extension()
+        .and_then(|extension| TableFormat::from_extension(&extension.to_string_lossy()));
+    let Some(format) = format else {
+        return Err(WikiGenError::io(
+            "Only .csv and .json files can be imported",
+            import_path.display(),
+        ));
+    };
+
+    let content = match fs::read_to_string(&import_path) {
+        Ok(content) => content,
+        Err(err) => {
+            return Err(WikiGenError::io(
+                format!("Failed to read import file: {}", err),
+                import_path.display(),
+            ));
+        }
+    };
+
+    let existing_rows = get_table_rows(conn, spec).await?;
+    let mut referenced_ids = HashMap::new();
+    for (column, table) in spec.references {
+        referenced_ids.insert(*column, get_table_ids(conn, table).await?);
+    }
+
+    let imported_table = read_table_file(spec, format, &content);
+    Ok(diff_table_rows(
+        spec,
+        imported_table,
+        &existing_rows,
+        &referenced_ids,
+    ))
+}
+
+pub fn table_rows_to_csv(spec: &TableSpec, rows: &[TableRow]) -> String {
+    let mut csv_rows = vec![spec
+        .columns
+        .iter()
+        .map(|column| column.name.to_string())
+        .collect::<Vec<_>>()];
+
+    for row in rows {
+        csv_rows.push(
+            spec.columns
+                .iter()
+                .map(|column| match row.get(column.name) {
+                    Some(Value::String(text)) => text.clone(),
+                    Some(Value::Null) | None => String::new(),
+                    Some(value) => value.to_string(),
+                })
+                .collect(),
+        );
+    }
+
+    write_csv(&csv_rows)
+}
+
+pub fn read_table_file(spec: &TableSpec, format: TableFormat, content: &str) -> ImportedTable {
+    match format {
+        TableFormat::Csv => read_csv_rows(spec, content),
+        TableFormat::Json => read_json_rows(spec, content),
+    }
+}
+
+fn read_csv_rows(spec: &TableSpec, content: &str) -> ImportedTable {
+    let mut imported_table = ImportedTable::default();
+
+    let records = match parse_csv(content) {
+        Ok(records) => records,
+        Err((line, message)) => {
+            imported_table.errors.push(RowError {
+                line,
+                column: None,
+                message,
+            });
+            return imported_table;
+        }
+    };
+    let Some((header, records)) = records.split_first() else {
+        return imported_table;
+    };
+
+    let mut header_valid = true;
+    for name in &header.fields {
+        if spec.column(name.trim()).is_none() {
+            header_valid = false;
+            imported_table.errors.push(RowError {
+                line: header.line,
+                column: Some(name.clone()),
+                message: format!("{} has no column named {}", spec.name, name),
+            });
+        }
+    }
+    for column in spec.columns {
+        if !header.fields.iter().any(|name| name.trim() == column.name) {
+            header_valid = false;
+            imported_table.errors.push(RowError {
+                line: header.line,
+                column: Some(column.name.to_string()),
+                message: format!("Missing column {}", column.name),
+            });
+        }
+    }
+    if !header_valid {
+        return imported_table;
+    }
+
+    imported_table.total_rows = records.len();
+    for record in records {
+        if record.fields.len() != header.fields.len() {
+            imported_table.errors.push(RowError {
+                line: record.line,
+                column: None,
+                message: format!(
+                    "Expected {} fields but found {}",
+                    header.fields.len(),
+                    record.fields.len()
+                ),
+            });
+            continue;
+        }
+
+        let raw_values: HashMap<&str, Value> = header
+            .fields
+            .iter()
+            .map(|name| name.trim())
+            .zip(
+                record
+                    .fields
+                    .iter()
+                    .map(|field| Value::from(field.as_str())),
+            )
+            .collect();
+        read_row(spec, record.line, &raw_values, &mut imported_table);
+    }
+
+    imported_table
+}
+
+fn read_json_rows(spec: &TableSpec, content: &str) -> ImportedTable {
+    let mut imported_table = ImportedTable::default();
+
+    let entries = match serde_json::from_str::<Vec<Value>>(content) {
+        Ok(entries) => entries,
+        Err(err) => {
+            imported_table.errors.push(RowError {
+                line: err.line(),
+                column: None,
+                message: format!("File is not a list of rows: {}", err),
+            });
+            return imported_table;
+        }
+    };
+
+    imported_table.total_rows = entries.len();
+    let entry_lines = json_entry_lines(content);
+    for (index, entry) in entries.iter().enumerate() {
+        let line = entry_lines.get(index).copied().unwrap_or(index + 1);
+        let Some(entry) = entry.as_object() else {
+            imported_table.errors.push(RowError {
+                line,
+                column: None,
+                message: "Row is not an object".to_string(),
+            });
+            continue;
+        };
+
+        let unknown_columns: Vec<&String> = entry
+            .keys()
+            .filter(|name| spec.column(name).is_none())
+            .collect();
+        for name in &unknown_columns {
+            imported_table.errors.push(RowError {
+                line,
+                column: Some(name.to_string()),
+                message: format!("{} has no column named {}", spec.name, name),
+            });
+        }
+        if !unknown_columns.is_empty() {
+            continue;
+        }
+
+        // Columns left out of a row are treated like empty cells
+        let raw_values: HashMap<&str, Value> = entry
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect();
+        read_row(spec, line, &raw_values, &mut imported_table);
+    }
+
+    imported_table
+}
+
+// Line each entry of the file's top level array starts on, so errors point at
+// the file rather than at the entry's position in the array. Only called on
+// content that parsed, so the brackets and strings are balanced.
+fn json_entry_lines(content: &str) -> Vec<usize> {
+    let mut entry_lines = Vec::new();
+    let mut line = 1;
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut expecting_entry = false;
+
+    for character in content.chars() {
+        if character == '\n' {
+            line += 1;
+        }
+        if in_string {
+            match character {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        if character.is_whitespace() {
+            continue;
+        }
+        if expecting_entry && character != ']' {
+            entry_lines.push(line);
+            expecting_entry = false;
+        }
+        match character {
+            '"' => in_string = true,
+            '[' | '{' => {
+                depth += 1;
+                expecting_entry = depth == 1;
+            }
+            ']' | '}' => depth -= 1,
+            ',' if depth == 1 => expecting_entry = true,
+            _ => {}
+        }
+    }
+
+    entry_lines
+}
+
+fn read_row(
+    spec: &TableSpec,
+    line: usize,
+    raw_values: &HashMap<&str, Value>,
+    imported_table: &mut ImportedTable,
+) {
+    let mut values = TableRow::new();
+    let mut row_valid = true;
+    let blank = Value::Null;
+
+    for column in spec.columns {
+        let raw_value = raw_values.get(column.name).unwrap_or(&blank);
+        match parse_cell(column, raw_value) {
+            Ok(value) => {
+                values.insert(column.name.to_string(), value);
+            }
+            Err(message) => {
+                row_valid = false;
+                imported_table.errors.push(RowError {
+                    line,
+                    column: Some(column.name.to_string()),
+                    message,
+                });
+            }
+        }
+    }
+
+    if row_valid {
+        imported_table.rows.push(ImportedRow { line, values });
+    }
+}
+
+pub fn parse_cell(column: &TableColumn, raw_value: &Value) -> Result<Value, String> {
+    let is_blank = match raw_value {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        _ => false,
+    };
+    if is_blank {
+        return match column.blank {
+            BlankValue::Null => Ok(Value::Null),
+            BlankValue::EmptyText if column.kind == ColumnKind::Text => Ok(Value::from("")),
+            _ => Err(format!("{} is required", column.name)),
+        };
+    }
+
+    match (column.kind, raw_value) {
+        (ColumnKind::Integer, Value::Number(number)) => match number.as_i64() {
+            Some(number) => Ok(Value::from(number)),
+            None => Err(format!("{} must be a whole number", column.name)),
+        },
+        (ColumnKind::Integer, Value::String(text)) => match text.trim().parse::<i64>() {
+            Ok(number) => Ok(Value::from(number)),
+            Err(_) => Err(format!(
+                "{} must be a whole number, got {}",
+                column.name, text
+            )),
+        },
+        (ColumnKind::Text, Value::String(text)) => Ok(Value::from(text.as_str())),
+        (ColumnKind::Text, Value::Number(number)) => Ok(Value::from(number.to_string())),
+        _ => Err(format!(
+            "{} has an unsupported value {}",
+            column.name, raw_value
+        )),
+    }
+}
+
+// Compares the valid rows of an import file with the table's current rows.
+// Rows are matched on the table's key, and rows without an id are new.
+pub fn diff_table_rows(
+    spec: &TableSpec,
+    imported_table: ImportedTable,
+    existing_rows: &[TableRow],
+    referenced_ids: &HashMap<&str, HashSet<i64>>,
+) -> TableImportPreview {
+    let existing_by_key: HashMap<String, &TableRow> = existing_rows
+        .iter()
+        .filter_map(|row| row_key(spec, row).map(|key| (key, row)))
+        .collect();
+
+    let mut preview = TableImportPreview {
+        table: spec.name.to_string(),
+        total_rows: imported_table.total_rows,
+        errors: imported_table.errors,
+        changes: Vec::new(),
+        unchanged: 0,
+        missing: 0,
+    };
+    let mut seen_keys: HashMap<String, usize> = HashMap::new();
+
+    for row in imported_table.rows {
+        let mut row_valid = true;
+        for (column, ids) in referenced_ids {
+            let Some(id) = row.values.get(*column).and_then(Value::as_i64) else {
+                continue;
+            };
+            if !ids.contains(&id) {
+                row_valid = false;
+                preview.errors.push(RowError {
+                    line: row.line,
+                    column: Some(column.to_string()),
+                    message: format!("No row with id {} for {}", id, column),
+                });
+            }
+        }
+
+        let key = row_key(spec, &row.values);
+        if let Some(key) = &key {
+            if let Some(first_line) = seen_keys.get(key) {
+                row_valid = false;
+                preview.errors.push(RowError {
+                    line: row.line,
+                    column: None,
+                    message: format!("Duplicate of the row on line {}", first_line),
+                });
+            } else {
+                seen_keys.insert(key.clone(), row.line);
+            }
+        }
+        if !row_valid {
+            continue;
+        }
+
+        let existing_row = key.as_ref().and_then(|key| existing_by_key.get(key));
+        match existing_row {
+            Some(existing_row) => {
+                let fields: Vec<FieldChange> = spec
+                    .columns
+                    .iter()
+                    .filter_map(|column| {
+                        let old = existing_row
+                            .get(column.name)
+                            .cloned()
+                            .unwrap_or(Value::Null);
+                        let new = row.values.get(column.name).cloned().unwrap_or(Value::Null);
+                        if old == new {
+                            return None;
+                        }
+                        Some(FieldChange {
+                            column: column.name.to_string(),
+                            old,
+                            new,
+                        })
+                    })
+                    .collect();
+
+                if fields.is_empty() {
+                    preview.unchanged += 1;
+                } else {
+                    preview.changes.push(RowChange {
+                        line: row.line,
+                        kind: RowChangeKind::Modified,
+                        key: key_values(spec, &row.values),
+                        fields,
+                    });
+                }
+            }
+            None => {
+                let fields = spec
+                    .columns
+                    .iter()
+                    .map(|column| FieldChange {
+                        column: column.name.to_string(),
+                        old: Value::Null,
+                        new: row.values.get(column.name).cloned().unwrap_or(Value::Null),
+                    })
+                    .collect();
+                preview.changes.push(RowChange {
+                    line: row.line,
+                    kind: RowChangeKind::Added,
+                    key: if key.is_some() {
+                        key_values(spec, &row.values)
+                    } else {
+                        TableRow::new()
+                    },
+                    fields,
+                });
+            }
+        }
+    }
+
+    preview.missing = existing_by_key
+        .keys()
+        .filter(|key| !seen_keys.contains_key(*key))
+        .count();
+
+    preview
+}
+
+// Rows with a blank key column have no key and are always new
+fn row_key(spec: &TableSpec, row: &TableRow) -> Option<String> {
+    let mut key_parts = Vec::new();
+    for column in spec.key {
+        match row.get(*column) {
+            Some(Value::Null) | None => return None,
+            Some(value) => key_parts.push(value.to_string()),
+        }
+    }
+    Some(key_parts.join("|"))
+}
+
+fn key_values(spec: &TableSpec, row: &TableRow) -> TableRow {
+    spec.key
+        .iter()
+        .map(|column| {
+            (
+                column.to_string(),
+                row.get(*column).cloned().unwrap_or(Value::Null),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,355 @@
+// Keeps a journal of edits for the changelog page. Triggers record database
+// edits as they happen, route edits are diffed in from routes.json since the
+// frontend writes that file directly.
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::errors::{WikiGenError, WikiGenResult};
+
+// Columns whose edits players care about, render and the change flags are
+// left out since they only affect the wiki itself
+const JOURNALED_COLUMNS: [(&str, &[&str]); 5] = [
+    (
+        "pokemon",
+        &[
+            "name",
+            "dex_number",
+            "types",
+            "abilities",
+            "hp",
+            "attack",
+            "defense",
+            "sp_attack",
+            "sp_defense",
+            "speed",
+            "evolution_method",
+            "evolution_level",
+            "evolution_item",
+            "evolution_other",
+            "evolves_into",
+        ],
+    ),
+    (
+        "moves",
+        &["name", "power", "accuracy", "pp", "type", "damage_class"],
+    ),
+    ("abilities", &["name", "effect"]),
+    ("items", &["name", "effect"]),
+    ("natures", &["name", "increased_stat", "decreased_stat"]),
+];
+
+// A move in a Pokemon's moveset as the journal shows it, e.g.
+// "shadow-ball (level-up 30)"
+const MOVESET_VALUE: &str = "COALESCE((SELECT name FROM moves WHERE id = {row}.move), 'move ' || {row}.move) || ' (' || {row}.learn_method || COALESCE(' ' || {row}.level_learned, '') || ')'";
+
+// An edit to one field of a row. Rows that were added have no old value and
+// rows that were removed have no new value, with an empty field.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, FromRow)]
+pub struct JournalEntry {
+    pub id: i64,
+    // The table, or "routes"
+    pub subject: String,
+    pub name: String,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub recorded_at: String,
+    // Date of the wiki commit that published it, None until then
+    pub published_at: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn new(
+        subject: &str,
+        name: &str,
+        field: &str,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) -> Self {
+        JournalEntry {
+            id: 0,
+            subject: subject.to_string(),
+            name: name.to_string(),
+            field: field.to_string(),
+            old_value,
+            new_value,
+            recorded_at: String::new(),
+            published_at: None,
+        }
+    }
+}
+
+// Whether the entry is an edit to a database column, where a missing value
+// means NULL. For moves, routes and whole rows it means added or removed.
+pub fn is_column_field(subject: &str, field: &str) -> bool {
+    JOURNALED_COLUMNS
+        .iter()
+        .any(|(table, columns)| *table == subject && columns.contains(&field))
+}
+
+// Moveset columns the moveset triggers read
+const MOVESET_COLUMNS: &[&str] = &["pokemon", "move", "learn_method", "level_learned"];
+
+const CHANGE_JOURNAL_TABLE_SQL: &str = "CREATE TABLE IF NOT EXISTS change_journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    subject TEXT NOT NULL,
+    name TEXT NOT NULL,
+    field TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    recorded_at TEXT NOT NULL DEFAULT (datetime('now')),
+    published_at TEXT
+)";
+
+fn trigger_tables() -> impl Iterator<Item = &'static str> {
+    JOURNALED_COLUMNS
+        .iter()
+        .map(|(table, _)| *table)
+        .chain(["pokemon_movesets"])
+}
+
+// Triggers that record edits. Every statement is safe to run again.
+pub fn change_journal_triggers_sql() -> String {
+    let mut sql = String::new();
+    let insert = "INSERT INTO change_journal (subject, name, field, old_value, new_value)";
+
+    for (table, columns) in JOURNALED_COLUMNS {
+        sql.push_str(&format!(
+            "CREATE TRIGGER IF NOT EXISTS journal_{table}_insert AFTER INSERT ON {table} BEGIN \
+             {insert} VALUES ('{table}', NEW.name, '', NULL, ''); END;\n"
+        ));
+        sql.push_str(&format!(
+            "CREATE TRIGGER IF NOT EXISTS journal_{table}_delete AFTER DELETE ON {table} BEGIN \
+             {insert} VALUES ('{table}', OLD.name, '', '', NULL); END;\n"
+        ));
+        let column_inserts: String = columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "{insert} SELECT '{table}', NEW.name, '{column}', OLD.\"{column}\", \
+                     NEW.\"{column}\" WHERE OLD.\"{column}\" IS NOT NEW.\"{column}\"; "
+                )
+            })
+            .collect();
+        sql.push_str(&format!(
+            "CREATE TRIGGER IF NOT EXISTS journal_{table}_update AFTER UPDATE ON {table} BEGIN \
+             {column_inserts}END;\n"
+        ));
+    }
+
+    // Moveset rows are named after their Pokemon, skipped when the Pokemon
+    // itself is being deleted
+    let new_move = MOVESET_VALUE.replace("{row}", "NEW");
+    let old_move = MOVESET_VALUE.replace("{row}", "OLD");
+    sql.push_str(&format!(
+        "CREATE TRIGGER IF NOT EXISTS journal_pokemon_movesets_insert AFTER INSERT ON pokemon_movesets BEGIN \
+         {insert} SELECT 'pokemon', name, 'move', NULL, {new_move} FROM pokemon WHERE id = NEW.pokemon; END;\n"
+    ));
+    sql.push_str(&format!(
+        "CREATE TRIGGER IF NOT EXISTS journal_pokemon_movesets_delete AFTER DELETE ON pokemon_movesets BEGIN \
+         {insert} SELECT 'pokemon', name, 'move', {old_move}, NULL FROM pokemon WHERE id = OLD.pokemon; END;\n"
+    ));
+    sql.push_str(&format!(
+        "CREATE TRIGGER IF NOT EXISTS journal_pokemon_movesets_update AFTER UPDATE ON pokemon_movesets BEGIN \
+         {insert} SELECT 'pokemon', name, 'move', {old_move}, {new_move} FROM pokemon \
+         WHERE id = NEW.pokemon AND {old_move} IS NOT {new_move}; END;\n"
+    ));
+
+    sql
+}
+
+// SQLite only checks trigger bodies when they run, so triggers naming a
+// column an older schema lacks would break every edit and migration of
+// that table
+pub fn drop_change_journal_triggers_sql() -> String {
+    trigger_tables()
+        .flat_map(|table| {
+            ["insert", "delete", "update"]
+                .map(|event| format!("DROP TRIGGER IF EXISTS journal_{table}_{event};\n"))
+        })
+        .collect()
+}
+
+// Creates the journal table, and its triggers once the schema has every
+// column they read. Returns whether the triggers are in place.
+pub async fn create_change_journal(conn: &Pool<Sqlite>) -> WikiGenResult<bool> {
+    if let Err(err) = sqlx::query(CHANGE_JOURNAL_TABLE_SQL).execute(conn).await {
+        return Err(WikiGenError::database(
+            format!("Failed to create change journal: {}", err),
+            "change_journal",
+        ));
+    }
+
+    if !journaled_columns_exist(conn).await? {
+        drop_change_journal_triggers(conn).await?;
+        return Ok(false);
+    }
+    match sqlx::query(&change_journal_triggers_sql())
+        .execute(conn)
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to create change journal triggers: {}", err),
+            "change_journal",
+        )),
+    }
+}
+
+pub async fn drop_change_journal_triggers(conn: &Pool<Sqlite>) -> WikiGenResult<()> {
+    match sqlx::query(&drop_change_journal_triggers_sql())
+        .execute(conn)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to drop change journal triggers: {}", err),
+            "change_journal",
+        )),
+    }
+}
+
+async fn journaled_columns_exist(conn: &Pool<Sqlite>) -> WikiGenResult<bool> {
+    let journaled_columns = JOURNALED_COLUMNS
+        .iter()
+        .map(|(table, columns)| (*table, *columns))
+        .chain([("pokemon_movesets", MOVESET_COLUMNS)]);
+    for (table, columns) in journaled_columns {
+        let table_columns: Vec<String> =
+            match sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+                .bind(table)
+                .fetch_all(conn)
+                .await
+            {
+                Ok(table_columns) => table_columns,
+                Err(err) => {
+                    return Err(WikiGenError::database(
+                        format!("Failed to read columns of {}: {}", table, err),
+                        "change_journal",
+                    ))
+                }
+            };
+        if !columns.iter().all(|column| {
+            table_columns
+                .iter()
+                .any(|table_column| table_column.as_str() == *column)
+        }) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+pub async fn get_journal_entries(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<JournalEntry>> {
+    match sqlx::query_as::<_, JournalEntry>("SELECT * FROM change_journal ORDER BY id ASC")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(entries) => Ok(entries),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get change journal: {}", err),
+            "change_journal",
+        )),
+    }
+}
+
+pub async fn insert_journal_entries(
+    conn: &Pool<Sqlite>,
+    entries: &[JournalEntry],
+) -> WikiGenResult<()> {
+    let mut transaction = match conn.begin().await {
+        Ok(transaction) => transaction,
+        Err(err) => {
+            return Err(WikiGenError::database(
+                format!("Failed to start recording changes: {}", err),
+                "change_journal",
+            ))
+        }
+    };
+
+    for entry in entries {
+        let result = sqlx::query(
+            "INSERT INTO change_journal (subject, name, field, old_value, new_value) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&entry.subject)
+        .bind(&entry.name)
+        .bind(&entry.field)
+        .bind(&entry.old_value)
+        .bind(&entry.new_value)
+        .execute(&mut *transaction)
+        .await;
+        if let Err(err) = result {
+            return Err(WikiGenError::database(
+                format!("Failed to record change: {}", err),
+                "change_journal",
+            ));
+        }
+    }
+
+    match transaction.commit().await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to save recorded changes: {}", err),
+            "change_journal",
+        )),
+    }
+}
+
+// Stamps everything recorded since the last publish with the publish date
+// and returns the ids of the entries it stamped
+pub async fn publish_journal_entries(
+    conn: &Pool<Sqlite>,
+    published_at: &str,
+) -> WikiGenResult<Vec<i64>> {
+    match sqlx::query_scalar::<_, i64>(
+        "UPDATE change_journal SET published_at = ? WHERE published_at IS NULL RETURNING id",
+    )
+    .bind(published_at)
+    .fetch_all(conn)
+    .await
+    {
+        Ok(entry_ids) => Ok(entry_ids),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to publish changes: {}", err),
+            "change_journal",
+        )),
+    }
+}
+
+// Puts entries back in the unpublished changes, for a publish that didn't
+// make it into a commit
+pub async fn unpublish_journal_entries(
+    conn: &Pool<Sqlite>,
+    entry_ids: &[i64],
+) -> WikiGenResult<()> {
+    let mut transaction = match conn.begin().await {
+        Ok(transaction) => transaction,
+        Err(err) => {
+            return Err(WikiGenError::database(
+                format!("Failed to start unpublishing changes: {}", err),
+                "change_journal",
+            ))
+        }
+    };
+
+    for entry_id in entry_ids {
+        let result = sqlx::query("UPDATE change_journal SET published_at = NULL WHERE id = ?")
+            .bind(entry_id)
+            .execute(&mut *transaction)
+            .await;
+        if let Err(err) = result {
+            return Err(WikiGenError::database(
+                format!("Failed to unpublish change: {}", err),
+                "change_journal",
+            ));
+        }
+    }
+
+    match transaction.commit().await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to save unpublished changes: {}", err),
+            "change_journal",
+        )),
+    }
+}
@@ -1,87 +1,210 @@
+pub mod change_journal;
+pub mod repository;
+pub mod session;
+
 use std::{
     fs::{self, File},
     path::PathBuf,
 };
 
 use sqlx::{migrate::MigrateDatabase, Pool, Sqlite, SqlitePool};
-use tauri::{Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_store::StoreExt;
 
 use crate::{
+    errors::{WikiGenError, WikiGenResult},
+    helpers::matchups::invalidate_matchup_table,
     logger::{self, write_log, LogLevel},
     page_generators::game_routes::Routes,
-    structs::mkdocs_structs::MKDocsConfig,
+    structs::{matchup_models::WikiTypes, mkdocs_structs::MKDocsConfig},
 };
 
-pub async fn get_sqlite_connection(
-    sqlite_file_path: std::path::PathBuf,
-) -> Result<Pool<Sqlite>, String> {
-    let sqlite_connection_string = format!("sqlite:{}", sqlite_file_path.to_str().unwrap());
+pub fn get_app_data_dir(app_handle: &AppHandle) -> WikiGenResult<PathBuf> {
+    match app_handle.path().app_data_dir() {
+        Ok(app_data_dir) => Ok(app_data_dir),
+        Err(err) => Err(WikiGenError::io(
+            format!("Failed to resolve app data directory: {}", err),
+            "",
+        )),
+    }
+}
+
+pub fn get_resource_dir(app_handle: &AppHandle) -> WikiGenResult<PathBuf> {
+    match app_handle.path().resource_dir() {
+        Ok(resource_dir) => Ok(resource_dir),
+        Err(err) => Err(WikiGenError::io(
+            format!("Failed to resolve resource directory: {}", err),
+            "",
+        )),
+    }
+}
+
+pub fn read_template(resources_path: &PathBuf, template_name: &str) -> WikiGenResult<String> {
+    let template_path = resources_path
+        .join("resources")
+        .join("generator_assets")
+        .join("templates")
+        .join(template_name);
+
+    match fs::read_to_string(&template_path) {
+        Ok(template) => Ok(template),
+        Err(err) => Err(WikiGenError::missing_template(
+            format!("Failed to read template file: {}", err),
+            template_path.display(),
+        )),
+    }
+}
+
+pub async fn get_sqlite_connection(sqlite_file_path: PathBuf) -> WikiGenResult<Pool<Sqlite>> {
+    let sqlite_connection_string = format!("sqlite:{}", sqlite_file_path.display());
     if !Sqlite::database_exists(&sqlite_connection_string)
         .await
         .unwrap_or(false)
     {
-        return Err("Database does not exist".to_string());
+        return Err(WikiGenError::database(
+            "Database does not exist",
+            sqlite_file_path.display(),
+        ));
     }
     match SqlitePool::connect(&sqlite_connection_string).await {
         Ok(conn) => Ok(conn),
-        Err(err) => Err(format!("Failed to connect to database: {}", err)),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to connect to database: {}", err),
+            sqlite_file_path.display(),
+        )),
     }
 }
 
-pub fn get_mkdocs_config(file_path: &PathBuf) -> Result<MKDocsConfig, String> {
-    let mkdocs_yaml_file = match std::fs::File::open(file_path) {
-        Ok(file) => file,
+pub fn get_mkdocs_config(file_path: &PathBuf) -> WikiGenResult<MKDocsConfig> {
+    let mkdocs_yaml = match fs::read_to_string(file_path) {
+        Ok(mkdocs_yaml) => mkdocs_yaml,
         Err(err) => {
-            return Err(format!("Failed to open mkdocs yaml file: {}", err));
+            return Err(WikiGenError::io(
+                format!("Failed to open mkdocs yaml file: {}", err),
+                file_path.display(),
+            ));
         }
     };
-    let config: MKDocsConfig = match serde_yaml::from_reader(mkdocs_yaml_file) {
+    let config = match MKDocsConfig::from_yaml(&mkdocs_yaml) {
         Ok(mkdocs) => mkdocs,
         Err(err) => {
-            return Err(format!("Failed to parse mkdocs yaml file: {}", err));
+            return Err(WikiGenError::yaml(
+                format!("Failed to parse mkdocs yaml file: {}", err),
+                file_path.display(),
+            ));
         }
     };
 
     return Ok(config);
 }
 
-pub fn get_routes(file_path: &PathBuf) -> Result<Routes, String> {
+pub fn get_routes(file_path: &PathBuf) -> WikiGenResult<Routes> {
     let routes_file = match std::fs::File::open(file_path) {
         Ok(file) => file,
         Err(err) => {
-            return Err(format!("Failed to open routes file: {}", err));
+            return Err(WikiGenError::io(
+                format!("Failed to open routes file: {}", err),
+                file_path.display(),
+            ));
         }
     };
     let routes: Routes = match serde_yaml::from_reader(routes_file) {
         Ok(routes) => routes,
         Err(err) => {
-            return Err(format!("Failed to parse routes file: {}", err));
+            return Err(WikiGenError::yaml(
+                format!("Failed to parse routes file: {}", err),
+                file_path.display(),
+            ));
         }
     };
 
     return Ok(routes);
 }
 
+pub fn update_routes(wiki_name: &str, base_path: &PathBuf, routes: &Routes) -> WikiGenResult<()> {
+    let routes_file_path = base_path.join(wiki_name).join("data").join("routes.json");
+
+    let routes_json = match serde_json::to_string_pretty(routes) {
+        Ok(routes_json) => routes_json,
+        Err(err) => {
+            let message = format!("{wiki_name}: Failed to serialize routes: {err}");
+            write_log(base_path, LogLevel::Error, &message);
+            return Err(message.into());
+        }
+    };
+
+    if let Err(err) = fs::write(&routes_file_path, routes_json) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to update routes file: {err}"),
+            routes_file_path.display(),
+        );
+        write_log(base_path, LogLevel::Error, &error);
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+pub fn get_types(file_path: &PathBuf) -> Result<WikiTypes, String> {
+    let types_file = match std::fs::File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(format!("Failed to open types file: {}", err));
+        }
+    };
+    let types: WikiTypes = match serde_json::from_reader(types_file) {
+        Ok(types) => types,
+        Err(err) => {
+            return Err(format!("Failed to parse types file: {}", err));
+        }
+    };
+
+    return Ok(types);
+}
+
+pub fn update_types(wiki_name: &str, base_path: &PathBuf, types: &WikiTypes) -> WikiGenResult<()> {
+    let types_file_path = base_path.join(wiki_name).join("data").join("types.json");
+
+    let types_json = match serde_json::to_string(types) {
+        Ok(types_json) => types_json,
+        Err(err) => {
+            let message = format!("{wiki_name}: Failed to serialize types: {err}");
+            write_log(base_path, LogLevel::Error, &message);
+            return Err(message.into());
+        }
+    };
+
+    if let Err(err) = fs::write(&types_file_path, types_json) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to update types file: {err}"),
+            types_file_path.display(),
+        );
+        write_log(base_path, LogLevel::Error, &error);
+        return Err(error);
+    }
+    invalidate_matchup_table(wiki_name, base_path);
+    Ok(())
+}
+
 #[tauri::command]
-pub fn load_token(token: &str, app: tauri::AppHandle) -> Result<(), String> {
+pub fn load_token(token: &str, app: tauri::AppHandle) -> WikiGenResult<()> {
     let store = match app.store("store.json") {
         Ok(store) => store,
         Err(err) => {
-            return Err(format!("Failed to load store: {}", err));
+            return Err(WikiGenError::io(
+                format!("Failed to load store: {}", err),
+                "store.json",
+            ));
         }
     };
     store.set("token", token);
+    let token_logs_path = get_app_data_dir(&app)?.join("token_logs");
 
     match app.emit("token-loaded", ()) {
         Ok(_) => {}
         Err(err) => {
             let error = format!("Failed to emit token-loaded event: {}", err);
-            logger::write_log(
-                &app.path().app_data_dir().unwrap().join("token_logs"),
-                logger::LogLevel::Error,
-                &error,
-            );
+            logger::write_log(&token_logs_path, logger::LogLevel::Error, &error);
         }
     };
 
@@ -89,11 +212,7 @@ pub fn load_token(token: &str, app: tauri::AppHandle) -> Result<(), String> {
     if let Some(window) = window {
         if let Err(err) = window.close() {
             let error = format!("Failed to close window: {}", err);
-            logger::write_log(
-                &app.path().app_data_dir().unwrap().join("token_logs"),
-                logger::LogLevel::Error,
-                &error,
-            );
+            logger::write_log(&token_logs_path, logger::LogLevel::Error, &error);
         }
     }
 
@@ -104,16 +223,29 @@ pub fn update_mkdocs_yaml(
     wiki_name: &str,
     base_path: &PathBuf,
     mkdocs_config: &MKDocsConfig,
-) -> Result<(), String> {
+) -> WikiGenResult<()> {
     let mkdocs_yaml_file_path = base_path.join(wiki_name).join("dist").join("mkdocs.yml");
 
-    if let Err(err) = fs::write(
-        mkdocs_yaml_file_path,
-        serde_yaml::to_string(&mkdocs_config).unwrap(),
-    ) {
-        let message = format!("{wiki_name}: Failed to update mkdocs yaml file: {err}");
-        write_log(&base_path, LogLevel::Error, &message);
-        return Err(message);
+    let previous_yaml = fs::read_to_string(&mkdocs_yaml_file_path).ok();
+    let mkdocs_yaml = match mkdocs_config.to_yaml(previous_yaml.as_deref()) {
+        Ok(mkdocs_yaml) => mkdocs_yaml,
+        Err(err) => {
+            let error = WikiGenError::yaml(
+                format!("{wiki_name}: Failed to serialize mkdocs yaml: {err}"),
+                mkdocs_yaml_file_path.display(),
+            );
+            write_log(&base_path, LogLevel::Error, &error);
+            return Err(error);
+        }
+    };
+
+    if let Err(err) = fs::write(&mkdocs_yaml_file_path, mkdocs_yaml) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to update mkdocs yaml file: {err}"),
+            mkdocs_yaml_file_path.display(),
+        );
+        write_log(&base_path, LogLevel::Error, &error);
+        return Err(error);
     }
     Ok(())
 }
@@ -122,19 +254,22 @@ pub fn create_docs_file(
     wiki_name: &str,
     base_path: &PathBuf,
     file_name: &str,
-) -> Result<File, String> {
-    let file = match File::create(
-        base_path
-            .join(wiki_name)
-            .join("dist")
-            .join("docs")
-            .join(format!("{}", file_name)),
-    ) {
+) -> WikiGenResult<File> {
+    let file_path = base_path
+        .join(wiki_name)
+        .join("dist")
+        .join("docs")
+        .join(format!("{}", file_name));
+
+    let file = match File::create(&file_path) {
         Ok(file) => file,
         Err(err) => {
-            let message = format!("{wiki_name}: Failed to create {file_name} file: {err}");
-            write_log(&base_path, LogLevel::Error, &message);
-            return Err(message);
+            let error = WikiGenError::io(
+                format!("{wiki_name}: Failed to create {file_name} file: {err}"),
+                file_path.display(),
+            );
+            write_log(&base_path, LogLevel::Error, &error);
+            return Err(error);
         }
     };
 
@@ -145,27 +280,35 @@ pub fn remove_docs_file(
     wiki_name: &str,
     base_path: &PathBuf,
     file_name: &str,
-) -> Result<(), String> {
+) -> WikiGenResult<()> {
     let file_path = base_path
         .join(wiki_name)
         .join("dist")
         .join("docs")
         .join(format!("{}", file_name));
 
-    if let Err(err) = fs::remove_file(file_path) {
-        let message = format!("{wiki_name}: Failed to remove {file_name} file: {err}");
-        write_log(&base_path, LogLevel::Error, &message);
-        return Err(message);
+    if let Err(err) = fs::remove_file(&file_path) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to remove {file_name} file: {err}"),
+            file_path.display(),
+        );
+        write_log(&base_path, LogLevel::Error, &error);
+        return Err(error);
     }
     Ok(())
 }
 
-pub fn page_exists_in_mkdocs(mut mkdocs_config: MKDocsConfig, page_title: &str) -> (bool, usize) {
-    let nav_entries = mkdocs_config.nav.as_sequence_mut().unwrap();
+pub fn page_exists_in_mkdocs(
+    mut mkdocs_config: MKDocsConfig,
+    page_title: &str,
+) -> WikiGenResult<(bool, usize)> {
+    let nav_entries = mkdocs_config.nav_entries_mut()?;
     let mut item_page_exists = false;
     let mut page_index = 0;
     for (index, entry) in nav_entries.iter_mut().enumerate() {
-        let map_entries = entry.as_mapping_mut().unwrap();
+        let Some(map_entries) = entry.as_mapping_mut() else {
+            continue;
+        };
         if let Some(_) = map_entries.get_mut(serde_yaml::Value::String(page_title.to_string())) {
             item_page_exists = true;
             page_index = index;
@@ -173,5 +316,5 @@ pub fn page_exists_in_mkdocs(mut mkdocs_config: MKDocsConfig, page_title: &str)
         }
     }
 
-    (item_page_exists, page_index)
+    Ok((item_page_exists, page_index))
 }
@@ -0,0 +1,698 @@
+// Typed queries for the wiki tables. Values are always bound as parameters,
+// never formatted into the SQL, so names containing quotes are safe.
+use std::{collections::HashSet, path::PathBuf};
+
+use serde_json::Value;
+use sqlx::{Pool, QueryBuilder, Row, Sqlite, SqliteConnection};
+
+use crate::{
+    errors::{WikiGenError, WikiGenResult},
+    structs::{
+        page_models::{Ability, Item, ItemLocation, MachineMove, Move, MoveType, Nature},
+        pokemon_structs::{DBAbility, DBPokemon, PokemonMove, SpeciesUpdate},
+        table_models::{ColumnKind, RowChange, RowChangeKind, TableRow, TableSpec},
+    },
+};
+
+pub async fn get_pokemon_by_ids(
+    conn: &Pool<Sqlite>,
+    pokemon_ids: &[usize],
+) -> WikiGenResult<Vec<DBPokemon>> {
+    if pokemon_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut query_builder = QueryBuilder::<Sqlite>::new("SELECT * FROM pokemon WHERE id IN (");
+    let mut separated = query_builder.separated(", ");
+    for pokemon_id in pokemon_ids {
+        separated.push_bind(*pokemon_id as i64);
+    }
+    separated.push_unseparated(") ORDER BY dex_number ASC");
+
+    match query_builder
+        .build_query_as::<DBPokemon>()
+        .fetch_all(conn)
+        .await
+    {
+        Ok(pokemon_list) => Ok(pokemon_list),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to fetch pokemon from database: {}", err),
+            "pokemon",
+        )),
+    }
+}
+
+pub async fn get_all_pokemon(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<DBPokemon>> {
+    match sqlx::query_as::<_, DBPokemon>("SELECT * FROM pokemon ORDER BY dex_number ASC")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(pokemon_list) => Ok(pokemon_list),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to fetch pokemon from database: {}", err),
+            "pokemon",
+        )),
+    }
+}
+
+// Pairs of pokemon id and their comma separated types
+pub async fn get_pokemon_types(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<(u32, String)>> {
+    match sqlx::query_as::<_, (u32, String)>("SELECT id, types FROM pokemon")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(pokemon_types) => Ok(pokemon_types),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get pokemon types: {}", err),
+            "pokemon",
+        )),
+    }
+}
+
+// Pokemon types are stored as a comma separated list, so the type is
+// wrapped in commas to avoid renaming types that share a prefix
+pub async fn rename_pokemon_type(
+    conn: &mut SqliteConnection,
+    old_type: &str,
+    new_type: &str,
+) -> WikiGenResult<()> {
+    match sqlx::query(
+        "UPDATE pokemon
+        SET types = TRIM(REPLACE(',' || types || ',', ',' || $1 || ',', ',' || $2 || ','), ',')
+        WHERE ',' || types || ',' LIKE '%,' || $1 || ',%'",
+    )
+    .bind(old_type)
+    .bind(new_type)
+    .execute(conn)
+    .await
+    {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to rename pokemon types: {}", err),
+            "pokemon",
+        )),
+    }
+}
+
+// Number of pokemon and moves that still use the type
+pub async fn count_type_usage(conn: &Pool<Sqlite>, type_name: &str) -> WikiGenResult<i64> {
+    match sqlx::query_scalar::<_, i64>(
+        "SELECT
+            (SELECT COUNT(*) FROM pokemon WHERE ',' || types || ',' LIKE '%,' || $1 || ',%')
+            + (SELECT COUNT(*) FROM moves WHERE type = $1)",
+    )
+    .bind(type_name)
+    .fetch_one(conn)
+    .await
+    {
+        Ok(usage_count) => Ok(usage_count),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to check type usage: {}", err),
+            "pokemon, moves",
+        )),
+    }
+}
+
+// Every type a pokemon or move uses, "none" excluded
+pub async fn get_used_types(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<String>> {
+    let type_lists = match sqlx::query_scalar::<_, String>(
+        "SELECT types FROM pokemon UNION SELECT type FROM moves",
+    )
+    .fetch_all(conn)
+    .await
+    {
+        Ok(type_lists) => type_lists,
+        Err(err) => {
+            return Err(WikiGenError::database(
+                format!("Failed to get used types: {}", err),
+                "pokemon, moves",
+            ))
+        }
+    };
+
+    let mut used_types = type_lists
+        .iter()
+        .flat_map(|types| types.split(","))
+        .map(|_type| _type.trim().to_string())
+        .filter(|_type| !_type.is_empty() && _type.as_str() != "none")
+        .collect::<Vec<String>>();
+    used_types.sort();
+    used_types.dedup();
+    Ok(used_types)
+}
+
+pub async fn get_moves(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<Move>> {
+    match sqlx::query_as::<_, Move>("SELECT * FROM moves")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(moves) => Ok(moves),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get moves: {}", err),
+            "moves",
+        )),
+    }
+}
+
+pub async fn get_move_types(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<MoveType>> {
+    match sqlx::query_as::<_, MoveType>("SELECT name, type, damage_class FROM moves")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(move_types) => Ok(move_types),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get move types: {}", err),
+            "moves",
+        )),
+    }
+}
+
+pub async fn get_machine_moves(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<MachineMove>> {
+    match sqlx::query_as::<_, MachineMove>(
+        "SELECT
+            m.name, m.machine_name, m.power, m.accuracy, m.pp, m.type, m.damage_class,
+            (
+                SELECT COUNT(DISTINCT pm.pokemon) FROM pokemon_movesets pm
+                WHERE pm.move = m.id AND pm.learn_method = 'machine'
+            ) as learnable_by
+        FROM moves m
+        WHERE m.machine_name IS NOT NULL AND m.machine_name != ''",
+    )
+    .fetch_all(conn)
+    .await
+    {
+        Ok(machine_moves) => Ok(machine_moves),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get machine moves: {}", err),
+            "moves",
+        )),
+    }
+}
+
+pub async fn rename_move_type(
+    conn: &mut SqliteConnection,
+    old_type: &str,
+    new_type: &str,
+) -> WikiGenResult<()> {
+    match sqlx::query("UPDATE moves SET type = $2 WHERE type = $1")
+        .bind(old_type)
+        .bind(new_type)
+        .execute(conn)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to rename move types: {}", err),
+            "moves",
+        )),
+    }
+}
+
+pub async fn get_movesets_by_pokemon_ids(
+    conn: &Pool<Sqlite>,
+    pokemon_ids: &[usize],
+) -> WikiGenResult<Vec<PokemonMove>> {
+    if pokemon_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut query_builder = QueryBuilder::<Sqlite>::new(
+        "SELECT
+            pokemon_movesets.*,
+            pokemon_movesets.move as move_id,
+            m.name as move_name, m.type as move_type,
+            m.power as power, m.accuracy as accuracy, m.pp as pp,
+            m.damage_class as damage_class, m.machine_name as machine_name
+        FROM pokemon_movesets
+        LEFT JOIN moves m on m.id = pokemon_movesets.move
+        WHERE pokemon_movesets.pokemon IN (",
+    );
+    let mut separated = query_builder.separated(", ");
+    for pokemon_id in pokemon_ids {
+        separated.push_bind(*pokemon_id as i64);
+    }
+    separated.push_unseparated(")");
+
+    match query_builder
+        .build_query_as::<PokemonMove>()
+        .fetch_all(conn)
+        .await
+    {
+        Ok(pokemon_movesets) => Ok(pokemon_movesets),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to fetch pokemon movesets from database: {}", err),
+            "pokemon_movesets",
+        )),
+    }
+}
+
+pub async fn get_abilities(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<Ability>> {
+    match sqlx::query_as::<_, Ability>("SELECT * FROM abilities")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(abilities) => Ok(abilities),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get abilities: {}", err),
+            "abilities",
+        )),
+    }
+}
+
+// Abilities with their ids, as needed to build pokemon pages
+pub async fn get_db_abilities(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<DBAbility>> {
+    match sqlx::query_as::<_, DBAbility>("SELECT * FROM abilities")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(abilities_list) => Ok(abilities_list),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to fetch abilities from database: {}", err),
+            "abilities",
+        )),
+    }
+}
+
+pub async fn get_items(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<Item>> {
+    match sqlx::query_as::<_, Item>("SELECT * FROM items")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(items) => Ok(items),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get items: {}", err),
+            "items",
+        )),
+    }
+}
+
+pub async fn get_natures(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<Nature>> {
+    match sqlx::query_as::<_, Nature>("SELECT * FROM natures")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(natures) => Ok(natures),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get natures: {}", err),
+            "natures",
+        )),
+    }
+}
+
+pub async fn get_item_locations(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<ItemLocation>> {
+    match sqlx::query_as::<_, ItemLocation>("SELECT * FROM item_location ORDER BY item_name")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(item_locations) => Ok(item_locations),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to fetch item locations: {}", err),
+            "item_location",
+        )),
+    }
+}
+
+// Names of the migrations already applied to the wiki's database
+pub async fn get_applied_migrations(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<String>> {
+    match sqlx::query_scalar::<_, String>("SELECT name FROM migrations ORDER BY execution_order")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(migrations) => Ok(migrations),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get applied migrations: {}", err),
+            "migrations",
+        )),
+    }
+}
+
+// Writes a consistent copy of the database, including anything
+// still held by the open pool, to the given file
+pub async fn snapshot_database(conn: &Pool<Sqlite>, snapshot_path: &PathBuf) -> WikiGenResult<()> {
+    match sqlx::query("VACUUM INTO $1")
+        .bind(snapshot_path.display().to_string())
+        .execute(conn)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to snapshot database: {}", err),
+            snapshot_path.display(),
+        )),
+    }
+}
+
+// Every row of one of the tabular tables, with values typed by the table's columns
+pub async fn get_table_rows(conn: &Pool<Sqlite>, spec: &TableSpec) -> WikiGenResult<Vec<TableRow>> {
+    let columns: Vec<String> = spec
+        .columns
+        .iter()
+        .map(|column| format!("\"{}\"", column.name))
+        .collect();
+    let mut query_builder = QueryBuilder::<Sqlite>::new(format!(
+        "SELECT {} FROM {} ORDER BY {}",
+        columns.join(", "),
+        spec.name,
+        spec.key.join(", ")
+    ));
+
+    let rows = match query_builder.build().fetch_all(conn).await {
+        Ok(rows) => rows,
+        Err(err) => {
+            return Err(WikiGenError::database(
+                format!("Failed to get rows: {}", err),
+                spec.name,
+            ))
+        }
+    };
+
+    let mut table_rows = Vec::new();
+    for row in rows {
+        let mut table_row = TableRow::new();
+        for column in spec.columns {
+            let value = match column.kind {
+                ColumnKind::Integer => row
+                    .try_get::<Option<i64>, _>(column.name)
+                    .map(|value| value.map_or(Value::Null, Value::from)),
+                ColumnKind::Text => row
+                    .try_get::<Option<String>, _>(column.name)
+                    .map(|value| value.map_or(Value::Null, Value::from)),
+            };
+            match value {
+                Ok(value) => table_row.insert(column.name.to_string(), value),
+                Err(err) => {
+                    return Err(WikiGenError::database(
+                        format!("Failed to read column {}: {}", column.name, err),
+                        spec.name,
+                    ))
+                }
+            };
+        }
+        table_rows.push(table_row);
+    }
+
+    Ok(table_rows)
+}
+
+pub async fn get_table_ids(conn: &Pool<Sqlite>, table_name: &str) -> WikiGenResult<HashSet<i64>> {
+    let query = format!("SELECT id FROM {}", table_name);
+    match sqlx::query_scalar::<_, i64>(&query).fetch_all(conn).await {
+        Ok(ids) => Ok(ids.into_iter().collect()),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get ids: {}", err),
+            table_name,
+        )),
+    }
+}
+
+// Applies an import preview in a single transaction, so a failing row
+// leaves the table exactly as it was
+pub async fn apply_table_changes(
+    conn: &Pool<Sqlite>,
+    spec: &TableSpec,
+    changes: &[RowChange],
+) -> WikiGenResult<()> {
+    let mut transaction = match conn.begin().await {
+        Ok(transaction) => transaction,
+        Err(err) => {
+            return Err(WikiGenError::database(
+                format!("Failed to start import: {}", err),
+                spec.name,
+            ))
+        }
+    };
+
+    for change in changes {
+        let mut query_builder = match change.kind {
+            RowChangeKind::Added => insert_row_query(spec, change),
+            RowChangeKind::Modified => update_row_query(spec, change),
+        };
+        if let Err(err) = query_builder.build().execute(&mut *transaction).await {
+            return Err(WikiGenError::database(
+                format!("Failed to import row on line {}: {}", change.line, err),
+                spec.name,
+            ));
+        }
+    }
+
+    match transaction.commit().await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to save import: {}", err),
+            spec.name,
+        )),
+    }
+}
+
+fn insert_row_query(spec: &TableSpec, change: &RowChange) -> QueryBuilder<'static, Sqlite> {
+    // New rows without an id get one from the database
+    let fields: Vec<_> = change
+        .fields
+        .iter()
+        .filter(|field| !(spec.generated_key && field.column == "id" && field.new.is_null()))
+        .collect();
+
+    let mut query_builder = QueryBuilder::<Sqlite>::new(format!("INSERT INTO {} (", spec.name));
+    let mut separated = query_builder.separated(", ");
+    for field in &fields {
+        separated.push(format!("\"{}\"", field.column));
+    }
+    query_builder.push(") VALUES (");
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            query_builder.push(", ");
+        }
+        push_value_bind(&mut query_builder, &field.new);
+    }
+    query_builder.push(")");
+
+    query_builder
+}
+
+fn update_row_query(spec: &TableSpec, change: &RowChange) -> QueryBuilder<'static, Sqlite> {
+    let mut query_builder = QueryBuilder::<Sqlite>::new(format!("UPDATE {} SET ", spec.name));
+    for (index, field) in change.fields.iter().enumerate() {
+        if index > 0 {
+            query_builder.push(", ");
+        }
+        query_builder.push(format!("\"{}\" = ", field.column));
+        push_value_bind(&mut query_builder, &field.new);
+    }
+    query_builder.push(" WHERE ");
+    for (index, (column, value)) in change.key.iter().enumerate() {
+        if index > 0 {
+            query_builder.push(" AND ");
+        }
+        query_builder.push(format!("\"{}\" = ", column));
+        push_value_bind(&mut query_builder, value);
+    }
+
+    query_builder
+}
+
+fn push_value_bind(query_builder: &mut QueryBuilder<'_, Sqlite>, value: &Value) {
+    match value {
+        Value::Number(number) => query_builder.push_bind(number.as_i64()),
+        Value::String(text) => query_builder.push_bind(text.clone()),
+        _ => query_builder.push_bind(None::<String>),
+    };
+}
+
+// Every pokemon as id, dex number, name and types
+pub async fn get_pokemon_names(
+    conn: &Pool<Sqlite>,
+) -> WikiGenResult<Vec<(u32, u32, String, String)>> {
+    match sqlx::query_as::<_, (u32, u32, String, String)>(
+        "SELECT id, dex_number, name, types FROM pokemon ORDER BY dex_number",
+    )
+    .fetch_all(conn)
+    .await
+    {
+        Ok(pokemon_names) => Ok(pokemon_names),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get pokemon names: {}", err),
+            "pokemon",
+        )),
+    }
+}
+
+pub async fn get_move_names(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<(u32, String)>> {
+    match sqlx::query_as::<_, (u32, String)>("SELECT id, name FROM moves")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(move_names) => Ok(move_names),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to get move names: {}", err),
+            "moves",
+        )),
+    }
+}
+
+// Applies every update in one transaction, so an import either lands
+// completely or leaves the wiki untouched. Machines are pointed at their
+// moves in the same transaction, and moves that held one of these machines
+// before lose it, so a machine is never listed twice.
+pub async fn apply_species_updates(
+    conn: &Pool<Sqlite>,
+    updates: &[SpeciesUpdate],
+    machines: &[(String, u32)],
+) -> WikiGenResult<()> {
+    let mut transaction = match conn.begin().await {
+        Ok(transaction) => transaction,
+        Err(err) => {
+            return Err(WikiGenError::database(
+                format!("Failed to start species update: {}", err),
+                "pokemon",
+            ))
+        }
+    };
+
+    for update in updates {
+        if let Some(mut query_builder) = species_update_query(update) {
+            if let Err(err) = query_builder.build().execute(&mut *transaction).await {
+                return Err(WikiGenError::database(
+                    format!("Failed to update pokemon {}: {}", update.pokemon_id, err),
+                    "pokemon",
+                ));
+            }
+        }
+
+        let movesets = [
+            (
+                "level-up",
+                update.level_up_moves.as_ref().map(|moves| {
+                    moves
+                        .iter()
+                        .map(|(move_id, level)| (*move_id, Some(*level)))
+                        .collect::<Vec<_>>()
+                }),
+            ),
+            (
+                "machine",
+                update
+                    .machine_moves
+                    .as_ref()
+                    .map(|moves| moves.iter().map(|move_id| (*move_id, None)).collect()),
+            ),
+        ];
+        for (learn_method, moves) in movesets {
+            let Some(moves) = moves else {
+                continue;
+            };
+
+            if let Err(err) =
+                sqlx::query("DELETE FROM pokemon_movesets WHERE pokemon = $1 AND learn_method = $2")
+                    .bind(update.pokemon_id)
+                    .bind(learn_method)
+                    .execute(&mut *transaction)
+                    .await
+            {
+                return Err(WikiGenError::database(
+                    format!(
+                        "Failed to clear moveset of pokemon {}: {}",
+                        update.pokemon_id, err
+                    ),
+                    "pokemon_movesets",
+                ));
+            }
+
+            // A move learned at several levels keeps the first one
+            for (move_id, level) in moves {
+                if let Err(err) = sqlx::query(
+                    "INSERT OR IGNORE INTO pokemon_movesets (pokemon, move, learn_method, level_learned)
+                    VALUES ($1, $2, $3, $4)",
+                )
+                .bind(update.pokemon_id)
+                .bind(move_id)
+                .bind(learn_method)
+                .bind(level)
+                .execute(&mut *transaction)
+                .await
+                {
+                    return Err(WikiGenError::database(
+                        format!(
+                            "Failed to update moveset of pokemon {}: {}",
+                            update.pokemon_id, err
+                        ),
+                        "pokemon_movesets",
+                    ));
+                }
+            }
+        }
+    }
+
+    for (machine_name, move_id) in machines {
+        if let Err(err) =
+            sqlx::query("UPDATE moves SET machine_name = NULL WHERE machine_name = $1")
+                .bind(machine_name.as_str())
+                .execute(&mut *transaction)
+                .await
+        {
+            return Err(WikiGenError::database(
+                format!("Failed to clear {}: {}", machine_name, err),
+                "moves",
+            ));
+        }
+        if let Err(err) = sqlx::query("UPDATE moves SET machine_name = $1 WHERE id = $2")
+            .bind(machine_name.as_str())
+            .bind(*move_id)
+            .execute(&mut *transaction)
+            .await
+        {
+            return Err(WikiGenError::database(
+                format!("Failed to assign {}: {}", machine_name, err),
+                "moves",
+            ));
+        }
+    }
+
+    match transaction.commit().await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::database(
+            format!("Failed to save species update: {}", err),
+            "pokemon",
+        )),
+    }
+}
+
+fn species_update_query(update: &SpeciesUpdate) -> Option<QueryBuilder<'static, Sqlite>> {
+    let mut columns: Vec<(&str, Value)> = Vec::new();
+    if let Some(types) = &update.types {
+        columns.push(("types", Value::from(types.as_str())));
+    }
+    if let Some(abilities) = &update.abilities {
+        columns.push(("abilities", Value::from(abilities.as_str())));
+    }
+    if let Some(stats) = &update.stats {
+        columns.push(("hp", Value::from(stats.hp)));
+        columns.push(("attack", Value::from(stats.attack)));
+        columns.push(("defense", Value::from(stats.defense)));
+        columns.push(("sp_attack", Value::from(stats.sp_attack)));
+        columns.push(("sp_defense", Value::from(stats.sp_defense)));
+        columns.push(("speed", Value::from(stats.speed)));
+    }
+    if let Some(evolution) = &update.evolution {
+        columns.push(("evolution_method", Value::from(evolution.method.as_str())));
+        columns.push(("evolution_level", Value::from(evolution.level)));
+        columns.push(("evolution_item", Value::from(evolution.item.clone())));
+        columns.push(("evolution_other", Value::from(evolution.other.clone())));
+        columns.push(("evolves_into", Value::from(evolution.evolves_into.clone())));
+    }
+    if columns.is_empty() {
+        return None;
+    }
+
+    let mut query_builder = QueryBuilder::<Sqlite>::new("UPDATE pokemon SET ");
+    for (index, (column, value)) in columns.iter().enumerate() {
+        if index > 0 {
+            query_builder.push(", ");
+        }
+        query_builder.push(format!("{} = ", column));
+        push_value_bind(&mut query_builder, value);
+    }
+    query_builder.push(" WHERE id = ");
+    query_builder.push_bind(update.pokemon_id);
+
+    Some(query_builder)
+}
@@ -0,0 +1,165 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    database::{
+        change_journal::create_change_journal, get_app_data_dir, get_mkdocs_config,
+        get_sqlite_connection, update_mkdocs_yaml,
+    },
+    errors::{WikiGenError, WikiGenResult},
+    logger::{self, LogLevel},
+    structs::mkdocs_structs::MKDocsConfig,
+};
+
+// Everything a command needs to work on the selected wiki, opened once and shared
+pub struct WikiSession {
+    pub wiki_name: String,
+    pub base_path: PathBuf,
+    pub wiki_path: PathBuf,
+    pub docs_path: PathBuf,
+    pub mkdocs_yaml_path: PathBuf,
+    pub pool: Pool<Sqlite>,
+    // The parsed config is kept alongside the modified time it was read at,
+    // so edits made outside the app (by hand or a backup restore) are picked up
+    mkdocs_config: Mutex<(Option<SystemTime>, MKDocsConfig)>,
+}
+
+impl WikiSession {
+    pub async fn open(wiki_name: &str, base_path: &PathBuf) -> WikiGenResult<Self> {
+        let wiki_path = base_path.join(wiki_name);
+        let sqlite_path = wiki_path.join(format!("{}.db", wiki_name));
+        let mkdocs_yaml_path = wiki_path.join("dist").join("mkdocs.yml");
+
+        let pool = get_sqlite_connection(sqlite_path).await?;
+        // Older databases may be missing a journaled table or column, which
+        // only costs the changelog so the wiki still opens
+        match create_change_journal(&pool).await {
+            Ok(true) => {}
+            Ok(false) => logger::write_log(
+                &wiki_path,
+                LogLevel::Debug,
+                "Change journal is off until the wiki's migrations have run",
+            ),
+            Err(err) => logger::write_log(&wiki_path, LogLevel::Error, &err),
+        }
+        let mkdocs_config = get_mkdocs_config(&mkdocs_yaml_path)?;
+
+        Ok(WikiSession {
+            wiki_name: wiki_name.to_string(),
+            base_path: base_path.clone(),
+            docs_path: wiki_path.join("dist").join("docs"),
+            wiki_path,
+            mkdocs_config: Mutex::new((modified_time(&mkdocs_yaml_path), mkdocs_config)),
+            mkdocs_yaml_path,
+            pool,
+        })
+    }
+
+    pub fn mkdocs_config(&self) -> WikiGenResult<MKDocsConfig> {
+        let mut cached_config = self.mkdocs_config.lock().map_err(|err| {
+            WikiGenError::from(format!("Failed to access mkdocs config: {}", err))
+        })?;
+
+        let modified = modified_time(&self.mkdocs_yaml_path);
+        if modified != cached_config.0 {
+            *cached_config = (modified, get_mkdocs_config(&self.mkdocs_yaml_path)?);
+        }
+
+        return Ok(cached_config.1.clone());
+    }
+
+    pub fn update_mkdocs_config(&self, mkdocs_config: &MKDocsConfig) -> WikiGenResult<()> {
+        update_mkdocs_yaml(&self.wiki_name, &self.base_path, mkdocs_config)?;
+
+        let mut cached_config = self.mkdocs_config.lock().map_err(|err| {
+            WikiGenError::from(format!("Failed to access mkdocs config: {}", err))
+        })?;
+        *cached_config = (modified_time(&self.mkdocs_yaml_path), mkdocs_config.clone());
+
+        Ok(())
+    }
+}
+
+fn modified_time(file_path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(file_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[derive(Default)]
+pub struct WikiSessionState(tokio::sync::Mutex<Option<Arc<WikiSession>>>);
+
+// Returns the open session for the wiki, opening it first if a different
+// wiki (or none) is currently selected
+pub async fn get_wiki_session(
+    wiki_name: &str,
+    app_handle: &AppHandle,
+) -> WikiGenResult<Arc<WikiSession>> {
+    let base_path = get_app_data_dir(app_handle)?;
+    let session_state = app_handle.state::<WikiSessionState>();
+    let mut current_session = session_state.0.lock().await;
+
+    if let Some(session) = current_session.as_ref() {
+        if session.wiki_name == wiki_name {
+            return Ok(session.clone());
+        }
+    }
+
+    let session = match WikiSession::open(wiki_name, &base_path).await {
+        Ok(session) => Arc::new(session),
+        Err(err) => {
+            let message = format!("{wiki_name}: Failed to open wiki session: {err}");
+            logger::write_log(&base_path.join(wiki_name), LogLevel::Error, &message);
+            return Err(err);
+        }
+    };
+    *current_session = Some(session.clone());
+
+    return Ok(session);
+}
+
+// The session of the selected wiki, without opening one
+pub async fn current_wiki_session(app_handle: &AppHandle) -> Option<Arc<WikiSession>> {
+    let session_state = app_handle.state::<WikiSessionState>();
+    let current_session = session_state.0.lock().await;
+    current_session.clone()
+}
+
+#[tauri::command]
+pub async fn open_wiki_session(wiki_name: &str, app_handle: AppHandle) -> WikiGenResult<()> {
+    get_wiki_session(wiki_name, &app_handle).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn close_wiki_session(app_handle: AppHandle) -> WikiGenResult<()> {
+    let session_state = app_handle.state::<WikiSessionState>();
+    let mut current_session = session_state.0.lock().await;
+
+    if let Some(session) = current_session.take() {
+        session.pool.close().await;
+    }
+    Ok(())
+}
+
+// Closes the session if it belongs to the wiki, so its files can be replaced
+pub async fn release_wiki_session(wiki_name: &str, app_handle: &AppHandle) {
+    let session_state = app_handle.state::<WikiSessionState>();
+    let mut current_session = session_state.0.lock().await;
+
+    if current_session
+        .as_ref()
+        .is_some_and(|session| session.wiki_name == wiki_name)
+    {
+        if let Some(session) = current_session.take() {
+            session.pool.close().await;
+        }
+    }
+}
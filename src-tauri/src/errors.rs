@@ -0,0 +1,149 @@
+use std::fmt::Display;
+
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+// Returned by every command. The frontend receives it as
+// { code, message, context } so it can react to specific failures.
+#[derive(Debug)]
+pub enum WikiGenError {
+    Io { message: String, context: String },
+    Database { message: String, context: String },
+    Yaml { message: String, context: String },
+    MissingTemplate { message: String, context: String },
+    InvalidNav { message: String, context: String },
+    // Validation failures and errors from helpers that still report plain strings
+    Other { message: String, context: String },
+}
+
+pub type WikiGenResult<T> = Result<T, WikiGenError>;
+
+impl WikiGenError {
+    pub fn io(message: impl Display, context: impl Display) -> Self {
+        WikiGenError::Io {
+            message: message.to_string(),
+            context: context.to_string(),
+        }
+    }
+
+    pub fn database(message: impl Display, context: impl Display) -> Self {
+        WikiGenError::Database {
+            message: message.to_string(),
+            context: context.to_string(),
+        }
+    }
+
+    pub fn yaml(message: impl Display, context: impl Display) -> Self {
+        WikiGenError::Yaml {
+            message: message.to_string(),
+            context: context.to_string(),
+        }
+    }
+
+    pub fn missing_template(message: impl Display, context: impl Display) -> Self {
+        WikiGenError::MissingTemplate {
+            message: message.to_string(),
+            context: context.to_string(),
+        }
+    }
+
+    pub fn invalid_nav(message: impl Display, context: impl Display) -> Self {
+        WikiGenError::InvalidNav {
+            message: message.to_string(),
+            context: context.to_string(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            WikiGenError::Io { .. } => "io_error",
+            WikiGenError::Database { .. } => "database_error",
+            WikiGenError::Yaml { .. } => "yaml_error",
+            WikiGenError::MissingTemplate { .. } => "missing_template",
+            WikiGenError::InvalidNav { .. } => "invalid_nav",
+            WikiGenError::Other { .. } => "error",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            WikiGenError::Io { message, .. }
+            | WikiGenError::Database { message, .. }
+            | WikiGenError::Yaml { message, .. }
+            | WikiGenError::MissingTemplate { message, .. }
+            | WikiGenError::InvalidNav { message, .. }
+            | WikiGenError::Other { message, .. } => message,
+        }
+    }
+
+    pub fn context(&self) -> &str {
+        match self {
+            WikiGenError::Io { context, .. }
+            | WikiGenError::Database { context, .. }
+            | WikiGenError::Yaml { context, .. }
+            | WikiGenError::MissingTemplate { context, .. }
+            | WikiGenError::InvalidNav { context, .. }
+            | WikiGenError::Other { context, .. } => context,
+        }
+    }
+}
+
+impl Display for WikiGenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.context().is_empty() {
+            true => write!(f, "{}", self.message()),
+            false => write!(f, "{} ({})", self.message(), self.context()),
+        }
+    }
+}
+
+impl std::error::Error for WikiGenError {}
+
+impl Serialize for WikiGenError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("WikiGenError", 3)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", self.message())?;
+        error.serialize_field("context", self.context())?;
+        error.end()
+    }
+}
+
+impl From<String> for WikiGenError {
+    fn from(message: String) -> Self {
+        WikiGenError::Other {
+            message,
+            context: String::new(),
+        }
+    }
+}
+
+impl From<&str> for WikiGenError {
+    fn from(message: &str) -> Self {
+        WikiGenError::from(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for WikiGenError {
+    fn from(err: std::io::Error) -> Self {
+        WikiGenError::io(err, "")
+    }
+}
+
+impl From<sqlx::Error> for WikiGenError {
+    fn from(err: sqlx::Error) -> Self {
+        WikiGenError::database(err, "")
+    }
+}
+
+impl From<serde_yaml::Error> for WikiGenError {
+    fn from(err: serde_yaml::Error) -> Self {
+        WikiGenError::yaml(err, "")
+    }
+}
+
+// Lets helpers that still return plain strings use `?` on these errors
+impl From<WikiGenError> for String {
+    fn from(err: WikiGenError) -> Self {
+        err.to_string()
+    }
+}
@@ -1,173 +1,432 @@
-use std::{collections::HashMap, fs::File, path::PathBuf, str::FromStr};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::PathBuf,
+    sync::{Arc, LazyLock, Mutex},
+    time::SystemTime,
+};
 
-use crate::structs::{
-    matchup_models::GEN_DEFAULT,
-    pokemon_structs::{PokemonTypesEnum, POKEMON_TYPES_ARRAY},
+use crate::{
+    database::{get_types, update_types},
+    errors::{WikiGenError, WikiGenResult},
+    helpers::capitalize,
+    logger::{write_log, LogLevel},
+    structs::matchup_models::{
+        CalculatedDefenses, MatchupMap, TypeChart, TypeChartPreset, TypeEffectiveness, WikiTypes,
+        DEFAULT_TYPES, GEN_DEFAULT,
+    },
 };
 
-// May need this code in the function in the future to generate other matchup maps
-// For now it is unused because what it generates is already stored in the
-// matchup_map.json file in the wiki's data folder
-#[allow(dead_code)]
-pub fn generate_matchup_map(wiki_name: &str, base_path: PathBuf) -> Result<String, String> {
-    let data = GEN_DEFAULT;
+pub fn matchup_key(offense_type: &str, defense_type: &str) -> String {
+    format!(
+        "{} > {}",
+        capitalize(offense_type),
+        capitalize(defense_type)
+    )
+}
 
-    let mut matchup_map: HashMap<String, f32> = HashMap::new();
+pub fn preset_matchup_map(preset: &TypeChartPreset) -> MatchupMap {
+    let preset_types = preset.types();
+    let mut matchup_map: MatchupMap = HashMap::new();
 
-    for (row_index, row_value) in data.iter().enumerate() {
+    for (row_index, row_value) in GEN_DEFAULT.iter().enumerate() {
         for (col_index, col_value) in row_value.iter().enumerate() {
-            let type_one = POKEMON_TYPES_ARRAY[row_index];
-            let type_two = POKEMON_TYPES_ARRAY[col_index];
-            let key = format!("{} > {}", type_one, type_two);
-            let value = col_value;
+            let type_one = DEFAULT_TYPES[row_index];
+            let type_two = DEFAULT_TYPES[col_index];
+            if !preset_types.contains(&type_one) || !preset_types.contains(&type_two) {
+                continue;
+            }
+
+            matchup_map.insert(matchup_key(type_one, type_two), *col_value);
+        }
+    }
+
+    for (offense_type, defense_type, effectiveness) in preset.overrides() {
+        matchup_map.insert(matchup_key(offense_type, defense_type), *effectiveness);
+    }
+
+    return matchup_map;
+}
+
+// The effectiveness of a matchup on the modern chart, when both types are in it
+fn default_effectiveness(offense_type: &str, defense_type: &str) -> Option<f32> {
+    let row = DEFAULT_TYPES
+        .iter()
+        .position(|_type| *_type == offense_type)?;
+    let col = DEFAULT_TYPES
+        .iter()
+        .position(|_type| *_type == defense_type)?;
+    Some(GEN_DEFAULT[row][col])
+}
+
+// The preset's types and matchups for the wiki, nothing is written.
+// Types the user added are kept along with their existing matchups, and so
+// are types the preset leaves out that pokemon or moves still use, since
+// removing them would leave those pokemon without defenses.
+pub fn preset_types_and_matchups(
+    wiki_name: &str,
+    base_path: &PathBuf,
+    preset: &TypeChartPreset,
+    used_types: &[String],
+) -> (WikiTypes, MatchupMap) {
+    let types_file_path = base_path.join(wiki_name).join("data").join("types.json");
+    let existing_types = match get_types(&types_file_path) {
+        Ok(wiki_types) => wiki_types.matchup_types(),
+        Err(_) => Vec::new(),
+    };
+    let existing_matchup_map = get_matchup_map(wiki_name, base_path).unwrap_or_default();
+    let preset_types = preset.types();
+
+    let mut kept_types: Vec<String> = Vec::new();
+    for _type in existing_types
+        .iter()
+        .filter(|_type| !DEFAULT_TYPES.contains(&_type.as_str()))
+        .chain(used_types.iter())
+    {
+        if !preset_types.contains(&_type.as_str()) && !kept_types.contains(_type) {
+            kept_types.push(_type.clone());
+        }
+    }
+
+    // Kept types from the main games stay in their usual place
+    let mut types: Vec<String> = DEFAULT_TYPES
+        .iter()
+        .filter(|_type| preset_types.contains(_type) || kept_types.contains(&_type.to_string()))
+        .map(|_type| _type.to_string())
+        .collect();
+    for kept_type in &kept_types {
+        if !types.contains(kept_type) {
+            types.push(kept_type.clone());
+        }
+    }
 
-            matchup_map.insert(key, *value);
+    let mut matchup_map = preset_matchup_map(preset);
+    for kept_type in &kept_types {
+        for _type in &types {
+            for (offense_type, defense_type) in [(kept_type, _type), (_type, kept_type)] {
+                let key = matchup_key(offense_type, defense_type);
+                let effectiveness = existing_matchup_map
+                    .get(&key)
+                    .copied()
+                    .or_else(|| default_effectiveness(offense_type, defense_type))
+                    .unwrap_or(1.0);
+                matchup_map.insert(key, effectiveness);
+            }
         }
     }
 
+    types.insert(0, "none".to_string());
+    (WikiTypes { types }, matchup_map)
+}
+
+pub fn get_matchup_map(wiki_name: &str, base_path: &PathBuf) -> Result<MatchupMap, String> {
     let matchup_map_path = base_path
         .join(wiki_name)
         .join("data")
         .join("matchup_map.json");
+    let matchup_file = match File::open(matchup_map_path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(format!("Failed to open matchup map file: {}", err));
+        }
+    };
 
-    let matchup_map_json_file = File::create(matchup_map_path).unwrap();
-    serde_json::to_writer(matchup_map_json_file, &matchup_map).unwrap();
+    let matchup_map: MatchupMap = match serde_json::from_reader(matchup_file) {
+        Ok(map_file) => map_file,
+        Err(err) => {
+            return Err(format!("Failed to parse matchup map file: {}", err));
+        }
+    };
 
-    Ok("Matchup Map Generated".to_string())
+    return Ok(matchup_map);
 }
 
-fn get_matchup_map(wiki_name: &str, base_path: &PathBuf) -> HashMap<String, f32> {
+pub fn update_matchup_map(
+    wiki_name: &str,
+    base_path: &PathBuf,
+    matchup_map: &MatchupMap,
+) -> WikiGenResult<()> {
     let matchup_map_path = base_path
         .join(wiki_name)
         .join("data")
         .join("matchup_map.json");
-    let matchup_file = File::open(matchup_map_path).unwrap();
 
-    let matchup_map: HashMap<String, f32> = match serde_json::from_reader(matchup_file) {
-        Ok(map_file) => map_file,
-        Err(error) => {
-            println!("{}", error);
-            return HashMap::new();
+    let matchup_map_json = match serde_json::to_string_pretty(matchup_map) {
+        Ok(matchup_map_json) => matchup_map_json,
+        Err(err) => {
+            let message = format!("{wiki_name}: Failed to serialize matchup map: {err}");
+            write_log(base_path, LogLevel::Error, &message);
+            return Err(message.into());
         }
     };
 
-    return matchup_map;
+    if let Err(err) = fs::write(&matchup_map_path, matchup_map_json) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to update matchup map file: {err}"),
+            matchup_map_path.display(),
+        );
+        write_log(base_path, LogLevel::Error, &error);
+        return Err(error);
+    }
+    invalidate_matchup_table(wiki_name, base_path);
+    Ok(())
 }
 
-fn matchup_for_pair(
+pub fn get_calculated_defenses(
     wiki_name: &str,
-    defense_type: &str,
-    offense_type: &str,
     base_path: &PathBuf,
-) -> f32 {
-    let matchup_map = get_matchup_map(wiki_name, base_path);
-    let key = format!("{} > {}", offense_type, defense_type);
-    let value = matchup_map.get(&key).unwrap();
-    return *value;
+) -> Result<CalculatedDefenses, String> {
+    let calculated_defenses_path = base_path
+        .join(wiki_name)
+        .join("data")
+        .join("calculated_defenses.json");
+    let calculated_defenses_file = match File::open(calculated_defenses_path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(format!("Failed to open calculated defenses file: {}", err));
+        }
+    };
+
+    match serde_json::from_reader(calculated_defenses_file) {
+        Ok(calculated_defenses) => Ok(calculated_defenses),
+        Err(err) => Err(format!("Failed to parse calculated defenses file: {}", err)),
+    }
 }
 
-fn matchup_for(
+pub fn update_calculated_defenses(
     wiki_name: &str,
-    defense_types: Vec<PokemonTypesEnum>,
-    offense_type: &PokemonTypesEnum,
     base_path: &PathBuf,
-) -> f32 {
-    let filtered_defense_types = defense_types
-        .iter()
-        .filter(|defense_type| !matches!(defense_type, PokemonTypesEnum::None));
-
-    let mapped_defense_types = filtered_defense_types.map(|defense_type| {
-        matchup_for_pair(
-            wiki_name,
-            &defense_type.to_string(),
-            &offense_type.to_string(),
-            base_path,
-        )
-    });
-
-    return mapped_defense_types.fold(1.0, |x, y| x * y);
-}
+    calculated_defenses: &CalculatedDefenses,
+) -> WikiGenResult<()> {
+    let calculated_defenses_path = base_path
+        .join(wiki_name)
+        .join("data")
+        .join("calculated_defenses.json");
+
+    let calculated_defenses_json = match serde_json::to_string_pretty(calculated_defenses) {
+        Ok(calculated_defenses_json) => calculated_defenses_json,
+        Err(err) => {
+            let message = format!("{wiki_name}: Failed to serialize calculated defenses: {err}");
+            write_log(base_path, LogLevel::Error, &message);
+            return Err(message.into());
+        }
+    };
 
-#[derive(Debug)]
-struct DefensiveMatchups {
-    pokemon_type: String,
-    effectiveness: f32,
+    if let Err(err) = fs::write(&calculated_defenses_path, calculated_defenses_json) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to update calculated defenses file: {err}"),
+            calculated_defenses_path.display(),
+        );
+        write_log(base_path, LogLevel::Error, &error);
+        return Err(error);
+    }
+    Ok(())
 }
 
-fn generate_defensive_matchups(
-    wiki_name: &str,
-    defense_types: Vec<PokemonTypesEnum>,
-    pokemon_type: &PokemonTypesEnum,
-    base_path: &PathBuf,
-) -> DefensiveMatchups {
-    let effectiveness = matchup_for(wiki_name, defense_types, pokemon_type, base_path);
+pub fn get_type_chart(wiki_name: &str, base_path: &PathBuf) -> Result<TypeChart, String> {
+    let type_chart_path = base_path
+        .join(wiki_name)
+        .join("data")
+        .join("type_chart.json");
 
-    return DefensiveMatchups {
-        pokemon_type: pokemon_type.to_string(),
-        effectiveness,
+    // Wikis created before presets existed always used the modern chart
+    if !type_chart_path.try_exists().unwrap_or(false) {
+        return Ok(TypeChart {
+            preset: TypeChartPreset::GenSixOnwards,
+        });
+    }
+
+    let type_chart_file = match File::open(type_chart_path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(format!("Failed to open type chart file: {}", err));
+        }
     };
+
+    match serde_json::from_reader(type_chart_file) {
+        Ok(type_chart) => Ok(type_chart),
+        Err(err) => Err(format!("Failed to parse type chart file: {}", err)),
+    }
 }
 
-fn defensive_matchups(
+pub fn update_type_chart(
     wiki_name: &str,
-    defense_types: Vec<PokemonTypesEnum>,
     base_path: &PathBuf,
-) -> Vec<DefensiveMatchups> {
-    let matchups: Vec<DefensiveMatchups> = POKEMON_TYPES_ARRAY
-        .iter()
-        .map(|pokemon_type| {
-            generate_defensive_matchups(wiki_name, defense_types.clone(), pokemon_type, base_path)
-        })
-        .collect();
+    type_chart: &TypeChart,
+) -> WikiGenResult<()> {
+    let type_chart_path = base_path
+        .join(wiki_name)
+        .join("data")
+        .join("type_chart.json");
 
-    return matchups;
+    let type_chart_json = match serde_json::to_string(type_chart) {
+        Ok(type_chart_json) => type_chart_json,
+        Err(err) => {
+            let message = format!("{wiki_name}: Failed to serialize type chart: {err}");
+            write_log(base_path, LogLevel::Error, &message);
+            return Err(message.into());
+        }
+    };
+
+    if let Err(err) = fs::write(&type_chart_path, type_chart_json) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to update type chart file: {err}"),
+            type_chart_path.display(),
+        );
+        write_log(base_path, LogLevel::Error, &error);
+        return Err(error);
+    }
+    Ok(())
 }
 
-fn group_matchups_by_effectiveness(
-    matchups: &Vec<DefensiveMatchups>,
-    effectiveness: f32,
-) -> Vec<String> {
-    let filtered_matchups: Vec<&DefensiveMatchups> = matchups
-        .iter()
-        .filter(|matchup| matchup.effectiveness == effectiveness)
-        .collect();
+// Every generator reuses the table loaded for the wiki. It is kept alongside
+// the modified times of types.json and matchup_map.json it was read at, so
+// files replaced outside the type commands, like by an import or by deleting
+// and recreating the wiki, are read again.
+type CachedMatchupTable = (Option<SystemTime>, Option<SystemTime>, Arc<MatchupTable>);
 
-    let mapped_matchups = filtered_matchups
-        .iter()
-        .map(|&matchup| matchup.pokemon_type.to_string())
-        .collect();
+static MATCHUP_TABLES: LazyLock<Mutex<HashMap<PathBuf, CachedMatchupTable>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
 
-    return mapped_matchups;
+fn modified_time(file_path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(file_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
 }
 
-#[allow(dead_code)]
-pub fn get_defensive_matchups(
-    types: &Vec<String>,
-    wiki_name: &str,
-    base_path: &PathBuf,
-) -> HashMap<String, Vec<String>> {
-    let defense_types = types
-        .iter()
-        .map(|_type| PokemonTypesEnum::from_str(&_type).unwrap())
-        .collect();
-    // Consdier removing some of the effectiveness levels since they may never be used
-    let effectiveness_levels = [8.0, 4.0, 2.0, 1.0, 0.5, 0.25, 0.125, 0.0];
+#[derive(Debug, Clone, Default)]
+pub struct MatchupTable {
+    types: Vec<String>,
+    matchup_map: MatchupMap,
+}
 
-    let matchups = defensive_matchups(wiki_name, defense_types, base_path);
+impl MatchupTable {
+    pub fn new(types: Vec<String>, matchup_map: MatchupMap) -> Self {
+        MatchupTable { types, matchup_map }
+    }
 
-    let mut matchups_by_effectiveness = HashMap::new();
+    pub fn load(wiki_name: &str, base_path: &PathBuf) -> Result<Self, String> {
+        let types_file_path = base_path.join(wiki_name).join("data").join("types.json");
+        let wiki_types = get_types(&types_file_path)?;
+        let matchup_map = get_matchup_map(wiki_name, base_path)?;
 
-    for effectiveness in effectiveness_levels {
-        let grouped_matchups = group_matchups_by_effectiveness(&matchups, effectiveness);
-        matchups_by_effectiveness.insert(effectiveness.to_string(), grouped_matchups);
+        Ok(MatchupTable::new(wiki_types.matchup_types(), matchup_map))
     }
 
-    let mut filtered_matchups_by_effectiveness = HashMap::new();
-    for (key, value) in matchups_by_effectiveness {
-        if value.len() != 0 {
-            filtered_matchups_by_effectiveness.insert(key, value);
+    // Types that take part in matchups, "none" excluded
+    pub fn types(&self) -> &[String] {
+        &self.types
+    }
+
+    pub fn matchup_map(&self) -> &MatchupMap {
+        &self.matchup_map
+    }
+
+    // Pairs missing from the map are neutral, like the type chart page shows
+    // them, but a type the wiki doesn't have is an error
+    pub fn effectiveness(&self, offense_type: &str, defense_type: &str) -> Result<f32, String> {
+        for _type in [offense_type, defense_type] {
+            if !self
+                .types
+                .iter()
+                .any(|wiki_type| wiki_type.as_str() == _type)
+            {
+                return Err(format!("Type {} does not exist", _type));
+            }
+        }
+        let key = matchup_key(offense_type, defense_type);
+        Ok(*self.matchup_map.get(&key).unwrap_or(&1.0))
+    }
+
+    fn combined_effectiveness(
+        &self,
+        offense_type: &str,
+        defense_types: &[String],
+    ) -> Result<f32, String> {
+        let mut effectiveness = 1.0;
+        for defense_type in defense_types {
+            if defense_type.as_str() == "none" || defense_type.is_empty() {
+                continue;
+            }
+            effectiveness *= self.effectiveness(offense_type, defense_type)?;
+        }
+        Ok(effectiveness)
+    }
+
+    pub fn defensive_matchups(
+        &self,
+        defense_types: &[String],
+    ) -> Result<TypeEffectiveness, String> {
+        let defense_types = defense_types
+            .iter()
+            .map(|_type| _type.to_lowercase())
+            .collect::<Vec<String>>();
+
+        let mut matchups_by_effectiveness: HashMap<String, Vec<String>> = HashMap::new();
+        for offense_type in &self.types {
+            let effectiveness = self.combined_effectiveness(offense_type, &defense_types)?;
+            matchups_by_effectiveness
+                .entry(effectiveness.to_string())
+                .or_default()
+                .push(capitalize(offense_type));
+        }
+
+        return Ok(TypeEffectiveness(matchups_by_effectiveness));
+    }
+
+    // Keys match the pokemon.types column with the comma swapped for a hyphen, e.g. "grass-poison"
+    pub fn calculate_defenses(
+        &self,
+        type_combinations: &[String],
+    ) -> Result<CalculatedDefenses, String> {
+        let mut calculated_defenses: CalculatedDefenses = HashMap::new();
+        for type_combination in type_combinations {
+            let defense_types = type_combination
+                .split(",")
+                .map(str::to_string)
+                .collect::<Vec<String>>();
+            calculated_defenses.insert(
+                type_combination.replace(",", "-"),
+                self.defensive_matchups(&defense_types)?,
+            );
         }
+
+        return Ok(calculated_defenses);
     }
+}
 
-    return filtered_matchups_by_effectiveness;
+pub fn get_matchup_table(
+    wiki_name: &str,
+    base_path: &PathBuf,
+) -> Result<Arc<MatchupTable>, String> {
+    let wiki_path = base_path.join(wiki_name);
+    let types_modified = modified_time(&wiki_path.join("data").join("types.json"));
+    let matchup_map_modified = modified_time(&wiki_path.join("data").join("matchup_map.json"));
+    let mut matchup_tables = MATCHUP_TABLES
+        .lock()
+        .map_err(|err| format!("Failed to access matchup tables: {}", err))?;
+
+    if let Some((cached_types_modified, cached_matchup_map_modified, matchup_table)) =
+        matchup_tables.get(&wiki_path)
+    {
+        if *cached_types_modified == types_modified
+            && *cached_matchup_map_modified == matchup_map_modified
+        {
+            return Ok(matchup_table.clone());
+        }
+    }
+
+    let matchup_table = Arc::new(MatchupTable::load(wiki_name, base_path)?);
+    matchup_tables.insert(
+        wiki_path,
+        (types_modified, matchup_map_modified, matchup_table.clone()),
+    );
+
+    return Ok(matchup_table);
+}
+
+pub fn invalidate_matchup_table(wiki_name: &str, base_path: &PathBuf) {
+    if let Ok(mut matchup_tables) = MATCHUP_TABLES.lock() {
+        matchup_tables.remove(&base_path.join(wiki_name));
+    }
 }
@@ -1,10 +1,24 @@
 // Unsure if this is the best way to handle/organize "helper" functions
 // in rust. It might not be idiomatic, but it's a start.
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
 use std::net::TcpStream;
 use std::path::Path;
-use std::process::{id, Command};
+use std::process::{id, Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
 use sysinfo::{Pid, System};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::errors::WikiGenResult;
+
+pub const MKDOCS_LOG_EVENT: &str = "mkdocs-server-log";
+pub const MKDOCS_STATUS_EVENT: &str = "mkdocs-server-status";
+
+// Servers stopped on purpose, so their exit isn't reported as a failure
+#[derive(Default)]
+pub struct MkdocsProcessState(pub Mutex<HashSet<usize>>);
 
 #[derive(Debug, Serialize, Clone)]
 enum MkdocsServerStatus {
@@ -12,6 +26,8 @@ enum MkdocsServerStatus {
     Running,
     Stopped,
     Occupied,
+    // Exited without being stopped, usually a build error
+    Failed,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -21,10 +37,34 @@ pub struct Payload {
     process_id: usize,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MkdocsLogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+// One line of mkdocs output
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MkdocsLog {
+    pub process_id: usize,
+    pub level: MkdocsLogLevel,
+    pub message: String,
+    // Page or config file the message is about, when mkdocs names one
+    pub file: Option<String>,
+    pub line: Option<usize>,
+}
+
 // User will need to have python3 or mkdocs installed.
 // Either inform the user to install it or install it for them.
 #[tauri::command]
-pub async fn spawn_mkdocs_process(mkdocs_file_path: String, port: u16) -> Result<Payload, Payload> {
+pub async fn spawn_mkdocs_process(
+    mkdocs_file_path: String,
+    port: u16,
+    app_handle: AppHandle,
+) -> Result<Payload, Payload> {
     let mut is_port_in_use = false;
 
     match TcpStream::connect(("0.0.0.0", port)) {
@@ -35,9 +75,10 @@ pub async fn spawn_mkdocs_process(mkdocs_file_path: String, port: u16) -> Result
     if is_port_in_use {
         let system = System::new_all();
         let mut killed_process = false;
-        for (_, process) in system.processes() {
+        for (process_id, process) in system.processes() {
             if process.parent() == Some(Pid::from_u32(id())) {
                 killed_process = true;
+                mark_stopped(&app_handle, process_id.as_u32() as usize);
                 process.kill();
                 break;
             }
@@ -52,27 +93,85 @@ pub async fn spawn_mkdocs_process(mkdocs_file_path: String, port: u16) -> Result
         }
     }
 
-    let mut mkdocs_command = Command::new("mkdocs");
-    let mkdocs_serve = mkdocs_command
+    let mut spawn_result = mkdocs_serve_command("mkdocs", &[], &mkdocs_file_path, port).spawn();
+    // pip installs whose scripts folder isn't on the PATH still have the module.
+    // Either way the spawned process is mkdocs itself, so its id is the one
+    // stopped later and the one the port check finds as our child.
+    if matches!(&spawn_result, Err(err) if err.kind() == ErrorKind::NotFound) {
+        let python = if cfg!(windows) { "python" } else { "python3" };
+        spawn_result =
+            mkdocs_serve_command(python, &["-m", "mkdocs"], &mkdocs_file_path, port).spawn();
+    }
+
+    // mkdocs isn't installed everywhere, the built-in preview covers that case
+    match spawn_result {
+        Ok(mut child) => {
+            let process_id = child.id() as usize;
+            let last_error = Arc::new(Mutex::new(None));
+            let mut output_readers = Vec::new();
+            if let Some(stdout) = child.stdout.take() {
+                output_readers.push(stream_mkdocs_output(
+                    stdout,
+                    process_id,
+                    last_error.clone(),
+                    app_handle.clone(),
+                ));
+            }
+            // mkdocs logs to stderr
+            if let Some(stderr) = child.stderr.take() {
+                output_readers.push(stream_mkdocs_output(
+                    stderr,
+                    process_id,
+                    last_error.clone(),
+                    app_handle.clone(),
+                ));
+            }
+            watch_mkdocs_process(child, output_readers, last_error, app_handle);
+
+            Ok(Payload {
+                message: format!("Mkdocs Server started at localhost:{}", &port),
+                status: MkdocsServerStatus::Started,
+                process_id,
+            })
+        }
+        Err(err) => Err(Payload {
+            message: format!(
+                "Failed to start Mkdocs Server, is mkdocs installed? {}",
+                err
+            ),
+            status: MkdocsServerStatus::Stopped,
+            process_id: 0,
+        }),
+    }
+}
+
+fn mkdocs_serve_command(
+    program: &str,
+    launcher_args: &[&str],
+    mkdocs_file_path: &str,
+    port: u16,
+) -> Command {
+    let mut mkdocs_command = Command::new(program);
+    mkdocs_command
+        .args(launcher_args)
         .arg("serve")
         .arg("-a")
         .arg(format!("0.0.0.0:{}", &port))
         .arg("-f")
-        .arg(Path::new(&mkdocs_file_path));
-
-    let child_stdout = mkdocs_serve.spawn().expect("Failed to start Mkdocs Server");
-
-    Ok(Payload {
-        message: format!("Mkdocs Server started at localhost:{}", &port),
-        status: MkdocsServerStatus::Started,
-        process_id: child_stdout.id() as usize,
-    })
+        .arg(Path::new(mkdocs_file_path))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    mkdocs_command
 }
 
 #[tauri::command]
-pub async fn kill_mkdocs_process(process_id: usize) -> Result<Payload, String> {
+pub async fn kill_mkdocs_process(
+    process_id: usize,
+    app_handle: AppHandle,
+) -> WikiGenResult<Payload> {
     let system = System::new_all();
     if let Some(process) = system.process(Pid::from(process_id)) {
+        mark_stopped(&app_handle, process_id);
         process.kill();
     }
 
@@ -84,7 +183,7 @@ pub async fn kill_mkdocs_process(process_id: usize) -> Result<Payload, String> {
 }
 
 #[tauri::command]
-pub async fn check_process_status(process_id: usize) -> Result<Payload, String> {
+pub async fn check_process_status(process_id: usize) -> WikiGenResult<Payload> {
     let system = System::new_all();
     if system.process(Pid::from(process_id)).is_some() {
         Ok(Payload {
@@ -100,3 +199,148 @@ pub async fn check_process_status(process_id: usize) -> Result<Payload, String>
         })
     }
 }
+
+fn mark_stopped(app_handle: &AppHandle, process_id: usize) {
+    if let Ok(mut stopped_processes) = app_handle.state::<MkdocsProcessState>().0.lock() {
+        stopped_processes.insert(process_id);
+    }
+}
+
+// Emits every line mkdocs prints, remembering the last error for the
+// failure message
+fn stream_mkdocs_output(
+    output: impl Read + Send + 'static,
+    process_id: usize,
+    last_error: Arc<Mutex<Option<String>>>,
+    app_handle: AppHandle,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut level = MkdocsLogLevel::Info;
+        for line in BufReader::new(output).lines().map_while(Result::ok) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let log = parse_mkdocs_log(process_id, &line, level);
+            level = log.level;
+            // Lines like `in "mkdocs.yml", line 3` only say where the error was
+            let is_location =
+                log.message.starts_with("in \"") || log.message.starts_with("File \"");
+            if log.level == MkdocsLogLevel::Error && !is_location {
+                if let Ok(mut last_error) = last_error.lock() {
+                    *last_error = Some(log.message.clone());
+                }
+            }
+            // Nothing to do when the window is gone
+            let _ = app_handle.emit(MKDOCS_LOG_EVENT, log);
+        }
+    })
+}
+
+// Waits for the server to exit and reports it as failed unless it was stopped
+fn watch_mkdocs_process(
+    mut child: Child,
+    output_readers: Vec<JoinHandle<()>>,
+    last_error: Arc<Mutex<Option<String>>>,
+    app_handle: AppHandle,
+) {
+    thread::spawn(move || {
+        let process_id = child.id() as usize;
+        let exit_status = child.wait();
+        // The last lines are read before saying why it exited
+        for output_reader in output_readers {
+            let _ = output_reader.join();
+        }
+
+        let was_stopped = match app_handle.state::<MkdocsProcessState>().0.lock() {
+            Ok(mut stopped_processes) => stopped_processes.remove(&process_id),
+            Err(_) => false,
+        };
+        let payload = match was_stopped {
+            true => Payload {
+                message: "Mkdocs Server stopped".to_string(),
+                status: MkdocsServerStatus::Stopped,
+                process_id,
+            },
+            false => {
+                let reason = last_error
+                    .lock()
+                    .ok()
+                    .and_then(|last_error| last_error.clone())
+                    .or_else(|| exit_status.ok().map(|status| status.to_string()))
+                    .unwrap_or_else(|| "unknown error".to_string());
+                Payload {
+                    message: format!("Mkdocs Server exited unexpectedly: {}", reason),
+                    status: MkdocsServerStatus::Failed,
+                    process_id,
+                }
+            }
+        };
+        let _ = app_handle.emit(MKDOCS_STATUS_EVENT, payload);
+    });
+}
+
+// mkdocs logs as "WARNING -  Doc file 'index.md' contains a link ...". Lines
+// without a level, like tracebacks, belong to the message before them.
+pub fn parse_mkdocs_log(
+    process_id: usize,
+    line: &str,
+    previous_level: MkdocsLogLevel,
+) -> MkdocsLog {
+    let line = line.trim();
+    let leveled_message = line
+        .split_once(" -  ")
+        .and_then(|(prefix, message)| Some((log_level(prefix.trim())?, message.trim())));
+    let (level, message) = match leveled_message {
+        Some(leveled_message) => leveled_message,
+        // How mkdocs reports config errors before giving up
+        None if line.starts_with("Error: ") || line.starts_with("Traceback") => {
+            (MkdocsLogLevel::Error, line)
+        }
+        None => (previous_level, line),
+    };
+    let (file, line_number) = log_location(message);
+
+    MkdocsLog {
+        process_id,
+        level,
+        message: message.to_string(),
+        file,
+        line: line_number,
+    }
+}
+
+fn log_level(prefix: &str) -> Option<MkdocsLogLevel> {
+    match prefix {
+        "DEBUG" => Some(MkdocsLogLevel::Debug),
+        "INFO" => Some(MkdocsLogLevel::Info),
+        "WARNING" => Some(MkdocsLogLevel::Warning),
+        "ERROR" | "CRITICAL" => Some(MkdocsLogLevel::Error),
+        _ => None,
+    }
+}
+
+// Pages are named as in "Doc file 'index.md'" or "page 'index.md'", config
+// and python files as in `in "mkdocs.yml", line 3`
+fn log_location(message: &str) -> (Option<String>, Option<usize>) {
+    let file = [
+        ("Doc file '", '\''),
+        ("page '", '\''),
+        ("File \"", '"'),
+        ("in \"", '"'),
+    ]
+    .iter()
+    .find_map(|(marker, quote)| {
+        let (_, after_marker) = message.split_once(marker)?;
+        let (file, _) = after_marker.split_once(*quote)?;
+        Some(file.to_string())
+    });
+    let line = message.split_once(", line ").and_then(|(_, after_line)| {
+        let digits: String = after_line
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        digits.parse().ok()
+    });
+
+    (file, line)
+}
@@ -5,6 +5,7 @@ use std::{fs, io, path::Path};
 
 pub mod matchups;
 pub mod mkdocs_process;
+pub mod types;
 
 pub fn get_pokemon_dex_formatted_name(dex_number: u32) -> String {
     let mut pokedex_markdown_file_name = format!("00{}", dex_number);
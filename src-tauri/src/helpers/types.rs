@@ -0,0 +1,668 @@
+use std::{fs, io, path::PathBuf};
+
+use sqlx::{Pool, Sqlite};
+use tauri::AppHandle;
+
+use crate::{
+    database::{
+        get_app_data_dir, get_resource_dir, get_routes, get_types,
+        repository::{
+            count_type_usage, get_pokemon_types, get_used_types, rename_move_type,
+            rename_pokemon_type,
+        },
+        session::{get_wiki_session, WikiSession},
+        update_routes, update_types,
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::{
+        capitalize,
+        matchups::{
+            get_matchup_map, get_type_chart, invalidate_matchup_table, matchup_key,
+            preset_types_and_matchups, update_calculated_defenses, update_matchup_map,
+            update_type_chart, MatchupTable,
+        },
+    },
+    logger::{self, write_log, LogLevel},
+    page_generators::{
+        game_routes::Routes,
+        pokemon_pages::{generate_pokemon_pages, get_pokemon_list_abilities_and_movesets},
+    },
+    structs::matchup_models::{
+        CalculatedDefenses, MatchupMap, TypeChart, TypeChartPreset, WikiTypes,
+    },
+};
+
+fn format_type_name(type_name: &str) -> String {
+    type_name.trim().to_lowercase().replace(" ", "_")
+}
+
+// Dual types are joined with a hyphen in calculated_defenses.json, so a
+// hyphenated name couldn't be told apart from a pair of types
+fn validate_new_type_name(type_name: &str) -> WikiGenResult<()> {
+    if type_name.is_empty() {
+        return Err("Type name cannot be empty".into());
+    }
+    if type_name.contains('-') || type_name.contains(',') {
+        return Err(format!("Type {} cannot contain a hyphen or comma", type_name).into());
+    }
+    Ok(())
+}
+
+fn get_wiki_types(wiki_name: &str, base_path: &PathBuf) -> Result<WikiTypes, String> {
+    let types_file_path = base_path.join(wiki_name).join("data").join("types.json");
+    match get_types(&types_file_path) {
+        Ok(types) => Ok(types),
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+fn get_wiki_matchup_map(wiki_name: &str, base_path: &PathBuf) -> Result<MatchupMap, String> {
+    match get_matchup_map(wiki_name, base_path) {
+        Ok(matchup_map) => Ok(matchup_map),
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+// Type combinations of the wiki's pokemon as pokemon.types stores them, e.g. "grass,poison"
+pub async fn get_type_combinations(conn: &Pool<Sqlite>) -> WikiGenResult<Vec<String>> {
+    let mut type_combinations = get_pokemon_types(conn)
+        .await?
+        .into_iter()
+        .map(|(_, types)| types)
+        .collect::<Vec<String>>();
+    type_combinations.sort();
+    type_combinations.dedup();
+    Ok(type_combinations)
+}
+
+// Defenses the pokemon pages currently show. They are recalculated from the
+// type files rather than read from calculated_defenses.json, which older
+// versions left stale after editing a matchup. A chart that can't be read
+// counts as every pokemon changing.
+pub fn current_defenses(
+    wiki_name: &str,
+    base_path: &PathBuf,
+    type_combinations: &[String],
+) -> CalculatedDefenses {
+    MatchupTable::load(wiki_name, base_path)
+        .and_then(|matchup_table| matchup_table.calculate_defenses(type_combinations))
+        .unwrap_or_default()
+}
+
+// Writes the types and matchups along with the defenses they give the wiki's
+// pokemon. The defenses are calculated before anything is written, so a
+// failure leaves the wiki as it was.
+pub fn save_types_and_matchups(
+    wiki_name: &str,
+    base_path: &PathBuf,
+    wiki_types: &WikiTypes,
+    matchup_map: &MatchupMap,
+    type_combinations: &[String],
+) -> WikiGenResult<CalculatedDefenses> {
+    let calculated_defenses = MatchupTable::new(wiki_types.matchup_types(), matchup_map.clone())
+        .calculate_defenses(type_combinations)?;
+
+    update_types(wiki_name, base_path, wiki_types)?;
+    update_matchup_map(wiki_name, base_path, matchup_map)?;
+    update_calculated_defenses(wiki_name, base_path, &calculated_defenses)?;
+
+    Ok(calculated_defenses)
+}
+
+pub fn changed_pokemon_ids(
+    pokemon_types: &[(u32, String)],
+    previous_defenses: &CalculatedDefenses,
+    calculated_defenses: &CalculatedDefenses,
+) -> Vec<usize> {
+    pokemon_types
+        .iter()
+        .filter(|(_, types)| {
+            let key = types.replace(",", "-");
+            previous_defenses.get(&key) != calculated_defenses.get(&key)
+        })
+        .map(|(id, _)| *id as usize)
+        .collect()
+}
+
+// Regenerates the pages of pokemon whose defenses changed and returns how many there were
+async fn regenerate_changed_pokemon_pages(
+    session: &WikiSession,
+    resources_path: &PathBuf,
+    previous_defenses: &CalculatedDefenses,
+    calculated_defenses: &CalculatedDefenses,
+) -> WikiGenResult<usize> {
+    let pokemon_types = get_pokemon_types(&session.pool).await?;
+    let affected_pokemon_ids =
+        changed_pokemon_ids(&pokemon_types, previous_defenses, calculated_defenses);
+    if affected_pokemon_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let (pokemon_list, abilities_list, movesets) =
+        get_pokemon_list_abilities_and_movesets(&session.pool, &affected_pokemon_ids).await?;
+    generate_pokemon_pages(
+        session,
+        &pokemon_list,
+        &abilities_list,
+        &movesets,
+        resources_path,
+    )?;
+
+    Ok(pokemon_list.len())
+}
+
+// Saves a type or matchup edit and brings the pokemon pages it affects up to date
+async fn save_type_chart_edit(
+    session: &WikiSession,
+    resources_path: &PathBuf,
+    wiki_types: &WikiTypes,
+    matchup_map: &MatchupMap,
+) -> WikiGenResult<usize> {
+    let type_combinations = get_type_combinations(&session.pool).await?;
+    let previous_defenses =
+        current_defenses(&session.wiki_name, &session.base_path, &type_combinations);
+    let calculated_defenses = save_types_and_matchups(
+        &session.wiki_name,
+        &session.base_path,
+        wiki_types,
+        matchup_map,
+        &type_combinations,
+    )?;
+
+    regenerate_changed_pokemon_pages(
+        session,
+        resources_path,
+        &previous_defenses,
+        &calculated_defenses,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn add_type(
+    wiki_name: &str,
+    type_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+    let resources_path = get_resource_dir(&app_handle)?;
+    let new_type = format_type_name(type_name);
+    validate_new_type_name(&new_type)?;
+
+    let mut wiki_types = get_wiki_types(wiki_name, &base_path)?;
+    if wiki_types.types.contains(&new_type) {
+        return Err(format!("Type {} already exists", new_type).into());
+    }
+    wiki_types.types.push(new_type.clone());
+
+    // New types start out neutral against everything until the user edits the matchups
+    let mut matchup_map = get_wiki_matchup_map(wiki_name, &base_path)?;
+    for existing_type in wiki_types.matchup_types() {
+        matchup_map.insert(matchup_key(&new_type, &existing_type), 1.0);
+        matchup_map.insert(matchup_key(&existing_type, &new_type), 1.0);
+    }
+
+    if let Err(err) =
+        save_type_chart_edit(&session, &resources_path, &wiki_types, &matchup_map).await
+    {
+        logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+        return Err(err);
+    }
+
+    Ok(format!("Type {} added", new_type))
+}
+
+// Original state of the files rename_type changes, so they can be put back
+// if a later step or the database commit fails
+#[derive(Default)]
+pub struct FileChanges {
+    // None for files that didn't exist yet
+    snapshots: Vec<(PathBuf, Option<Vec<u8>>)>,
+    renamed: Vec<(PathBuf, PathBuf)>,
+}
+
+impl FileChanges {
+    pub fn snapshot(&mut self, file_path: PathBuf) -> WikiGenResult<()> {
+        let original = match fs::read(&file_path) {
+            Ok(original) => Some(original),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => {
+                return Err(WikiGenError::io(
+                    format!(
+                        "Failed to read {} before updating it: {}",
+                        file_path.display(),
+                        err
+                    ),
+                    file_path.display(),
+                ))
+            }
+        };
+        self.snapshots.push((file_path, original));
+        Ok(())
+    }
+
+    pub fn rename(&mut self, from: PathBuf, to: PathBuf) -> WikiGenResult<()> {
+        if let Err(err) = fs::rename(&from, &to) {
+            return Err(WikiGenError::io(
+                format!("Failed to rename {}: {}", from.display(), err),
+                from.display(),
+            ));
+        }
+        self.renamed.push((from, to));
+        Ok(())
+    }
+
+    pub fn undo(self, wiki_name: &str, base_path: &PathBuf) {
+        for (file_path, original) in self.snapshots.into_iter().rev() {
+            let result = match original {
+                Some(original) => fs::write(&file_path, original),
+                None => fs::remove_file(&file_path),
+            };
+            if let Err(err) = result {
+                let message = format!(
+                    "{wiki_name}: Failed to restore {}: {err}",
+                    file_path.display()
+                );
+                write_log(base_path, LogLevel::Error, &message);
+            }
+        }
+        for (from, to) in self.renamed.into_iter().rev() {
+            if let Err(err) = fs::rename(&to, &from) {
+                let message = format!("{wiki_name}: Failed to restore {}: {err}", from.display());
+                write_log(base_path, LogLevel::Error, &message);
+            }
+        }
+        invalidate_matchup_table(wiki_name, base_path);
+    }
+}
+
+// The database is renamed in a transaction that is only committed once every
+// file has been written, and the files are put back if anything fails along the way
+
+#[tauri::command]
+pub async fn rename_type(
+    wiki_name: &str,
+    old_type_name: &str,
+    new_type_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+    let resources_path = get_resource_dir(&app_handle)?;
+    let old_type = format_type_name(old_type_name);
+    let new_type = format_type_name(new_type_name);
+    validate_new_type_name(&new_type)?;
+    if old_type == "none" {
+        return Err("The none type cannot be renamed".into());
+    }
+
+    let mut wiki_types = get_wiki_types(wiki_name, &base_path)?;
+    if wiki_types.types.contains(&new_type) {
+        return Err(format!("Type {} already exists", new_type).into());
+    }
+    let Some(type_position) = wiki_types.types.iter().position(|_type| *_type == old_type) else {
+        return Err(format!("Type {} does not exist", old_type).into());
+    };
+    wiki_types.types[type_position] = new_type.clone();
+
+    let matchup_map = get_wiki_matchup_map(wiki_name, &base_path)?;
+    let old_type_key = capitalize(&old_type);
+    let new_type_key = capitalize(&new_type);
+    let renamed_matchup_map = matchup_map
+        .into_iter()
+        .map(|(key, value)| {
+            let renamed_key = match key.split_once(" > ") {
+                Some((offense, defense)) => {
+                    let offense = if offense == old_type_key {
+                        new_type_key.as_str()
+                    } else {
+                        offense
+                    };
+                    let defense = if defense == old_type_key {
+                        new_type_key.as_str()
+                    } else {
+                        defense
+                    };
+                    format!("{} > {}", offense, defense)
+                }
+                None => key,
+            };
+            (renamed_key, value)
+        })
+        .collect();
+
+    let routes_json_file_path = base_path.join(wiki_name).join("data").join("routes.json");
+    let mut routes = match get_routes(&routes_json_file_path) {
+        Ok(routes) => routes,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+    rename_trainer_pokemon_types(&mut routes, &old_type, &new_type);
+
+    let types_image_path = base_path
+        .join(wiki_name)
+        .join("dist")
+        .join("docs")
+        .join("img")
+        .join("types");
+    let old_image_path = types_image_path.join(format!("{}.png", old_type));
+    let new_image_path = types_image_path.join(format!("{}.png", new_type));
+    let rename_image = old_image_path.try_exists().unwrap_or(false);
+    if rename_image && new_image_path.try_exists().unwrap_or(true) {
+        return Err(WikiGenError::io(
+            format!("An image for type {} already exists", new_type),
+            new_image_path.display(),
+        ));
+    }
+
+    let conn = session.pool.clone();
+    let type_combinations = get_type_combinations(&conn).await?;
+    let previous_defenses = current_defenses(wiki_name, &base_path, &type_combinations);
+    // calculated_defenses.json is keyed by the pokemon's types, which the rename changes too
+    let mut renamed_type_combinations = type_combinations
+        .iter()
+        .map(|types| {
+            types
+                .split(",")
+                .map(|_type| {
+                    if _type == old_type {
+                        new_type.as_str()
+                    } else {
+                        _type
+                    }
+                })
+                .collect::<Vec<&str>>()
+                .join(",")
+        })
+        .collect::<Vec<String>>();
+    renamed_type_combinations.sort();
+    renamed_type_combinations.dedup();
+
+    let mut transaction = match conn.begin().await {
+        Ok(transaction) => transaction,
+        Err(err) => {
+            let error = WikiGenError::database(
+                format!("Failed to start renaming type {}: {}", old_type, err),
+                "pokemon, moves",
+            );
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &error);
+            return Err(error);
+        }
+    };
+    if let Err(err) = rename_pokemon_type(&mut *transaction, &old_type, &new_type).await {
+        logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+        return Err(err);
+    }
+    if let Err(err) = rename_move_type(&mut *transaction, &old_type, &new_type).await {
+        logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+        return Err(err);
+    }
+
+    let mut file_changes = FileChanges::default();
+    let data_path = base_path.join(wiki_name).join("data");
+    let result = async {
+        for file_name in [
+            "routes.json",
+            "types.json",
+            "matchup_map.json",
+            "calculated_defenses.json",
+        ] {
+            file_changes.snapshot(data_path.join(file_name))?;
+        }
+        update_routes(wiki_name, &base_path, &routes)?;
+        if rename_image {
+            file_changes.rename(old_image_path, new_image_path)?;
+        }
+        let calculated_defenses = save_types_and_matchups(
+            wiki_name,
+            &base_path,
+            &wiki_types,
+            &renamed_matchup_map,
+            &renamed_type_combinations,
+        )?;
+
+        match transaction.commit().await {
+            Ok(_) => Ok(calculated_defenses),
+            Err(err) => Err(WikiGenError::database(
+                format!("Failed to rename type {}: {}", old_type, err),
+                "pokemon, moves",
+            )),
+        }
+    }
+    .await;
+    // The transaction is rolled back when it is dropped without a commit
+    let calculated_defenses = match result {
+        Ok(calculated_defenses) => calculated_defenses,
+        Err(err) => {
+            file_changes.undo(wiki_name, &base_path);
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    // Every page of a pokemon with the type shows its name and image
+    if let Err(err) = regenerate_changed_pokemon_pages(
+        &session,
+        &resources_path,
+        &previous_defenses,
+        &calculated_defenses,
+    )
+    .await
+    {
+        logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+        return Err(err);
+    }
+
+    Ok(format!("Type {} renamed to {}", old_type, new_type))
+}
+
+fn rename_trainer_pokemon_types(routes: &mut Routes, old_type: &str, new_type: &str) {
+    for (_, route_properties) in routes.routes.iter_mut() {
+        for (_, trainer_info) in route_properties.trainers.iter_mut() {
+            for pokemon in trainer_info.pokemon_team.iter_mut() {
+                for _type in pokemon.types.iter_mut() {
+                    if _type == old_type {
+                        *_type = new_type.to_string();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn remove_type(
+    wiki_name: &str,
+    type_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+    let resources_path = get_resource_dir(&app_handle)?;
+    let removed_type = format_type_name(type_name);
+    if removed_type == "none" {
+        return Err("The none type cannot be removed".into());
+    }
+
+    let mut wiki_types = get_wiki_types(wiki_name, &base_path)?;
+    if !wiki_types.types.contains(&removed_type) {
+        return Err(format!("Type {} does not exist", removed_type).into());
+    }
+
+    let conn = session.pool.clone();
+
+    // Removing a type that is still in use would leave pokemon and moves
+    // pointing at a type with no matchups, so the user has to reassign them first
+    let usage_count = match count_type_usage(&conn, &removed_type).await {
+        Ok(usage_count) => usage_count,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    if usage_count > 0 {
+        return Err(format!(
+            "Type {} is still used by {} pokemon or moves",
+            removed_type, usage_count
+        )
+        .into());
+    }
+
+    wiki_types.types.retain(|_type| *_type != removed_type);
+
+    let removed_type_key = capitalize(&removed_type);
+    let mut matchup_map = get_wiki_matchup_map(wiki_name, &base_path)?;
+    matchup_map.retain(|key, _| match key.split_once(" > ") {
+        Some((offense, defense)) => offense != removed_type_key && defense != removed_type_key,
+        None => true,
+    });
+
+    if let Err(err) =
+        save_type_chart_edit(&session, &resources_path, &wiki_types, &matchup_map).await
+    {
+        logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+        return Err(err);
+    }
+
+    Ok(format!("Type {} removed", removed_type))
+}
+
+#[tauri::command]
+pub async fn update_type_matchup(
+    wiki_name: &str,
+    offense_type: &str,
+    defense_type: &str,
+    effectiveness: f32,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+    let resources_path = get_resource_dir(&app_handle)?;
+    let offense_type = format_type_name(offense_type);
+    let defense_type = format_type_name(defense_type);
+
+    let wiki_types = get_wiki_types(wiki_name, &base_path)?;
+    let matchup_types = wiki_types.matchup_types();
+    for _type in [&offense_type, &defense_type] {
+        if !matchup_types.contains(_type) {
+            return Err(format!("Type {} does not exist", _type).into());
+        }
+    }
+
+    let mut matchup_map = get_wiki_matchup_map(wiki_name, &base_path)?;
+    matchup_map.insert(matchup_key(&offense_type, &defense_type), effectiveness);
+    if let Err(err) =
+        save_type_chart_edit(&session, &resources_path, &wiki_types, &matchup_map).await
+    {
+        logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+        return Err(err);
+    }
+
+    Ok(format!(
+        "{} matchup updated",
+        matchup_key(&offense_type, &defense_type)
+    ))
+}
+
+#[tauri::command]
+pub fn get_type_chart_preset(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<TypeChartPreset> {
+    let base_path = get_app_data_dir(&app_handle)?;
+    match get_type_chart(wiki_name, &base_path) {
+        Ok(type_chart) => Ok(type_chart.preset),
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            Err(err.into())
+        }
+    }
+}
+
+// Switches the wiki's data files to the preset. Every pokemon's defenses are
+// calculated before anything is written, so a failure leaves the wiki as it was.
+pub async fn apply_type_chart_preset(
+    wiki_name: &str,
+    base_path: &PathBuf,
+    preset: &TypeChartPreset,
+    conn: &Pool<Sqlite>,
+) -> WikiGenResult<CalculatedDefenses> {
+    let used_types = get_used_types(conn).await?;
+    let type_combinations = get_type_combinations(conn).await?;
+
+    let (wiki_types, matchup_map) =
+        preset_types_and_matchups(wiki_name, base_path, preset, &used_types);
+    let calculated_defenses = save_types_and_matchups(
+        wiki_name,
+        base_path,
+        &wiki_types,
+        &matchup_map,
+        &type_combinations,
+    )?;
+    update_type_chart(wiki_name, base_path, &TypeChart { preset: *preset })?;
+
+    Ok(calculated_defenses)
+}
+
+#[tauri::command]
+pub async fn set_type_chart_preset(
+    wiki_name: &str,
+    preset: TypeChartPreset,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+    let resources_path = get_resource_dir(&app_handle)?;
+    let conn = session.pool.clone();
+
+    let previous_defenses = match get_type_combinations(&conn).await {
+        Ok(type_combinations) => current_defenses(wiki_name, &base_path, &type_combinations),
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    let calculated_defenses =
+        match apply_type_chart_preset(wiki_name, &base_path, &preset, &conn).await {
+            Ok(calculated_defenses) => calculated_defenses,
+            Err(err) => {
+                logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+                return Err(err);
+            }
+        };
+
+    let regenerated_count = match regenerate_changed_pokemon_pages(
+        &session,
+        &resources_path,
+        &previous_defenses,
+        &calculated_defenses,
+    )
+    .await
+    {
+        Ok(regenerated_count) => regenerated_count,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    if regenerated_count == 0 {
+        return Ok("Type chart updated. No pokemon pages affected".to_string());
+    }
+
+    Ok(format!(
+        "Type chart updated. {} pokemon pages regenerated",
+        regenerated_count
+    ))
+}
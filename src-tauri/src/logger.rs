@@ -1,4 +1,4 @@
-use std::{io::Write, path::PathBuf};
+use std::{fmt::Display, io::Write, path::PathBuf};
 
 #[derive(Debug)]
 pub enum LogLevel {
@@ -8,7 +8,7 @@ pub enum LogLevel {
     MigrationSuccess,
 }
 
-pub fn write_log(wiki_path: &PathBuf, log_level: LogLevel, message: &str) {
+pub fn write_log(wiki_path: &PathBuf, log_level: LogLevel, message: &(impl Display + ?Sized)) {
     let log_directory = wiki_path.join("logs");
     if !log_directory.try_exists().unwrap_or(false) {
         std::fs::create_dir_all(&log_directory).expect("Failed to create log directory");
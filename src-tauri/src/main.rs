@@ -1,32 +1,64 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+mod data_transfer;
 mod database;
+mod errors;
 mod helpers;
 mod logger;
 mod migrations;
 mod page_generators;
+mod static_site;
 mod structs;
 mod tests;
 mod wiki_preparation;
 
+use data_transfer::decomp::{import_decomp_species, preview_decomp_import};
+use data_transfer::randomizer_log::import_randomizer_log;
+use data_transfer::tabular::{export_table, import_table, preview_table_import};
 use database::load_token;
-use helpers::mkdocs_process::{check_process_status, kill_mkdocs_process, spawn_mkdocs_process};
+use database::session::{close_wiki_session, open_wiki_session, WikiSessionState};
+use helpers::mkdocs_process::{
+    check_process_status, kill_mkdocs_process, spawn_mkdocs_process, MkdocsProcessState,
+};
+use helpers::types::{
+    add_type, get_type_chart_preset, remove_type, rename_type, set_type_chart_preset,
+    update_type_matchup,
+};
 use page_generators::ability_page::generate_ability_page_with_handle;
+use page_generators::changelog_page::generate_changelog_page_with_handle;
 use page_generators::game_routes::{
     delete_route_page_from_mkdocs, generate_route_pages_with_handle,
 };
+use page_generators::home_page::generate_home_page_with_handle;
 use page_generators::item_page::{
     generate_item_changes_page_with_handle, generate_item_location_page_with_handle,
 };
+use page_generators::machine_page::generate_machine_page_with_handle;
+use page_generators::move_page::generate_move_page_with_handle;
 use page_generators::nature_page::generate_nature_page_with_handle;
 use page_generators::pokemon_pages::{
     generate_pokemon_pages_from_list, remove_pokemon_page_with_old_dex_number,
     update_pokemon_pages_with_stripped_name_with_handle,
 };
+use page_generators::type_chart_page::generate_type_chart_page_with_handle;
+use static_site::preview_server::PreviewServerState;
+use static_site::{build_static_site, preview_static_site};
 use tauri_plugin_sql;
 use wiki_preparation::backup_wiki::backup_wiki;
 use wiki_preparation::commit_wiki_changes::commit_wiki_changes;
 use wiki_preparation::create_wiki::create_wiki;
+use wiki_preparation::lint_wiki::lint_wiki;
+use wiki_preparation::reconcile_wiki::reconcile_wiki;
+use wiki_preparation::regenerate_wiki::{
+    cancel_wiki_regeneration, regenerate_wiki, RegenerationState,
+};
+use wiki_preparation::wiki_bundle::{export_wiki, import_wiki};
+use wiki_preparation::wiki_pages::{
+    create_custom_page, delete_custom_page, get_custom_pages, get_nav_layout, update_custom_page,
+    update_nav_order,
+};
+use wiki_preparation::wiki_plugins::{get_plugin_packages, get_wiki_plugins, update_wiki_plugins};
+use wiki_preparation::wiki_theme::{get_wiki_theme, update_wiki_theme, upload_theme_image};
 
 use migrations::check_and_run_migrations;
 use wiki_preparation::yaml_declaration::update_yaml;
@@ -42,6 +74,10 @@ fn main() {
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_cli::init())
         .plugin(tauri_plugin_process::init())
+        .manage(WikiSessionState::default())
+        .manage(RegenerationState::default())
+        .manage(PreviewServerState::default())
+        .manage(MkdocsProcessState::default())
         .invoke_handler(tauri::generate_handler![
             create_wiki,
             load_token,
@@ -55,12 +91,51 @@ fn main() {
             commit_wiki_changes,
             generate_item_changes_page_with_handle,
             generate_nature_page_with_handle,
+            generate_home_page_with_handle,
+            generate_changelog_page_with_handle,
             generate_ability_page_with_handle,
+            generate_move_page_with_handle,
+            generate_machine_page_with_handle,
+            generate_type_chart_page_with_handle,
             update_yaml,
             delete_route_page_from_mkdocs,
             generate_item_location_page_with_handle,
             remove_pokemon_page_with_old_dex_number,
             update_pokemon_pages_with_stripped_name_with_handle,
+            add_type,
+            rename_type,
+            remove_type,
+            update_type_matchup,
+            get_type_chart_preset,
+            set_type_chart_preset,
+            open_wiki_session,
+            close_wiki_session,
+            export_wiki,
+            import_wiki,
+            export_table,
+            preview_table_import,
+            import_table,
+            import_randomizer_log,
+            preview_decomp_import,
+            import_decomp_species,
+            lint_wiki,
+            reconcile_wiki,
+            regenerate_wiki,
+            cancel_wiki_regeneration,
+            build_static_site,
+            preview_static_site,
+            get_wiki_theme,
+            update_wiki_theme,
+            upload_theme_image,
+            get_wiki_plugins,
+            update_wiki_plugins,
+            get_plugin_packages,
+            get_custom_pages,
+            create_custom_page,
+            update_custom_page,
+            delete_custom_page,
+            get_nav_layout,
+            update_nav_order,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
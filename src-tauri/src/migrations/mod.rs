@@ -9,10 +9,15 @@ use std::{
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Pool, Sqlite};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 use crate::{
-    database::get_sqlite_connection,
+    database::{
+        change_journal::{create_change_journal, drop_change_journal_triggers},
+        get_app_data_dir, get_resource_dir, get_sqlite_connection,
+        session::{current_wiki_session, WikiSession},
+    },
+    errors::WikiGenResult,
     logger::{self, write_log, LogLevel},
     page_generators::game_routes::{RouteProperties, Routes, TrainerInfo, WildEncounter},
 };
@@ -113,13 +118,14 @@ impl Migration {
 }
 
 #[tauri::command]
-pub async fn check_and_run_migrations(app_handle: AppHandle) -> Result<String, String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
-    let resources_path = app_handle.path().resource_dir().unwrap();
+pub async fn check_and_run_migrations(app_handle: AppHandle) -> WikiGenResult<String> {
+    let base_path = get_app_data_dir(&app_handle)?;
+    let resources_path = get_resource_dir(&app_handle)?;
+    let session = current_wiki_session(&app_handle).await;
 
     let migrations = gather_migrations(&base_path, &resources_path)?;
     //Passing down resources for one-time sprite fix. Remove later
-    run_migrations(migrations, &base_path, &resources_path).await?;
+    run_migrations(migrations, &base_path, &resources_path, session.as_deref()).await?;
 
     Ok("Migration Completed".to_string())
 }
@@ -171,6 +177,7 @@ pub async fn run_migrations(
     migrations: Vec<Migration>,
     base_path: &PathBuf,
     resources_path: &PathBuf, //Passing down resources for one-time sprite fix
+    session: Option<&WikiSession>,
 ) -> Result<String, String> {
     let wiki_json_file_path = base_path.join("wikis.json");
     let wikis_file = match File::open(&wiki_json_file_path) {
@@ -189,21 +196,21 @@ pub async fn run_migrations(
             return Err(format!("Wiki path does not exist: {:?}", wiki_path));
         }
 
-        let sqlite_file_path = wiki_path.join(format!("{}.db", wiki_name));
-
-        // Create backup
-        if let Err(err) = std::fs::copy(
-            sqlite_file_path.clone(),
-            wiki_path.join(format!("{}.db.bak", wiki_name)),
-        ) {
-            logger::write_log(
-                &wiki_path,
-                logger::LogLevel::MigrationError,
-                &format!("Failed to create backup: {}", err),
-            );
+        // The selected wiki is migrated through its session's pool, the
+        // others get a pool of their own for the duration
+        if let Some(session) = session.filter(|session| session.wiki_name == *wiki_name) {
+            run_wiki_migrations(
+                wiki_name,
+                &session.pool,
+                &migrations,
+                base_path,
+                resources_path,
+            )
+            .await?;
             continue;
-        };
+        }
 
+        let sqlite_file_path = wiki_path.join(format!("{}.db", wiki_name));
         let conn = match get_sqlite_connection(sqlite_file_path).await {
             Ok(conn) => conn,
             Err(err) => {
@@ -215,114 +222,158 @@ pub async fn run_migrations(
                 continue;
             }
         };
+        let result =
+            run_wiki_migrations(wiki_name, &conn, &migrations, base_path, resources_path).await;
+        conn.close().await;
+        result?;
+    }
 
-        let existing_migrations = match sqlx::query_as::<_, Migration>("SELECT * FROM migrations")
-            .fetch_all(&conn)
-            .await
-        {
-            Ok(mut migrations) => {
-                migrations.sort_by(|a, b| a.execution_order.cmp(&b.execution_order));
-                let existing_migration_names = migrations
-                    .iter()
-                    .map(|m| m.name.clone())
-                    .collect::<Vec<String>>();
-                existing_migration_names
-            }
-            Err(err) => {
-                if err.to_string().contains("no such table") {
-                    create_migrations_table(&conn).await?;
-                    let existing_migration_names = Vec::new();
-                    existing_migration_names
-                } else {
-                    logger::write_log(
-                        &wiki_path,
-                        logger::LogLevel::MigrationError,
-                        &format!("Failed to fetch existing migrations: {}", err),
-                    );
-                    continue;
-                }
-            }
-        };
+    Ok("Migrations Successful".to_string())
+}
 
-        for migration in migrations.iter() {
-            // We check if the migration has already been executed
-            // If it has, we skip it
-            if existing_migrations.contains(&migration.name) {
-                continue;
-            }
+// Brings a single wiki up to date. Failures are logged to the wiki's
+// migration log and leave the remaining migrations for the next run.
+pub async fn run_wiki_migrations(
+    wiki_name: &str,
+    conn: &Pool<Sqlite>,
+    migrations: &[Migration],
+    base_path: &PathBuf,
+    resources_path: &PathBuf, //Passing down resources for one-time sprite fix
+) -> Result<(), String> {
+    let wiki_path = base_path.join(wiki_name);
 
-            if let Err(err) = migration.execute_migration(&wiki_path, &conn).await {
+    let sqlite_file_path = wiki_path.join(format!("{}.db", wiki_name));
+
+    // Create backup
+    if let Err(err) = std::fs::copy(
+        &sqlite_file_path,
+        wiki_path.join(format!("{}.db.bak", wiki_name)),
+    ) {
+        logger::write_log(
+            &wiki_path,
+            logger::LogLevel::MigrationError,
+            &format!("Failed to create backup: {}", err),
+        );
+        return Ok(());
+    };
+
+    let existing_migrations = match sqlx::query_as::<_, Migration>("SELECT * FROM migrations")
+        .fetch_all(conn)
+        .await
+    {
+        Ok(mut migrations) => {
+            migrations.sort_by(|a, b| a.execution_order.cmp(&b.execution_order));
+            let existing_migration_names = migrations
+                .iter()
+                .map(|m| m.name.clone())
+                .collect::<Vec<String>>();
+            existing_migration_names
+        }
+        Err(err) => {
+            if err.to_string().contains("no such table") {
+                create_migrations_table(conn).await?;
+                let existing_migration_names = Vec::new();
+                existing_migration_names
+            } else {
                 logger::write_log(
                     &wiki_path,
                     logger::LogLevel::MigrationError,
-                    &format!("Failed to execute migration {}: {}", migration.name, err),
+                    &format!("Failed to fetch existing migrations: {}", err),
                 );
-                continue;
+                return Ok(());
             }
         }
+    };
 
-        // Bespoke migration for sprite updates.
-        if let Err(err) = std::fs::copy(
-            resources_path
-                .join("resources")
-                .join("generator_assets")
-                .join("pokemon_sprites")
-                .join("mega-diancie.png"),
-            wiki_path
-                .join("dist")
-                .join("docs")
-                .join("img")
-                .join("pokemon")
-                .join("mega-diancie.png"),
-        ) {
-            logger::write_log(
-                &wiki_path,
-                logger::LogLevel::MigrationError,
-                &format!("Failed to update sprites: {}", err),
-            );
+    // The journal triggers name columns the migrations add, rename and drop,
+    // so they're recreated once the schema is current
+    if let Err(err) = drop_change_journal_triggers(conn).await {
+        logger::write_log(&wiki_path, logger::LogLevel::MigrationError, &err);
+        return Ok(());
+    }
+
+    for migration in migrations.iter() {
+        // We check if the migration has already been executed
+        // If it has, we skip it
+        if existing_migrations.contains(&migration.name) {
             continue;
-        };
+        }
 
-        if let Err(err) = std::fs::copy(
-            resources_path
-                .join("resources")
-                .join("generator_assets")
-                .join("pokemon_sprites")
-                .join("primal-groudon.png"),
-            wiki_path
-                .join("dist")
-                .join("docs")
-                .join("img")
-                .join("pokemon")
-                .join("primal-groudon.png"),
-        ) {
+        if let Err(err) = migration.execute_migration(&wiki_path, conn).await {
             logger::write_log(
                 &wiki_path,
                 logger::LogLevel::MigrationError,
-                &format!("Failed to update sprites: {}", err),
+                &format!("Failed to execute migration {}: {}", migration.name, err),
             );
             continue;
-        };
-
-        match update_route_properties(&base_path, &wiki_name).await {
-            Ok(_) => (),
-            Err(err) => {
-                logger::write_log(
-                    &wiki_path,
-                    logger::LogLevel::MigrationError,
-                    &format!("Failed to update route properties: {}", err),
-                );
-            }
         }
+    }
 
+    if let Err(err) = create_change_journal(conn).await {
+        logger::write_log(&wiki_path, logger::LogLevel::MigrationError, &err);
+    }
+
+    // Bespoke migration for sprite updates.
+    if let Err(err) = std::fs::copy(
+        resources_path
+            .join("resources")
+            .join("generator_assets")
+            .join("pokemon_sprites")
+            .join("mega-diancie.png"),
+        wiki_path
+            .join("dist")
+            .join("docs")
+            .join("img")
+            .join("pokemon")
+            .join("mega-diancie.png"),
+    ) {
+        logger::write_log(
+            &wiki_path,
+            logger::LogLevel::MigrationError,
+            &format!("Failed to update sprites: {}", err),
+        );
+        return Ok(());
+    };
+
+    if let Err(err) = std::fs::copy(
+        resources_path
+            .join("resources")
+            .join("generator_assets")
+            .join("pokemon_sprites")
+            .join("primal-groudon.png"),
+        wiki_path
+            .join("dist")
+            .join("docs")
+            .join("img")
+            .join("pokemon")
+            .join("primal-groudon.png"),
+    ) {
         logger::write_log(
             &wiki_path,
-            logger::LogLevel::MigrationSuccess,
-            &format!("Migrations successful"),
+            logger::LogLevel::MigrationError,
+            &format!("Failed to update sprites: {}", err),
         );
+        return Ok(());
+    };
+
+    match update_route_properties(&base_path, &wiki_name).await {
+        Ok(_) => (),
+        Err(err) => {
+            logger::write_log(
+                &wiki_path,
+                logger::LogLevel::MigrationError,
+                &format!("Failed to update route properties: {}", err),
+            );
+        }
     }
 
-    Ok("Migrations Successful".to_string())
+    logger::write_log(
+        &wiki_path,
+        logger::LogLevel::MigrationSuccess,
+        &format!("Migrations successful"),
+    );
+
+    Ok(())
 }
 
 async fn update_route_properties(base_path: &PathBuf, wiki_name: &str) -> Result<(), String> {
@@ -1,66 +1,49 @@
 use std::io::Write;
 
 use serde_yaml::{Mapping, Value};
-use sqlx::FromRow;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 use crate::{
     database::{
-        create_docs_file, get_mkdocs_config, get_sqlite_connection, page_exists_in_mkdocs,
-        remove_docs_file, update_mkdocs_yaml,
+        create_docs_file, page_exists_in_mkdocs, remove_docs_file,
+        repository::get_abilities,
+        session::{get_wiki_session, WikiSession},
     },
+    errors::{WikiGenError, WikiGenResult},
     helpers::{capitalize_and_remove_hyphens, FALSE, TRUE},
     logger::{self, write_log, LogLevel},
+    structs::page_models::Ability,
+    wiki_preparation::wiki_pages::get_nav_order,
 };
 
-#[derive(Debug, Clone, FromRow)]
-pub struct Ability {
-    pub name: String,
-    pub effect: String,
-    pub is_modified: i32,
-    pub is_new: i32,
-}
-
 #[tauri::command]
 pub async fn generate_ability_page_with_handle(
     wiki_name: &str,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
 
-    let sqlite_file_path = base_path.join(wiki_name).join(format!("{}.db", wiki_name));
-    let conn = match get_sqlite_connection(sqlite_file_path).await {
-        Ok(conn) => conn,
-        Err(err) => {
-            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
-            return Err(err);
-        }
-    };
+    let conn = session.pool.clone();
 
-    let abilities = match sqlx::query_as::<_, Ability>("SELECT * FROM abilities")
-        .fetch_all(&conn)
-        .await
-    {
+    let abilities = match get_abilities(&conn).await {
         Ok(abilities) => abilities,
         Err(err) => {
-            let message = format!("Failed to get abilities: {}", err);
-            logger::write_log(
-                &base_path.join(wiki_name),
-                logger::LogLevel::Error,
-                &message,
-            );
-            return Err(message);
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
         }
     };
 
-    return generate_ability_page(wiki_name, &abilities, &base_path);
+    return generate_ability_page(&session, &abilities);
 }
 
 pub fn generate_ability_page(
-    wiki_name: &str,
+    session: &WikiSession,
     abilities: &[Ability],
-    base_path: &std::path::PathBuf,
-) -> Result<String, String> {
+) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+
     let mut ability_changes_markdown = String::new();
     let mut ability_new = String::new();
     let mut ability_modified = String::new();
@@ -108,8 +91,7 @@ pub fn generate_ability_page(
         ability_changes_markdown.push_str(&entry);
     }
 
-    let mkdocs_yaml_file_path = base_path.join(wiki_name).join("dist").join("mkdocs.yml");
-    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+    let mut mkdocs_config = match session.mkdocs_config() {
         Ok(config) => config,
         Err(err) => {
             logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
@@ -117,7 +99,8 @@ pub fn generate_ability_page(
         }
     };
 
-    let (page_exists, page_index) = page_exists_in_mkdocs(mkdocs_config.clone(), "Ability Changes");
+    let (page_exists, page_index) =
+        page_exists_in_mkdocs(mkdocs_config.clone(), "Ability Changes")?;
 
     if ability_changes_markdown.is_empty() {
         if !page_exists {
@@ -126,13 +109,9 @@ pub fn generate_ability_page(
 
         remove_docs_file(wiki_name, base_path, "ability_changes.md")?;
 
-        mkdocs_config
-            .nav
-            .as_sequence_mut()
-            .unwrap()
-            .remove(page_index);
+        mkdocs_config.nav_entries_mut()?.remove(page_index);
 
-        update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+        session.update_mkdocs_config(&mkdocs_config)?;
 
         return Ok("No Ability changes to generate. Ability Changes page removed".to_string());
     }
@@ -142,9 +121,12 @@ pub fn generate_ability_page(
     if let Err(err) =
         ability_changes_file.write_all(format!("{}", ability_changes_markdown).as_bytes())
     {
-        let message = format!("{wiki_name}: Failed to write ability changes file: {err}");
-        write_log(&base_path, LogLevel::Error, &message);
-        return Err(message);
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to write ability changes file: {err}"),
+            "ability_changes.md",
+        );
+        write_log(&base_path, LogLevel::Error, &error);
+        return Err(error);
     }
 
     if page_exists {
@@ -157,13 +139,10 @@ pub fn generate_ability_page(
         Value::String("ability_changes.md".to_string()),
     );
 
-    mkdocs_config
-        .nav
-        .as_sequence_mut()
-        .unwrap()
-        .insert(1, Value::Mapping(ability_changes));
+    let nav_order = get_nav_order(&base_path.join(wiki_name))?;
+    mkdocs_config.insert_nav_entry(Value::Mapping(ability_changes), &nav_order)?;
 
-    update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
 
     Ok("Abilities Page Generated".to_string())
 }
@@ -0,0 +1,418 @@
+// Writes changelog.md from the change journal, grouped by the date each
+// batch of edits was published, e.g. "Gengar: SpA 130→120"
+use std::{collections::HashSet, fs, path::Path};
+
+use indexmap::IndexMap;
+use serde_yaml::{Mapping, Value};
+use tauri::AppHandle;
+
+use crate::{
+    database::{
+        change_journal::{
+            get_journal_entries, insert_journal_entries, is_column_field, publish_journal_entries,
+            unpublish_journal_entries, JournalEntry,
+        },
+        get_routes,
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::capitalize_and_remove_hyphens,
+    logger::{self, LogLevel},
+    page_generators::game_routes::{RouteProperties, Routes},
+    structs::mkdocs_structs::nav_entry_title,
+    wiki_preparation::wiki_pages::get_nav_order,
+};
+
+pub const CHANGELOG_FILE: &str = "changelog.md";
+const CHANGELOG_TITLE: &str = "Changelog";
+// routes.json as of the last time its changes were recorded
+const ROUTES_SNAPSHOT_FILE: &str = "changelog_routes.json";
+
+// Section order on the page, with the heading for each subject
+const SUBJECT_HEADINGS: [(&str, &str); 6] = [
+    ("pokemon", "Pokemon"),
+    ("moves", "Moves"),
+    ("abilities", "Abilities"),
+    ("items", "Items"),
+    ("natures", "Natures"),
+    ("routes", "Routes"),
+];
+
+const STAT_LABELS: [(&str, &str); 6] = [
+    ("hp", "HP"),
+    ("attack", "Atk"),
+    ("defense", "Def"),
+    ("sp_attack", "SpA"),
+    ("sp_defense", "SpD"),
+    ("speed", "Spe"),
+];
+
+#[tauri::command]
+pub async fn generate_changelog_page_with_handle(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    let result = match record_route_changes(&session).await {
+        Ok(()) => generate_changelog_page(&session).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = &result {
+        logger::write_log(&session.wiki_path, LogLevel::Error, err);
+    }
+    result
+}
+
+pub async fn generate_changelog_page(session: &WikiSession) -> WikiGenResult<String> {
+    let entries = get_journal_entries(&session.pool).await?;
+    let changelog_path = session.docs_path.join(CHANGELOG_FILE);
+    let mut mkdocs_config = session.mkdocs_config()?;
+    let nav_entries = mkdocs_config.nav_entries_mut()?;
+    let page_position = nav_entries
+        .iter()
+        .position(|entry| nav_entry_title(entry) == Some(CHANGELOG_TITLE));
+
+    let Some(markdown) = changelog_markdown(&entries) else {
+        // Nothing to show, same as the other change pages
+        if let Some(index) = page_position {
+            nav_entries.remove(index);
+            session.update_mkdocs_config(&mkdocs_config)?;
+        }
+        if changelog_path.try_exists().unwrap_or(false) {
+            fs::remove_file(&changelog_path).map_err(|err| {
+                WikiGenError::io(
+                    format!("Failed to remove changelog: {err}"),
+                    changelog_path.display(),
+                )
+            })?;
+        }
+        return Ok("No changes recorded yet".to_string());
+    };
+
+    fs::write(&changelog_path, markdown).map_err(|err| {
+        WikiGenError::io(
+            format!("Failed to write changelog: {err}"),
+            changelog_path.display(),
+        )
+    })?;
+    if page_position.is_none() {
+        let mut changelog_entry = Mapping::new();
+        changelog_entry.insert(
+            Value::String(CHANGELOG_TITLE.to_string()),
+            Value::String(CHANGELOG_FILE.to_string()),
+        );
+        let nav_order = get_nav_order(&session.wiki_path)?;
+        mkdocs_config.insert_nav_entry(Value::Mapping(changelog_entry), &nav_order)?;
+        session.update_mkdocs_config(&mkdocs_config)?;
+    }
+
+    Ok("Changelog generated".to_string())
+}
+
+// Stamps the unpublished edits with today's date and rewrites the page so
+// they show under it. Returns the stamped entries for unpublish_changelog.
+pub async fn publish_changelog(session: &WikiSession) -> WikiGenResult<Vec<i64>> {
+    record_route_changes(session).await?;
+    let published_at = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let entry_ids = publish_journal_entries(&session.pool, &published_at).await?;
+    generate_changelog_page(session).await?;
+    Ok(entry_ids)
+}
+
+// Undoes publish_changelog when the commit it was for failed, so the edits
+// go out with the next one
+pub async fn unpublish_changelog(session: &WikiSession, entry_ids: &[i64]) -> WikiGenResult<()> {
+    if entry_ids.is_empty() {
+        return Ok(());
+    }
+    unpublish_journal_entries(&session.pool, entry_ids).await?;
+    generate_changelog_page(session).await?;
+    Ok(())
+}
+
+// Adds what changed in routes.json since the last snapshot to the journal.
+// The first run only takes the snapshot, so existing routes aren't all
+// reported as new.
+pub async fn record_route_changes(session: &WikiSession) -> WikiGenResult<()> {
+    let data_path = session.wiki_path.join("data");
+    let routes_path = data_path.join("routes.json");
+    let snapshot_path = data_path.join(ROUTES_SNAPSHOT_FILE);
+    if !routes_path.try_exists().unwrap_or(false) {
+        return Ok(());
+    }
+
+    if snapshot_path.try_exists().unwrap_or(false) {
+        let old_routes = get_routes(&snapshot_path)?;
+        let new_routes = get_routes(&routes_path)?;
+        let entries = diff_routes(&old_routes, &new_routes);
+        if !entries.is_empty() {
+            insert_journal_entries(&session.pool, &entries).await?;
+        }
+    }
+    copy_snapshot(&routes_path, &snapshot_path)
+}
+
+fn copy_snapshot(routes_path: &Path, snapshot_path: &Path) -> WikiGenResult<()> {
+    match fs::copy(routes_path, snapshot_path) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::io(
+            format!("Failed to save routes snapshot: {err}"),
+            snapshot_path.display(),
+        )),
+    }
+}
+
+pub fn diff_routes(old_routes: &Routes, new_routes: &Routes) -> Vec<JournalEntry> {
+    let mut entries = Vec::new();
+    for (route_name, new_route) in &new_routes.routes {
+        match old_routes.routes.get(route_name) {
+            Some(old_route) => diff_route(route_name, old_route, new_route, &mut entries),
+            None => entries.push(JournalEntry::new(
+                "routes",
+                route_name,
+                "",
+                None,
+                Some(String::new()),
+            )),
+        }
+    }
+    for route_name in old_routes.routes.keys() {
+        if !new_routes.routes.contains_key(route_name) {
+            entries.push(JournalEntry::new(
+                "routes",
+                route_name,
+                "",
+                Some(String::new()),
+                None,
+            ));
+        }
+    }
+    entries
+}
+
+fn diff_route(
+    route_name: &str,
+    old_route: &RouteProperties,
+    new_route: &RouteProperties,
+    entries: &mut Vec<JournalEntry>,
+) {
+    let changes = [
+        (encounter_rates(old_route), encounter_rates(new_route)),
+        (trainer_teams(old_route), trainer_teams(new_route)),
+    ];
+    for (old_values, new_values) in changes {
+        for (field, new_value) in &new_values {
+            let old_value = old_values.get(field);
+            if old_value != Some(new_value) {
+                entries.push(JournalEntry::new(
+                    "routes",
+                    route_name,
+                    field,
+                    old_value.cloned(),
+                    Some(new_value.clone()),
+                ));
+            }
+        }
+        for (field, old_value) in &old_values {
+            if !new_values.contains_key(field) {
+                entries.push(JournalEntry::new(
+                    "routes",
+                    route_name,
+                    field,
+                    Some(old_value.clone()),
+                    None,
+                ));
+            }
+        }
+    }
+}
+
+// "Mareep (grass)" to "10%"
+fn encounter_rates(route: &RouteProperties) -> IndexMap<String, String> {
+    route
+        .wild_encounters
+        .iter()
+        .map(|encounter| {
+            let area = match encounter.route_variant.as_str() {
+                "" | "default" => encounter.encounter_area.clone(),
+                variant => format!("{}, {}", encounter.encounter_area, variant),
+            };
+            (
+                format!(
+                    "{} ({})",
+                    capitalize_and_remove_hyphens(&encounter.name),
+                    area
+                ),
+                format!("{}%", encounter.encounter_rate),
+            )
+        })
+        .collect()
+}
+
+// "Trainer Joey" to "Rattata 5, Pidgey 7"
+fn trainer_teams(route: &RouteProperties) -> IndexMap<String, String> {
+    route
+        .trainers
+        .iter()
+        .map(|(trainer_name, trainer)| {
+            let team: Vec<String> = trainer
+                .pokemon_team
+                .iter()
+                .map(|pokemon| {
+                    format!(
+                        "{} {}",
+                        capitalize_and_remove_hyphens(&pokemon.name),
+                        pokemon.level
+                    )
+                })
+                .collect();
+            (format!("Trainer {trainer_name}"), team.join(", "))
+        })
+        .collect()
+}
+
+// None when there is nothing to report once edits that cancel out are dropped
+pub fn changelog_markdown(entries: &[JournalEntry]) -> Option<String> {
+    let mut unpublished = Vec::new();
+    let mut published: IndexMap<&str, Vec<&JournalEntry>> = IndexMap::new();
+    for entry in entries {
+        match &entry.published_at {
+            Some(published_at) => published
+                .entry(published_at.as_str())
+                .or_default()
+                .push(entry),
+            None => unpublished.push(entry),
+        }
+    }
+    published.sort_by(|date, _, other_date, _| other_date.cmp(date));
+
+    let mut groups = Vec::new();
+    if !unpublished.is_empty() {
+        groups.push(("Unreleased", unpublished));
+    }
+    groups.extend(published);
+
+    let mut markdown = String::new();
+    for (heading, group) in groups {
+        let condensed = condense_entries(&group);
+        if condensed.is_empty() {
+            continue;
+        }
+        markdown.push_str(&format!("## {heading}\n\n"));
+        for (subject, subject_heading) in SUBJECT_HEADINGS {
+            let lines: Vec<String> = condensed
+                .iter()
+                .filter(|entry| entry.subject == subject)
+                .map(describe_entry)
+                .collect();
+            if lines.is_empty() {
+                continue;
+            }
+            markdown.push_str(&format!("### {subject_heading}\n\n"));
+            for line in lines {
+                markdown.push_str(&format!("- {line}\n"));
+            }
+            markdown.push('\n');
+        }
+    }
+
+    match markdown.is_empty() {
+        true => None,
+        false => Some(format!("# Changelog\n\n{}", markdown.trim_end()) + "\n"),
+    }
+}
+
+// Folds repeated edits to the same thing into one from the first old value
+// to the last new one, and drops those that end where they started. Rows
+// added or removed in the same batch only show that, not every field.
+pub fn condense_entries(entries: &[&JournalEntry]) -> Vec<JournalEntry> {
+    let mut condensed: IndexMap<(String, String, String, String), JournalEntry> = IndexMap::new();
+    for entry in entries {
+        let key = (
+            entry.subject.clone(),
+            entry.name.clone(),
+            entry.field.clone(),
+            entry_identity(entry),
+        );
+        match condensed.get_mut(&key) {
+            Some(existing) => existing.new_value = entry.new_value.clone(),
+            None => {
+                condensed.insert(key, (*entry).clone());
+            }
+        }
+    }
+
+    let whole_rows: HashSet<(String, String)> = condensed
+        .values()
+        .filter(|entry| entry.field.is_empty() && entry.old_value != entry.new_value)
+        .map(|entry| (entry.subject.clone(), entry.name.clone()))
+        .collect();
+
+    condensed
+        .into_values()
+        .filter(|entry| entry.old_value != entry.new_value)
+        .filter(|entry| {
+            entry.field.is_empty()
+                || !whole_rows.contains(&(entry.subject.clone(), entry.name.clone()))
+        })
+        .collect()
+}
+
+// Moves are journaled as "shadow-ball (level-up 30)", so a level change is
+// the same move and learn method with a different value
+fn entry_identity(entry: &JournalEntry) -> String {
+    if entry.subject != "pokemon" || entry.field != "move" {
+        return String::new();
+    }
+    let value = entry
+        .old_value
+        .as_deref()
+        .or(entry.new_value.as_deref())
+        .unwrap_or_default();
+    match value.rsplit_once(' ') {
+        Some((identity, level))
+            if level
+                .trim_end_matches(')')
+                .chars()
+                .all(|c| c.is_ascii_digit()) =>
+        {
+            identity.to_string()
+        }
+        _ => value.to_string(),
+    }
+}
+
+pub fn describe_entry(entry: &JournalEntry) -> String {
+    let name = match entry.subject.as_str() {
+        "routes" => entry.name.clone(),
+        _ => capitalize_and_remove_hyphens(&entry.name),
+    };
+    let label = match STAT_LABELS.iter().find(|(field, _)| *field == entry.field) {
+        Some((_, label)) => label.to_string(),
+        None if is_column_field(&entry.subject, &entry.field) => {
+            capitalize_and_remove_hyphens(&entry.field.replace('_', "-"))
+        }
+        // Moves already name themselves in the value
+        None if entry.field == "move" => String::new(),
+        None => entry.field.clone(),
+    };
+
+    let change = if is_column_field(&entry.subject, &entry.field) {
+        format!(
+            "{label} {}→{}",
+            entry.old_value.as_deref().unwrap_or("-"),
+            entry.new_value.as_deref().unwrap_or("-")
+        )
+    } else {
+        match (&entry.old_value, &entry.new_value) {
+            (None, Some(new_value)) => format!("added {label} {new_value}"),
+            (Some(old_value), None) => format!("removed {label} {old_value}"),
+            (Some(old_value), Some(new_value)) => format!("{label} {old_value}→{new_value}"),
+            (None, None) => format!("changed {label}"),
+        }
+    };
+
+    let change = change.split_whitespace().collect::<Vec<&str>>().join(" ");
+    format!("{name}: {change}")
+}
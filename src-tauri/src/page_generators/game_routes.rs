@@ -1,20 +1,33 @@
 use std::{
     cmp::Ordering,
-    fs::{self, read_to_string, File},
+    collections::HashMap,
+    fs::{self, File},
     io::Write,
     path::PathBuf,
+    sync::Arc,
 };
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_yaml::{Mapping, Value};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 use crate::{
-    database::{get_mkdocs_config, get_routes, update_mkdocs_yaml},
-    helpers::{capitalize_and_remove_hyphens, get_pokemon_dex_formatted_name},
+    database::{
+        get_resource_dir, get_routes, read_template,
+        repository::get_move_types,
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::{
+        capitalize_and_remove_hyphens, get_pokemon_dex_formatted_name,
+        matchups::{get_matchup_table, MatchupTable},
+    },
     logger::{self, write_log, LogLevel},
-    structs::mkdocs_structs::MKDocsConfig,
+    structs::{
+        mkdocs_structs::{nav_entry_title, MKDocsConfig},
+        page_models::MoveType,
+    },
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -43,11 +56,27 @@ pub struct TrainerInfo {
 
 impl TrainerInfo {
     fn generate_markdown(
+        wiki_path: &PathBuf,
         trainers: &IndexMap<String, TrainerInfo>,
         trainer_pokemon_template: String,
+        type_coverage: &TypeCoverage,
     ) -> String {
         let mut markdown_trainers = String::new();
         for (name, trainer_info) in trainers {
+            // The card is still worth showing without its summary
+            let team_summary = |version: &str, indentation: &str| match trainer_info
+                .generate_team_summary(version, type_coverage, indentation)
+            {
+                Ok(team_summary) => team_summary,
+                Err(err) => {
+                    logger::write_log(
+                        wiki_path,
+                        logger::LogLevel::Error,
+                        &format!("Failed to summarize {}'s team: {}", name, err),
+                    );
+                    String::new()
+                }
+            };
             let trainer_sprite = match !trainer_info.sprite.is_empty() {
                 true => {
                     format!(
@@ -61,8 +90,9 @@ impl TrainerInfo {
 
             if trainer_info.versions.is_empty() {
                 trainer_entry = format!(
-                    "<div class=\"trainer-pokemon-container\">\n{}</div>",
-                    trainer_info.generate_entry("", &trainer_pokemon_template)
+                    "<div class=\"trainer-pokemon-container\">\n{}</div>{}",
+                    trainer_info.generate_entry("", &trainer_pokemon_template),
+                    team_summary("", "\t\t")
                 );
             } else {
                 for version in &trainer_info.versions {
@@ -82,8 +112,9 @@ impl TrainerInfo {
 
                     let version_title = format!("\n\n\t\t=== \"{version}\"");
                     let entry = format!(
-                        "\t<div class=\"trainer-pokemon-container\">\n{}</div>",
-                        trainer_info.generate_entry(version, &trainer_pokemon_template)
+                        "\t<div class=\"trainer-pokemon-container\">\n{}</div>{}",
+                        trainer_info.generate_entry(version, &trainer_pokemon_template),
+                        team_summary(version, "\t\t\t")
                     );
                     trainer_entry.push_str(&format!("{version_title}\n\t\t{entry}"));
                 }
@@ -105,7 +136,7 @@ impl TrainerInfo {
             let mut item_image = "<div></div>".to_string();
             if !pokemon.item.is_empty() {
                 item_image = format!(
-                    "<img src=\"../../img/items/{}.png\" alt={} style=\"width: 25px;\"/>",
+                    "<img src=\"../../img/items/{}.png\" alt=\"{}\" style=\"width: 25px;\"/>",
                     pokemon.item, pokemon.item
                 );
             }
@@ -120,16 +151,19 @@ impl TrainerInfo {
                 nature = capitalize_and_remove_hyphens(&pokemon.nature);
             }
 
-            let type_one = pokemon.types.get(0).unwrap().as_str();
-            let formatted_type_one = format!(
-                "<img src=\"../../img/types/{}.png\" alt={} style=\"width: 50px;\"/>",
-                type_one, type_one
-            );
+            // Pokemon picked in the route editor before their types loaded have none
+            let formatted_type_one = match pokemon.types.first() {
+                Some(type_one) => format!(
+                    "<img src=\"../../img/types/{}.png\" alt=\"{}\" style=\"width: 50px;\"/>",
+                    type_one, type_one
+                ),
+                None => "<div></div>".to_string(),
+            };
 
             let mut formatted_type_two = "<div></div>".to_string();
             if pokemon.types.len() > 1 {
                 formatted_type_two = format!(
-                    "<img src=\"../../img/types/{}.png\" alt={} style=\"width: 50px;\"/>",
+                    "<img src=\"../../img/types/{}.png\" alt=\"{}\" style=\"width: 50px;\"/>",
                     pokemon.types.get(1).unwrap(),
                     pokemon.types.get(1).unwrap()
                 );
@@ -177,6 +211,136 @@ impl TrainerInfo {
 
         return pokemon_team;
     }
+
+    // Summarizes what the team threatens and what threatens the team,
+    // so players can plan a counter without reading every card.
+    // The summary is left off the card when the matchups can't be worked out.
+    pub fn generate_team_summary(
+        &self,
+        version: &str,
+        type_coverage: &TypeCoverage,
+        indentation: &str,
+    ) -> Result<String, String> {
+        let Some(matchup_table) = &type_coverage.matchup_table else {
+            return Ok(String::new());
+        };
+
+        let team = self
+            .pokemon_team
+            .iter()
+            .filter(|pokemon| {
+                pokemon.trainer_versions.contains(&version.to_string()) || version == ""
+            })
+            .collect::<Vec<_>>();
+        if team.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut move_types: Vec<String> = Vec::new();
+        for pokemon in &team {
+            for move_name in &pokemon.moves {
+                // Moves of a type the wiki removed have no matchups
+                let Some(move_type) = type_coverage
+                    .move_types
+                    .get(move_name)
+                    .filter(|move_type| matchup_table.types().contains(&move_type.move_type))
+                else {
+                    continue;
+                };
+                if move_type.damage_class == "status" || move_types.contains(&move_type.move_type) {
+                    continue;
+                }
+                move_types.push(move_type.move_type.clone());
+            }
+        }
+
+        let mut super_effective_types: Vec<String> = Vec::new();
+        for defense_type in matchup_table.types() {
+            for move_type in &move_types {
+                if matchup_table.effectiveness(move_type, defense_type)? >= 2.0 {
+                    super_effective_types.push(defense_type.clone());
+                    break;
+                }
+            }
+        }
+
+        // Number of team members each attacking type is super effective against
+        let mut weakness_counts: IndexMap<String, usize> = matchup_table
+            .types()
+            .iter()
+            .map(|_type| (_type.clone(), 0))
+            .collect();
+        for pokemon in &team {
+            // Blank or unknown types have no matchups to look up
+            let pokemon_types = pokemon
+                .types
+                .iter()
+                .filter(|_type| matchup_table.types().contains(&_type.to_lowercase()))
+                .cloned()
+                .collect::<Vec<String>>();
+            let type_effectiveness = matchup_table.defensive_matchups(&pokemon_types)?;
+            for (effectiveness, attacking_types) in type_effectiveness.0 {
+                if effectiveness.parse::<f32>().unwrap_or(1.0) < 2.0 {
+                    continue;
+                }
+                for attacking_type in attacking_types {
+                    if let Some(count) = weakness_counts.get_mut(&attacking_type.to_lowercase()) {
+                        *count += 1;
+                    }
+                }
+            }
+        }
+
+        let shared_weaknesses = weakness_counts
+            .iter()
+            .filter(|(_, count)| **count >= 2)
+            .map(|(_type, count)| format!("{} x{}", get_type_image(_type), count))
+            .collect::<Vec<String>>();
+
+        // Counters are the types that hit the most team members, preferring
+        // ones the team can't hit back super effectively
+        let mut counter_types = weakness_counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .collect::<Vec<_>>();
+        counter_types.sort_by(|(first_type, first_count), (second_type, second_count)| {
+            second_count.cmp(first_count).then_with(|| {
+                super_effective_types
+                    .contains(first_type)
+                    .cmp(&super_effective_types.contains(second_type))
+            })
+        });
+        let counter_types = counter_types
+            .iter()
+            .take(3)
+            .map(|(_type, _)| get_type_image(_type))
+            .collect::<Vec<String>>();
+
+        let format_types = |types: Vec<String>| match types.is_empty() {
+            true => "-".to_string(),
+            false => types.join(" "),
+        };
+
+        return Ok(format!(
+            "\n{indentation}<div class=\"trainer-team-summary\"><p><strong>Hits super effectively:</strong> {}</p><p><strong>Shared weaknesses:</strong> {}</p><p><strong>Suggested counters:</strong> {}</p></div>",
+            format_types(
+                super_effective_types
+                    .iter()
+                    .map(|_type| get_type_image(_type))
+                    .collect()
+            ),
+            format_types(shared_weaknesses),
+            format_types(counter_types)
+        ));
+    }
+}
+
+// Everything the trainer team summaries need to work out type matchups
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TypeCoverage {
+    pub move_types: HashMap<String, MoveType>,
+    #[serde(skip)]
+    pub matchup_table: Option<Arc<MatchupTable>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -292,6 +456,7 @@ pub struct RouteGenerator {
     route_page_template: String,
     routes: Routes,
     mkdocs_config: MKDocsConfig,
+    type_coverage: TypeCoverage,
 }
 
 impl RouteGenerator {
@@ -301,7 +466,9 @@ impl RouteGenerator {
         resources_path: PathBuf,
         trainer_pokemon_template: String,
         route_page_template: String,
-    ) -> Result<Self, String> {
+        mkdocs_config: MKDocsConfig,
+        type_coverage: TypeCoverage,
+    ) -> WikiGenResult<Self> {
         let routes_json_file_path = base_path.join(&wiki_name).join("data").join("routes.json");
         let routes = match get_routes(&routes_json_file_path) {
             Ok(routes) => routes,
@@ -311,15 +478,6 @@ impl RouteGenerator {
             }
         };
 
-        let mkdocs_yaml_file_path = base_path.join(&wiki_name).join("dist").join("mkdocs.yml");
-        let mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
-            Ok(config) => config,
-            Err(err) => {
-                logger::write_log(&base_path.join(&wiki_name), logger::LogLevel::Error, &err);
-                return Err(err);
-            }
-        };
-
         let docs_path = base_path.join(&wiki_name).join("dist").join("docs");
 
         Ok(Self {
@@ -331,28 +489,26 @@ impl RouteGenerator {
             route_page_template,
             routes,
             mkdocs_config,
+            type_coverage,
         })
     }
 
-    fn generate_route_pages(&self, route_names: Vec<&str>) -> Result<String, String> {
+    // Returns the config with the routes nav updated for the generated pages
+    fn generate_route_pages(&self, route_names: Vec<&str>) -> WikiGenResult<MKDocsConfig> {
         let mut config = self.mkdocs_config.clone();
-        let nav_entries = config.nav.as_sequence_mut().unwrap();
-
-        let mut mkdocs_routes: &mut Vec<Value> = &mut Vec::new();
-        for entry in nav_entries {
-            let map_entries = entry.as_mapping_mut().unwrap();
-            if let Some(map_entry) = map_entries.get_mut(Value::String("Routes".to_string())) {
-                mkdocs_routes = map_entry.as_sequence_mut().unwrap();
+        let mkdocs_routes = match config.nav_section_mut("Routes") {
+            Ok(mkdocs_routes) => mkdocs_routes,
+            Err(err) => {
+                write_log(&self.base_path.join(&self.wiki_name), LogLevel::Error, &err);
+                return Err(err);
             }
-        }
+        };
 
         for route_name in route_names {
-            let route_properties = &self.routes.routes.get(route_name).unwrap();
-
             let mut page_entry_exists = false;
             let mut page_position = 0;
             for (index, page_entry) in mkdocs_routes.iter_mut().enumerate() {
-                if page_entry.as_mapping().unwrap().contains_key(route_name) {
+                if nav_entry_title(page_entry) == Some(route_name) {
                     page_entry_exists = true;
                     page_position = index;
                     break;
@@ -360,10 +516,12 @@ impl RouteGenerator {
             }
 
             // Checking for deleted routes
-            if !self.routes.routes.contains_key(route_name) && page_entry_exists {
-                mkdocs_routes.remove(page_position);
+            let Some(route_properties) = self.routes.routes.get(route_name) else {
+                if page_entry_exists {
+                    mkdocs_routes.remove(page_position);
+                }
                 continue;
-            }
+            };
 
             if route_properties.render == false && page_entry_exists {
                 mkdocs_routes.remove(page_position);
@@ -396,16 +554,19 @@ impl RouteGenerator {
 
             if let Err(err) = markdown_file.write_all(format!("{}", route_page_markdown).as_bytes())
             {
-                let message = format!(
-                    "{}: Failed to write route markdown file: {err}",
-                    self.wiki_name
+                let error = WikiGenError::io(
+                    format!(
+                        "{}: Failed to write route markdown file: {err}",
+                        self.wiki_name
+                    ),
+                    format!("routes/{route_name}.md"),
                 );
                 write_log(
                     &self.base_path.join(&self.wiki_name),
                     LogLevel::Error,
-                    &message,
+                    &error,
                 );
-                return Err(message);
+                return Err(error);
             }
 
             let mut route_page_entry = Mapping::new();
@@ -419,14 +580,13 @@ impl RouteGenerator {
             }
 
             mkdocs_routes.sort_by(|a, b| {
-                let first = a.as_mapping().unwrap().keys().next().unwrap();
-                let second = b.as_mapping().unwrap().keys().next().unwrap();
-
-                let Some(first_route) = self.routes.routes.get(first.as_str().unwrap()) else {
+                let Some(first_route) = nav_entry_title(a).and_then(|a| self.routes.routes.get(a))
+                else {
                     return Ordering::Equal;
                 };
 
-                let Some(second_route) = self.routes.routes.get(second.as_str().unwrap()) else {
+                let Some(second_route) = nav_entry_title(b).and_then(|b| self.routes.routes.get(b))
+                else {
                     return Ordering::Equal;
                 };
 
@@ -434,9 +594,7 @@ impl RouteGenerator {
             })
         }
 
-        update_mkdocs_yaml(&self.wiki_name, &self.base_path, &config)?;
-
-        Ok("Route Page Generated".to_string())
+        Ok(config)
     }
 
     fn generate_route_page_from_template(
@@ -482,8 +640,10 @@ impl RouteGenerator {
         if !route_properties.trainers.is_empty() {
             trainer_encounter_tab.push_str("=== \"Trainer Encounters\"");
             let trainer_table = TrainerInfo::generate_markdown(
+                &self.base_path.join(&self.wiki_name),
                 &route_properties.trainers,
                 self.trainer_pokemon_template.clone(),
+                &self.type_coverage,
             );
             trainer_encounters.push_str(&trainer_table);
         }
@@ -518,54 +678,56 @@ pub async fn generate_route_pages_with_handle(
     wiki_name: &str,
     route_names: Vec<&str>,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
-    let resources_path = app_handle.path().resource_dir().unwrap();
-
-    let trainer_pokemon_template = match read_to_string(
-        resources_path
-            .join("resources")
-            .join("generator_assets")
-            .join("templates")
-            .join("trainer_pokemon_template.md"),
-    ) {
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let resources_path = get_resource_dir(&app_handle)?;
+    let type_coverage = get_type_coverage(&session).await;
+
+    return generate_route_pages(&session, &resources_path, route_names, type_coverage);
+}
+
+pub fn generate_route_pages(
+    session: &WikiSession,
+    resources_path: &PathBuf,
+    route_names: Vec<&str>,
+    type_coverage: TypeCoverage,
+) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+
+    let trainer_pokemon_template =
+        match read_template(resources_path, "trainer_pokemon_template.md") {
+            Ok(template) => template,
+            Err(err) => {
+                logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+                return Err(err);
+            }
+        };
+
+    let route_page_template = match read_template(resources_path, "route_page_template.md") {
         Ok(template) => template,
         Err(err) => {
-            let message = format!("Failed to read template file: {err}",);
-            logger::write_log(
-                &base_path.join(wiki_name),
-                logger::LogLevel::Error,
-                &message,
-            );
-            return Err(message);
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
         }
     };
 
-    let route_page_template = match read_to_string(
-        resources_path
-            .join("resources")
-            .join("generator_assets")
-            .join("templates")
-            .join("route_page_template.md"),
-    ) {
-        Ok(template) => template,
+    let mkdocs_config = match session.mkdocs_config() {
+        Ok(config) => config,
         Err(err) => {
-            let message = format!("Failed to read template file: {err}",);
-            logger::write_log(
-                &base_path.join(&wiki_name),
-                logger::LogLevel::Error,
-                &message,
-            );
-            return Err(message);
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
         }
     };
 
     let route_generator = match RouteGenerator::new(
         wiki_name.to_string(),
         base_path.clone(),
-        resources_path,
+        resources_path.clone(),
         trainer_pokemon_template,
         route_page_template,
+        mkdocs_config,
+        type_coverage,
     ) {
         Ok(generator) => generator,
         Err(err) => {
@@ -574,7 +736,38 @@ pub async fn generate_route_pages_with_handle(
         }
     };
 
-    return route_generator.generate_route_pages(route_names);
+    let mkdocs_config = route_generator.generate_route_pages(route_names)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
+
+    Ok("Route Page Generated".to_string())
+}
+
+// Team summaries are extra information on the route page, so any missing
+// data only skips them instead of failing the route generation
+pub async fn get_type_coverage(session: &WikiSession) -> TypeCoverage {
+    let matchup_table = match get_matchup_table(&session.wiki_name, &session.base_path) {
+        Ok(matchup_table) => matchup_table,
+        Err(err) => {
+            logger::write_log(&session.wiki_path, logger::LogLevel::Error, &err);
+            return TypeCoverage::default();
+        }
+    };
+
+    let move_types = match get_move_types(&session.pool).await {
+        Ok(move_types) => move_types,
+        Err(err) => {
+            logger::write_log(&session.wiki_path, logger::LogLevel::Error, &err);
+            return TypeCoverage::default();
+        }
+    };
+
+    TypeCoverage {
+        move_types: move_types
+            .into_iter()
+            .map(|move_type| (move_type.name.clone(), move_type))
+            .collect(),
+        matchup_table: Some(matchup_table),
+    }
 }
 
 #[tauri::command]
@@ -582,56 +775,50 @@ pub async fn delete_route_page_from_mkdocs(
     wiki_name: &str,
     route_name: &str,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
 
-    let mkdocs_yaml_file_path = base_path.join(wiki_name).join("dist").join("mkdocs.yml");
-    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+    let mut mkdocs_config = match session.mkdocs_config() {
         Ok(config) => config,
         Err(err) => {
-            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            logger::write_log(&session.wiki_path, logger::LogLevel::Error, &err);
             return Err(err);
         }
     };
 
-    let nav_entries = mkdocs_config.nav.as_sequence_mut().unwrap();
-
-    let mut mkdocs_routes: &mut Vec<Value> = &mut Vec::new();
-    for entry in nav_entries {
-        let map_entries = entry.as_mapping_mut().unwrap();
-        if let Some(map_entry) = map_entries.get_mut(Value::String("Routes".to_string())) {
-            mkdocs_routes = map_entry.as_sequence_mut().unwrap();
+    let mkdocs_routes = match mkdocs_config.nav_section_mut("Routes") {
+        Ok(mkdocs_routes) => mkdocs_routes,
+        Err(err) => {
+            logger::write_log(&session.wiki_path, logger::LogLevel::Error, &err);
+            return Err(err);
         }
-    }
+    };
 
-    let mut page_position = 0;
-    for (index, page_entry) in mkdocs_routes.iter_mut().enumerate() {
-        if page_entry.as_mapping().unwrap().contains_key(route_name) {
-            page_position = index;
-            break;
-        }
+    // A route that was never rendered has no nav entry or page to remove
+    if let Some(page_position) = mkdocs_routes
+        .iter()
+        .position(|page_entry| nav_entry_title(page_entry) == Some(route_name))
+    {
+        mkdocs_routes.remove(page_position);
     }
 
-    mkdocs_routes.remove(page_position);
-
-    let route_file_path = base_path
-        .join(wiki_name)
-        .join("dist")
-        .join("docs")
+    let route_file_path = session
+        .docs_path
         .join("routes")
         .join(format!("{route_name}.md"));
 
-    if let Err(err) = fs::remove_file(route_file_path) {
-        let message = format!("Failed to delete route file: {err}");
-        logger::write_log(
-            &base_path.join(wiki_name),
-            logger::LogLevel::Error,
-            &message,
-        );
-        return Err(message);
+    if route_file_path.try_exists().unwrap_or(false) {
+        if let Err(err) = fs::remove_file(&route_file_path) {
+            let error = WikiGenError::io(
+                format!("Failed to delete route file: {err}"),
+                route_file_path.display(),
+            );
+            logger::write_log(&session.wiki_path, logger::LogLevel::Error, &error);
+            return Err(error);
+        }
     }
 
-    update_mkdocs_yaml(wiki_name, &base_path, &mkdocs_config)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
 
     Ok("Route Page Deleted".to_string())
 }
@@ -646,6 +833,13 @@ fn extract_move(_move: &Option<&String>) -> String {
     }
 }
 
+fn get_type_image(_type: &str) -> String {
+    format!(
+        "<img src=\"../../img/types/{}.png\" alt=\"{}\" style=\"width: 50px;\"/>",
+        _type, _type
+    )
+}
+
 fn evaluate_attribute(attribute: &str) -> String {
     match attribute {
         "" => return "-".to_string(),
@@ -0,0 +1,320 @@
+// Writes index.md as an overview of the wiki: the description, how much the
+// hack changes, quick links to the routes and the pages updated last. The
+// intro between the markers is the user's and carries over on regeneration.
+use std::{collections::HashMap, fs, path::Path, process::Command};
+
+use serde_yaml::{Mapping, Value};
+use tauri::AppHandle;
+
+use crate::{
+    database::{
+        get_routes,
+        repository::{get_abilities, get_all_pokemon, get_items, get_moves, get_natures},
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::{capitalize_and_remove_hyphens, TRUE},
+    logger::{self, LogLevel},
+    static_site::site::nav_titles,
+    structs::mkdocs_structs::nav_entry_file,
+    wiki_preparation::wiki_pages::get_nav_order,
+};
+
+pub const HOME_PAGE_FILE: &str = "index.md";
+pub const INTRO_START: &str = "<!-- wikigen:intro:start -->";
+pub const INTRO_END: &str = "<!-- wikigen:intro:end -->";
+const DEFAULT_INTRO: &str = "Write an introduction to your wiki here. Anything between these markers is kept when the home page is regenerated.";
+const RECENT_PAGE_COUNT: usize = 10;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HomePage {
+    pub site_name: String,
+    pub site_description: String,
+    // Pokemon don't track changes, so this is how many have pages
+    pub pokemon_pages: usize,
+    pub moves: ChangeCount,
+    pub abilities: ChangeCount,
+    pub items: ChangeCount,
+    pub natures: ChangeCount,
+    // Titles and files of the rendered routes, in wiki order
+    pub routes: Vec<(String, String)>,
+    pub recent_pages: Vec<RecentPage>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChangeCount {
+    pub new: usize,
+    pub modified: usize,
+}
+
+impl ChangeCount {
+    pub fn from_flags(flags: impl Iterator<Item = (i32, i32)>) -> Self {
+        let mut count = ChangeCount::default();
+        for (is_new, is_modified) in flags {
+            if is_new == TRUE {
+                count.new += 1;
+            }
+            if is_modified == TRUE {
+                count.modified += 1;
+            }
+        }
+        count
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentPage {
+    pub title: String,
+    // Relative to docs
+    pub file: String,
+    // YYYY-MM-DD of the last commit that changed it
+    pub date: String,
+}
+
+#[tauri::command]
+pub async fn generate_home_page_with_handle(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match generate_session_home_page(&session).await {
+        Ok(message) => Ok(message),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+async fn generate_session_home_page(session: &WikiSession) -> WikiGenResult<String> {
+    let mut mkdocs_config = session.mkdocs_config()?;
+    let pokemon = get_all_pokemon(&session.pool).await?;
+    let moves = get_moves(&session.pool).await?;
+    let abilities = get_abilities(&session.pool).await?;
+    let items = get_items(&session.pool).await?;
+    let natures = get_natures(&session.pool).await?;
+    let routes = get_routes(&session.wiki_path.join("data").join("routes.json"))?;
+
+    let mut rendered_routes: Vec<(&String, i32)> = routes
+        .routes
+        .iter()
+        .filter(|(_, route_properties)| route_properties.render)
+        .map(|(route_name, route_properties)| (route_name, route_properties.position))
+        .collect();
+    rendered_routes.sort_by_key(|(_, position)| *position);
+
+    let mut titles = HashMap::new();
+    nav_titles(&mkdocs_config.nav, &mut titles);
+    let dist_path = session.docs_path.parent().unwrap_or(&session.docs_path);
+    let recent_pages = recently_updated_pages(dist_path)
+        .into_iter()
+        .filter(|(file, _)| session.docs_path.join(file).is_file())
+        .take(RECENT_PAGE_COUNT)
+        .map(|(file, date)| RecentPage {
+            title: titles
+                .get(&file)
+                .cloned()
+                .unwrap_or_else(|| page_title_from_file(&file)),
+            file,
+            date,
+        })
+        .collect();
+
+    let home_page = HomePage {
+        site_name: mkdocs_config.site_name.clone(),
+        site_description: mkdocs_config.site_description.clone(),
+        pokemon_pages: pokemon
+            .iter()
+            .filter(|pokemon| pokemon.render == "true")
+            .count(),
+        moves: ChangeCount::from_flags(moves.iter().map(|m| (m.is_new, m.is_modified))),
+        abilities: ChangeCount::from_flags(abilities.iter().map(|a| (a.is_new, a.is_modified))),
+        items: ChangeCount::from_flags(items.iter().map(|i| (i.is_new, i.is_modified))),
+        natures: ChangeCount::from_flags(natures.iter().map(|n| (n.is_new, n.is_modified))),
+        routes: rendered_routes
+            .into_iter()
+            .map(|(route_name, _)| (route_name.to_string(), format!("routes/{route_name}.md")))
+            .collect(),
+        recent_pages,
+    };
+
+    let home_page_path = session.docs_path.join(HOME_PAGE_FILE);
+    let existing_markdown = fs::read_to_string(&home_page_path).ok();
+    let markdown = home_page_markdown(&home_page, existing_markdown.as_deref())?;
+    fs::write(&home_page_path, markdown).map_err(|err| {
+        WikiGenError::io(
+            format!("Failed to write home page: {err}"),
+            home_page_path.display(),
+        )
+    })?;
+
+    // Home is put back if it was removed by hand, first unless the user
+    // ordered the nav otherwise
+    let has_home_entry = mkdocs_config
+        .nav_entries_mut()?
+        .iter()
+        .any(|entry| nav_entry_file(entry) == Some(HOME_PAGE_FILE));
+    if !has_home_entry {
+        let mut home_entry = Mapping::new();
+        home_entry.insert(
+            Value::String("Home".to_string()),
+            Value::String(HOME_PAGE_FILE.to_string()),
+        );
+        let nav_order = get_nav_order(&session.wiki_path)?;
+        mkdocs_config.insert_nav_entry(Value::Mapping(home_entry), &nav_order)?;
+        session.update_mkdocs_config(&mkdocs_config)?;
+    }
+
+    Ok("Home page generated".to_string())
+}
+
+pub fn home_page_markdown(
+    home_page: &HomePage,
+    existing_markdown: Option<&str>,
+) -> WikiGenResult<String> {
+    let intro = match existing_markdown {
+        Some(existing_markdown) => user_intro(existing_markdown)?,
+        None => None,
+    }
+    .unwrap_or_else(|| DEFAULT_INTRO.to_string());
+
+    let mut markdown = format!("# {}\n\n", home_page.site_name);
+    if !home_page.site_description.trim().is_empty() {
+        markdown.push_str(&format!("{}\n\n", home_page.site_description.trim()));
+    }
+    markdown.push_str(&format!("{INTRO_START}\n{intro}\n{INTRO_END}\n\n"));
+
+    markdown.push_str("## Overview\n\n");
+    markdown.push_str(&format!(
+        "{} Pokemon have pages in this wiki.\n\n",
+        home_page.pokemon_pages
+    ));
+    let change_counts = [
+        ("Moves", "move_changes.md", home_page.moves),
+        ("Abilities", "ability_changes.md", home_page.abilities),
+        ("Items", "item_changes.md", home_page.items),
+        ("Natures", "nature_changes.md", home_page.natures),
+    ];
+    if change_counts
+        .iter()
+        .any(|(_, _, count)| count.new + count.modified > 0)
+    {
+        markdown.push_str("| Changes | New | Modified |\n| :-- | :--: | :--: |\n");
+        for (name, file, count) in change_counts {
+            if count.new + count.modified == 0 {
+                continue;
+            }
+            markdown.push_str(&format!(
+                "| [{name}]({file}) | {} | {} |\n",
+                count.new, count.modified
+            ));
+        }
+        markdown.push('\n');
+    }
+
+    if !home_page.routes.is_empty() {
+        markdown.push_str("## Routes\n\n");
+        for (title, file) in &home_page.routes {
+            markdown.push_str(&format!("- [{}]({})\n", title, link_path(file)));
+        }
+        markdown.push('\n');
+    }
+
+    if !home_page.recent_pages.is_empty() {
+        markdown.push_str("## Recently Updated\n\n");
+        for page in &home_page.recent_pages {
+            markdown.push_str(&format!(
+                "- [{}]({}) - {}\n",
+                page.title,
+                link_path(&page.file),
+                page.date
+            ));
+        }
+        markdown.push('\n');
+    }
+
+    markdown.truncate(markdown.trim_end().len());
+    markdown.push('\n');
+    Ok(markdown)
+}
+
+// The text between the intro markers. A page without them that isn't the
+// placeholder create_wiki used to write was written by hand, so all of it is
+// kept as the intro.
+// Without the end marker there's no telling where the intro stops, so the
+// page is left alone rather than replacing the intro with the default
+pub fn user_intro(markdown: &str) -> WikiGenResult<Option<String>> {
+    if let Some((_, after_start)) = markdown.split_once(INTRO_START) {
+        let Some((intro, _)) = after_start.split_once(INTRO_END) else {
+            return Err(WikiGenError::from(format!(
+                "{HOME_PAGE_FILE} has the intro start marker but no {INTRO_END} after it. Add it after your intro to regenerate the home page."
+            )));
+        };
+        return Ok(Some(intro.trim_matches('\n').to_string()));
+    }
+    let markdown = markdown.trim();
+    match markdown.is_empty() || markdown == "# Index" {
+        true => Ok(None),
+        false => Ok(Some(markdown.to_string())),
+    }
+}
+
+// Markdown pages changed by the latest commits of the dist repo, newest
+// first, as paths relative to docs with the commit date. Empty when the wiki
+// hasn't been committed yet or git isn't installed.
+fn recently_updated_pages(dist_path: &Path) -> Vec<(String, String)> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dist_path)
+        .args([
+            "log",
+            "--format=%x00%cs",
+            "--name-only",
+            "--diff-filter=AM",
+            "-n",
+            "50",
+            "--",
+            "docs",
+        ])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_recent_pages(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+pub fn parse_recent_pages(git_log: &str) -> Vec<(String, String)> {
+    let mut pages: Vec<(String, String)> = Vec::new();
+    let mut date = "";
+    for line in git_log.lines() {
+        if let Some(commit_date) = line.strip_prefix('\0') {
+            date = commit_date;
+            continue;
+        }
+        let Some(file) = line.strip_prefix("docs/") else {
+            continue;
+        };
+        if !file.ends_with(".md") || file == HOME_PAGE_FILE {
+            continue;
+        }
+        if !pages.iter().any(|(page, _)| page == file) {
+            pages.push((file.to_string(), date.to_string()));
+        }
+    }
+    pages
+}
+
+fn page_title_from_file(file: &str) -> String {
+    let stem = file.rsplit('/').next().unwrap_or(file);
+    capitalize_and_remove_hyphens(stem.trim_end_matches(".md"))
+}
+
+// Route names have spaces, which end a markdown link target
+fn link_path(file: &str) -> String {
+    file.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
@@ -1,65 +1,45 @@
-use std::{io::Write, path::PathBuf};
+use std::io::Write;
 
 use serde_yaml::{Mapping, Value};
-use sqlx::FromRow;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 use crate::{
     database::{
-        create_docs_file, get_mkdocs_config, get_sqlite_connection, page_exists_in_mkdocs,
-        remove_docs_file, update_mkdocs_yaml,
+        create_docs_file, page_exists_in_mkdocs, remove_docs_file,
+        repository::{get_item_locations, get_items},
+        session::{get_wiki_session, WikiSession},
     },
+    errors::{WikiGenError, WikiGenResult},
     helpers::{capitalize_and_remove_hyphens, FALSE, TRUE},
     logger::{self, write_log, LogLevel},
+    structs::page_models::{Item, ItemLocation},
+    wiki_preparation::wiki_pages::get_nav_order,
 };
 
-#[derive(Debug, Clone, FromRow)]
-pub struct Item {
-    pub name: String,
-    pub effect: String,
-    pub is_modified: i32,
-    pub is_new: i32,
-}
-
 #[tauri::command]
 pub async fn generate_item_changes_page_with_handle(
     wiki_name: &str,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
-    let sqlite_path = base_path.join(wiki_name).join(format!("{}.db", wiki_name));
-    let conn = match get_sqlite_connection(sqlite_path).await {
-        Ok(conn) => conn,
-        Err(err) => {
-            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
-            return Err(err);
-        }
-    };
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+    let conn = session.pool.clone();
 
-    let items = match sqlx::query_as::<_, Item>("SELECT * FROM items")
-        .fetch_all(&conn)
-        .await
-    {
+    let items = match get_items(&conn).await {
         Ok(items) => items,
         Err(err) => {
-            let message = format!("Failed to get items: {}", err);
-            logger::write_log(
-                &base_path.join(wiki_name),
-                logger::LogLevel::Error,
-                &message,
-            );
-            return Err(message);
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
         }
     };
 
-    return generate_item_changes_page(wiki_name, &items, &base_path);
+    return generate_item_changes_page(&session, &items);
 }
 
-pub fn generate_item_changes_page(
-    wiki_name: &str,
-    items: &[Item],
-    base_path: &PathBuf,
-) -> Result<String, String> {
+pub fn generate_item_changes_page(session: &WikiSession, items: &[Item]) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+
     let mut item_changes_markdown = String::new();
     let mut item_new = String::new();
     let mut item_modified = String::new();
@@ -111,8 +91,7 @@ pub fn generate_item_changes_page(
         item_changes_markdown.push_str(&entry);
     }
 
-    let mkdocs_yaml_file_path = base_path.join(wiki_name).join("dist").join("mkdocs.yml");
-    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+    let mut mkdocs_config = match session.mkdocs_config() {
         Ok(config) => config,
         Err(err) => {
             logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
@@ -120,7 +99,7 @@ pub fn generate_item_changes_page(
         }
     };
 
-    let (page_exists, page_index) = page_exists_in_mkdocs(mkdocs_config.clone(), "Item Changes");
+    let (page_exists, page_index) = page_exists_in_mkdocs(mkdocs_config.clone(), "Item Changes")?;
 
     if item_changes_markdown.is_empty() {
         if !page_exists {
@@ -129,13 +108,9 @@ pub fn generate_item_changes_page(
 
         remove_docs_file(wiki_name, base_path, "item_changes.md")?;
 
-        mkdocs_config
-            .nav
-            .as_sequence_mut()
-            .unwrap()
-            .remove(page_index);
+        mkdocs_config.nav_entries_mut()?.remove(page_index);
 
-        update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+        session.update_mkdocs_config(&mkdocs_config)?;
 
         return Ok("No Item changes to generate. Item Changes page removed".to_string());
     }
@@ -143,9 +118,12 @@ pub fn generate_item_changes_page(
     let mut item_changes_file = create_docs_file(wiki_name, base_path, "item_changes.md")?;
 
     if let Err(err) = item_changes_file.write_all(format!("{}", item_changes_markdown).as_bytes()) {
-        let message = format!("{wiki_name}: Failed to write item changes file: {err}");
-        write_log(&base_path, LogLevel::Error, &message);
-        return Err(message);
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to write item changes file: {err}"),
+            "item_changes.md",
+        );
+        write_log(&base_path, LogLevel::Error, &error);
+        return Err(error);
     }
 
     if page_exists {
@@ -158,67 +136,42 @@ pub fn generate_item_changes_page(
         Value::String("item_changes.md".to_string()),
     );
 
-    mkdocs_config
-        .nav
-        .as_sequence_mut()
-        .unwrap()
-        .insert(1, Value::Mapping(item_changes));
+    let nav_order = get_nav_order(&base_path.join(wiki_name))?;
+    mkdocs_config.insert_nav_entry(Value::Mapping(item_changes), &nav_order)?;
 
-    update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
 
     Ok("Items Page Generated".to_string())
 }
 
-#[derive(Debug, Clone, FromRow)]
-pub struct ItemLocation {
-    pub item_name: String,
-    pub route: String,
-    pub specific_location: Option<String>,
-    pub method: Option<String>,
-    pub requirements: Option<String>,
-}
-
 #[tauri::command]
 pub async fn generate_item_location_page_with_handle(
     wiki_name: &str,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+
+    let conn = session.pool.clone();
 
-    let sqlite_path = base_path.join(wiki_name).join(format!("{}.db", wiki_name));
-    let conn = match get_sqlite_connection(sqlite_path).await {
-        Ok(conn) => conn,
+    let item_locations = match get_item_locations(&conn).await {
+        Ok(item_locations) => item_locations,
         Err(err) => {
             logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
             return Err(err);
         }
     };
 
-    let item_locations =
-        match sqlx::query_as::<_, ItemLocation>("SELECT * FROM item_location ORDER BY item_name;")
-            .fetch_all(&conn)
-            .await
-        {
-            Ok(item_locations) => item_locations,
-            Err(err) => {
-                let message = format!("Failed to fetch item locations: {err}");
-                logger::write_log(
-                    &base_path.join(wiki_name),
-                    logger::LogLevel::Error,
-                    &message,
-                );
-                return Err(message);
-            }
-        };
-
-    return generate_item_location_page(wiki_name, &item_locations, &base_path);
+    return generate_item_location_page(&session, &item_locations);
 }
 
 pub fn generate_item_location_page(
-    wiki_name: &str,
+    session: &WikiSession,
     item_locations: &[ItemLocation],
-    base_path: &PathBuf,
-) -> Result<String, String> {
+) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+
     let mut item_locations_markdown = String::new();
     let mut item_location_entries = String::new();
 
@@ -253,8 +206,7 @@ pub fn generate_item_location_page(
         item_location_entries.push_str(&entry);
     }
 
-    let mkdocs_yaml_file_path = base_path.join(wiki_name).join("dist").join("mkdocs.yml");
-    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+    let mut mkdocs_config = match session.mkdocs_config() {
         Ok(config) => config,
         Err(err) => {
             logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
@@ -262,7 +214,7 @@ pub fn generate_item_location_page(
         }
     };
 
-    let (page_exists, page_index) = page_exists_in_mkdocs(mkdocs_config.clone(), "Item Locations");
+    let (page_exists, page_index) = page_exists_in_mkdocs(mkdocs_config.clone(), "Item Locations")?;
 
     if item_location_entries.is_empty() {
         if !page_exists {
@@ -271,13 +223,9 @@ pub fn generate_item_location_page(
 
         remove_docs_file(wiki_name, base_path, "item_locations.md")?;
 
-        mkdocs_config
-            .nav
-            .as_sequence_mut()
-            .unwrap()
-            .remove(page_index);
+        mkdocs_config.nav_entries_mut()?.remove(page_index);
 
-        update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+        session.update_mkdocs_config(&mkdocs_config)?;
 
         return Ok("No Item Locations to generate. Item Locations page removed".to_string());
     }
@@ -295,9 +243,12 @@ pub fn generate_item_location_page(
     if let Err(err) =
         item_locations_file.write_all(format!("{}", item_locations_markdown).as_bytes())
     {
-        let message = format!("{wiki_name}: Failed to write item locations file: {err}");
-        write_log(&base_path, LogLevel::Error, &message);
-        return Err(message);
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to write item locations file: {err}"),
+            "item_locations.md",
+        );
+        write_log(&base_path, LogLevel::Error, &error);
+        return Err(error);
     }
 
     if page_exists {
@@ -310,13 +261,10 @@ pub fn generate_item_location_page(
         Value::String("item_locations.md".to_string()),
     );
 
-    mkdocs_config
-        .nav
-        .as_sequence_mut()
-        .unwrap()
-        .insert(1, Value::Mapping(item_locations));
+    let nav_order = get_nav_order(&base_path.join(wiki_name))?;
+    mkdocs_config.insert_nav_entry(Value::Mapping(item_locations), &nav_order)?;
 
-    update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
 
     Ok("Item Location Page Generated".to_string())
 }
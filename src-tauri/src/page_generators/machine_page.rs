@@ -0,0 +1,184 @@
+use std::io::Write;
+
+use serde_yaml::{Mapping, Value};
+use tauri::AppHandle;
+
+use crate::{
+    database::{
+        create_docs_file, page_exists_in_mkdocs, remove_docs_file,
+        repository::{get_item_locations, get_machine_moves},
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::capitalize_and_remove_hyphens,
+    logger::{self, write_log, LogLevel},
+    page_generators::pokemon_page_generator_functions::get_markdown_image_for_type_from,
+    structs::page_models::{ItemLocation, MachineMove},
+    wiki_preparation::wiki_pages::get_nav_order,
+};
+
+#[tauri::command]
+pub async fn generate_machine_page_with_handle(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+
+    let conn = session.pool.clone();
+
+    let machine_moves = match get_machine_moves(&conn).await {
+        Ok(machine_moves) => machine_moves,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    let item_locations = match get_item_locations(&conn).await {
+        Ok(item_locations) => item_locations,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    return generate_machine_page(&session, &machine_moves, &item_locations);
+}
+
+pub fn generate_machine_page(
+    session: &WikiSession,
+    machine_moves: &[MachineMove],
+    item_locations: &[ItemLocation],
+) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+
+    let mut sorted_machine_moves = machine_moves.to_vec();
+    sorted_machine_moves.sort_by_key(|machine_move| machine_sort_key(&machine_move.machine_name));
+
+    let mut machine_entries = String::new();
+    for machine_move in &sorted_machine_moves {
+        let power = match machine_move.power {
+            Some(power) => power.to_string(),
+            None => "-".to_string(),
+        };
+        let accuracy = match machine_move.accuracy {
+            Some(accuracy) => accuracy.to_string(),
+            None => "-".to_string(),
+        };
+        let pp = match machine_move.pp {
+            Some(pp) => pp.to_string(),
+            None => "-".to_string(),
+        };
+
+        let locations = item_locations
+            .iter()
+            .filter(|location| {
+                location.item_name.to_lowercase() == machine_move.machine_name.to_lowercase()
+            })
+            .map(|location| match &location.specific_location {
+                Some(specific_location) if !specific_location.is_empty() => {
+                    format!("{} ({})", location.route, specific_location)
+                }
+                _ => location.route.clone(),
+            })
+            .collect::<Vec<String>>();
+        let locations = match locations.is_empty() {
+            true => "-".to_string(),
+            false => locations.join("<br/>"),
+        };
+
+        let entry = format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            machine_move.machine_name.to_uppercase(),
+            capitalize_and_remove_hyphens(&machine_move.name),
+            power,
+            accuracy,
+            pp,
+            get_markdown_image_for_type_from(&machine_move.move_type, ""),
+            get_markdown_image_for_type_from(&machine_move.damage_class, ""),
+            locations,
+            machine_move.learnable_by
+        );
+        machine_entries.push_str(&entry);
+    }
+
+    let mut mkdocs_config = match session.mkdocs_config() {
+        Ok(config) => config,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    let (page_exists, page_index) = page_exists_in_mkdocs(mkdocs_config.clone(), "TMs & HMs")?;
+
+    if machine_entries.is_empty() {
+        if !page_exists {
+            return Ok("No TMs or HMs to generate".to_string());
+        }
+
+        remove_docs_file(wiki_name, base_path, "machines.md")?;
+
+        mkdocs_config.nav_entries_mut()?.remove(page_index);
+
+        session.update_mkdocs_config(&mkdocs_config)?;
+
+        return Ok("No TMs or HMs to generate. TMs & HMs page removed".to_string());
+    }
+
+    let machines_markdown = format!(
+        "| Machine | Move | Power | Accuracy | PP | Type | Damage Class | Location | Learnable By |
+        | :--: | :-- | :--: | :--: | :--: | :--: | :--: | :-- | :--: |
+        {}
+        ",
+        machine_entries
+    );
+
+    let mut machines_file = create_docs_file(wiki_name, base_path, "machines.md")?;
+
+    if let Err(err) = machines_file.write_all(format!("{}", machines_markdown).as_bytes()) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to write machines file: {err}"),
+            "machines.md",
+        );
+        write_log(&base_path, LogLevel::Error, &error);
+        return Err(error);
+    }
+
+    if page_exists {
+        return Ok("TMs & HMs Page Updated".to_string());
+    }
+
+    let mut machines = Mapping::new();
+    machines.insert(
+        Value::String("TMs & HMs".to_string()),
+        Value::String("machines.md".to_string()),
+    );
+
+    let nav_order = get_nav_order(&base_path.join(wiki_name))?;
+    mkdocs_config.insert_nav_entry(Value::Mapping(machines), &nav_order)?;
+
+    session.update_mkdocs_config(&mkdocs_config)?;
+
+    Ok("TMs & HMs Page Generated".to_string())
+}
+
+// TMs are listed before HMs, each ordered by their machine number.
+// Anything that doesn't follow the tm01/hm01 naming ends up last.
+fn machine_sort_key(machine_name: &str) -> (u8, u32, String) {
+    let machine_name = machine_name.to_lowercase();
+    let prefix_order = match machine_name.get(0..2) {
+        Some("tm") => 0,
+        Some("tr") => 1,
+        Some("hm") => 2,
+        _ => 3,
+    };
+    let number = machine_name
+        .get(2..)
+        .and_then(|number| number.parse::<u32>().ok())
+        .unwrap_or(u32::MAX);
+
+    (prefix_order, number, machine_name)
+}
@@ -1,6 +1,11 @@
 pub mod ability_page;
+pub mod changelog_page;
 pub mod game_routes;
+pub mod home_page;
 pub mod item_page;
+pub mod machine_page;
+pub mod move_page;
 pub mod nature_page;
 mod pokemon_page_generator_functions;
 pub mod pokemon_pages;
+pub mod type_chart_page;
@@ -0,0 +1,233 @@
+use std::{fmt::Display, io::Write};
+
+use serde_yaml::{Mapping, Value};
+use tauri::AppHandle;
+
+use crate::{
+    database::{
+        create_docs_file, get_resource_dir, get_sqlite_connection, page_exists_in_mkdocs,
+        remove_docs_file,
+        repository::get_moves,
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::{capitalize_and_remove_hyphens, FALSE, TRUE},
+    logger::{self, write_log, LogLevel},
+    page_generators::pokemon_page_generator_functions::get_markdown_image_for_type_from,
+    structs::page_models::Move,
+    wiki_preparation::wiki_pages::get_nav_order,
+};
+
+#[tauri::command]
+pub async fn generate_move_page_with_handle(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+    let resources_path = get_resource_dir(&app_handle)?;
+
+    let conn = session.pool.clone();
+
+    let moves = match get_moves(&conn).await {
+        Ok(moves) => moves,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    // The shipped database is only used to show what a modified move looked like
+    // before, so the page can still be generated without it.
+    let initial_db_path = resources_path
+        .join("resources")
+        .join("generator_assets")
+        .join("initial.db");
+    let original_moves = match get_sqlite_connection(initial_db_path).await {
+        Ok(initial_conn) => match get_moves(&initial_conn).await {
+            Ok(original_moves) => original_moves,
+            Err(err) => {
+                let message = format!("Failed to get original moves: {}", err);
+                logger::write_log(
+                    &base_path.join(wiki_name),
+                    logger::LogLevel::Error,
+                    &message,
+                );
+                Vec::new()
+            }
+        },
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            Vec::new()
+        }
+    };
+
+    return generate_move_page(&session, &moves, &original_moves);
+}
+
+pub fn generate_move_page(
+    session: &WikiSession,
+    moves: &[Move],
+    original_moves: &[Move],
+) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+
+    let mut move_changes_markdown = String::new();
+    let mut move_new = String::new();
+    let mut move_modified = String::new();
+
+    for _move in moves {
+        if _move.is_new == FALSE && _move.is_modified == FALSE {
+            continue;
+        }
+
+        if _move.is_new == TRUE {
+            if move_new.is_empty() {
+                move_new.push_str(&format!(
+                    "| New Moves | Power | Accuracy | PP | Type | Damage Class |
+                    | :--: | :--: | :--: | :--: | :--: | :--: |
+                    "
+                ))
+            }
+            move_new.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                capitalize_and_remove_hyphens(&_move.name),
+                format_optional_stat(&_move.power),
+                format_optional_stat(&_move.accuracy),
+                format_optional_stat(&_move.pp),
+                get_markdown_image_for_type_from(&_move.move_type, ""),
+                get_markdown_image_for_type_from(&_move.damage_class, "")
+            ));
+        }
+
+        if _move.is_modified == TRUE {
+            if move_modified.is_empty() {
+                move_modified.push_str(&format!(
+                    "| Modified Moves | Power | Accuracy | PP | Type | Damage Class |
+                    | :--: | :--: | :--: | :--: | :--: | :--: |
+                    "
+                ))
+            }
+
+            let original_move = original_moves
+                .iter()
+                .find(|original| original.name == _move.name);
+            move_modified.push_str(&create_modified_move_entry(_move, original_move));
+        }
+    }
+
+    if !move_new.is_empty() {
+        let entry = format!("{}\n", move_new);
+        move_changes_markdown.push_str(&entry);
+    }
+    if !move_modified.is_empty() {
+        let entry = format!("{}\n", move_modified);
+        move_changes_markdown.push_str(&entry);
+    }
+
+    let mut mkdocs_config = match session.mkdocs_config() {
+        Ok(config) => config,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    let (page_exists, page_index) = page_exists_in_mkdocs(mkdocs_config.clone(), "Move Changes")?;
+
+    if move_changes_markdown.is_empty() {
+        if !page_exists {
+            return Ok("No Move changes to generate".to_string());
+        }
+
+        remove_docs_file(wiki_name, base_path, "move_changes.md")?;
+
+        mkdocs_config.nav_entries_mut()?.remove(page_index);
+
+        session.update_mkdocs_config(&mkdocs_config)?;
+
+        return Ok("No Move changes to generate. Move Changes page removed".to_string());
+    }
+
+    let mut move_changes_file = create_docs_file(wiki_name, base_path, "move_changes.md")?;
+
+    if let Err(err) = move_changes_file.write_all(format!("{}", move_changes_markdown).as_bytes()) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to write move changes file: {err}"),
+            "move_changes.md",
+        );
+        write_log(&base_path, LogLevel::Error, &error);
+        return Err(error);
+    }
+
+    if page_exists {
+        return Ok("Move Changes Page Updated".to_string());
+    }
+
+    let mut move_changes = Mapping::new();
+    move_changes.insert(
+        Value::String("Move Changes".to_string()),
+        Value::String("move_changes.md".to_string()),
+    );
+
+    let nav_order = get_nav_order(&base_path.join(wiki_name))?;
+    mkdocs_config.insert_nav_entry(Value::Mapping(move_changes), &nav_order)?;
+
+    session.update_mkdocs_config(&mkdocs_config)?;
+
+    Ok("Moves Page Generated".to_string())
+}
+
+fn create_modified_move_entry(_move: &Move, original_move: Option<&Move>) -> String {
+    let Some(original_move) = original_move else {
+        return format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            capitalize_and_remove_hyphens(&_move.name),
+            format_optional_stat(&_move.power),
+            format_optional_stat(&_move.accuracy),
+            format_optional_stat(&_move.pp),
+            get_markdown_image_for_type_from(&_move.move_type, ""),
+            get_markdown_image_for_type_from(&_move.damage_class, "")
+        );
+    };
+
+    format!(
+        "| {} | {} | {} | {} | {} | {} |\n",
+        capitalize_and_remove_hyphens(&_move.name),
+        format_change(
+            format_optional_stat(&original_move.power),
+            format_optional_stat(&_move.power)
+        ),
+        format_change(
+            format_optional_stat(&original_move.accuracy),
+            format_optional_stat(&_move.accuracy)
+        ),
+        format_change(
+            format_optional_stat(&original_move.pp),
+            format_optional_stat(&_move.pp)
+        ),
+        format_change(
+            get_markdown_image_for_type_from(&original_move.move_type, ""),
+            get_markdown_image_for_type_from(&_move.move_type, "")
+        ),
+        format_change(
+            get_markdown_image_for_type_from(&original_move.damage_class, ""),
+            get_markdown_image_for_type_from(&_move.damage_class, "")
+        )
+    )
+}
+
+fn format_change(old_value: String, new_value: String) -> String {
+    if old_value == new_value {
+        return new_value;
+    }
+    format!("{} → {}", old_value, new_value)
+}
+
+fn format_optional_stat<T: Display>(stat: &Option<T>) -> String {
+    match stat {
+        Some(stat) => stat.to_string(),
+        None => "-".to_string(),
+    }
+}
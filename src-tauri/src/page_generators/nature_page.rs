@@ -1,66 +1,45 @@
 use std::io::Write;
 
 use serde_yaml::{Mapping, Value};
-use sqlx::FromRow;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 use crate::{
     database::{
-        create_docs_file, get_mkdocs_config, get_sqlite_connection, page_exists_in_mkdocs,
-        remove_docs_file, update_mkdocs_yaml,
+        create_docs_file, page_exists_in_mkdocs, remove_docs_file,
+        repository::get_natures,
+        session::{get_wiki_session, WikiSession},
     },
+    errors::{WikiGenError, WikiGenResult},
     helpers::{capitalize, capitalize_and_remove_hyphens, FALSE, TRUE},
     logger::{self, write_log, LogLevel},
+    structs::page_models::Nature,
+    wiki_preparation::wiki_pages::get_nav_order,
 };
 
-#[derive(Debug, Clone, FromRow)]
-pub struct Nature {
-    pub name: String,
-    pub increased_stat: Option<String>,
-    pub decreased_stat: Option<String>,
-    pub is_modified: i32,
-    pub is_new: i32,
-}
-
 #[tauri::command]
 pub async fn generate_nature_page_with_handle(
     wiki_name: &str,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
-    let sqlite_path = base_path.join(wiki_name).join(format!("{}.db", wiki_name));
-    let conn = match get_sqlite_connection(sqlite_path).await {
-        Ok(conn) => conn,
-        Err(err) => {
-            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
-            return Err(err);
-        }
-    };
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+    let conn = session.pool.clone();
 
-    let natures = match sqlx::query_as::<_, Nature>("SELECT * FROM natures")
-        .fetch_all(&conn)
-        .await
-    {
+    let natures = match get_natures(&conn).await {
         Ok(natures) => natures,
         Err(err) => {
-            let message = format!("Failed to get natures: {}", err);
-            logger::write_log(
-                &base_path.join(wiki_name),
-                logger::LogLevel::Error,
-                &message,
-            );
-            return Err(message);
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
         }
     };
 
-    return generate_nature_page(wiki_name, &natures, &base_path);
+    return generate_nature_page(&session, &natures);
 }
 
-pub fn generate_nature_page(
-    wiki_name: &str,
-    natures: &[Nature],
-    base_path: &std::path::PathBuf,
-) -> Result<String, String> {
+pub fn generate_nature_page(session: &WikiSession, natures: &[Nature]) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+
     let mut nature_changes_markdown = String::new();
     let mut nature_new = String::new();
     let mut nature_modified = String::new();
@@ -119,8 +98,7 @@ pub fn generate_nature_page(
         nature_changes_markdown.push_str(&entry);
     }
 
-    let mkdocs_yaml_file_path = base_path.join(wiki_name).join("dist").join("mkdocs.yml");
-    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+    let mut mkdocs_config = match session.mkdocs_config() {
         Ok(config) => config,
         Err(err) => {
             logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
@@ -128,7 +106,7 @@ pub fn generate_nature_page(
         }
     };
 
-    let (page_exists, page_index) = page_exists_in_mkdocs(mkdocs_config.clone(), "Nature Changes");
+    let (page_exists, page_index) = page_exists_in_mkdocs(mkdocs_config.clone(), "Nature Changes")?;
 
     if nature_changes_markdown.is_empty() {
         if !page_exists {
@@ -137,13 +115,9 @@ pub fn generate_nature_page(
 
         remove_docs_file(wiki_name, base_path, "nature_changes.md")?;
 
-        mkdocs_config
-            .nav
-            .as_sequence_mut()
-            .unwrap()
-            .remove(page_index);
+        mkdocs_config.nav_entries_mut()?.remove(page_index);
 
-        update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+        session.update_mkdocs_config(&mkdocs_config)?;
 
         return Ok("No Nature changes to generate. Nature Changes page removed".to_string());
     }
@@ -153,9 +127,12 @@ pub fn generate_nature_page(
     if let Err(err) =
         nature_changes_file.write_all(format!("{}", nature_changes_markdown).as_bytes())
     {
-        let message = format!("{wiki_name}: Failed to write nature changes file: {err}");
-        write_log(&base_path, LogLevel::Error, &message);
-        return Err(message);
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to write nature changes file: {err}"),
+            "nature_changes.md",
+        );
+        write_log(&base_path, LogLevel::Error, &error);
+        return Err(error);
     }
 
     if page_exists {
@@ -168,13 +145,10 @@ pub fn generate_nature_page(
         Value::String("nature_changes.md".to_string()),
     );
 
-    mkdocs_config
-        .nav
-        .as_sequence_mut()
-        .unwrap()
-        .insert(1, Value::Mapping(nature_changes));
+    let nav_order = get_nav_order(&base_path.join(wiki_name))?;
+    mkdocs_config.insert_nav_entry(Value::Mapping(nature_changes), &nav_order)?;
 
-    update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
 
     Ok("Natures Page Generated".to_string())
 }
@@ -1,6 +1,9 @@
 use crate::{
     helpers::{capitalize, capitalize_and_remove_hyphens},
-    structs::pokemon_structs::{DBPokemon, PokemonMove},
+    structs::{
+        matchup_models::TypeEffectiveness,
+        pokemon_structs::{DBPokemon, PokemonMove},
+    },
 };
 
 use super::game_routes::WildEncounter;
@@ -24,10 +27,10 @@ pub fn create_evolution_table(pokemon: &DBPokemon) -> String {
         Some(level) => level.to_string(),
         None => 0.to_string(),
     };
-    let item_level_note = match pokemon.evolution_method.as_str() {
-        "item" => pokemon.evolution_item.as_ref().unwrap(),
+    let item_level_note: &str = match pokemon.evolution_method.as_str() {
+        "item" => pokemon.evolution_item.as_deref().unwrap_or(no_change),
         "level_up" => &level,
-        "other" => pokemon.evolution_other.as_ref().unwrap(),
+        "other" => pokemon.evolution_other.as_deref().unwrap_or(no_change),
         _ => no_change,
     };
 
@@ -158,10 +161,54 @@ pub fn create_locations_table(wild_encounters: &[WildEncounter]) -> String {
     );
 }
 
-fn get_markdown_image_for_type(_type: &String) -> String {
+pub fn create_type_defenses_table(type_defenses: &TypeEffectiveness) -> String {
+    // Neutral matchups are left out since they make up most of the chart
+    let effectiveness_levels = [8.0, 4.0, 2.0, 0.5, 0.25, 0.125, 0.0];
+
+    let mut markdown_defenses = String::new();
+    for effectiveness in effectiveness_levels {
+        let Some(types) = type_defenses.0.get(&effectiveness.to_string()) else {
+            continue;
+        };
+        let type_images = types
+            .iter()
+            .map(|_type| {
+                format!(
+                    "<img src='../../img/types/{}.png' style='width: 60px;'/>",
+                    _type.to_lowercase()
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let table_entry = format!("\t| {}x | {} |\n", effectiveness, type_images);
+        markdown_defenses.push_str(&table_entry);
+    }
+
+    if markdown_defenses.is_empty() {
+        return "".to_string();
+    }
+
+    return format!(
+        "## Type Defenses\n| Damage Taken | Types |
+        | :--: | :-- |
+        {}
+        ",
+        markdown_defenses
+    );
+}
+
+pub fn get_markdown_image_for_type(_type: &str) -> String {
+    return get_markdown_image_for_type_from(_type, "../");
+}
+
+// `path_to_docs` leads from the page's markdown file back to docs,
+// empty for pages at the docs root
+pub fn get_markdown_image_for_type_from(_type: &str, path_to_docs: &str) -> String {
     return format!(
-        "![{}](../img/types/{}.png)",
+        "![{}]({}img/types/{}.png)",
         _type.to_lowercase(),
+        path_to_docs,
         _type.to_lowercase()
     );
 }
@@ -1,21 +1,35 @@
 use std::{
-    fs::{self, read_to_string, File},
+    fs::{self, File},
     io::Write,
     path::PathBuf,
 };
 
 use serde_yaml::{Mapping, Value};
 use sqlx::Sqlite;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 use crate::{
-    database::{get_mkdocs_config, get_routes, get_sqlite_connection, update_mkdocs_yaml},
-    helpers::{capitalize, capitalize_and_remove_hyphens, get_pokemon_dex_formatted_name},
+    database::{
+        get_resource_dir, get_routes, read_template,
+        repository::{get_db_abilities, get_movesets_by_pokemon_ids, get_pokemon_by_ids},
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::{
+        capitalize, capitalize_and_remove_hyphens, get_pokemon_dex_formatted_name,
+        matchups::get_matchup_table,
+    },
     logger,
     page_generators::pokemon_page_generator_functions::{
         create_evolution_table, create_learnable_moves_table, create_level_up_moves_table,
+        create_type_defenses_table,
+    },
+    structs::{
+        matchup_models::TypeEffectiveness,
+        mkdocs_structs::{nav_entry_file, nav_entry_title},
+        pokemon_structs::{DBAbility, DBPokemon, PokemonMove},
     },
-    structs::pokemon_structs::{DBAbility, DBPokemon, PokemonMove},
+    wiki_preparation::wiki_plugins::add_page_redirect,
 };
 
 use super::{game_routes::WildEncounter, pokemon_page_generator_functions::create_locations_table};
@@ -24,22 +38,21 @@ use super::{game_routes::WildEncounter, pokemon_page_generator_functions::create
 pub async fn update_pokemon_pages_with_stripped_name_with_handle(
     wiki_name: &str,
     app_handle: AppHandle,
-) -> Result<(), String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
+) -> WikiGenResult<()> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
 
-    if let Err(err) = update_pokemon_pages_with_stripped_name(wiki_name, &base_path) {
+    if let Err(err) = update_pokemon_pages_with_stripped_name(&session) {
         return Err(err);
     }
 
     Ok(())
 }
 
-pub fn update_pokemon_pages_with_stripped_name(
-    wiki_name: &str,
-    base_path: &PathBuf,
-) -> Result<(), String> {
-    let mkdocs_yaml_file_path = base_path.join(wiki_name).join("dist").join("mkdocs.yml");
-    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+pub fn update_pokemon_pages_with_stripped_name(session: &WikiSession) -> WikiGenResult<()> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+
+    let mut mkdocs_config = match session.mkdocs_config() {
         Ok(config) => config,
         Err(err) => {
             logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
@@ -47,39 +60,34 @@ pub fn update_pokemon_pages_with_stripped_name(
         }
     };
 
-    let mut mkdocs_pokemon: &mut Vec<Value> = &mut Vec::new();
-
-    let nav_entries = mkdocs_config.nav.as_sequence_mut().unwrap();
-    for entry in nav_entries {
-        let map_entries = entry.as_mapping_mut().unwrap();
-        if let Some(map_entry) = map_entries.get_mut(Value::String("Pokemon".to_string())) {
-            mkdocs_pokemon = map_entry.as_sequence_mut().unwrap();
+    let mkdocs_pokemon = match mkdocs_config.nav_section_mut("Pokemon") {
+        Ok(mkdocs_pokemon) => mkdocs_pokemon,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
         }
-    }
+    };
 
     let new_page_entries: &mut Vec<Value> = &mut Vec::new();
-    for page_entry in mkdocs_pokemon.iter_mut() {
-        let key = page_entry
-            .as_mapping()
-            .unwrap()
-            .keys()
-            .next()
-            .unwrap()
-            .as_str();
-
-        let value = page_entry
-            .as_mapping()
-            .unwrap()
-            .values()
-            .next()
-            .unwrap()
-            .as_str()
-            .unwrap();
-
-        let stripped_name = capitalize_and_remove_hyphens(&key.unwrap().to_string().split_off(6));
+    for page_entry in mkdocs_pokemon.iter() {
+        let (Some(key), Some(value)) = (nav_entry_title(page_entry), nav_entry_file(page_entry))
+        else {
+            return Err(WikiGenError::invalid_nav(
+                "Pokemon nav entry is not a page",
+                format!("{:?}", page_entry),
+            ));
+        };
+        let Some(dex_number) = extract_pokemon_id(key) else {
+            return Err(WikiGenError::invalid_nav(
+                "Pokemon nav entry has no dex number",
+                key,
+            ));
+        };
+
+        let stripped_name = capitalize_and_remove_hyphens(&key.to_string().split_off(6));
         let new_key = format!(
             "{} - {}",
-            get_pokemon_dex_formatted_name(u32::try_from(extract_pokemon_id(key.clone())).unwrap()),
+            get_pokemon_dex_formatted_name(dex_number),
             stripped_name
         );
         let mut new_entry = Mapping::new();
@@ -89,11 +97,9 @@ pub fn update_pokemon_pages_with_stripped_name(
 
     mkdocs_pokemon.clear();
 
-    for new_entry in new_page_entries {
-        mkdocs_pokemon.push(Value::Mapping(new_entry.as_mapping().unwrap().clone()));
-    }
+    mkdocs_pokemon.append(new_page_entries);
 
-    update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
 
     Ok(())
 }
@@ -104,11 +110,11 @@ pub async fn remove_pokemon_page_with_old_dex_number(
     pokemon_name: &str,
     old_dex_number: u32,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
 
-    let mkdocs_yaml_file_path = base_path.join(wiki_name).join("dist").join("mkdocs.yml");
-    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+    let mut mkdocs_config = match session.mkdocs_config() {
         Ok(config) => config,
         Err(err) => {
             logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
@@ -116,15 +122,13 @@ pub async fn remove_pokemon_page_with_old_dex_number(
         }
     };
 
-    let mut mkdocs_pokemon: &mut Vec<Value> = &mut Vec::new();
-
-    let nav_entries = mkdocs_config.nav.as_sequence_mut().unwrap();
-    for entry in nav_entries {
-        let map_entries = entry.as_mapping_mut().unwrap();
-        if let Some(map_entry) = map_entries.get_mut(Value::String("Pokemon".to_string())) {
-            mkdocs_pokemon = map_entry.as_sequence_mut().unwrap();
+    let mkdocs_pokemon = match mkdocs_config.nav_section_mut("Pokemon") {
+        Ok(mkdocs_pokemon) => mkdocs_pokemon,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
         }
-    }
+    };
 
     let pokedex_markdown_file_name = get_pokemon_dex_formatted_name(old_dex_number);
     let entry_key = format!(
@@ -136,7 +140,7 @@ pub async fn remove_pokemon_page_with_old_dex_number(
     let mut page_entry_exists = false;
     let mut page_position = 0;
     for (index, page_entry) in mkdocs_pokemon.iter_mut().enumerate() {
-        if page_entry.as_mapping().unwrap().contains_key(&entry_key) {
+        if nav_entry_title(page_entry) == Some(entry_key.as_str()) {
             page_entry_exists = true;
             page_position = index;
             break;
@@ -147,28 +151,42 @@ pub async fn remove_pokemon_page_with_old_dex_number(
         return Ok("Page with old dex number not present".to_string());
     }
     mkdocs_pokemon.remove(page_position);
+    let old_page_file = format!("{}-{}.md", &pokedex_markdown_file_name, pokemon_name);
+    // The page under the new dex number, when it has been generated already
+    let new_page_file = mkdocs_pokemon
+        .iter()
+        .filter_map(nav_entry_file)
+        .find(|file| {
+            file.strip_prefix("pokemon/")
+                .and_then(|page| page.split_once('-'))
+                .is_some_and(|(_, page_name)| page_name == format!("{pokemon_name}.md"))
+        })
+        .map(str::to_string);
+    if let Some(new_page_file) = new_page_file {
+        add_page_redirect(
+            &mut mkdocs_config,
+            &format!("pokemon/{old_page_file}"),
+            &new_page_file,
+        );
+    }
     let pokemon_page_path = base_path
         .join(wiki_name)
         .join("dist")
         .join("docs")
         .join("pokemon")
-        .join(format!(
-            "{}-{}.md",
-            &pokedex_markdown_file_name, pokemon_name
-        ));
+        .join(old_page_file);
     if pokemon_page_path.try_exists().unwrap_or(false) {
-        if let Err(err) = fs::remove_file(pokemon_page_path) {
-            let message = format!("Failed to remove pokemon page: {err}");
-            logger::write_log(
-                &base_path.join(wiki_name),
-                logger::LogLevel::Error,
-                &message,
+        if let Err(err) = fs::remove_file(&pokemon_page_path) {
+            let error = WikiGenError::io(
+                format!("Failed to remove pokemon page: {err}"),
+                pokemon_page_path.display(),
             );
-            return Err(message);
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &error);
+            return Err(error);
         }
     }
 
-    update_mkdocs_yaml(wiki_name, &base_path, &mkdocs_config)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
 
     Ok("".to_string())
 }
@@ -179,18 +197,12 @@ pub async fn generate_pokemon_pages_from_list(
     wiki_name: &str,
     pokemon_ids: Vec<usize>,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let base_path = app_handle.path().app_data_dir().unwrap();
-    let resources_path = app_handle.path().resource_dir().unwrap();
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+    let resources_path = get_resource_dir(&app_handle)?;
 
-    let sqlite_file_path = base_path.join(wiki_name).join(format!("{}.db", wiki_name));
-    let conn = match get_sqlite_connection(sqlite_file_path).await {
-        Ok(conn) => conn,
-        Err(err) => {
-            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
-            return Err(err);
-        }
-    };
+    let conn = session.pool.clone();
     let (pokemon_list, abilities_list, movesets) =
         match get_pokemon_list_abilities_and_movesets(&conn, &pokemon_ids).await {
             Ok((pokemon_list, abilities_list, movesets)) => {
@@ -203,86 +215,35 @@ pub async fn generate_pokemon_pages_from_list(
         };
 
     return generate_pokemon_pages(
-        wiki_name,
+        &session,
         &pokemon_list,
         &abilities_list,
         &movesets,
-        &base_path,
         &resources_path,
     );
 }
 
-async fn get_pokemon_list_abilities_and_movesets(
+pub async fn get_pokemon_list_abilities_and_movesets(
     conn: &sqlx::Pool<Sqlite>,
     pokemon_ids: &[usize],
-) -> Result<(Vec<DBPokemon>, Vec<DBAbility>, Vec<PokemonMove>), String> {
-    let id_list = &pokemon_ids
-        .iter()
-        .map(|n| n.to_string())
-        .collect::<Vec<_>>()
-        .join(",");
+) -> WikiGenResult<(Vec<DBPokemon>, Vec<DBAbility>, Vec<PokemonMove>)> {
+    let pokemon_list = get_pokemon_by_ids(conn, pokemon_ids).await?;
+    let abilities_list = get_db_abilities(conn).await?;
+    let movesets = get_movesets_by_pokemon_ids(conn, pokemon_ids).await?;
 
-    let pokemon_query = format!(
-        "SELECT pokemon.* FROM pokemon WHERE pokemon.id IN ({}) ORDER BY dex_number ASC",
-        id_list
-    );
-    let pokemon_list = match sqlx::query_as::<_, DBPokemon>(&pokemon_query)
-        .fetch_all(conn)
-        .await
-    {
-        Ok(pokemon_list) => pokemon_list,
-        Err(err) => {
-            return Err(format!("Failed to fetch pokemon from database: {}", err));
-        }
-    };
-
-    let abilities_list = match sqlx::query_as::<_, DBAbility>("SELECT * FROM abilities")
-        .fetch_all(conn)
-        .await
-    {
-        Ok(abilities_list) => abilities_list,
-        Err(err) => {
-            return Err(format!("Failed to fetch abilities from database: {}", err));
-        }
-    };
-
-    let moveset_query = format!(
-        "SELECT
-            pokemon_movesets.*,
-            pokemon_movesets.move as move_id,
-            m.name as move_name, m.type as move_type,
-            m.power as power, m.accuracy as accuracy, m.pp as pp,
-            m.damage_class as damage_class, m.machine_name as machine_name
-        FROM pokemon_movesets
-        LEFT JOIN moves m on m.id = pokemon_movesets.move
-        WHERE pokemon_movesets.pokemon IN ({})",
-        id_list
-    );
-
-    let movesets = match sqlx::query_as::<_, PokemonMove>(&moveset_query)
-        .fetch_all(conn)
-        .await
-    {
-        Ok(pokemon_movesets) => pokemon_movesets,
-        Err(err) => {
-            return Err(format!(
-                "Failed to fetch pokemon movesets from database: {}",
-                err
-            ));
-        }
-    };
     return Ok((pokemon_list, abilities_list, movesets));
 }
 
 pub fn generate_pokemon_pages(
-    wiki_name: &str,
+    session: &WikiSession,
     pokemon_list: &[DBPokemon],
     abilities_list: &[DBAbility],
     movesets: &[PokemonMove],
-    base_path: &PathBuf,
     resources_path: &PathBuf,
-) -> Result<String, String> {
-    let docs_path = base_path.join(wiki_name).join("dist").join("docs");
+) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+    let docs_path = &session.docs_path;
 
     let routes_json_file_path = base_path.join(wiki_name).join("data").join("routes.json");
     let routes = match get_routes(&routes_json_file_path) {
@@ -295,7 +256,7 @@ pub fn generate_pokemon_pages(
 
     let dex_numbers = pokemon_list
         .iter()
-        .map(|p| usize::try_from(p.dex_number).unwrap())
+        .map(|p| p.dex_number as usize)
         .collect::<Vec<_>>();
 
     // Gather all wild encounters for the selected pokemon
@@ -308,8 +269,17 @@ pub fn generate_pokemon_pages(
         }
     }
 
-    let mkdocs_yaml_file_path = base_path.join(wiki_name).join("dist").join("mkdocs.yml");
-    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+    // Type defenses are optional on the page, so a wiki with missing
+    // type data still gets the rest of its pokemon pages generated
+    let matchup_table = match get_matchup_table(wiki_name, base_path) {
+        Ok(matchup_table) => Some(matchup_table),
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            None
+        }
+    };
+
+    let mut mkdocs_config = match session.mkdocs_config() {
         Ok(config) => config,
         Err(err) => {
             logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
@@ -317,18 +287,13 @@ pub fn generate_pokemon_pages(
         }
     };
 
-    let mut mkdocs_pokemon: &mut Vec<Value> = &mut Vec::new();
-
-    let nav_entries = mkdocs_config.nav.as_sequence_mut().unwrap();
-    for entry in nav_entries {
-        let map_entries = entry.as_mapping_mut().unwrap();
-        match map_entries.get_mut(Value::String("Pokemon".to_string())) {
-            Some(map_entry) => {
-                mkdocs_pokemon = map_entry.as_sequence_mut().unwrap();
-            }
-            None => {}
+    let mkdocs_pokemon = match mkdocs_config.nav_section_mut("Pokemon") {
+        Ok(mkdocs_pokemon) => mkdocs_pokemon,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
         }
-    }
+    };
 
     for pokemon in pokemon_list {
         let pokedex_markdown_file_name = get_pokemon_dex_formatted_name(pokemon.dex_number);
@@ -340,7 +305,7 @@ pub fn generate_pokemon_pages(
         let mut page_entry_exists = false;
         let mut page_position = 0;
         for (index, page_entry) in mkdocs_pokemon.iter_mut().enumerate() {
-            if page_entry.as_mapping().unwrap().contains_key(&entry_key) {
+            if nav_entry_title(page_entry) == Some(entry_key.as_str()) {
                 page_entry_exists = true;
                 page_position = index;
                 break;
@@ -394,49 +359,56 @@ pub fn generate_pokemon_pages(
         let current_pokemon_locations = wild_encounters
             .iter()
             .cloned()
-            .filter(|w| {
-                w.id == usize::try_from(pokemon.dex_number).unwrap() && w.name == pokemon.name
-            })
+            .filter(|w| w.id == pokemon.dex_number as usize && w.name == pokemon.name)
             .collect::<Vec<_>>();
 
-        let template = match read_to_string(
-            resources_path
-                .join("resources")
-                .join("generator_assets")
-                .join("templates")
-                .join("pokemon_page_template.md"),
-        ) {
+        let template = match read_template(resources_path, "pokemon_page_template.md") {
             Ok(template) => template,
             Err(err) => {
-                let message = format!("Failed to read template file: {err}");
-                logger::write_log(
-                    &base_path.join(wiki_name),
-                    logger::LogLevel::Error,
-                    &message,
-                );
-                return Err(message);
+                logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+                return Err(err);
             }
         };
 
+        let type_defenses = matchup_table.as_ref().and_then(|matchup_table| {
+            let defense_types = pokemon
+                .types
+                .split(",")
+                .map(str::to_string)
+                .collect::<Vec<String>>();
+            match matchup_table.defensive_matchups(&defense_types) {
+                Ok(type_defenses) => Some(type_defenses),
+                Err(err) => {
+                    let message = format!("{}: {}", pokemon.name, err);
+                    logger::write_log(
+                        &base_path.join(wiki_name),
+                        logger::LogLevel::Error,
+                        &message,
+                    );
+                    None
+                }
+            }
+        });
+
         let pokemon_markdown_string = generate_page_from_template(
             &template,
             &pokemon,
             &abilities_list,
             &current_pokemon_movset,
             &current_pokemon_locations,
+            type_defenses.as_ref(),
         );
 
         if let Err(err) = markdown_file.write_all(format!("{pokemon_markdown_string}").as_bytes()) {
-            let message = format!(
-                "Error writing to markdown file for {}: {}",
-                pokemon.name, err
+            let error = WikiGenError::io(
+                format!(
+                    "Error writing to markdown file for {}: {}",
+                    pokemon.name, err
+                ),
+                &entry_key,
             );
-            logger::write_log(
-                &base_path.join(wiki_name),
-                logger::LogLevel::Error,
-                &message,
-            );
-            return Err(message);
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &error);
+            return Err(error);
         };
 
         let mut pokemon_page_entry = Mapping::new();
@@ -453,30 +425,18 @@ pub fn generate_pokemon_pages(
 
             // Sort pokemon entries so new ones don't appear out of order
             // in the navigation
-            mkdocs_pokemon.sort_by(|a, b| {
-                let first = a.as_mapping().unwrap().keys().next().unwrap();
-                let second = b.as_mapping().unwrap().keys().next().unwrap();
-
-                extract_pokemon_id(first.as_str()).cmp(&extract_pokemon_id(second.as_str()))
-            })
+            mkdocs_pokemon.sort_by_key(|entry| nav_entry_title(entry).and_then(extract_pokemon_id))
         }
     }
 
-    update_mkdocs_yaml(wiki_name, base_path, &mkdocs_config)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
 
     return Ok("Pokemon Pages Generated".to_string());
 }
 
-fn extract_pokemon_id(key: Option<&str>) -> i32 {
-    // This long chain is just meant to get, format and trim dex number
-    return key
-        .unwrap()
-        .split_once("-")
-        .unwrap()
-        .0
-        .trim()
-        .parse::<i32>()
-        .unwrap();
+fn extract_pokemon_id(key: &str) -> Option<u32> {
+    // Nav keys look like "001 - Bulbasaur"
+    return key.split_once("-")?.0.trim().parse::<u32>().ok();
 }
 
 pub fn generate_page_from_template(
@@ -485,6 +445,7 @@ pub fn generate_page_from_template(
     abilities_list: &[DBAbility],
     movesets: &[PokemonMove],
     locations: &[WildEncounter],
+    type_defenses: Option<&TypeEffectiveness>,
 ) -> String {
     let type_images: Vec<String> = pokemon
         .types
@@ -563,6 +524,11 @@ pub fn generate_page_from_template(
 
     let evolution_change = create_evolution_table(&pokemon);
 
+    let type_defenses_table = match type_defenses {
+        Some(type_defenses) => create_type_defenses_table(type_defenses),
+        None => "".to_string(),
+    };
+
     let result = template
         .replace("{{pokemon_img_name}}", &pokemon.name)
         .replace(
@@ -637,6 +603,7 @@ pub fn generate_page_from_template(
             "{{speed_rank}}",
             calculate_bar_rank(pokemon.speed).to_string().as_str(),
         )
+        .replace("{{type_defenses}}", &type_defenses_table)
         .replace("{{evolution_change}}", &evolution_change)
         .replace("{{locations}}", &location_table)
         .replace("{{level_up_moves}}", &level_up_moves)
@@ -0,0 +1,181 @@
+use std::io::Write;
+
+use serde_yaml::{Mapping, Value};
+use tauri::AppHandle;
+
+use crate::{
+    database::{
+        create_docs_file, page_exists_in_mkdocs,
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::{
+        capitalize_and_remove_hyphens,
+        matchups::{get_matchup_table, get_type_chart, matchup_key, preset_matchup_map},
+    },
+    logger::{self, write_log, LogLevel},
+    structs::matchup_models::MatchupMap,
+    wiki_preparation::wiki_pages::get_nav_order,
+};
+
+#[tauri::command]
+pub async fn generate_type_chart_page_with_handle(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+
+    let matchup_table = match get_matchup_table(wiki_name, &base_path) {
+        Ok(matchup_table) => matchup_table,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err.into());
+        }
+    };
+
+    // Changes are highlighted against the chart the wiki was set up with
+    let type_chart = match get_type_chart(wiki_name, &base_path) {
+        Ok(type_chart) => type_chart,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err.into());
+        }
+    };
+    let standard_matchup_map = preset_matchup_map(&type_chart.preset);
+
+    return generate_type_chart_page(
+        &session,
+        matchup_table.types(),
+        matchup_table.matchup_map(),
+        &standard_matchup_map,
+    );
+}
+
+pub fn generate_type_chart_page(
+    session: &WikiSession,
+    types: &[String],
+    matchup_map: &MatchupMap,
+    standard_matchup_map: &MatchupMap,
+) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    let base_path = &session.base_path;
+
+    let offensive_chart = create_type_chart_table(
+        types,
+        |attacking_type, defending_type| matchup_key(attacking_type, defending_type),
+        matchup_map,
+        standard_matchup_map,
+    );
+    let defensive_chart = create_type_chart_table(
+        types,
+        |defending_type, attacking_type| matchup_key(attacking_type, defending_type),
+        matchup_map,
+        standard_matchup_map,
+    );
+
+    let type_chart_markdown = format!(
+        "Highlighted cells differ from the standard type chart.\n\n=== \"Offense\"\n\tRows are the attacking type, columns are the defending type.\n\n\t{}\n\n=== \"Defense\"\n\tRows are the defending type, columns are the attacking type.\n\n\t{}\n",
+        offensive_chart, defensive_chart
+    );
+
+    let mut type_chart_file = create_docs_file(wiki_name, base_path, "type_chart.md")?;
+
+    if let Err(err) = type_chart_file.write_all(type_chart_markdown.as_bytes()) {
+        let error = WikiGenError::io(
+            format!("{wiki_name}: Failed to write type chart file: {err}"),
+            "type_chart.md",
+        );
+        write_log(&base_path, LogLevel::Error, &error);
+        return Err(error);
+    }
+
+    let mut mkdocs_config = match session.mkdocs_config() {
+        Ok(config) => config,
+        Err(err) => {
+            logger::write_log(&base_path.join(wiki_name), logger::LogLevel::Error, &err);
+            return Err(err);
+        }
+    };
+
+    let (page_exists, _) = page_exists_in_mkdocs(mkdocs_config.clone(), "Type Chart")?;
+    if page_exists {
+        return Ok("Type Chart Page Updated".to_string());
+    }
+
+    let mut type_chart = Mapping::new();
+    type_chart.insert(
+        Value::String("Type Chart".to_string()),
+        Value::String("type_chart.md".to_string()),
+    );
+
+    let nav_order = get_nav_order(&base_path.join(wiki_name))?;
+    mkdocs_config.insert_nav_entry(Value::Mapping(type_chart), &nav_order)?;
+
+    session.update_mkdocs_config(&mkdocs_config)?;
+
+    Ok("Type Chart Page Generated".to_string())
+}
+
+// Written as raw html since markdown tables don't allow headers on both axes.
+// The key function receives (row type, column type) and returns the matchup key for that cell.
+fn create_type_chart_table(
+    types: &[String],
+    key_for_cell: impl Fn(&str, &str) -> String,
+    matchup_map: &MatchupMap,
+    standard_matchup_map: &MatchupMap,
+) -> String {
+    let mut header = String::from("<th></th>");
+    for _type in types {
+        header.push_str(&format!("<th>{}</th>", get_type_image(_type)));
+    }
+
+    let mut rows = String::new();
+    for row_type in types {
+        let mut row = format!("<th>{}</th>", get_type_image(row_type));
+        for column_type in types {
+            let key = key_for_cell(row_type, column_type);
+            let effectiveness = *matchup_map.get(&key).unwrap_or(&1.0);
+
+            let mut cell_style = "text-align: center;".to_string();
+            let mut title = String::new();
+            if standard_matchup_map.get(&key) != Some(&effectiveness) {
+                cell_style.push_str(" background-color: #ffdd57; color: #000000;");
+                title = match standard_matchup_map.get(&key) {
+                    Some(standard) => format!(" title=\"Standard: {}x\"", standard),
+                    None => " title=\"Not in the standard chart\"".to_string(),
+                };
+            }
+
+            row.push_str(&format!(
+                "<td style=\"{}\"{}>{}</td>",
+                cell_style,
+                title,
+                format_effectiveness(effectiveness)
+            ));
+        }
+        rows.push_str(&format!("<tr>{}</tr>", row));
+    }
+
+    format!(
+        "<table class=\"type-chart\"><thead><tr>{}</tr></thead><tbody>{}</tbody></table>",
+        header, rows
+    )
+}
+
+fn format_effectiveness(effectiveness: f32) -> String {
+    match effectiveness {
+        x if x == 1.0 => "".to_string(),
+        x if x == 0.5 => "½".to_string(),
+        x if x == 0.25 => "¼".to_string(),
+        x => x.to_string(),
+    }
+}
+
+fn get_type_image(_type: &str) -> String {
+    format!(
+        "<img src=\"../img/types/{}.png\" alt=\"{}\" style=\"width: 50px;\"/>",
+        _type,
+        capitalize_and_remove_hyphens(_type)
+    )
+}
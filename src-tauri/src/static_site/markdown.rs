@@ -0,0 +1,918 @@
+// Renders the markdown the page generators write to HTML, covering the
+// Python-Markdown extensions mkdocs.yml turns on for every wiki: tables,
+// admonitions, collapsible details, content tabs and abbreviations.
+// Generated pages indent their HTML freely, so indented code blocks aren't
+// supported, only fenced ones.
+
+const INDENT_WIDTH: usize = 4;
+const VOID_ELEMENTS: [&str; 10] = [
+    "area", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source",
+];
+
+#[derive(Debug, PartialEq)]
+enum Block {
+    Heading {
+        level: usize,
+        text: String,
+    },
+    Paragraph(String),
+    Table {
+        header: Vec<String>,
+        alignments: Vec<Option<&'static str>>,
+        rows: Vec<Vec<String>>,
+    },
+    List {
+        ordered: bool,
+        items: Vec<String>,
+    },
+    Code {
+        language: String,
+        code: String,
+    },
+    Html(Vec<String>),
+    Rule,
+    Tabs(Vec<(String, Vec<Block>)>),
+    Admonition {
+        kind: String,
+        title: Option<String>,
+        // None for a plain admonition, Some(open) for a collapsible one
+        collapsible: Option<bool>,
+        body: Vec<Block>,
+    },
+}
+
+struct MarkdownRenderer {
+    abbreviations: Vec<(String, String)>,
+    // Pages other than index.md are written one folder deeper than their
+    // markdown file, so relative links need to go up one more level
+    relative_prefix: &'static str,
+    // Folders between docs and the markdown file
+    folder_depth: usize,
+    tab_sets: usize,
+}
+
+// `file` is the page's path under docs, e.g. "pokemon/001-bulbasaur.md"
+pub fn render_markdown(content: &str, file: &str) -> String {
+    let is_index_page = file == "index.md" || file.ends_with("/index.md");
+    let mut abbreviations = Vec::new();
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        match abbreviation(line.trim()) {
+            Some(entry) => abbreviations.push(entry),
+            None => lines.push(expand_leading_tabs(line)),
+        }
+    }
+    // Longer terms first so "Sp. Atk" wins over "Sp"
+    abbreviations.sort_by_key(|(term, _)| std::cmp::Reverse(term.len()));
+
+    let mut renderer = MarkdownRenderer {
+        abbreviations,
+        relative_prefix: if is_index_page { "" } else { "../" },
+        folder_depth: file.matches('/').count(),
+        tab_sets: 0,
+    };
+    let mut html = String::new();
+    renderer.render_blocks(&parse_blocks(&lines), &mut html);
+    html
+}
+
+// Plain text of rendered HTML, used for the search index
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn parse_blocks(lines: &[String]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        let content = line.trim();
+        if content.is_empty() {
+            index += 1;
+            continue;
+        }
+        let indent = indentation(line);
+
+        if let Some(title) = tab_title(content) {
+            let (body, next_index) = indented_body(lines, index + 1, indent);
+            let tab = (title, parse_blocks(&body));
+            // Tabs that follow each other make up one set
+            match blocks.last_mut() {
+                Some(Block::Tabs(tabs)) => tabs.push(tab),
+                _ => blocks.push(Block::Tabs(vec![tab])),
+            }
+            index = next_index;
+            continue;
+        }
+
+        if let Some((kind, title, collapsible)) = admonition_header(content) {
+            let (body, next_index) = indented_body(lines, index + 1, indent);
+            blocks.push(Block::Admonition {
+                kind,
+                title,
+                collapsible,
+                body: parse_blocks(&body),
+            });
+            index = next_index;
+            continue;
+        }
+
+        if content.starts_with("```") || content.starts_with("~~~") {
+            let fence = &content[..3];
+            let language = content[3..].trim().to_string();
+            let mut code_lines = Vec::new();
+            index += 1;
+            while index < lines.len() && !lines[index].trim().starts_with(fence) {
+                code_lines.push(dedent(&lines[index], indent).to_string());
+                index += 1;
+            }
+            // Skips the closing fence
+            index += 1;
+            blocks.push(Block::Code {
+                language,
+                code: code_lines.join("\n"),
+            });
+            continue;
+        }
+
+        if let Some(level) = heading_level(content) {
+            blocks.push(Block::Heading {
+                level,
+                text: content[level..]
+                    .trim()
+                    .trim_end_matches('#')
+                    .trim()
+                    .to_string(),
+            });
+            index += 1;
+            continue;
+        }
+
+        if is_rule(content) {
+            blocks.push(Block::Rule);
+            index += 1;
+            continue;
+        }
+
+        if content.starts_with('<') {
+            let mut depth = 0;
+            let mut html_lines: Vec<String> = Vec::new();
+            while index < lines.len() {
+                let html_line = lines[index].trim();
+                if depth <= 0
+                    && !html_lines.is_empty()
+                    && (html_line.is_empty() || !html_line.starts_with('<'))
+                {
+                    break;
+                }
+                depth += tag_depth_change(html_line);
+                html_lines.push(html_line.to_string());
+                index += 1;
+            }
+            blocks.push(Block::Html(html_lines));
+            continue;
+        }
+
+        if starts_table(lines, index) {
+            let header = table_cells(content);
+            let alignments = table_cells(lines[index + 1].trim())
+                .iter()
+                .map(|cell| alignment(cell))
+                .collect();
+            index += 2;
+            let mut rows = Vec::new();
+            while index < lines.len() && lines[index].trim().starts_with('|') {
+                rows.push(table_cells(lines[index].trim()));
+                index += 1;
+            }
+            blocks.push(Block::Table {
+                header,
+                alignments,
+                rows,
+            });
+            continue;
+        }
+
+        if let Some((ordered, _)) = list_item(content) {
+            let mut items: Vec<String> = Vec::new();
+            while index < lines.len() {
+                let item_line = lines[index].trim();
+                if item_line.is_empty() {
+                    let list_continues = lines
+                        .get(index + 1)
+                        .and_then(|next_line| list_item(next_line.trim()))
+                        .is_some_and(|(next_ordered, _)| next_ordered == ordered);
+                    if !list_continues {
+                        break;
+                    }
+                    index += 1;
+                    continue;
+                }
+                match list_item(item_line) {
+                    Some((item_ordered, text)) if item_ordered == ordered => {
+                        items.push(text.to_string())
+                    }
+                    Some(_) => break,
+                    None => match items.last_mut() {
+                        Some(item) if !starts_block(item_line) => {
+                            item.push('\n');
+                            item.push_str(item_line);
+                        }
+                        _ => break,
+                    },
+                }
+                index += 1;
+            }
+            blocks.push(Block::List { ordered, items });
+            continue;
+        }
+
+        let mut paragraph_lines = vec![content];
+        index += 1;
+        while index < lines.len() {
+            let next_line = lines[index].trim();
+            if next_line.is_empty() || starts_block(next_line) || starts_table(lines, index) {
+                break;
+            }
+            paragraph_lines.push(next_line);
+            index += 1;
+        }
+        blocks.push(Block::Paragraph(paragraph_lines.join("\n")));
+    }
+
+    blocks
+}
+
+// Lines indented past the block header, dedented by one level. Raw HTML that
+// was opened inside the block keeps going until it's closed, whatever its
+// indentation, the same way Python-Markdown reads it.
+fn indented_body(lines: &[String], start: usize, indent: usize) -> (Vec<String>, usize) {
+    let body_indent = indent + INDENT_WIDTH;
+    let mut body = Vec::new();
+    let mut depth = 0;
+    let mut index = start;
+    while index < lines.len() {
+        let line = &lines[index];
+        if depth <= 0 && !line.trim().is_empty() && indentation(line) < body_indent {
+            break;
+        }
+        depth += tag_depth_change(line);
+        body.push(dedent(line, body_indent).to_string());
+        index += 1;
+    }
+    while body.last().is_some_and(|line| line.trim().is_empty()) {
+        body.pop();
+    }
+
+    (body, index)
+}
+
+fn starts_block(line: &str) -> bool {
+    tab_title(line).is_some()
+        || admonition_header(line).is_some()
+        || heading_level(line).is_some()
+        || list_item(line).is_some()
+        || line.starts_with("```")
+        || line.starts_with("~~~")
+        || line.starts_with('<')
+        || is_rule(line)
+}
+
+fn starts_table(lines: &[String], index: usize) -> bool {
+    lines[index].trim().starts_with('|')
+        && lines
+            .get(index + 1)
+            .is_some_and(|next_line| is_table_separator(next_line.trim()))
+}
+
+fn expand_leading_tabs(line: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '\t' => expanded.push_str(&" ".repeat(INDENT_WIDTH)),
+            ' ' => expanded.push(' '),
+            _ => break,
+        }
+        rest = &rest[1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn dedent(line: &str, width: usize) -> &str {
+    let indent = indentation(line).min(width);
+    &line[indent..]
+}
+
+// `*[HP]: Hit Points`
+fn abbreviation(line: &str) -> Option<(String, String)> {
+    let rest = line.strip_prefix("*[")?;
+    let (term, definition) = rest.split_once("]:")?;
+    if term.is_empty() {
+        return None;
+    }
+    Some((term.to_string(), definition.trim().to_string()))
+}
+
+// `=== "Title"`
+fn tab_title(line: &str) -> Option<String> {
+    let rest = line
+        .strip_prefix("===!")
+        .or_else(|| line.strip_prefix("===+"))
+        .or_else(|| line.strip_prefix("==="))?;
+    quoted(rest.trim())
+}
+
+// `!!! note "Title"`, `??? note` or `???+ note ""`
+fn admonition_header(line: &str) -> Option<(String, Option<String>, Option<bool>)> {
+    let (collapsible, rest) = if let Some(rest) = line.strip_prefix("???+") {
+        (Some(true), rest)
+    } else if let Some(rest) = line.strip_prefix("???") {
+        (Some(false), rest)
+    } else {
+        (None, line.strip_prefix("!!!")?)
+    };
+    if !rest.starts_with(' ') {
+        return None;
+    }
+
+    let rest = rest.trim();
+    let (kind, title) = match rest.find(' ') {
+        Some(space) => (&rest[..space], quoted(rest[space..].trim())),
+        None => (rest, None),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    let title = match title {
+        Some(title) if title.is_empty() => None,
+        Some(title) => Some(title),
+        None => Some(capitalize(kind)),
+    };
+
+    Some((kind.to_string(), title, collapsible))
+}
+
+fn quoted(text: &str) -> Option<String> {
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    Some(inner.to_string())
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+// Python-Markdown doesn't need a space after the hashes, so generated
+// headings like `##Evolution Change` are headings too
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    match level {
+        1..=6 if !line[level..].trim().is_empty() => Some(level),
+        _ => None,
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| compact.chars().all(|c| c == *marker))
+}
+
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(marker) {
+            return Some((false, text.trim()));
+        }
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        if let Some(text) = line[digits..].strip_prefix(". ") {
+            return Some((true, text.trim()));
+        }
+    }
+    None
+}
+
+fn is_table_separator(line: &str) -> bool {
+    line.starts_with('|')
+        && line.contains('-')
+        && line
+            .chars()
+            .all(|c| matches!(c, '|' | '-' | ':' | ' ' | '\t'))
+}
+
+fn table_cells(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix('|').unwrap_or(trimmed);
+    trimmed
+        .split('|')
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+fn alignment(separator_cell: &str) -> Option<&'static str> {
+    match (
+        separator_cell.starts_with(':'),
+        separator_cell.ends_with(':'),
+    ) {
+        (true, true) => Some("center"),
+        (true, false) => Some("left"),
+        (false, true) => Some("right"),
+        (false, false) => None,
+    }
+}
+
+// How many HTML elements the line opens minus how many it closes
+fn tag_depth_change(line: &str) -> i32 {
+    let mut change = 0;
+    let mut rest = line;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('!') || tag.ends_with('/') {
+            continue;
+        }
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if name.is_empty() || VOID_ELEMENTS.contains(&name.as_str()) {
+            continue;
+        }
+        change += if closing { -1 } else { 1 };
+    }
+    change
+}
+
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if (c == ' ' || c == '-' || c == '_') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+impl MarkdownRenderer {
+    fn render_blocks(&mut self, blocks: &[Block], html: &mut String) {
+        for block in blocks {
+            match block {
+                Block::Heading { level, text } => html.push_str(&format!(
+                    "<h{level} id=\"{}\">{}</h{level}>\n",
+                    slugify(&html_to_text(&self.render_inline(text))),
+                    self.render_inline(text)
+                )),
+                Block::Paragraph(text) => {
+                    html.push_str(&format!("<p>{}</p>\n", self.render_inline(text)))
+                }
+                Block::Table {
+                    header,
+                    alignments,
+                    rows,
+                } => {
+                    html.push_str("<table>\n<thead>\n<tr>");
+                    for (column, cell) in header.iter().enumerate() {
+                        html.push_str(&format!(
+                            "<th{}>{}</th>",
+                            alignment_style(alignments, column),
+                            self.render_inline(cell)
+                        ));
+                    }
+                    html.push_str("</tr>\n</thead>\n<tbody>\n");
+                    for row in rows {
+                        html.push_str("<tr>");
+                        for (column, cell) in row.iter().enumerate() {
+                            html.push_str(&format!(
+                                "<td{}>{}</td>",
+                                alignment_style(alignments, column),
+                                self.render_inline(cell)
+                            ));
+                        }
+                        html.push_str("</tr>\n");
+                    }
+                    html.push_str("</tbody>\n</table>\n");
+                }
+                Block::List { ordered, items } => {
+                    let tag = if *ordered { "ol" } else { "ul" };
+                    html.push_str(&format!("<{tag}>\n"));
+                    for item in items {
+                        html.push_str(&format!("<li>{}</li>\n", self.render_inline(item)));
+                    }
+                    html.push_str(&format!("</{tag}>\n"));
+                }
+                Block::Code { language, code } => {
+                    let class = match language.is_empty() {
+                        true => String::new(),
+                        false => format!(" class=\"language-{}\"", escape_html(language)),
+                    };
+                    html.push_str(&format!(
+                        "<pre><code{class}>{}</code></pre>\n",
+                        escape_html(code)
+                    ));
+                }
+                Block::Html(lines) => {
+                    // Markdown between the tags, like the images in wild
+                    // encounter cards, is still rendered
+                    for line in lines {
+                        match line.starts_with('<') {
+                            true => html.push_str(line),
+                            false => html.push_str(&self.render_inline(line)),
+                        }
+                        html.push('\n');
+                    }
+                }
+                Block::Rule => html.push_str("<hr>\n"),
+                Block::Tabs(tabs) => {
+                    self.tab_sets += 1;
+                    let tab_set = self.tab_sets;
+                    html.push_str("<div class=\"tabbed-set\">\n");
+                    for (tab_index, (title, _)) in tabs.iter().enumerate() {
+                        let checked = if tab_index == 0 { " checked" } else { "" };
+                        html.push_str(&format!(
+                            "<input type=\"radio\" name=\"__tabbed_{tab_set}\" id=\"__tabbed_{tab_set}_{}\"{checked}><label for=\"__tabbed_{tab_set}_{}\">{}</label>\n",
+                            tab_index + 1,
+                            tab_index + 1,
+                            self.render_inline(title)
+                        ));
+                    }
+                    html.push_str("<div class=\"tabbed-content\">\n");
+                    for (_, body) in tabs {
+                        html.push_str("<div class=\"tabbed-block\">\n");
+                        self.render_blocks(body, html);
+                        html.push_str("</div>\n");
+                    }
+                    html.push_str("</div>\n</div>\n");
+                }
+                Block::Admonition {
+                    kind,
+                    title,
+                    collapsible,
+                    body,
+                } => {
+                    let kind = escape_html(kind);
+                    match collapsible {
+                        Some(open) => {
+                            let open = if *open { " open" } else { "" };
+                            let title = title.clone().unwrap_or_else(|| capitalize(&kind));
+                            html.push_str(&format!(
+                                "<details class=\"{kind}\"{open}>\n<summary>{}</summary>\n",
+                                self.render_inline(&title)
+                            ));
+                            self.render_blocks(body, html);
+                            html.push_str("</details>\n");
+                        }
+                        None => {
+                            html.push_str(&format!("<div class=\"admonition {kind}\">\n"));
+                            if let Some(title) = title {
+                                html.push_str(&format!(
+                                    "<p class=\"admonition-title\">{}</p>\n",
+                                    self.render_inline(title)
+                                ));
+                            }
+                            self.render_blocks(body, html);
+                            html.push_str("</div>\n");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_inline(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut html = String::new();
+        let mut plain = String::new();
+        let mut index = 0;
+        while index < chars.len() {
+            let c = chars[index];
+            let next = chars.get(index + 1).copied();
+
+            if c == '\\' && next.is_some_and(|next| next.is_ascii_punctuation()) {
+                plain.push(chars[index + 1]);
+                index += 2;
+                continue;
+            }
+
+            if c == '`' {
+                if let Some(end) = find_char(&chars, index + 1, '`') {
+                    html.push_str(&self.render_plain(&plain));
+                    plain.clear();
+                    let code: String = chars[index + 1..end].iter().collect();
+                    html.push_str(&format!("<code>{}</code>", escape_html(&code)));
+                    index = end + 1;
+                    continue;
+                }
+            }
+
+            let is_image = c == '!' && next == Some('[');
+            if c == '[' || is_image {
+                let label_start = if is_image { index + 1 } else { index };
+                if let Some((label, destination, end)) = link_at(&chars, label_start) {
+                    html.push_str(&self.render_plain(&plain));
+                    plain.clear();
+                    let (url, title) = split_link_title(&destination);
+                    let title = match title {
+                        Some(title) => format!(" title=\"{}\"", escape_html(&title)),
+                        None => String::new(),
+                    };
+                    match is_image {
+                        true => html.push_str(&format!(
+                            "<img src=\"{}\" alt=\"{}\"{title}>",
+                            escape_html(&self.resolve_url(&url)),
+                            escape_html(&label)
+                        )),
+                        false => html.push_str(&format!(
+                            "<a href=\"{}\"{title}>{}</a>",
+                            escape_html(&self.resolve_url(&url)),
+                            self.render_inline(&label)
+                        )),
+                    }
+                    index = end;
+                    continue;
+                }
+            }
+
+            if c == '<' && next.is_some_and(|next| next.is_ascii_alphabetic() || next == '/') {
+                if let Some(end) = find_char(&chars, index + 1, '>') {
+                    html.push_str(&self.render_plain(&plain));
+                    plain.clear();
+                    html.extend(&chars[index..=end]);
+                    index = end + 1;
+                    continue;
+                }
+            }
+
+            if c == '*' || c == '_' {
+                if let Some((tag, inner, end)) = emphasis_at(&chars, index) {
+                    html.push_str(&self.render_plain(&plain));
+                    plain.clear();
+                    html.push_str(&format!("<{tag}>{}</{tag}>", self.render_inline(&inner)));
+                    index = end;
+                    continue;
+                }
+            }
+
+            plain.push(c);
+            index += 1;
+        }
+        html.push_str(&self.render_plain(&plain));
+        html
+    }
+
+    // Escapes text and wraps abbreviations in <abbr> for their tooltip
+    fn render_plain(&self, text: &str) -> String {
+        if self.abbreviations.is_empty() {
+            return escape_text(text);
+        }
+
+        let mut html = String::new();
+        let mut index = 0;
+        while index < text.len() {
+            let at_word_start = text[..index]
+                .chars()
+                .next_back()
+                .is_none_or(|previous| !previous.is_alphanumeric());
+            let abbreviation = self.abbreviations.iter().find(|(term, _)| {
+                at_word_start
+                    && text[index..].starts_with(term.as_str())
+                    && text[index + term.len()..]
+                        .chars()
+                        .next()
+                        .is_none_or(|following| !following.is_alphanumeric())
+            });
+            match abbreviation {
+                Some((term, definition)) => {
+                    html.push_str(&format!(
+                        "<abbr title=\"{}\">{}</abbr>",
+                        escape_html(definition),
+                        escape_text(term)
+                    ));
+                    index += term.len();
+                }
+                None => {
+                    let c = text[index..].chars().next().unwrap_or_default();
+                    html.push_str(&escape_text(&text[index..index + c.len_utf8()]));
+                    index += c.len_utf8();
+                }
+            }
+        }
+        html
+    }
+
+    fn resolve_url(&self, url: &str) -> String {
+        let is_relative = !(url.is_empty()
+            || url.contains("://")
+            || url.starts_with('/')
+            || url.starts_with('#')
+            || url.starts_with("mailto:")
+            || url.starts_with("data:"));
+        if !is_relative {
+            return url.to_string();
+        }
+
+        let (path, anchor) = match url.find('#') {
+            Some(anchor_start) => url.split_at(anchor_start),
+            None => (url, ""),
+        };
+        // Like mkdocs, paths that climb out of docs are left as written.
+        // Route pages write their image paths for the built page's URL this way.
+        if self.leaves_docs(path) {
+            return url.to_string();
+        }
+        let path = match path.strip_suffix(".md") {
+            Some("index") => String::new(),
+            Some(page) => match page.strip_suffix("/index") {
+                Some(folder) => format!("{folder}/"),
+                None => format!("{page}/"),
+            },
+            None => path.to_string(),
+        };
+        format!("{}{path}{anchor}", self.relative_prefix)
+    }
+
+    fn leaves_docs(&self, path: &str) -> bool {
+        let mut depth = self.folder_depth;
+        for segment in path.split('/') {
+            match segment {
+                ".." => match depth.checked_sub(1) {
+                    Some(parent_depth) => depth = parent_depth,
+                    None => return true,
+                },
+                "" | "." => {}
+                _ => depth += 1,
+            }
+        }
+        false
+    }
+}
+
+fn alignment_style(alignments: &[Option<&'static str>], column: usize) -> String {
+    match alignments.get(column).copied().flatten() {
+        Some(alignment) => format!(" style=\"text-align: {alignment}\""),
+        None => String::new(),
+    }
+}
+
+// Like escape_html, but leaves entities such as &nbsp; as they are
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::new();
+    for (index, c) in text.char_indices() {
+        match c {
+            '&' if is_entity(&text[index..]) => escaped.push('&'),
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn is_entity(text: &str) -> bool {
+    let Some(end) = text.find(';') else {
+        return false;
+    };
+    let name = &text[1..end];
+    !name.is_empty()
+        && name.len() <= 10
+        && (name.chars().all(|c| c.is_ascii_alphanumeric())
+            || name
+                .strip_prefix('#')
+                .is_some_and(|number| number.chars().all(|c| c.is_ascii_alphanumeric())))
+}
+
+fn find_char(chars: &[char], start: usize, target: char) -> Option<usize> {
+    (start..chars.len()).find(|index| chars[*index] == target)
+}
+
+// `[label](destination)` starting at the opening bracket, returning the
+// index right after the closing parenthesis
+fn link_at(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+    let mut depth = 0;
+    let mut label_end = None;
+    for (index, c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    label_end = Some(index);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let label_end = label_end?;
+    if chars.get(label_end + 1) != Some(&'(') {
+        return None;
+    }
+    let destination_end = find_char(chars, label_end + 2, ')')?;
+
+    Some((
+        chars[start + 1..label_end].iter().collect(),
+        chars[label_end + 2..destination_end]
+            .iter()
+            .collect::<String>()
+            .trim()
+            .to_string(),
+        destination_end + 1,
+    ))
+}
+
+fn split_link_title(destination: &str) -> (String, Option<String>) {
+    match destination.find(" \"") {
+        Some(title_start) => (
+            destination[..title_start].trim().to_string(),
+            quoted(destination[title_start..].trim()),
+        ),
+        None => (destination.to_string(), None),
+    }
+}
+
+// `**strong**`, `*em*`, `__strong__` or `_em_`. Underscores only count at
+// word boundaries so names like snake_case stay as they are.
+fn emphasis_at(chars: &[char], start: usize) -> Option<(&'static str, String, usize)> {
+    let marker = chars[start];
+    let width = if chars.get(start + 1) == Some(&marker) {
+        2
+    } else {
+        1
+    };
+    if marker == '_' && start > 0 && chars[start - 1].is_alphanumeric() {
+        return None;
+    }
+    let inner_start = start + width;
+    if chars.get(inner_start).is_none_or(|c| c.is_whitespace()) {
+        return None;
+    }
+
+    let mut index = inner_start + 1;
+    while index + width <= chars.len() {
+        let closes = chars[index..index + width].iter().all(|c| *c == marker)
+            && !chars[index - 1].is_whitespace()
+            && (marker != '_'
+                || chars
+                    .get(index + width)
+                    .is_none_or(|c| !c.is_alphanumeric()));
+        if closes {
+            let tag = if width == 2 { "strong" } else { "em" };
+            return Some((
+                tag,
+                chars[inner_start..index].iter().collect(),
+                index + width,
+            ));
+        }
+        index += 1;
+    }
+    None
+}
@@ -0,0 +1,69 @@
+// Builds and previews wikis without Python or mkdocs installed
+pub mod markdown;
+pub mod preview_server;
+pub mod site;
+
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    database::{
+        get_resource_dir, read_template,
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::WikiGenResult,
+    logger::{self, LogLevel},
+    static_site::{
+        preview_server::{start_preview_server, PreviewServerState},
+        site::{build_site, SiteAssets},
+    },
+};
+
+#[tauri::command]
+pub async fn build_static_site(wiki_name: &str, app_handle: AppHandle) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match build_session_site(&session, &app_handle) {
+        Ok(page_count) => Ok(format!("Built {} pages", page_count)),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+// Builds the site and serves it, returning the URL it can be opened at
+#[tauri::command]
+pub async fn preview_static_site(
+    wiki_name: &str,
+    port: u16,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    let preview_state = app_handle.state::<PreviewServerState>();
+    let result = build_session_site(&session, &app_handle)
+        .and_then(|_| start_preview_server(&preview_state, session.base_path.clone(), port));
+    match result {
+        Ok(port) => Ok(format!("http://localhost:{}/{}/", port, wiki_name)),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+fn build_session_site(session: &WikiSession, app_handle: &AppHandle) -> WikiGenResult<usize> {
+    let resources_path = get_resource_dir(app_handle)?;
+    let assets = SiteAssets {
+        page_template: read_template(&resources_path, "static_site_page_template.html")?,
+        stylesheet: read_template(&resources_path, "static_site.css")?,
+        search_script: read_template(&resources_path, "static_site_search.js")?,
+    };
+
+    build_site(
+        &session.docs_path,
+        &session.wiki_path.join("dist").join("site"),
+        &session.mkdocs_config()?,
+        &assets,
+    )
+}
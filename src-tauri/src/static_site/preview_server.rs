@@ -0,0 +1,212 @@
+// Serves built sites at localhost:{port}/{wiki_name}/ like `mkdocs serve`
+// does, which the absolute links in route pages rely on. Every wiki's site
+// is served from the app data folder, so one server covers them all.
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+};
+
+use crate::errors::{WikiGenError, WikiGenResult};
+
+// Port the server is listening on once started
+#[derive(Default)]
+pub struct PreviewServerState(pub Mutex<Option<u16>>);
+
+#[derive(Debug, PartialEq)]
+pub enum PreviewResponse {
+    File(PathBuf),
+    // Folders are redirected to their trailing slash URL so relative links
+    // on the page resolve against the right folder
+    Redirect(String),
+    NotFound,
+}
+
+// Starts the server unless it's already running and returns its port
+pub fn start_preview_server(
+    state: &PreviewServerState,
+    base_path: PathBuf,
+    port: u16,
+) -> WikiGenResult<u16> {
+    let mut running_port = state
+        .0
+        .lock()
+        .map_err(|err| WikiGenError::from(format!("Failed to access preview server: {}", err)))?;
+    if let Some(running_port) = *running_port {
+        return Ok(running_port);
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port)).map_err(|err| {
+        WikiGenError::io(
+            format!("Failed to start preview server: {}", err),
+            format!("port {}", port),
+        )
+    })?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let base_path = base_path.clone();
+            thread::spawn(move || handle_connection(stream, &base_path));
+        }
+    });
+
+    *running_port = Some(port);
+    Ok(port)
+}
+
+pub fn resolve_request(base_path: &Path, request_target: &str) -> PreviewResponse {
+    let path = request_target.split(['?', '#']).next().unwrap_or_default();
+    let decoded_path = percent_decode(path);
+
+    let segments: Vec<&str> = decoded_path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    // Keeps requests inside the wikis' site folders
+    if segments
+        .iter()
+        .any(|segment| *segment == ".." || *segment == "." || segment.contains('\\'))
+    {
+        return PreviewResponse::NotFound;
+    }
+    let Some((wiki_name, page_segments)) = segments.split_first() else {
+        return PreviewResponse::NotFound;
+    };
+    let mut file_path = base_path.join(wiki_name).join("dist").join("site");
+    file_path.extend(page_segments);
+
+    if file_path.is_dir() {
+        if !path.ends_with('/') {
+            return PreviewResponse::Redirect(format!("{path}/"));
+        }
+        file_path.push("index.html");
+    }
+    match file_path.is_file() {
+        true => PreviewResponse::File(file_path),
+        false => PreviewResponse::NotFound,
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, base_path: &Path) {
+    let mut request_line = String::new();
+    if BufReader::new(&stream)
+        .read_line(&mut request_line)
+        .is_err()
+    {
+        return;
+    }
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return;
+    };
+
+    let _ = stream.write_all(&build_response(base_path, method, target));
+}
+
+// HEAD gets the same status and headers as GET, Content-Length included,
+// without the body
+pub fn build_response(base_path: &Path, method: &str, target: &str) -> Vec<u8> {
+    let response = match (method, resolve_request(base_path, target)) {
+        ("GET" | "HEAD", PreviewResponse::File(file_path)) => match fs::read(&file_path) {
+            Ok(body) => http_response("200 OK", content_type(&file_path), &[], body),
+            Err(_) => not_found(),
+        },
+        ("GET" | "HEAD", PreviewResponse::Redirect(location)) => http_response(
+            "301 Moved Permanently",
+            "text/plain",
+            &[("Location", &location)],
+            Vec::new(),
+        ),
+        ("GET" | "HEAD", PreviewResponse::NotFound) => not_found(),
+        _ => http_response(
+            "405 Method Not Allowed",
+            "text/plain",
+            &[],
+            b"Method not allowed".to_vec(),
+        ),
+    };
+    if method != "HEAD" {
+        return response;
+    }
+    match response.windows(4).position(|window| window == b"\r\n\r\n") {
+        Some(headers_end) => response[..headers_end + 4].to_vec(),
+        None => response,
+    }
+}
+
+fn not_found() -> Vec<u8> {
+    http_response(
+        "404 Not Found",
+        "text/html; charset=utf-8",
+        &[],
+        b"<h1>Page not found</h1><p>Build the site again if the page was added recently.</p>"
+            .to_vec(),
+    )
+}
+
+fn http_response(
+    status: &str,
+    content_type: &str,
+    headers: &[(&str, &str)],
+    body: Vec<u8>,
+) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str("\r\n");
+
+    let mut response = response.into_bytes();
+    response.extend(body);
+    response
+}
+
+fn content_type(file_path: &Path) -> &'static str {
+    let extension = file_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "html" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "woff2" => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
@@ -0,0 +1,452 @@
+// Turns dist/docs into a static site laid out like `mkdocs build` with
+// directory URLs: pokemon/001-bulbasaur.md becomes
+// pokemon/001-bulbasaur/index.html, so the paths the generators write
+// resolve the same way under either builder
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::Serialize;
+use serde_yaml::Value;
+
+use crate::{
+    errors::{WikiGenError, WikiGenResult},
+    static_site::markdown::{escape_html, html_to_text, render_markdown},
+    structs::mkdocs_structs::MKDocsConfig,
+    wiki_preparation::wiki_plugins::page_redirects,
+};
+
+// Read from the templates folder by the command
+pub struct SiteAssets {
+    pub page_template: String,
+    pub stylesheet: String,
+    pub search_script: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavLink {
+    pub title: String,
+    // URL relative to the site root, None for sections
+    pub url: Option<String>,
+    pub children: Vec<NavLink>,
+}
+
+// Same layout as the search_index.json mkdocs writes
+#[derive(Debug, Serialize)]
+struct SearchIndex {
+    docs: Vec<SearchDocument>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchDocument {
+    location: String,
+    title: String,
+    text: String,
+}
+
+// URL of the page built from a markdown file, relative to the site root
+pub fn page_url(markdown_path: &str) -> String {
+    let page = markdown_path.strip_suffix(".md").unwrap_or(markdown_path);
+    if page == "index" {
+        return String::new();
+    }
+    match page.strip_suffix("/index") {
+        Some(folder) => format!("{folder}/"),
+        None => format!("{page}/"),
+    }
+}
+
+pub fn nav_links(nav: &Value) -> Vec<NavLink> {
+    let Some(entries) = nav.as_sequence() else {
+        return Vec::new();
+    };
+
+    let mut links = Vec::new();
+    for entry in entries {
+        match entry {
+            // A bare `- page.md` entry
+            Value::String(file) => links.push(NavLink {
+                title: file.trim_end_matches(".md").to_string(),
+                url: Some(link_url(file)),
+                children: Vec::new(),
+            }),
+            Value::Mapping(mapping) => {
+                for (title, target) in mapping {
+                    let Some(title) = title.as_str() else {
+                        continue;
+                    };
+                    links.push(match target {
+                        Value::String(file) => NavLink {
+                            title: title.to_string(),
+                            url: Some(link_url(file)),
+                            children: Vec::new(),
+                        },
+                        _ => NavLink {
+                            title: title.to_string(),
+                            url: None,
+                            children: nav_links(target),
+                        },
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    links
+}
+
+fn link_url(target: &str) -> String {
+    match target.contains("://") {
+        true => target.to_string(),
+        false => page_url(target),
+    }
+}
+
+// Nav titles keyed by markdown file, used as the page titles
+pub fn nav_titles(nav: &Value, titles: &mut HashMap<String, String>) {
+    let Some(entries) = nav.as_sequence() else {
+        return;
+    };
+    for mapping in entries.iter().filter_map(Value::as_mapping) {
+        for (title, target) in mapping {
+            match (title.as_str(), target) {
+                (Some(title), Value::String(file)) => {
+                    titles.insert(file.clone(), title.to_string());
+                }
+                (_, Value::Sequence(_)) => nav_titles(target, titles),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn render_nav(links: &[NavLink], root: &str, current_url: &str) -> String {
+    let mut html = String::from("<ul>\n");
+    for link in links {
+        match &link.url {
+            Some(url) => {
+                let href = match url.contains("://") {
+                    true => url.clone(),
+                    false => format!("{root}{url}"),
+                };
+                let active = if url == current_url {
+                    " class=\"active\""
+                } else {
+                    ""
+                };
+                html.push_str(&format!(
+                    "<li><a href=\"{}\"{active}>{}</a></li>\n",
+                    escape_html(&href),
+                    escape_html(&link.title)
+                ));
+            }
+            None => {
+                // Sections start closed unless they hold the current page
+                let open = if contains_url(&link.children, current_url) {
+                    " open"
+                } else {
+                    ""
+                };
+                html.push_str(&format!(
+                    "<li class=\"nav-section\"><details{open}><summary>{}</summary>\n{}</details></li>\n",
+                    escape_html(&link.title),
+                    render_nav(&link.children, root, current_url)
+                ));
+            }
+        }
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+fn contains_url(links: &[NavLink], url: &str) -> bool {
+    links
+        .iter()
+        .any(|link| link.url.as_deref() == Some(url) || contains_url(&link.children, url))
+}
+
+// Builds the whole site into `site_path`, replacing what was there, and
+// returns how many pages were rendered
+pub fn build_site(
+    docs_path: &Path,
+    site_path: &Path,
+    mkdocs_config: &MKDocsConfig,
+    assets: &SiteAssets,
+) -> WikiGenResult<usize> {
+    if site_path.try_exists().unwrap_or(false) {
+        fs::remove_dir_all(site_path).map_err(|err| {
+            WikiGenError::io(
+                format!("Failed to clear the previous site: {}", err),
+                site_path.display(),
+            )
+        })?;
+    }
+
+    let mut titles = HashMap::new();
+    nav_titles(&mkdocs_config.nav, &mut titles);
+    let nav = nav_links(&mkdocs_config.nav);
+
+    let mut search_index = SearchIndex { docs: Vec::new() };
+    for file in docs_files(docs_path)? {
+        let source_path = docs_path.join(&file);
+        if !file.ends_with(".md") {
+            copy_site_file(&source_path, &site_path.join(&file))?;
+            continue;
+        }
+
+        let markdown = fs::read_to_string(&source_path).map_err(|err| {
+            WikiGenError::io(
+                format!("Failed to read page: {}", err),
+                source_path.display(),
+            )
+        })?;
+        let content = render_markdown(&markdown, &file);
+
+        let url = page_url(&file);
+        let title = titles
+            .get(&file)
+            .cloned()
+            .or_else(|| first_heading(&markdown))
+            .unwrap_or_else(|| page_stem(&file));
+        let root = "../".repeat(url.matches('/').count());
+        let page = render_page(
+            &assets.page_template,
+            mkdocs_config,
+            &[
+                ("page_title", escape_html(&title)),
+                ("nav", render_nav(&nav, &root, &url)),
+            ],
+            &root,
+            &content,
+        );
+        write_site_file(&site_path.join(&url).join("index.html"), &page)?;
+
+        search_index.docs.push(SearchDocument {
+            location: url,
+            title,
+            text: html_to_text(&content),
+        });
+    }
+
+    // Stand-ins for the pages mkdocs-redirects writes, skipped where a real
+    // page was built at the same URL
+    for redirect in page_redirects(mkdocs_config) {
+        if redirect.from.starts_with('/') || redirect.from.split('/').any(|part| part == "..") {
+            continue;
+        }
+        let redirect_path = site_path.join(page_url(&redirect.from)).join("index.html");
+        if redirect_path.try_exists().unwrap_or(false) {
+            continue;
+        }
+        let root = "../".repeat(page_url(&redirect.from).matches('/').count());
+        let target = match redirect.to.contains("://") {
+            true => redirect.to.clone(),
+            false => format!("{root}{}", page_url(&redirect.to)),
+        };
+        write_site_file(&redirect_path, &redirect_page(&target))?;
+    }
+
+    let page_count = search_index.docs.len();
+    let search_json = serde_json::to_string(&search_index)
+        .map_err(|err| WikiGenError::from(format!("Failed to serialize search index: {}", err)))?;
+    let search_folder = site_path.join("search");
+    write_site_file(&search_folder.join("search_index.json"), &search_json)?;
+    // Also loaded as a script so search works when the site is opened from disk
+    write_site_file(
+        &search_folder.join("search_index.js"),
+        &format!("window.searchIndex = {search_json};\n"),
+    )?;
+    write_site_file(
+        &site_path.join("assets").join("static_site.css"),
+        &assets.stylesheet,
+    )?;
+    write_site_file(
+        &site_path.join("assets").join("static_site_search.js"),
+        &assets.search_script,
+    )?;
+
+    Ok(page_count)
+}
+
+fn redirect_page(target: &str) -> String {
+    let target = escape_html(target);
+    format!(
+        "<!doctype html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Redirecting...</title>\n\
+         <link rel=\"canonical\" href=\"{target}\">\n\
+         <meta http-equiv=\"refresh\" content=\"0; url={target}\">\n\
+         </head>\n<body>\n<a href=\"{target}\">Redirecting...</a>\n</body>\n</html>\n"
+    )
+}
+
+fn render_page(
+    template: &str,
+    mkdocs_config: &MKDocsConfig,
+    page_values: &[(&str, String)],
+    root: &str,
+    content: &str,
+) -> String {
+    let extra_css: String = mkdocs_config
+        .extra_css
+        .iter()
+        .map(|stylesheet| {
+            format!(
+                "<link rel=\"stylesheet\" href=\"{}\">\n",
+                escape_html(&format!("{root}{stylesheet}"))
+            )
+        })
+        .collect();
+    let favicon = match mkdocs_config.theme.favicon.is_empty() {
+        true => String::new(),
+        false => format!(
+            "<link rel=\"icon\" href=\"{}\">",
+            escape_html(&format!("{root}{}", mkdocs_config.theme.favicon))
+        ),
+    };
+    let logo = match mkdocs_config.theme.logo.is_empty() {
+        true => String::new(),
+        false => format!(
+            "<img class=\"site-logo\" src=\"{}\" alt=\"\">",
+            escape_html(&format!("{root}{}", mkdocs_config.theme.logo))
+        ),
+    };
+
+    let mut page = template
+        .replace("{{site_name}}", &escape_html(&mkdocs_config.site_name))
+        .replace(
+            "{{site_description}}",
+            &escape_html(&mkdocs_config.site_description),
+        )
+        .replace("{{favicon}}", &favicon)
+        .replace("{{logo}}", &logo)
+        .replace("{{fonts}}", &theme_fonts(mkdocs_config))
+        .replace("{{palettes}}", &theme_palettes(mkdocs_config))
+        .replace("{{extra_css}}", &extra_css)
+        .replace("{{root}}", root);
+    for (placeholder, value) in page_values {
+        page = page.replace(&format!("{{{{{placeholder}}}}}"), value);
+    }
+    // Content goes in last so placeholders written on a page stay as they are
+    page.replace("{{content}}", content)
+}
+
+// The palettes as JSON for the page to pick from with the system color
+// scheme, the same way material does
+fn theme_palettes(mkdocs_config: &MKDocsConfig) -> String {
+    let palettes: Vec<serde_json::Value> = mkdocs_config
+        .theme
+        .palette
+        .palettes()
+        .iter()
+        .map(|palette| {
+            serde_json::json!({
+                "media": palette.media,
+                "scheme": palette.scheme,
+                "primary": palette.primary,
+                "accent": palette.accent,
+            })
+        })
+        .collect();
+    // Keeps a `</script>` in a palette value from closing the script early
+    serde_json::Value::Array(palettes)
+        .to_string()
+        .replace("</", "<\\/")
+}
+
+fn theme_fonts(mkdocs_config: &MKDocsConfig) -> String {
+    let Some(font) = &mkdocs_config.theme.font else {
+        return String::new();
+    };
+
+    let mut families = Vec::new();
+    let mut variables = String::new();
+    for (family, variable) in [(&font.text, "--text-font"), (&font.code, "--code-font")] {
+        // Font names are only letters, digits, spaces and hyphens
+        let family: String = family
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-')
+            .collect();
+        if family.trim().is_empty() {
+            continue;
+        }
+        families.push(format!("family={}", family.trim().replace(' ', "+")));
+        variables.push_str(&format!("{variable}: \"{}\"; ", family.trim()));
+    }
+    if families.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "<link rel=\"stylesheet\" href=\"https://fonts.googleapis.com/css2?{}&display=swap\">\n<style>:root {{ {}}}</style>",
+        escape_html(&families.join("&")),
+        variables
+    )
+}
+
+fn first_heading(markdown: &str) -> Option<String> {
+    markdown
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("# "))
+        .map(|heading| heading.trim().to_string())
+}
+
+fn page_stem(file: &str) -> String {
+    let stem = file.rsplit('/').next().unwrap_or(file);
+    stem.trim_end_matches(".md").to_string()
+}
+
+// Every file under docs as a path relative to it with `/` separators
+fn docs_files(docs_path: &Path) -> WikiGenResult<Vec<String>> {
+    let mut files = Vec::new();
+    let mut folders = vec![(docs_path.to_path_buf(), String::new())];
+    while let Some((folder, prefix)) = folders.pop() {
+        let entries = fs::read_dir(&folder).map_err(|err| {
+            WikiGenError::io(
+                format!("Failed to read docs folder: {}", err),
+                folder.display(),
+            )
+        })?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let relative_path = format!("{prefix}{name}");
+            if entry.path().is_dir() {
+                folders.push((entry.path(), format!("{relative_path}/")));
+            } else {
+                files.push(relative_path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn write_site_file(file_path: &Path, contents: &str) -> WikiGenResult<()> {
+    create_parent_folder(file_path)?;
+    fs::write(file_path, contents).map_err(|err| {
+        WikiGenError::io(
+            format!("Failed to write site file: {}", err),
+            file_path.display(),
+        )
+    })
+}
+
+fn copy_site_file(source_path: &Path, file_path: &Path) -> WikiGenResult<()> {
+    create_parent_folder(file_path)?;
+    match fs::copy(source_path, file_path) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(WikiGenError::io(
+            format!("Failed to copy site file: {}", err),
+            source_path.display(),
+        )),
+    }
+}
+
+fn create_parent_folder(file_path: &Path) -> WikiGenResult<()> {
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).map_err(|err| {
+            WikiGenError::io(
+                format!("Failed to create site folder: {}", err),
+                parent.display(),
+            )
+        })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+// Errors are references that leave broken links or missing data on the
+// generated pages, warnings are things that still render but look wrong
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintCategory {
+    Evolutions,
+    Abilities,
+    Sprites,
+    TrainerPokemon,
+    TrainerMoves,
+    WildEncounters,
+    ItemLocations,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LintIssue {
+    pub severity: LintSeverity,
+    pub category: LintCategory,
+    // What the issue is about, like a pokemon name or a route and trainer
+    pub subject: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LintReport {
+    pub errors: usize,
+    pub warnings: usize,
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    pub fn add(
+        &mut self,
+        severity: LintSeverity,
+        category: LintCategory,
+        subject: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        match severity {
+            LintSeverity::Error => self.errors += 1,
+            LintSeverity::Warning => self.warnings += 1,
+        }
+        self.issues.push(LintIssue {
+            severity,
+            category,
+            subject: subject.into(),
+            message: message.into(),
+        });
+    }
+}
@@ -2,20 +2,86 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct TypeEffectiveness(pub HashMap<String, Vec<String>>);
 
-#[allow(dead_code)]
+pub type MatchupMap = HashMap<String, f32>;
+
+// Mirrors the wiki's data/types.json file. The list is user editable, so it can
+// hold any number of types, including ones that don't exist in the main games.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WikiTypes {
+    pub types: Vec<String>,
+}
+
+impl WikiTypes {
+    // "none" is only a placeholder for single typed pokemon and never takes part in matchups
+    pub fn matchup_types(&self) -> Vec<String> {
+        self.types
+            .iter()
+            .filter(|_type| _type.as_str() != "none")
+            .cloned()
+            .collect()
+    }
+}
+
+pub type CalculatedDefenses = HashMap<String, TypeEffectiveness>;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeChartPreset {
+    GenOne,
+    GenTwoToFive,
+    GenSixOnwards,
+}
+
+// Mirrors the wiki's data/type_chart.json file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TypeChart {
+    pub preset: TypeChartPreset,
+}
+
+impl TypeChartPreset {
+    pub fn types(&self) -> Vec<&'static str> {
+        let excluded_types: &[&str] = match self {
+            TypeChartPreset::GenOne => &["dark", "steel", "fairy"],
+            TypeChartPreset::GenTwoToFive => &["fairy"],
+            TypeChartPreset::GenSixOnwards => &[],
+        };
+        DEFAULT_TYPES
+            .iter()
+            .filter(|_type| !excluded_types.contains(_type))
+            .copied()
+            .collect()
+    }
+
+    // Matchups that differ from GEN_DEFAULT, listed as (offense, defense, effectiveness)
+    pub fn overrides(&self) -> &'static [(&'static str, &'static str, f32)] {
+        match self {
+            TypeChartPreset::GenOne => &[
+                ("bug", "poison", D),
+                ("poison", "bug", D),
+                ("ghost", "psychic", Z),
+                ("ice", "fire", N),
+            ],
+            TypeChartPreset::GenTwoToFive => &[("ghost", "steel", H), ("dark", "steel", H)],
+            TypeChartPreset::GenSixOnwards => &[],
+        }
+    }
+}
+
+// Row and column order of the GEN_DEFAULT matrix below
+pub const DEFAULT_TYPES: [&str; 18] = [
+    "normal", "fire", "water", "electric", "grass", "ice", "fighting", "poison", "ground",
+    "flying", "psychic", "bug", "rock", "ghost", "dragon", "dark", "steel", "fairy",
+];
+
 const N: f32 = 1.0;
-#[allow(dead_code)]
 const H: f32 = 0.5;
 // const X: f32 = f32::NAN;
-#[allow(dead_code)]
 const D: f32 = 2.0;
-#[allow(dead_code)]
 const Z: f32 = 0.0;
 
-#[allow(dead_code)]
 pub const GEN_DEFAULT: [[f32; 18]; 18] = [
     [N, N, N, N, N, N, N, N, N, N, N, N, H, Z, N, N, H, N],
     [N, H, H, N, D, D, N, N, N, N, N, D, H, N, H, N, D, N],
@@ -1,153 +1,462 @@
-use serde::de::Visitor;
-use serde::ser::SerializeMap;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use serde_yaml::Value;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_yaml::{Mapping, Value};
+
+use crate::errors::{WikiGenError, WikiGenResult};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MKDocsConfig {
     pub site_name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub site_url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub site_description: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub site_author: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub repo_url: String,
     pub theme: Theme,
     pub nav: Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub plugins: Vec<Plugin>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub markdown_extensions: Vec<MarkdownExtension>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub extra_css: Vec<String>,
+    // Keys added to mkdocs.yml by hand, like extra or copyright, written
+    // back as they were read
+    #[serde(flatten)]
+    pub other_keys: Mapping,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Theme {
-    pub name: String,
-    pub features: Vec<String>,
-    pub favicon: String,
-    pub palette: [Palette; 2],
-}
+impl MKDocsConfig {
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(&protect_yaml_tags(yaml))
+    }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Palette {
-    pub media: String,
-    pub primary: String,
-    pub scheme: String,
-    pub toggle: Toggle,
+    // Keys keep the order they have in `previous_yaml`, so hand edits to
+    // mkdocs.yml stay where the user put them
+    pub fn to_yaml(&self, previous_yaml: Option<&str>) -> Result<String, serde_yaml::Error> {
+        let mut config_value = serde_yaml::to_value(self)?;
+        if let Some(previous_value) = previous_yaml.and_then(|previous_yaml| {
+            serde_yaml::from_str::<Value>(&protect_yaml_tags(previous_yaml)).ok()
+        }) {
+            restore_empty_keys(
+                &mut config_value,
+                &previous_value,
+                &CONFIG_KEYS_SKIPPED_WHEN_EMPTY,
+            );
+            if let (Some(theme), Some(previous_theme)) =
+                (config_value.get_mut("theme"), previous_value.get("theme"))
+            {
+                restore_empty_keys(theme, previous_theme, &THEME_KEYS_SKIPPED_WHEN_EMPTY);
+            }
+            order_keys_like(&mut config_value, &previous_value);
+        }
+        Ok(restore_yaml_tags(&serde_yaml::to_string(&config_value)?))
+    }
+
+    pub fn nav_entries_mut(&mut self) -> WikiGenResult<&mut Vec<Value>> {
+        match self.nav.as_sequence_mut() {
+            Some(nav_entries) => Ok(nav_entries),
+            None => Err(WikiGenError::invalid_nav(
+                "Nav is not a list of pages",
+                "nav",
+            )),
+        }
+    }
+
+    // Pages listed under a top level section of the nav, e.g. "Pokemon" or "Routes"
+    pub fn nav_section_mut(&mut self, section_title: &str) -> WikiGenResult<&mut Vec<Value>> {
+        for entry in self.nav_entries_mut()? {
+            let Some(map_entries) = entry.as_mapping_mut() else {
+                continue;
+            };
+            let Some(section) = map_entries.get_mut(Value::String(section_title.to_string()))
+            else {
+                continue;
+            };
+            return match section.as_sequence_mut() {
+                Some(section_entries) => Ok(section_entries),
+                None => Err(WikiGenError::invalid_nav(
+                    format!("Nav section {} is not a list of pages", section_title),
+                    section_title,
+                )),
+            };
+        }
+
+        Err(WikiGenError::invalid_nav(
+            format!("Nav section {} is missing", section_title),
+            section_title,
+        ))
+    }
+
+    // Adds a top level entry right after the last entry `nav_order` ranks
+    // before it. Entries the order doesn't know go last.
+    pub fn insert_nav_entry(&mut self, entry: Value, nav_order: &[String]) -> WikiGenResult<()> {
+        let rank = |entry: &Value| {
+            nav_entry_title(entry)
+                .and_then(|title| nav_order.iter().position(|ordered| ordered == title))
+        };
+        let entry_rank = rank(&entry);
+        let nav_entries = self.nav_entries_mut()?;
+        let index = match entry_rank {
+            Some(entry_rank) => nav_entries
+                .iter()
+                .rposition(|existing| rank(existing).is_some_and(|rank| rank < entry_rank))
+                .map_or(0, |index| index + 1),
+            None => nav_entries.len(),
+        };
+        nav_entries.insert(index, entry);
+        Ok(())
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Toggle {
-    pub icon: String,
-    pub name: String,
+// Empty fields are left out so keys mkdocs.yml doesn't have aren't added
+// on save. Ones the file sets to an empty value on purpose are put back,
+// since e.g. `plugins: []` turns off the search plugin mkdocs adds otherwise.
+const CONFIG_KEYS_SKIPPED_WHEN_EMPTY: [&str; 7] = [
+    "site_url",
+    "site_description",
+    "site_author",
+    "repo_url",
+    "plugins",
+    "markdown_extensions",
+    "extra_css",
+];
+const THEME_KEYS_SKIPPED_WHEN_EMPTY: [&str; 4] = ["features", "favicon", "logo", "palette"];
+
+fn restore_empty_keys(value: &mut Value, previous_value: &Value, keys: &[&str]) {
+    let (Some(mapping), Some(previous_mapping)) =
+        (value.as_mapping_mut(), previous_value.as_mapping())
+    else {
+        return;
+    };
+    for key in keys {
+        let Some(previous_entry) = previous_mapping.get(*key) else {
+            continue;
+        };
+        let is_empty = match previous_entry {
+            Value::String(text) => text.is_empty(),
+            Value::Sequence(sequence) => sequence.is_empty(),
+            _ => false,
+        };
+        if is_empty && !mapping.contains_key(*key) {
+            mapping.insert(Value::from(*key), previous_entry.clone());
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Plugin {
-    pub search: Search,
+fn order_keys_like(value: &mut Value, previous_value: &Value) {
+    // Lists like the theme palettes are matched up by position
+    if let (Some(sequence), Some(previous_sequence)) =
+        (value.as_sequence_mut(), previous_value.as_sequence())
+    {
+        for (entry, previous_entry) in sequence.iter_mut().zip(previous_sequence) {
+            order_keys_like(entry, previous_entry);
+        }
+        return;
+    }
+
+    let (Some(mapping), Some(previous_mapping)) =
+        (value.as_mapping_mut(), previous_value.as_mapping())
+    else {
+        return;
+    };
+
+    let mut ordered_mapping = Mapping::new();
+    for (key, previous_entry) in previous_mapping {
+        if let Some(mut entry) = mapping.shift_remove(key) {
+            order_keys_like(&mut entry, previous_entry);
+            ordered_mapping.insert(key.clone(), entry);
+        }
+    }
+    // New keys go after the ones the file already had
+    ordered_mapping.extend(std::mem::take(mapping));
+    *mapping = ordered_mapping;
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Search {
-    pub lang: String,
+// serde_yaml drops tags like `!!python/name:material.extensions.emoji.twemoji`
+// and can't write `!!` back, so tagged values are read as plain strings
+// starting with this marker and written back as they were
+const YAML_TAG_MARKER: &str = "__wikigen_yaml_tag__";
+
+fn protect_yaml_tags(yaml: &str) -> String {
+    yaml.lines()
+        .map(protect_line_tags)
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum MarkdownExtension {
-    String(String),
-    /*
-    I originally used KeyValue map here to represent PymdownxTaskList and PymdownxTabbed.
-    Ideally, this made the program more extensible.
-    However, due to my skill issues with deserialization,
-    I opted to use these structs instead.
+fn protect_line_tags(line: &str) -> String {
+    let mut protected = String::with_capacity(line.len());
+    let mut rest = line;
+    let mut flow_depth = 0;
+    // A tag only starts a value, so the last character before it has to be
+    // the start of the line, `-`, `:`, `,` or an opening bracket
+    let mut value_can_start = true;
+
+    while let Some(character) = rest.chars().next() {
+        if character == '#' && (protected.is_empty() || protected.ends_with(' ')) {
+            break;
+        }
+        if character == '\'' || character == '"' {
+            let quoted_len = quoted_scalar_len(rest, character);
+            protected.push_str(&rest[..quoted_len]);
+            rest = &rest[quoted_len..];
+            value_can_start = false;
+            continue;
+        }
+        if character == '!' && value_can_start {
+            let tagged_len = tagged_value_len(rest, flow_depth > 0);
+            let tagged_value = rest[..tagged_len].trim_end();
+            protected.push('"');
+            protected.push_str(YAML_TAG_MARKER);
+            protected.push_str(&tagged_value.replace('\\', "\\\\").replace('"', "\\\""));
+            protected.push('"');
+            rest = &rest[tagged_value.len()..];
+            value_can_start = false;
+            continue;
+        }
 
-    This makes the work easier, however, this will break if I need to add more extensions
-    or a user adds their own extensions to the mkdocs.yml file.
-     */
-    PymdownxTaskList(PymdownxTaskList),
-    PymdownxTabbed(PymdownxTabbed),
+        match character {
+            '[' | '{' => flow_depth += 1,
+            ']' | '}' => flow_depth -= 1,
+            _ => {}
+        }
+        if !character.is_whitespace() {
+            value_can_start = matches!(character, '-' | ':' | ',' | '[' | '{');
+        }
+        protected.push(character);
+        rest = &rest[character.len_utf8()..];
+    }
+
+    protected.push_str(rest);
+    protected
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub struct PymdownxTaskList {
-    pub custom_checkbox: bool,
+// Length of a quoted scalar including both quotes, or the rest of the line
+// when it isn't closed
+fn quoted_scalar_len(text: &str, quote: char) -> usize {
+    let mut characters = text.char_indices().skip(1).peekable();
+    while let Some((index, character)) = characters.next() {
+        match (quote, character) {
+            ('"', '\\') => {
+                characters.next();
+            }
+            ('\'', '\'') if characters.peek().is_some_and(|(_, next)| *next == '\'') => {
+                characters.next();
+            }
+            _ if character == quote => return index + 1,
+            _ => {}
+        }
+    }
+    text.len()
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub struct PymdownxTabbed {
-    pub alternate_style: bool,
+// A tag and the value it applies to. Inside a flow collection the value
+// ends at the next `,` or closing bracket, otherwise at a comment or the
+// end of the line.
+fn tagged_value_len(text: &str, in_flow: bool) -> usize {
+    let mut depth = 0;
+    let mut previous = ' ';
+    for (index, character) in text.char_indices() {
+        match character {
+            '[' | '{' => depth += 1,
+            ']' | '}' if depth > 0 => depth -= 1,
+            ',' | ']' | '}' if in_flow && depth == 0 => return index,
+            '#' if previous == ' ' && depth == 0 => return index,
+            _ => {}
+        }
+        previous = character;
+    }
+    text.len()
 }
 
-impl Serialize for MarkdownExtension {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        match self {
-            MarkdownExtension::String(s) => serializer.serialize_str(s),
-            MarkdownExtension::PymdownxTabbed(p) => {
-                let mut map = serializer.serialize_map(Some(1))?;
-                map.serialize_entry("pymdownx.tabbed", &p)?;
-                map.end()
+fn restore_yaml_tags(yaml: &str) -> String {
+    yaml.lines()
+        .map(restore_line_tags)
+        .collect::<Vec<_>>()
+        .join("\n")
+        + if yaml.ends_with('\n') { "\n" } else { "" }
+}
+
+fn restore_line_tags(line: &str) -> String {
+    let mut restored = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(marker_index) = rest.find(YAML_TAG_MARKER) {
+        let before_marker = &rest[..marker_index];
+        let tagged_text = &rest[marker_index + YAML_TAG_MARKER.len()..];
+        let (tagged_value, tagged_len) = match before_marker.chars().last() {
+            Some(quote @ ('\'' | '"')) => {
+                let quoted_len = quoted_scalar_len(&rest[marker_index - 1..], quote);
+                let inner = &tagged_text[..quoted_len - 2 - YAML_TAG_MARKER.len()];
+                let value = match quote {
+                    '"' => unescape_double_quoted(inner),
+                    _ => inner.replace("''", "'"),
+                };
+                restored.push_str(&before_marker[..before_marker.len() - 1]);
+                (value, quoted_len - 1 - YAML_TAG_MARKER.len())
             }
-            MarkdownExtension::PymdownxTaskList(p) => {
-                let mut map = serializer.serialize_map(Some(1))?;
-                map.serialize_entry("pymdownx.tasklist", &p)?;
-                map.end()
+            _ => {
+                restored.push_str(before_marker);
+                (tagged_text.to_string(), tagged_text.len())
             }
+        };
+        restored.push_str(&tagged_value);
+        rest = &tagged_text[tagged_len..];
+    }
+
+    restored.push_str(rest);
+    restored
+}
+
+fn unescape_double_quoted(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut characters = text.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => unescaped.extend(characters.next()),
+            _ => unescaped.push(character),
         }
     }
+    unescaped
 }
 
-impl<'de> Deserialize<'de> for MarkdownExtension {
-    fn deserialize<D>(deserializer: D) -> Result<MarkdownExtension, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct MarkdownExtensionVisitor;
+// Title of a single `title: file.md` nav entry
+pub fn nav_entry_title(entry: &Value) -> Option<&str> {
+    entry.as_mapping()?.keys().next()?.as_str()
+}
 
-        impl<'de> Visitor<'de> for MarkdownExtensionVisitor {
-            type Value = MarkdownExtension;
+// File a single `title: file.md` nav entry points to
+pub fn nav_entry_file(entry: &Value) -> Option<&str> {
+    entry.as_mapping()?.values().next()?.as_str()
+}
 
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("string or key-value map")
-            }
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Theme {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub favicon: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub logo: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<ThemeFont>,
+    #[serde(default, skip_serializing_if = "ThemePalette::is_empty")]
+    pub palette: ThemePalette,
+    #[serde(flatten)]
+    pub other_keys: Mapping,
+}
 
-            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-            where
-                E: serde::de::Error,
-            {
-                Ok(MarkdownExtension::String(value.to_owned()))
-            }
+// Material takes either a list of palettes or a single palette mapping.
+// Anything else is kept as written and left alone by the theme settings.
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum ThemePalette {
+    List(Vec<Palette>),
+    Single(Palette),
+    Other(Value),
+}
 
-            fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
-            where
-                E: serde::de::Error,
-            {
-                Ok(MarkdownExtension::String(value))
-            }
+impl Default for ThemePalette {
+    fn default() -> Self {
+        ThemePalette::List(Vec::new())
+    }
+}
 
-            fn visit_map<A>(self, mut map: A) -> Result<MarkdownExtension, A::Error>
-            where
-                A: serde::de::MapAccess<'de>,
-            {
-                let key: String = map
-                    .next_key()?
-                    .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
-                match key.as_str() {
-                    "pymdownx.tasklist" => {
-                        let p: PymdownxTaskList = map.next_value()?;
-                        Ok(MarkdownExtension::PymdownxTaskList(p))
-                    }
-                    "pymdownx.tabbed" => {
-                        let p: PymdownxTabbed = map.next_value()?;
-                        Ok(MarkdownExtension::PymdownxTabbed(p))
-                    }
-                    _ => {
-                        let s: String = map.next_value()?;
-                        Ok(MarkdownExtension::String(s))
-                    }
-                }
-            }
+impl<'de> Deserialize<'de> for ThemePalette {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        if let Ok(palettes) = serde_yaml::from_value::<Vec<Palette>>(value.clone()) {
+            return Ok(ThemePalette::List(palettes));
+        }
+        if let Ok(palette) = serde_yaml::from_value::<Palette>(value.clone()) {
+            return Ok(ThemePalette::Single(palette));
         }
+        Ok(ThemePalette::Other(value))
+    }
+}
 
-        deserializer.deserialize_any(MarkdownExtensionVisitor)
+impl ThemePalette {
+    pub fn is_empty(&self) -> bool {
+        matches!(self, ThemePalette::List(palettes) if palettes.is_empty())
+    }
+
+    pub fn palettes(&self) -> &[Palette] {
+        match self {
+            ThemePalette::List(palettes) => palettes,
+            ThemePalette::Single(palette) => std::slice::from_ref(palette),
+            ThemePalette::Other(_) => &[],
+        }
+    }
+
+    pub fn palettes_mut(&mut self) -> &mut [Palette] {
+        match self {
+            ThemePalette::List(palettes) => palettes,
+            ThemePalette::Single(palette) => std::slice::from_mut(palette),
+            ThemePalette::Other(_) => &mut [],
+        }
+    }
+}
+
+// Google Fonts names, material falls back to Roboto when one is left out
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ThemeFont {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub text: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Palette {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub media: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub primary: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub accent: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub scheme: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toggle: Option<Toggle>,
+    #[serde(flatten)]
+    pub other_keys: Mapping,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Toggle {
+    pub icon: String,
+    pub name: String,
+}
+
+// An entry of plugins or markdown_extensions, either just its name or a map
+// of the name to its options, kept as written so any extension round trips
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ConfigEntry {
+    String(String),
+    Map(Mapping),
+}
+
+pub type Plugin = ConfigEntry;
+pub type MarkdownExtension = ConfigEntry;
+
+impl ConfigEntry {
+    pub fn with_option(name: &str, option: &str, value: impl Into<Value>) -> Self {
+        let mut options = Mapping::new();
+        options.insert(Value::String(option.to_string()), value.into());
+        let mut entry = Mapping::new();
+        entry.insert(Value::String(name.to_string()), Value::Mapping(options));
+        ConfigEntry::Map(entry)
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            ConfigEntry::String(name) => Some(name),
+            ConfigEntry::Map(entry) => entry.keys().next()?.as_str(),
+        }
     }
 }
@@ -1,4 +1,7 @@
+pub mod lint_models;
 pub mod matchup_models;
 pub mod mkdocs_structs;
 pub mod move_structs;
+pub mod page_models;
 pub mod pokemon_structs;
+pub mod table_models;
@@ -0,0 +1,71 @@
+// Rows the page generators read from the wiki database
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Ability {
+    pub name: String,
+    pub effect: String,
+    pub is_modified: i32,
+    pub is_new: i32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Item {
+    pub name: String,
+    pub effect: String,
+    pub is_modified: i32,
+    pub is_new: i32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ItemLocation {
+    pub item_name: String,
+    pub route: String,
+    pub specific_location: Option<String>,
+    pub method: Option<String>,
+    pub requirements: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct MachineMove {
+    pub name: String,
+    pub machine_name: String,
+    pub power: Option<u32>,
+    pub accuracy: Option<u32>,
+    pub pp: Option<u32>,
+    #[sqlx(rename = "type")]
+    pub move_type: String,
+    pub damage_class: String,
+    pub learnable_by: u32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Move {
+    pub name: String,
+    pub power: Option<u32>,
+    pub accuracy: Option<u32>,
+    pub pp: Option<u32>,
+    #[sqlx(rename = "type")]
+    pub move_type: String,
+    pub damage_class: String,
+    pub is_modified: i32,
+    pub is_new: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+pub struct MoveType {
+    pub name: String,
+    #[sqlx(rename = "type")]
+    pub move_type: String,
+    pub damage_class: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Nature {
+    pub name: String,
+    pub increased_stat: Option<String>,
+    pub decreased_stat: Option<String>,
+    pub is_modified: i32,
+    pub is_new: i32,
+}
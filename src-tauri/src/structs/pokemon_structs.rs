@@ -1,6 +1,5 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use std::{fmt, str::FromStr};
 
 #[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
 pub struct DBPokemon {
@@ -52,7 +51,7 @@ pub struct Ability {
     pub effect: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Stats {
     pub hp: u32,
     pub attack: u32,
@@ -62,82 +61,26 @@ pub struct Stats {
     pub speed: u32,
 }
 
-// TODO: Consider Removing this and matchup.rs in the future
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
-pub enum PokemonTypesEnum {
-    Normal,
-    Fire,
-    Water,
-    Electric,
-    Grass,
-    Ice,
-    Fighting,
-    Poison,
-    Ground,
-    Flying,
-    Psychic,
-    Bug,
-    Rock,
-    Ghost,
-    Dragon,
-    Dark,
-    Steel,
-    Fairy,
-    None,
-}
-
-impl fmt::Display for PokemonTypesEnum {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
-    }
+// Changes to one species coming from an importer. Fields left as None are
+// kept as they are, movesets replace every move learned by that method.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SpeciesUpdate {
+    pub pokemon_id: u32,
+    pub types: Option<String>,
+    pub abilities: Option<String>,
+    pub stats: Option<Stats>,
+    // Pairs of move id and the level it's learned at
+    pub level_up_moves: Option<Vec<(u32, u32)>>,
+    pub machine_moves: Option<Vec<u32>>,
+    pub evolution: Option<Evolution>,
 }
 
-impl FromStr for PokemonTypesEnum {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "normal" => Ok(PokemonTypesEnum::Normal),
-            "fire" => Ok(PokemonTypesEnum::Fire),
-            "water" => Ok(PokemonTypesEnum::Water),
-            "electric" => Ok(PokemonTypesEnum::Electric),
-            "grass" => Ok(PokemonTypesEnum::Grass),
-            "ice" => Ok(PokemonTypesEnum::Ice),
-            "fighting" => Ok(PokemonTypesEnum::Fighting),
-            "poison" => Ok(PokemonTypesEnum::Poison),
-            "ground" => Ok(PokemonTypesEnum::Ground),
-            "flying" => Ok(PokemonTypesEnum::Flying),
-            "psychic" => Ok(PokemonTypesEnum::Psychic),
-            "bug" => Ok(PokemonTypesEnum::Bug),
-            "rock" => Ok(PokemonTypesEnum::Rock),
-            "ghost" => Ok(PokemonTypesEnum::Ghost),
-            "dragon" => Ok(PokemonTypesEnum::Dragon),
-            "dark" => Ok(PokemonTypesEnum::Dark),
-            "steel" => Ok(PokemonTypesEnum::Steel),
-            "fairy" => Ok(PokemonTypesEnum::Fairy),
-            "none" => Ok(PokemonTypesEnum::None),
-            &_ => todo!(),
-        }
-    }
+// The evolution columns of a pokemon, as the Pokemon editor saves them
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Evolution {
+    pub method: String,
+    pub level: Option<u32>,
+    pub item: Option<String>,
+    pub other: Option<String>,
+    pub evolves_into: Option<String>,
 }
-
-pub const POKEMON_TYPES_ARRAY: [PokemonTypesEnum; 18] = [
-    PokemonTypesEnum::Normal,
-    PokemonTypesEnum::Fire,
-    PokemonTypesEnum::Water,
-    PokemonTypesEnum::Electric,
-    PokemonTypesEnum::Grass,
-    PokemonTypesEnum::Ice,
-    PokemonTypesEnum::Fighting,
-    PokemonTypesEnum::Poison,
-    PokemonTypesEnum::Ground,
-    PokemonTypesEnum::Flying,
-    PokemonTypesEnum::Psychic,
-    PokemonTypesEnum::Bug,
-    PokemonTypesEnum::Rock,
-    PokemonTypesEnum::Ghost,
-    PokemonTypesEnum::Dragon,
-    PokemonTypesEnum::Dark,
-    PokemonTypesEnum::Steel,
-    PokemonTypesEnum::Fairy,
-];
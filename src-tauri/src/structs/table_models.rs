@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnKind {
+    Integer,
+    Text,
+}
+
+// What an empty cell means for a column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlankValue {
+    Null,
+    EmptyText,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TableColumn {
+    pub name: &'static str,
+    pub kind: ColumnKind,
+    pub blank: BlankValue,
+}
+
+impl TableColumn {
+    const fn new(name: &'static str, kind: ColumnKind, blank: BlankValue) -> Self {
+        TableColumn { name, kind, blank }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TableSpec {
+    pub name: &'static str,
+    pub key: &'static [&'static str],
+    // Tables keyed by an autoincrement id accept rows without one as new rows
+    pub generated_key: bool,
+    pub columns: &'static [TableColumn],
+    // Columns holding the id of a row in another table
+    pub references: &'static [(&'static str, &'static str)],
+}
+
+impl TableSpec {
+    pub fn column(&self, name: &str) -> Option<&TableColumn> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
+
+const ID: TableColumn = TableColumn::new("id", ColumnKind::Integer, BlankValue::Null);
+const IS_MODIFIED: TableColumn =
+    TableColumn::new("is_modified", ColumnKind::Integer, BlankValue::Rejected);
+const IS_NEW: TableColumn = TableColumn::new("is_new", ColumnKind::Integer, BlankValue::Rejected);
+
+pub const TABLE_SPECS: [TableSpec; 7] = [
+    TableSpec {
+        name: "pokemon",
+        key: &["id"],
+        generated_key: true,
+        columns: &[
+            ID,
+            TableColumn::new("dex_number", ColumnKind::Integer, BlankValue::Rejected),
+            TableColumn::new("name", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("types", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("abilities", ColumnKind::Text, BlankValue::EmptyText),
+            TableColumn::new("hp", ColumnKind::Integer, BlankValue::Rejected),
+            TableColumn::new("attack", ColumnKind::Integer, BlankValue::Rejected),
+            TableColumn::new("defense", ColumnKind::Integer, BlankValue::Rejected),
+            TableColumn::new("sp_attack", ColumnKind::Integer, BlankValue::Rejected),
+            TableColumn::new("sp_defense", ColumnKind::Integer, BlankValue::Rejected),
+            TableColumn::new("speed", ColumnKind::Integer, BlankValue::Rejected),
+            TableColumn::new("evolution_method", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("evolution_level", ColumnKind::Integer, BlankValue::Null),
+            TableColumn::new("evolution_item", ColumnKind::Text, BlankValue::Null),
+            TableColumn::new("evolution_other", ColumnKind::Text, BlankValue::Null),
+            TableColumn::new("evolves_into", ColumnKind::Text, BlankValue::Null),
+            TableColumn::new("render", ColumnKind::Text, BlankValue::EmptyText),
+        ],
+        references: &[],
+    },
+    TableSpec {
+        name: "pokemon_movesets",
+        key: &["pokemon", "move", "learn_method"],
+        generated_key: false,
+        columns: &[
+            TableColumn::new("pokemon", ColumnKind::Integer, BlankValue::Rejected),
+            TableColumn::new("move", ColumnKind::Integer, BlankValue::Rejected),
+            TableColumn::new("learn_method", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("level_learned", ColumnKind::Integer, BlankValue::Null),
+        ],
+        references: &[("pokemon", "pokemon"), ("move", "moves")],
+    },
+    TableSpec {
+        name: "moves",
+        key: &["id"],
+        generated_key: true,
+        columns: &[
+            ID,
+            TableColumn::new("name", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("power", ColumnKind::Integer, BlankValue::Null),
+            TableColumn::new("accuracy", ColumnKind::Integer, BlankValue::Null),
+            TableColumn::new("pp", ColumnKind::Integer, BlankValue::Null),
+            TableColumn::new("type", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("damage_class", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("machine_name", ColumnKind::Text, BlankValue::Null),
+            IS_MODIFIED,
+            IS_NEW,
+        ],
+        references: &[],
+    },
+    TableSpec {
+        name: "abilities",
+        key: &["id"],
+        generated_key: true,
+        columns: &[
+            ID,
+            TableColumn::new("name", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("effect", ColumnKind::Text, BlankValue::EmptyText),
+            IS_MODIFIED,
+            IS_NEW,
+        ],
+        references: &[],
+    },
+    TableSpec {
+        name: "items",
+        key: &["id"],
+        generated_key: true,
+        columns: &[
+            ID,
+            TableColumn::new("name", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("effect", ColumnKind::Text, BlankValue::EmptyText),
+            IS_MODIFIED,
+            IS_NEW,
+        ],
+        references: &[],
+    },
+    TableSpec {
+        name: "natures",
+        key: &["id"],
+        generated_key: true,
+        columns: &[
+            ID,
+            TableColumn::new("name", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("increased_stat", ColumnKind::Text, BlankValue::Null),
+            TableColumn::new("decreased_stat", ColumnKind::Text, BlankValue::Null),
+            IS_MODIFIED,
+            IS_NEW,
+        ],
+        references: &[],
+    },
+    TableSpec {
+        name: "item_location",
+        key: &["id"],
+        generated_key: true,
+        columns: &[
+            ID,
+            TableColumn::new("item_name", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("route", ColumnKind::Text, BlankValue::Rejected),
+            TableColumn::new("specific_location", ColumnKind::Text, BlankValue::Null),
+            TableColumn::new("method", ColumnKind::Text, BlankValue::Null),
+            TableColumn::new("requirements", ColumnKind::Text, BlankValue::Null),
+        ],
+        references: &[],
+    },
+];
+
+pub fn table_spec(table_name: &str) -> Option<&'static TableSpec> {
+    TABLE_SPECS.iter().find(|spec| spec.name == table_name)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TableFormat {
+    Csv,
+    Json,
+}
+
+impl TableFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Json => "json",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<TableFormat> {
+        match extension.to_lowercase().as_str() {
+            "csv" => Some(TableFormat::Csv),
+            "json" => Some(TableFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+pub type TableRow = Map<String, Value>;
+
+// A row read from an import file. The line is the line of the file the
+// row starts on, for CSV and JSON files alike.
+#[derive(Debug, Clone)]
+pub struct ImportedRow {
+    pub line: usize,
+    pub values: TableRow,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RowError {
+    pub line: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RowChangeKind {
+    Added,
+    Modified,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FieldChange {
+    pub column: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RowChange {
+    pub line: usize,
+    pub kind: RowChangeKind,
+    // Empty for new rows that get their id from the database
+    pub key: TableRow,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableImportPreview {
+    pub table: String,
+    pub total_rows: usize,
+    pub errors: Vec<RowError>,
+    pub changes: Vec<RowChange>,
+    pub unchanged: usize,
+    // Rows in the database that the file doesn't mention. They are left as is.
+    pub missing: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportedTable {
+    pub rows: Vec<ImportedRow>,
+    pub errors: Vec<RowError>,
+    pub total_rows: usize,
+}
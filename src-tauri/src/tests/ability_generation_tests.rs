@@ -3,13 +3,14 @@ use std::fs::read_to_string;
 use serde_yaml::Value;
 
 use crate::{
-    database::get_mkdocs_config,
-    page_generators::ability_page::{generate_ability_page, Ability},
+    database::{get_mkdocs_config, session::WikiSession},
+    page_generators::ability_page::generate_ability_page,
+    structs::page_models::Ability,
 };
 
-#[test]
+#[tokio::test]
 // Ability Page is created and present in the mkdocs.yml file
-fn test_generate_ability_page_created() {
+async fn test_generate_ability_page_created() {
     let base_path =
         std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
 
@@ -20,7 +21,8 @@ fn test_generate_ability_page_created() {
         is_new: 0,
     }];
 
-    let result = generate_ability_page("testing", &abilities, &base_path);
+    let session = WikiSession::open("testing", &base_path).await.unwrap();
+    let result = generate_ability_page(&session, &abilities);
 
     assert!(result.unwrap() == "Abilities Page Generated".to_string());
     let generated_path = base_path
@@ -0,0 +1,228 @@
+use std::fs::{self, File};
+
+use serde_json::json;
+
+use crate::{
+    database::{
+        change_journal::{
+            create_change_journal, get_journal_entries, insert_journal_entries,
+            publish_journal_entries, unpublish_journal_entries, JournalEntry,
+        },
+        get_sqlite_connection,
+    },
+    page_generators::{
+        changelog_page::{changelog_markdown, diff_routes},
+        game_routes::Routes,
+    },
+};
+
+fn entry(
+    subject: &str,
+    name: &str,
+    field: &str,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    published_at: Option<&str>,
+) -> JournalEntry {
+    let mut entry = JournalEntry::new(
+        subject,
+        name,
+        field,
+        old_value.map(str::to_string),
+        new_value.map(str::to_string),
+    );
+    entry.published_at = published_at.map(str::to_string);
+    entry
+}
+
+fn routes(encounter_rate: f32, trainer_level: usize) -> Routes {
+    let encounter = json!({
+        "id": 179, "name": "mareep", "encounter_rate": encounter_rate,
+        "encounter_area": "grass", "route": "Route 4", "route_variant": "default",
+        "special_note": ""
+    });
+    let trainer_pokemon = json!({
+        "id": 19, "unique_id": "a", "types": ["normal"], "name": "rattata",
+        "level": trainer_level, "moves": [], "item": "", "nature": "", "ability": "",
+        "trainer_versions": []
+    });
+    serde_json::from_value(json!({
+        "routes": {
+            "Route 4": {
+                "render": true, "position": 0, "variants": ["default"],
+                "wild_encounters": if encounter_rate > 0.0 { vec![encounter] } else { vec![] },
+                "wild_encounter_area_levels": {},
+                "trainers": {
+                    "Joey": {
+                        "position": 0, "sprite": "youngster", "versions": [],
+                        "pokemon_team": [trainer_pokemon]
+                    }
+                }
+            }
+        },
+        "encounter_areas": ["grass"]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_changelog_groups_by_publish_date_and_condenses_edits() {
+    let entries = vec![
+        entry(
+            "pokemon",
+            "gengar",
+            "sp_attack",
+            Some("130"),
+            Some("125"),
+            Some("2024-05-01"),
+        ),
+        entry(
+            "pokemon",
+            "gengar",
+            "sp_attack",
+            Some("125"),
+            Some("120"),
+            Some("2024-05-01"),
+        ),
+        entry(
+            "moves",
+            "shadow-ball",
+            "power",
+            None,
+            Some("80"),
+            Some("2024-05-01"),
+        ),
+        // Reverted before publishing, so nothing to report
+        entry(
+            "items",
+            "potion",
+            "effect",
+            Some("Heals 20"),
+            Some("Heals 30"),
+            Some("2024-05-01"),
+        ),
+        entry(
+            "items",
+            "potion",
+            "effect",
+            Some("Heals 30"),
+            Some("Heals 20"),
+            Some("2024-05-01"),
+        ),
+        entry(
+            "pokemon",
+            "gengar",
+            "move",
+            Some("hex (level-up 30)"),
+            None,
+            Some("2024-06-01"),
+        ),
+        entry(
+            "pokemon",
+            "gengar",
+            "move",
+            None,
+            Some("hex (level-up 33)"),
+            Some("2024-06-01"),
+        ),
+        // Only the new Pokemon is listed, not each of its fields
+        entry("pokemon", "sprigatito", "", None, Some(""), None),
+        entry("pokemon", "sprigatito", "hp", Some("40"), Some("45"), None),
+        entry(
+            "routes",
+            "Route 4",
+            "Mareep (grass)",
+            None,
+            Some("10%"),
+            None,
+        ),
+    ];
+
+    assert_eq!(
+        changelog_markdown(&entries).unwrap(),
+        "# Changelog\n\n\
+         ## Unreleased\n\n\
+         ### Pokemon\n\n- Sprigatito: added\n\n\
+         ### Routes\n\n- Route 4: added Mareep (grass) 10%\n\n\
+         ## 2024-06-01\n\n\
+         ### Pokemon\n\n- Gengar: hex (level-up 30)→hex (level-up 33)\n\n\
+         ## 2024-05-01\n\n\
+         ### Pokemon\n\n- Gengar: SpA 130→120\n\n\
+         ### Moves\n\n- Shadow Ball: Power -→80\n"
+    );
+    assert_eq!(changelog_markdown(&entries[3..5]), None);
+}
+
+#[test]
+fn test_diff_routes_reports_encounters_and_trainers() {
+    let entries = diff_routes(&routes(0.0, 5), &routes(10.0, 6));
+    let changes: Vec<(&str, Option<&str>, Option<&str>)> = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.field.as_str(),
+                entry.old_value.as_deref(),
+                entry.new_value.as_deref(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        changes,
+        vec![
+            ("Mareep (grass)", None, Some("10%")),
+            ("Trainer Joey", Some("Rattata 5"), Some("Rattata 6")),
+        ]
+    );
+    assert!(diff_routes(&routes(10.0, 6), &routes(10.0, 6)).is_empty());
+}
+
+#[tokio::test]
+// A publish whose commit failed is undone without touching earlier publishes
+async fn test_unpublish_journal_entries() {
+    let base_path = std::env::temp_dir().join("wikigen_unpublish_journal_test");
+    let _ = fs::remove_dir_all(&base_path);
+    fs::create_dir_all(&base_path).unwrap();
+    let db_path = base_path.join("journal.db");
+    File::create(&db_path).unwrap();
+    let conn = get_sqlite_connection(db_path).await.unwrap();
+    create_change_journal(&conn).await.unwrap();
+
+    let gengar = entry(
+        "pokemon",
+        "gengar",
+        "sp_attack",
+        Some("130"),
+        Some("120"),
+        None,
+    );
+    insert_journal_entries(&conn, &[gengar]).await.unwrap();
+    let first_publish = publish_journal_entries(&conn, "2024-05-01").await.unwrap();
+    assert_eq!(first_publish.len(), 1);
+
+    let shadow_ball = entry("moves", "shadow-ball", "power", None, Some("80"), None);
+    insert_journal_entries(&conn, &[shadow_ball]).await.unwrap();
+    let second_publish = publish_journal_entries(&conn, "2024-05-01").await.unwrap();
+    assert_eq!(second_publish.len(), 1);
+    assert_ne!(first_publish, second_publish);
+
+    unpublish_journal_entries(&conn, &second_publish)
+        .await
+        .unwrap();
+    let published_at: Vec<(String, Option<String>)> = get_journal_entries(&conn)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|entry| (entry.name, entry.published_at))
+        .collect();
+    assert_eq!(
+        published_at,
+        vec![
+            ("gengar".to_string(), Some("2024-05-01".to_string())),
+            ("shadow-ball".to_string(), None),
+        ]
+    );
+
+    conn.close().await;
+    fs::remove_dir_all(&base_path).unwrap();
+}
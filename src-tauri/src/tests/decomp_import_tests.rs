@@ -0,0 +1,290 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+
+use crate::{
+    data_transfer::{
+        decomp::{
+            diff_species_updates, evolution_columns, read_evolutions, read_level_up_learnsets,
+            read_species_info, read_tmhm_learnsets, resolve_decomp_species, DecompData,
+            DecompEvolution,
+        },
+        randomizer_log::WikiLookup,
+    },
+    structs::pokemon_structs::{DBPokemon, PokemonMove},
+};
+
+const SPECIES_INFO: &str = r#"
+#define OLD_UNOWN_SPECIES_INFO                  \
+    {                                           \
+        .baseHP = 50,                           \
+    }
+
+const struct SpeciesInfo gSpeciesInfo[] =
+{
+    [SPECIES_NONE] = {0},
+
+    [SPECIES_BULBASAUR] =
+    {
+        .baseHP        = 45,
+        .baseAttack    = 49,
+        .baseDefense   = 49,
+        .baseSpeed     = 45,
+        .baseSpAttack  = 65,
+        .baseSpDefense = 65,
+        .types = { TYPE_GRASS, TYPE_POISON },
+        .abilities = {ABILITY_OVERGROW, ABILITY_NONE},
+        // .abilities = {ABILITY_CHLOROPHYLL, ABILITY_NONE},
+        .bodyColor = BODY_COLOR_GREEN,
+    },
+
+    [SPECIES_MR_MIME] =
+    {
+        .baseHP        = 40,
+        .baseAttack    = 45,
+        .baseDefense   = 65,
+        .baseSpeed     = 90,
+    #if P_UPDATED_STATS >= GEN_6
+        .baseSpAttack  = 100,
+    #else
+        .baseSpAttack  = 90,
+    #endif
+        .baseSpDefense = 120,
+        .type1 = TYPE_PSYCHIC,
+        .type2 = TYPE_PSYCHIC,
+        .abilities = {ABILITY_SOUNDPROOF, ABILITY_FILTER},
+    },
+};
+"#;
+
+const LEVEL_UP_LEARNSETS: &str = r#"
+static const u16 sBulbasaurLevelUpLearnset[] = {
+    LEVEL_UP_MOVE( 1, MOVE_TACKLE),
+    LEVEL_UP_MOVE( 4, MOVE_GROWL),
+    LEVEL_UP_MOVE( 7, MOVE_LEECH_SEED),
+    LEVEL_UP_MOVE(10, MOVE_TACKLE),
+    LEVEL_UP_END
+};
+
+/* Mr. Mime's learnset */
+static const u16 sMrMimeLevelUpLearnset[] = {
+    LEVEL_UP_MOVE( 1, MOVE_BARRIER),
+    LEVEL_UP_END
+};
+"#;
+
+const TMHM_LEARNSETS: &str = r#"
+const u32 gTMHMLearnsets[][2] =
+{
+    [SPECIES_NONE]        = TMHM_LEARNSET(0),
+
+    [SPECIES_BULBASAUR]   = TMHM_LEARNSET(TMHM(TM06_TOXIC)
+                                        | TMHM(TM09_BULLET_SEED)
+                                        | TMHM(HM01_CUT)),
+};
+"#;
+
+const EVOLUTION: &str = r#"
+const struct Evolution gEvolutionTable[NUM_SPECIES][EVOS_PER_MON] =
+{
+    [SPECIES_BULBASAUR]  = {{EVO_LEVEL, 16, SPECIES_IVYSAUR}},
+    [SPECIES_EEVEE]      = {{EVO_ITEM, ITEM_THUNDER_STONE, SPECIES_JOLTEON},
+                            {EVO_FRIENDSHIP_DAY, 0, SPECIES_ESPEON}},
+};
+"#;
+
+fn decomp_data() -> DecompData {
+    let mut decomp_data = DecompData::default();
+    read_species_info(SPECIES_INFO, &mut decomp_data);
+    read_level_up_learnsets(LEVEL_UP_LEARNSETS, None, &mut decomp_data);
+    read_tmhm_learnsets(TMHM_LEARNSETS, &mut decomp_data);
+    read_evolutions(EVOLUTION, &mut decomp_data);
+    decomp_data
+}
+
+fn wiki_lookup() -> WikiLookup {
+    WikiLookup {
+        pokemon: HashMap::from([
+            ("bulbasaur".to_string(), (1, 1, "grass,poison".to_string())),
+            ("ivysaur".to_string(), (2, 2, "grass,poison".to_string())),
+            (
+                "mr-mime".to_string(),
+                (122, 122, "psychic,fairy".to_string()),
+            ),
+        ]),
+        moves: HashMap::from([
+            ("tackle".to_string(), 33),
+            ("growl".to_string(), 45),
+            ("toxic".to_string(), 92),
+            ("cut".to_string(), 15),
+            ("barrier".to_string(), 112),
+        ]),
+        abilities: HashSet::from([
+            "overgrow".to_string(),
+            "soundproof".to_string(),
+            "filter".to_string(),
+        ]),
+        items: HashSet::new(),
+    }
+}
+
+fn bulbasaur() -> DBPokemon {
+    DBPokemon {
+        id: 1,
+        dex_number: 1,
+        name: "bulbasaur".to_string(),
+        types: "grass,poison".to_string(),
+        abilities: "overgrow".to_string(),
+        hp: 45,
+        attack: 49,
+        defense: 49,
+        sp_attack: 65,
+        sp_defense: 65,
+        speed: 45,
+        evolution_method: "no_change".to_string(),
+        evolution_level: None,
+        evolution_item: None,
+        evolution_other: None,
+        evolves_into: None,
+        render: "true".to_string(),
+    }
+}
+
+fn pokemon_move(move_id: u32, learn_method: &str, level_learned: Option<u32>) -> PokemonMove {
+    PokemonMove {
+        pokemon: 1,
+        move_id,
+        learn_method: learn_method.to_string(),
+        level_learned,
+        move_name: String::new(),
+        move_type: None,
+        power: None,
+        accuracy: None,
+        pp: None,
+        damage_class: String::new(),
+        machine_name: None,
+    }
+}
+
+#[test]
+fn test_read_decomp_headers() {
+    let decomp_data = decomp_data();
+
+    let species: Vec<&str> = decomp_data.species.keys().map(String::as_str).collect();
+    assert_eq!(species, vec!["bulbasaur", "mr-mime", "eevee"]);
+
+    let bulbasaur = &decomp_data.species["bulbasaur"];
+    assert_eq!(bulbasaur.types, vec!["grass", "poison"]);
+    assert_eq!(bulbasaur.abilities, vec!["overgrow"]);
+    assert_eq!(bulbasaur.stats.as_ref().unwrap().sp_attack, 65);
+    assert_eq!(
+        bulbasaur.level_up_moves.as_ref().unwrap()[2],
+        (7, "leech-seed".to_string())
+    );
+    assert_eq!(
+        bulbasaur.machine_moves,
+        Some(vec![
+            "toxic".to_string(),
+            "bullet-seed".to_string(),
+            "cut".to_string()
+        ])
+    );
+
+    let mr_mime = &decomp_data.species["mr-mime"];
+    assert_eq!(mr_mime.types, vec!["psychic"]);
+    assert_eq!(mr_mime.stats.as_ref().unwrap().sp_attack, 100);
+    assert_eq!(mr_mime.level_up_moves.as_ref().unwrap().len(), 1);
+    assert!(mr_mime.evolutions.is_empty());
+
+    assert_eq!(
+        decomp_data.species["eevee"].evolutions[0],
+        DecompEvolution {
+            method: "EVO_ITEM".to_string(),
+            parameter: "ITEM_THUNDER_STONE".to_string(),
+            target: "jolteon".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_decomp_evolution_columns() {
+    let decomp_data = decomp_data();
+
+    let level_up = evolution_columns(&decomp_data.species["bulbasaur"].evolutions);
+    assert_eq!(level_up.method, "level_up");
+    assert_eq!(level_up.level, Some(16));
+    assert_eq!(level_up.evolves_into.as_deref(), Some("ivysaur"));
+
+    let branching = evolution_columns(&decomp_data.species["eevee"].evolutions);
+    assert_eq!(branching.method, "other");
+    assert_eq!(
+        branching.other.as_deref(),
+        Some("Item Thunder Stone into Jolteon, Friendship Day into Espeon")
+    );
+    assert_eq!(branching.evolves_into.as_deref(), Some("jolteon, espeon"));
+
+    assert_eq!(evolution_columns(&[]).method, "no_change");
+}
+
+#[test]
+fn test_resolve_decomp_species() {
+    let mut unmatched = Vec::new();
+    let updates = resolve_decomp_species(&decomp_data(), &wiki_lookup(), &mut unmatched);
+
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[0].level_up_moves, Some(vec![(33, 1), (45, 4)]));
+    assert_eq!(updates[0].machine_moves, Some(vec![92, 15]));
+    assert_eq!(updates[1].types.as_deref(), Some("psychic"));
+    assert_eq!(updates[1].abilities.as_deref(), Some("soundproof,filter"));
+    assert_eq!(updates[1].evolution.as_ref().unwrap().method, "no_change");
+
+    let unmatched: Vec<(&str, &str)> = unmatched
+        .iter()
+        .map(|entry| (entry.section.as_str(), entry.entry.as_str()))
+        .collect();
+    assert_eq!(
+        unmatched,
+        vec![
+            ("level_up_learnsets.h", "leech-seed"),
+            ("tmhm_learnsets.h", "bullet-seed"),
+            ("Species", "eevee"),
+        ]
+    );
+}
+
+#[test]
+fn test_diff_decomp_species() {
+    let mut unmatched = Vec::new();
+    let updates = resolve_decomp_species(&decomp_data(), &wiki_lookup(), &mut unmatched);
+    let current_movesets = vec![
+        pokemon_move(33, "level-up", Some(1)),
+        pokemon_move(22, "level-up", Some(3)),
+        pokemon_move(92, "machine", Some(0)),
+        pokemon_move(15, "machine", Some(0)),
+    ];
+    let move_names = HashMap::from([
+        (33, "tackle".to_string()),
+        (45, "growl".to_string()),
+        (22, "vine-whip".to_string()),
+    ]);
+
+    let species_diffs =
+        diff_species_updates(&updates, &[bulbasaur()], &current_movesets, &move_names);
+
+    assert_eq!(species_diffs.len(), 1);
+    let changed_columns: Vec<(&str, &Value)> = species_diffs[0]
+        .fields
+        .iter()
+        .map(|field| (field.column.as_str(), &field.new))
+        .collect();
+    assert_eq!(
+        changed_columns,
+        vec![
+            ("evolution_method", &Value::from("level_up")),
+            ("evolution_level", &Value::from(16)),
+            ("evolves_into", &Value::from("ivysaur")),
+        ]
+    );
+    assert_eq!(species_diffs[0].moves_added, vec!["growl (level 4)"]);
+    assert_eq!(species_diffs[0].moves_removed, vec!["vine-whip (level 3)"]);
+}
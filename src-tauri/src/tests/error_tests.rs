@@ -0,0 +1,35 @@
+use serde_json::json;
+use serde_yaml::Value;
+
+use crate::{errors::WikiGenError, wiki_preparation::yaml_declaration::get_yaml};
+
+#[test]
+// The frontend relies on this shape to tell errors apart
+fn test_error_serializes_to_code_message_context() {
+    let error = WikiGenError::missing_template(
+        "Failed to read template file",
+        "templates/route_page_template.md",
+    );
+
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        json!({
+            "code": "missing_template",
+            "message": "Failed to read template file",
+            "context": "templates/route_page_template.md",
+        })
+    );
+}
+
+#[test]
+fn test_missing_nav_section_is_invalid_nav() {
+    let mut mkdocs_config = get_yaml("test", "", "", "", "");
+    mkdocs_config.nav = serde_yaml::from_str("- Home: index.md").unwrap();
+
+    let error = mkdocs_config.nav_section_mut("Pokemon").unwrap_err();
+    assert_eq!(error.code(), "invalid_nav");
+
+    mkdocs_config.nav = Value::String("index.md".to_string());
+    let error = mkdocs_config.nav_entries_mut().unwrap_err();
+    assert_eq!(error.code(), "invalid_nav");
+}
@@ -0,0 +1,78 @@
+use crate::page_generators::home_page::{
+    home_page_markdown, parse_recent_pages, user_intro, ChangeCount, HomePage, RecentPage,
+    INTRO_END, INTRO_START,
+};
+
+#[test]
+fn test_home_page_keeps_user_intro_on_regeneration() {
+    let mut home_page = HomePage {
+        site_name: "Emerald Plus".to_string(),
+        site_description: "A harder Emerald".to_string(),
+        pokemon_pages: 3,
+        moves: ChangeCount {
+            new: 2,
+            modified: 1,
+        },
+        routes: vec![("Route 101".to_string(), "routes/Route 101.md".to_string())],
+        ..Default::default()
+    };
+    let first_markdown = home_page_markdown(&home_page, Some("# Index")).unwrap();
+    assert!(first_markdown.starts_with("# Emerald Plus\n\nA harder Emerald\n\n"));
+    assert!(first_markdown.contains("| [Moves](move_changes.md) | 2 | 1 |"));
+    assert!(!first_markdown.contains("[Items]"));
+    assert!(first_markdown.contains("- [Route 101](routes/Route%20101.md)"));
+
+    let edited_markdown = first_markdown.replace(
+        &user_intro(&first_markdown).unwrap().unwrap(),
+        "Welcome!\n\nStart with the [routes](#routes).",
+    );
+    home_page.recent_pages = vec![RecentPage {
+        title: "001 - Bulbasaur".to_string(),
+        file: "pokemon/001-bulbasaur.md".to_string(),
+        date: "2024-05-01".to_string(),
+    }];
+    let regenerated_markdown = home_page_markdown(&home_page, Some(&edited_markdown)).unwrap();
+    assert!(regenerated_markdown.contains(&format!(
+        "{INTRO_START}\nWelcome!\n\nStart with the [routes](#routes).\n{INTRO_END}"
+    )));
+    assert!(
+        regenerated_markdown.contains("- [001 - Bulbasaur](pokemon/001-bulbasaur.md) - 2024-05-01")
+    );
+
+    // A home page written by hand before the markers existed becomes the intro
+    let hand_written = home_page_markdown(&home_page, Some("# My Wiki\n\nHello")).unwrap();
+    assert!(hand_written.contains(&format!("{INTRO_START}\n# My Wiki\n\nHello\n{INTRO_END}")));
+}
+
+#[test]
+// A broken end marker leaves the page alone instead of resetting the intro
+fn test_home_page_refuses_intro_without_end_marker() {
+    let home_page = HomePage {
+        site_name: "Emerald Plus".to_string(),
+        ..Default::default()
+    };
+    let existing_markdown = format!(
+        "# Emerald Plus\n\n{INTRO_START}\nWelcome!\n<!-- wikigen:intro:nd -->\n\n## Overview\n"
+    );
+
+    assert!(user_intro(&existing_markdown).is_err());
+    assert!(home_page_markdown(&home_page, Some(&existing_markdown)).is_err());
+}
+
+#[test]
+fn test_parse_recent_pages_keeps_latest_change_per_page() {
+    let git_log = "\x002024-05-02\n\ndocs/pokemon/001-bulbasaur.md\ndocs/index.md\n\
+                   docs/img/pokemon/bulbasaur.png\n\x002024-05-01\n\n\
+                   docs/routes/Route 101.md\ndocs/pokemon/001-bulbasaur.md\n";
+
+    assert_eq!(
+        parse_recent_pages(git_log),
+        vec![
+            (
+                "pokemon/001-bulbasaur.md".to_string(),
+                "2024-05-02".to_string()
+            ),
+            ("routes/Route 101.md".to_string(), "2024-05-01".to_string()),
+        ]
+    );
+}
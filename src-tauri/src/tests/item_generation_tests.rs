@@ -3,15 +3,14 @@ use std::fs::read_to_string;
 use serde_yaml::Value;
 
 use crate::{
-    database::get_mkdocs_config,
-    page_generators::item_page::{
-        generate_item_changes_page, generate_item_location_page, Item, ItemLocation,
-    },
+    database::{get_mkdocs_config, session::WikiSession},
+    page_generators::item_page::{generate_item_changes_page, generate_item_location_page},
+    structs::page_models::{Item, ItemLocation},
 };
 
-#[test]
+#[tokio::test]
 // Ability Page is created and present in the mkdocs.yml file
-fn test_generate_item_changes_page_created() {
+async fn test_generate_item_changes_page_created() {
     let base_path =
         std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
 
@@ -30,7 +29,8 @@ fn test_generate_item_changes_page_created() {
         },
     ];
 
-    let result = generate_item_changes_page("testing", &items, &base_path);
+    let session = WikiSession::open("testing", &base_path).await.unwrap();
+    let result = generate_item_changes_page(&session, &items);
     println!("{:?}", result);
     assert!(result.unwrap() == "Item Changes Page Updated".to_string());
     let generated_path = base_path
@@ -97,9 +97,9 @@ fn test_generate_item_changes_page_created() {
     };
 }
 
-#[test]
+#[tokio::test]
 // Ability Page is created and present in the mkdocs.yml file
-fn test_generate_item_locations_page_created() {
+async fn test_generate_item_locations_page_created() {
     let base_path =
         std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
 
@@ -120,7 +120,8 @@ fn test_generate_item_locations_page_created() {
         },
     ];
 
-    let result = generate_item_location_page("testing", &items_locations, &base_path);
+    let session = WikiSession::open("testing", &base_path).await.unwrap();
+    let result = generate_item_location_page(&session, &items_locations);
     println!("{:?}", result);
     assert!(result.unwrap() == "Item Locations Page Updated".to_string());
     let generated_path = base_path
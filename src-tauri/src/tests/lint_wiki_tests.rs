@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+
+use crate::{
+    page_generators::game_routes::{
+        RouteProperties, Routes, TrainerInfo, TrainerPokemon, WildEncounter,
+    },
+    structs::{
+        lint_models::{LintCategory, LintSeverity},
+        page_models::ItemLocation,
+        pokemon_structs::DBPokemon,
+    },
+    wiki_preparation::lint_wiki::{lint_wiki_data, WikiLintData},
+};
+
+fn pokemon(dex_number: u32, name: &str, abilities: &str, evolves_into: Option<&str>) -> DBPokemon {
+    DBPokemon {
+        id: dex_number,
+        dex_number,
+        name: name.to_string(),
+        types: "normal".to_string(),
+        abilities: abilities.to_string(),
+        hp: 50,
+        attack: 50,
+        defense: 50,
+        sp_attack: 50,
+        sp_defense: 50,
+        speed: 50,
+        evolution_method: match evolves_into {
+            Some(_) => "level_up".to_string(),
+            None => "no_change".to_string(),
+        },
+        evolution_level: evolves_into.map(|_| 16),
+        evolution_item: None,
+        evolution_other: None,
+        evolves_into: evolves_into
+            .filter(|evolves_into| !evolves_into.is_empty())
+            .map(str::to_string),
+        render: "true".to_string(),
+    }
+}
+
+fn trainer_pokemon(id: usize, name: &str, moves: &[&str], ability: &str) -> TrainerPokemon {
+    TrainerPokemon {
+        id,
+        unique_id: format!("{}_0_1000", id),
+        types: vec!["normal".to_string()],
+        name: name.to_string(),
+        level: 5,
+        moves: moves
+            .iter()
+            .map(|move_name| move_name.to_string())
+            .collect(),
+        item: String::new(),
+        nature: String::new(),
+        ability: ability.to_string(),
+        trainer_versions: Vec::new(),
+    }
+}
+
+fn lint_data() -> WikiLintData {
+    let route = RouteProperties {
+        render: true,
+        position: 0,
+        trainers: IndexMap::from([(
+            "Youngster Ben".to_string(),
+            TrainerInfo {
+                position: 0,
+                pokemon_team: vec![
+                    trainer_pokemon(1, "bulbasaur", &["tackle", "splash-dance"], "overgrow"),
+                    trainer_pokemon(4, "ivysaur", &[], "made-up"),
+                ],
+                sprite: String::new(),
+                versions: Vec::new(),
+            },
+        )]),
+        wild_encounters: vec![WildEncounter {
+            id: 150,
+            name: "mewtwo".to_string(),
+            encounter_rate: 100.0,
+            encounter_area: "grass".to_string(),
+            route: "Route 1".to_string(),
+            route_variant: "default".to_string(),
+            special_note: String::new(),
+        }],
+        variants: vec!["default".to_string()],
+        wild_encounter_area_levels: IndexMap::new(),
+    };
+
+    WikiLintData {
+        pokemon: vec![
+            pokemon(1, "bulbasaur", "overgrow", Some("ivysaur")),
+            pokemon(2, "ivysaur", "overgrow,chlorophyll", Some("venusaur")),
+            pokemon(3, "charmander", "blaze", Some("")),
+        ],
+        move_names: HashSet::from(["tackle".to_string()]),
+        ability_names: HashSet::from(["overgrow".to_string(), "blaze".to_string()]),
+        item_names: HashSet::from(["potion".to_string()]),
+        item_locations: vec![
+            ItemLocation {
+                item_name: "potion".to_string(),
+                route: "Route 1".to_string(),
+                specific_location: None,
+                method: None,
+                requirements: None,
+            },
+            ItemLocation {
+                item_name: "super-potion".to_string(),
+                route: "Route 2".to_string(),
+                specific_location: None,
+                method: None,
+                requirements: None,
+            },
+        ],
+        routes: Routes {
+            routes: IndexMap::from([("Route 1".to_string(), route)]),
+            encounter_areas: vec!["grass".to_string()],
+        },
+        sprites: Some(HashSet::from([
+            "bulbasaur".to_string(),
+            "ivysaur".to_string(),
+        ])),
+    }
+}
+
+#[test]
+fn test_lint_wiki_finds_broken_references() {
+    let report = lint_wiki_data(&lint_data());
+
+    let issues: Vec<(LintSeverity, LintCategory, &str)> = report
+        .issues
+        .iter()
+        .map(|issue| (issue.severity, issue.category, issue.subject.as_str()))
+        .collect();
+    assert_eq!(
+        issues,
+        vec![
+            (LintSeverity::Error, LintCategory::Abilities, "ivysaur"),
+            (LintSeverity::Error, LintCategory::Evolutions, "ivysaur"),
+            (
+                LintSeverity::Warning,
+                LintCategory::Evolutions,
+                "charmander"
+            ),
+            (LintSeverity::Warning, LintCategory::Sprites, "charmander"),
+            (
+                LintSeverity::Error,
+                LintCategory::TrainerMoves,
+                "Route 1 / Youngster Ben"
+            ),
+            (
+                LintSeverity::Error,
+                LintCategory::TrainerPokemon,
+                "Route 1 / Youngster Ben"
+            ),
+            (
+                LintSeverity::Error,
+                LintCategory::Abilities,
+                "Route 1 / Youngster Ben"
+            ),
+            (
+                LintSeverity::Error,
+                LintCategory::WildEncounters,
+                "Route 1 / grass"
+            ),
+            (LintSeverity::Error, LintCategory::ItemLocations, "Route 2"),
+        ]
+    );
+    assert_eq!(report.errors, 7);
+    assert_eq!(report.warnings, 2);
+    assert_eq!(
+        report.issues[5].message,
+        "ivysaur has dex number 4, but the wiki has it as 2"
+    );
+}
+
+#[test]
+fn test_lint_wiki_reports_missing_sprite_folder() {
+    let mut lint_data = lint_data();
+    lint_data.sprites = None;
+
+    let report = lint_wiki_data(&lint_data);
+
+    let sprite_issues: Vec<&str> = report
+        .issues
+        .iter()
+        .filter(|issue| issue.category == LintCategory::Sprites)
+        .map(|issue| issue.subject.as_str())
+        .collect();
+    assert_eq!(sprite_issues, vec!["img/pokemon"]);
+}
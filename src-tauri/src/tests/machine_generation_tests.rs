@@ -0,0 +1,96 @@
+use std::fs::read_to_string;
+
+use crate::{
+    database::{get_mkdocs_config, page_exists_in_mkdocs, session::WikiSession},
+    page_generators::machine_page::generate_machine_page,
+    structs::page_models::{ItemLocation, MachineMove},
+};
+
+#[tokio::test]
+// TMs & HMs Page is created, sorted by machine number and present in the mkdocs.yml file
+async fn test_generate_machine_page_created() {
+    let base_path =
+        std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
+
+    let machine_moves = vec![
+        MachineMove {
+            name: "cut".to_string(),
+            machine_name: "hm01".to_string(),
+            power: Some(50),
+            accuracy: Some(95),
+            pp: Some(30),
+            move_type: "normal".to_string(),
+            damage_class: "physical".to_string(),
+            learnable_by: 12,
+        },
+        MachineMove {
+            name: "toxic".to_string(),
+            machine_name: "tm06".to_string(),
+            power: None,
+            accuracy: Some(90),
+            pp: Some(10),
+            move_type: "poison".to_string(),
+            damage_class: "status".to_string(),
+            learnable_by: 200,
+        },
+    ];
+    let item_locations = vec![ItemLocation {
+        item_name: "tm06".to_string(),
+        route: "Route 1".to_string(),
+        specific_location: Some("Behind the tree".to_string()),
+        method: None,
+        requirements: None,
+    }];
+
+    let session = WikiSession::open("testing", &base_path).await.unwrap();
+    let result = generate_machine_page(&session, &machine_moves, &item_locations);
+
+    assert!(result.unwrap() == "TMs & HMs Page Generated".to_string());
+    let generated_path = base_path
+        .join("testing")
+        .join("dist")
+        .join("docs")
+        .join("machines.md");
+    assert!(generated_path.exists());
+
+    let generated_file = match read_to_string(&generated_path) {
+        Ok(file) => file,
+        Err(err) => panic!("Failed to read generated file: {}", err),
+    };
+
+    let toxic_position = generated_file.find("| TM06 | Toxic |").unwrap();
+    let cut_position = generated_file.find("| HM01 | Cut |").unwrap();
+    assert!(toxic_position < cut_position);
+    assert!(generated_file.contains("Route 1 (Behind the tree)"));
+    // The page sits at the docs root
+    assert!(generated_file
+        .contains("| ![poison](img/types/poison.png) | ![status](img/types/status.png) |"));
+
+    let mkdocs_yaml_file_path = base_path.join("testing").join("dist").join("mkdocs.yml");
+    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+        Ok(config) => config,
+        Err(err) => {
+            panic!("Failed to get mkdocs config: {}", err);
+        }
+    };
+
+    let (page_entry_exists, page_index) =
+        page_exists_in_mkdocs(mkdocs_config.clone(), "TMs & HMs").unwrap();
+    assert!(page_entry_exists);
+
+    // Clean up
+    mkdocs_config
+        .nav
+        .as_sequence_mut()
+        .unwrap()
+        .remove(page_index);
+    std::fs::remove_file(generated_path).unwrap();
+
+    match std::fs::write(
+        &mkdocs_yaml_file_path,
+        serde_yaml::to_string(&mut mkdocs_config).unwrap(),
+    ) {
+        Ok(_) => {}
+        Err(err) => panic!("Failed to update mkdocs yaml: {}", err),
+    };
+}
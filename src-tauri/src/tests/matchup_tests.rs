@@ -0,0 +1,57 @@
+use crate::{
+    helpers::matchups::{matchup_key, preset_matchup_map, MatchupTable},
+    structs::matchup_models::{TypeChartPreset, DEFAULT_TYPES},
+};
+
+fn default_matchup_table() -> MatchupTable {
+    let types = DEFAULT_TYPES
+        .iter()
+        .map(|_type| _type.to_string())
+        .collect::<Vec<String>>();
+    MatchupTable::new(types, preset_matchup_map(&TypeChartPreset::GenSixOnwards))
+}
+
+#[test]
+// Dual types multiply their matchups together
+fn test_defensive_matchups_combines_types() {
+    let matchup_table = default_matchup_table();
+
+    let type_effectiveness = matchup_table
+        .defensive_matchups(&vec!["grass".to_string(), "poison".to_string()])
+        .unwrap();
+
+    assert!(type_effectiveness
+        .0
+        .get("2")
+        .unwrap()
+        .contains(&"Fire".to_string()));
+    assert!(type_effectiveness
+        .0
+        .get("0.25")
+        .unwrap()
+        .contains(&"Grass".to_string()));
+}
+
+#[test]
+// Types missing from the matchup map are reported instead of panicking
+fn test_missing_matchup_returns_error() {
+    let matchup_table = default_matchup_table();
+
+    assert!(matchup_table.effectiveness("sound", "grass").is_err());
+    assert!(matchup_table
+        .defensive_matchups(&vec!["sound".to_string()])
+        .is_err());
+}
+
+#[test]
+// Pairs missing from the matchup map are neutral
+fn test_missing_pair_is_neutral() {
+    let types = vec!["grass".to_string(), "sound".to_string()];
+    let mut matchup_map = preset_matchup_map(&TypeChartPreset::GenSixOnwards);
+    matchup_map.insert(matchup_key("sound", "grass"), 2.0);
+    let matchup_table = MatchupTable::new(types, matchup_map);
+
+    assert_eq!(matchup_table.effectiveness("sound", "grass").unwrap(), 2.0);
+    assert_eq!(matchup_table.effectiveness("grass", "sound").unwrap(), 1.0);
+    assert!(matchup_table.effectiveness("fire", "sound").is_err());
+}
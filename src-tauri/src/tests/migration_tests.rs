@@ -1,4 +1,9 @@
-use crate::migrations::{gather_migrations, run_migrations};
+use std::fs::{self, File};
+
+use crate::{
+    database::{change_journal::change_journal_triggers_sql, get_sqlite_connection},
+    migrations::{gather_migrations, run_migrations, run_wiki_migrations, Migration},
+};
 
 #[test]
 fn test_gather_migrations() {
@@ -24,8 +29,95 @@ async fn test_run_migrations() {
         Err(err) => panic!("Error checking for migrations: {}", err),
     };
 
-    match run_migrations(migrations, &base_path, &resources_path).await {
+    match run_migrations(migrations, &base_path, &resources_path, None).await {
         Ok(migrations) => migrations,
         Err(err) => panic!("Error checking for migrations: {}", err),
     };
 }
+
+// Wikis opened by a build that created the journal triggers on an older schema
+#[tokio::test]
+async fn test_run_wiki_migrations_with_journal_triggers() {
+    let base_path = std::env::temp_dir().join("wikigen_migration_journal_test");
+    let wiki_name = "old_wiki";
+    let wiki_path = base_path.join(wiki_name);
+    let _ = fs::remove_dir_all(&base_path);
+    fs::create_dir_all(&wiki_path).unwrap();
+    File::create(wiki_path.join(format!("{}.db", wiki_name))).unwrap();
+
+    let conn = get_sqlite_connection(wiki_path.join(format!("{}.db", wiki_name)))
+        .await
+        .unwrap();
+    // Before abilities was added and evolved_pokemon was renamed
+    sqlx::query(
+        "CREATE TABLE pokemon (id INTEGER PRIMARY KEY, name TEXT, dex_number INTEGER,
+            types TEXT, hp INTEGER, attack INTEGER, defense INTEGER, sp_attack INTEGER,
+            sp_defense INTEGER, speed INTEGER, evolution_method TEXT, evolution_level INTEGER,
+            evolution_item TEXT, evolution_other TEXT, evolved_pokemon TEXT);
+        CREATE TABLE moves (id INTEGER PRIMARY KEY, name TEXT, power INTEGER,
+            accuracy INTEGER, pp INTEGER, type TEXT, damage_class TEXT);
+        CREATE TABLE abilities (id INTEGER PRIMARY KEY, name TEXT, effect TEXT);
+        CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, effect TEXT);
+        CREATE TABLE natures (id INTEGER PRIMARY KEY, name TEXT, increased_stat TEXT,
+            decreased_stat TEXT);
+        CREATE TABLE pokemon_movesets (id INTEGER PRIMARY KEY, pokemon INTEGER, move INTEGER,
+            learn_method TEXT, level_learned INTEGER);
+        CREATE TABLE change_journal (id INTEGER PRIMARY KEY AUTOINCREMENT,
+            subject TEXT NOT NULL, name TEXT NOT NULL, field TEXT NOT NULL, old_value TEXT,
+            new_value TEXT, recorded_at TEXT NOT NULL DEFAULT (datetime('now')),
+            published_at TEXT);
+        INSERT INTO pokemon (id, name, types, evolved_pokemon) VALUES (1, 'bulbasaur', 'grass', 'ivysaur');",
+    )
+    .execute(&conn)
+    .await
+    .unwrap();
+    sqlx::query(&change_journal_triggers_sql())
+        .execute(&conn)
+        .await
+        .unwrap();
+    // Every pokemon edit fails until the triggers are replaced
+    assert!(sqlx::query("UPDATE pokemon SET hp = 45 WHERE id = 1")
+        .execute(&conn)
+        .await
+        .is_err());
+
+    let migrations = vec![
+        Migration {
+            name: "alter-pokemon-add-abilities".to_string(),
+            app_version: "1.9.3".to_string(),
+            execution_order: 2,
+            sql: "ALTER TABLE pokemon ADD COLUMN abilities TEXT".to_string(),
+        },
+        Migration {
+            name: "alter-pokemon-column-evolved-pokemon".to_string(),
+            app_version: "1.9.3".to_string(),
+            execution_order: 3,
+            sql: "ALTER TABLE pokemon RENAME COLUMN evolved_pokemon TO evolves_into".to_string(),
+        },
+    ];
+    run_wiki_migrations(wiki_name, &conn, &migrations, &base_path, &base_path)
+        .await
+        .unwrap();
+
+    let migration_names: Vec<String> = sqlx::query_scalar("SELECT name FROM migrations")
+        .fetch_all(&conn)
+        .await
+        .unwrap();
+    for migration in migrations.iter() {
+        assert!(migration_names.contains(&migration.name));
+    }
+
+    sqlx::query("UPDATE pokemon SET abilities = 'overgrow' WHERE id = 1")
+        .execute(&conn)
+        .await
+        .unwrap();
+    let journaled_fields: Vec<String> =
+        sqlx::query_scalar("SELECT field FROM change_journal WHERE name = 'bulbasaur'")
+            .fetch_all(&conn)
+            .await
+            .unwrap();
+    assert_eq!(journaled_fields, vec!["abilities".to_string()]);
+
+    conn.close().await;
+    let _ = fs::remove_dir_all(&base_path);
+}
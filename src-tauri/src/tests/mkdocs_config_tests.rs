@@ -0,0 +1,175 @@
+use serde_yaml::Value;
+
+use crate::structs::mkdocs_structs::{MKDocsConfig, MarkdownExtension, ThemePalette};
+
+const HAND_EDITED_YAML: &str = r#"site_name: Emerald Plus
+copyright: Made by fans
+repo_url: https://github.com/example/emerald-plus
+theme:
+  name: material
+  logo: img/logo.png
+  palette:
+  - media: '(prefers-color-scheme: light)'
+    primary: red
+    accent: amber
+    scheme: default
+    toggle:
+      icon: material/eye-outline
+      name: Switch to dark mode
+  features:
+  - navigation.tabs
+nav:
+- Home: index.md
+- Pokemon: []
+- Routes: []
+plugins:
+- search
+- redirects:
+    redirect_maps:
+      old.md: index.md
+markdown_extensions:
+- admonition
+- toc:
+    permalink: true
+    toc_depth: 3
+extra:
+  social:
+  - icon: fontawesome/brands/discord
+    link: https://discord.gg/example
+extra_javascript:
+- javascripts/extra.js
+"#;
+
+const TAGGED_YAML: &str = r#"site_name: Emerald Plus
+theme:
+  name: material
+  palette:
+    scheme: slate
+    primary: red
+nav:
+- Home: index.md
+markdown_extensions:
+- pymdownx.emoji: {emoji_index: !!python/name:material.extensions.emoji.twemoji}
+- pymdownx.superfences:
+    custom_fences:
+    - name: mermaid
+      class: mermaid
+      format: !!python/name:pymdownx.superfences.fence_code_format
+"#;
+
+#[test]
+fn test_mkdocs_config_keeps_unknown_keys_and_order() {
+    let mut mkdocs_config = MKDocsConfig::from_yaml(HAND_EDITED_YAML).unwrap();
+
+    assert_eq!(
+        mkdocs_config
+            .markdown_extensions
+            .iter()
+            .map(MarkdownExtension::name)
+            .collect::<Vec<_>>(),
+        vec![Some("admonition"), Some("toc")]
+    );
+    assert_eq!(mkdocs_config.theme.palette.palettes().len(), 1);
+
+    mkdocs_config
+        .extra_css
+        .push("stylesheets/extra.css".to_string());
+    let written_yaml = mkdocs_config.to_yaml(Some(HAND_EDITED_YAML)).unwrap();
+
+    let mut expected: Value = serde_yaml::from_str(HAND_EDITED_YAML).unwrap();
+    // Only the stylesheet is added, empty fields the file lacks stay out
+    expected.as_mapping_mut().unwrap().insert(
+        Value::String("extra_css".to_string()),
+        Value::Sequence(vec![Value::String("stylesheets/extra.css".to_string())]),
+    );
+    let written: Value = serde_yaml::from_str(&written_yaml).unwrap();
+    assert_eq!(written, expected);
+
+    // Mappings compare without order, so the keys are checked separately
+    let keys = |value: &Value| -> Vec<String> {
+        value
+            .as_mapping()
+            .unwrap()
+            .keys()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect()
+    };
+    assert_eq!(keys(&written), keys(&expected));
+    assert_eq!(keys(&written["theme"]), keys(&expected["theme"]));
+    assert_eq!(
+        keys(&written["theme"]["palette"][0]),
+        keys(&expected["theme"]["palette"][0])
+    );
+}
+
+#[test]
+fn test_mkdocs_config_keeps_yaml_tags_and_palette_shapes() {
+    let mkdocs_config = MKDocsConfig::from_yaml(TAGGED_YAML).unwrap();
+
+    assert_eq!(
+        mkdocs_config
+            .markdown_extensions
+            .iter()
+            .map(MarkdownExtension::name)
+            .collect::<Vec<_>>(),
+        vec![Some("pymdownx.emoji"), Some("pymdownx.superfences")]
+    );
+    // A single palette mapping without a toggle is still read as a palette
+    let ThemePalette::Single(palette) = &mkdocs_config.theme.palette else {
+        panic!(
+            "Expected a single palette: {:?}",
+            mkdocs_config.theme.palette
+        );
+    };
+    assert_eq!(palette.scheme, "slate");
+    assert_eq!(palette.primary, "red");
+    assert!(palette.toggle.is_none());
+
+    let written_yaml = mkdocs_config.to_yaml(Some(TAGGED_YAML)).unwrap();
+    assert!(written_yaml.contains("emoji_index: !!python/name:material.extensions.emoji.twemoji\n"));
+    assert!(written_yaml.contains("format: !!python/name:pymdownx.superfences.fence_code_format\n"));
+    assert!(written_yaml.contains("  palette:\n    scheme: slate\n    primary: red\n"));
+    assert!(!written_yaml.contains("toggle"));
+
+    let reread_config = MKDocsConfig::from_yaml(&written_yaml).unwrap();
+    assert_eq!(
+        serde_yaml::to_value(&reread_config).unwrap(),
+        serde_yaml::to_value(&mkdocs_config).unwrap()
+    );
+
+    // Palette shapes material doesn't document are kept as written
+    let custom_palette_yaml = TAGGED_YAML.replace(
+        "  palette:\n    scheme: slate\n    primary: red\n",
+        "  palette: custom\n",
+    );
+    let mkdocs_config = MKDocsConfig::from_yaml(&custom_palette_yaml).unwrap();
+    assert!(matches!(
+        mkdocs_config.theme.palette,
+        ThemePalette::Other(Value::String(_))
+    ));
+    assert!(mkdocs_config
+        .to_yaml(None)
+        .unwrap()
+        .contains("  palette: custom\n"));
+}
+
+#[test]
+// Keys the file doesn't have aren't written back empty, and keys it sets to
+// an empty value on purpose stay
+fn test_mkdocs_config_round_trips_minimal_yaml() {
+    let minimal_yaml = "site_name: Test\ntheme:\n  name: material\nnav:\n- Home: index.md\n";
+    let mkdocs_config = MKDocsConfig::from_yaml(minimal_yaml).unwrap();
+    assert_eq!(
+        mkdocs_config.to_yaml(Some(minimal_yaml)).unwrap(),
+        minimal_yaml
+    );
+    assert_eq!(mkdocs_config.to_yaml(None).unwrap(), minimal_yaml);
+
+    let empty_values_yaml = "site_name: Test\nsite_url: ''\ntheme:\n  name: material\n  features: []\nnav:\n- Home: index.md\nplugins: []\n";
+    let mkdocs_config = MKDocsConfig::from_yaml(empty_values_yaml).unwrap();
+    assert_eq!(
+        mkdocs_config.to_yaml(Some(empty_values_yaml)).unwrap(),
+        empty_values_yaml
+    );
+}
@@ -0,0 +1,59 @@
+use crate::helpers::mkdocs_process::{parse_mkdocs_log, MkdocsLog, MkdocsLogLevel};
+
+#[test]
+fn test_parse_mkdocs_log_finds_level_and_file() {
+    let warning = parse_mkdocs_log(
+        42,
+        "WARNING -  Doc file 'pokemon/001-bulbasaur.md' contains a link 'old.md', but the target is not found among documentation files.",
+        MkdocsLogLevel::Info,
+    );
+    assert_eq!(
+        warning,
+        MkdocsLog {
+            process_id: 42,
+            level: MkdocsLogLevel::Warning,
+            message: "Doc file 'pokemon/001-bulbasaur.md' contains a link 'old.md', but the target is not found among documentation files.".to_string(),
+            file: Some("pokemon/001-bulbasaur.md".to_string()),
+            line: None,
+        }
+    );
+
+    let info = parse_mkdocs_log(
+        42,
+        "INFO    -  Building documentation...",
+        MkdocsLogLevel::Warning,
+    );
+    assert_eq!(info.level, MkdocsLogLevel::Info);
+    assert_eq!(info.message, "Building documentation...");
+    assert_eq!(info.file, None);
+}
+
+#[test]
+fn test_parse_mkdocs_log_config_errors_span_lines() {
+    let error = parse_mkdocs_log(
+        7,
+        "Error: MkDocs encountered an error parsing the configuration file: while parsing a block mapping",
+        MkdocsLogLevel::Info,
+    );
+    assert_eq!(error.level, MkdocsLogLevel::Error);
+
+    // The location on the next line has no level of its own
+    let location = parse_mkdocs_log(
+        7,
+        "  in \"/wikis/emerald/dist/mkdocs.yml\", line 12, column 3",
+        error.level,
+    );
+    assert_eq!(location.level, MkdocsLogLevel::Error);
+    assert_eq!(
+        location.file.as_deref(),
+        Some("/wikis/emerald/dist/mkdocs.yml")
+    );
+    assert_eq!(location.line, Some(12));
+
+    let traceback = parse_mkdocs_log(
+        7,
+        "  File \"/usr/lib/python3/site-packages/mkdocs/plugins.py\", line 520, in run_event",
+        MkdocsLogLevel::Error,
+    );
+    assert_eq!(traceback.line, Some(520));
+}
@@ -1,12 +1,52 @@
 #[cfg(test)]
 mod ability_generation_tests;
 #[cfg(test)]
+mod changelog_page_tests;
+#[cfg(test)]
+mod decomp_import_tests;
+#[cfg(test)]
+mod error_tests;
+#[cfg(test)]
+mod home_page_tests;
+#[cfg(test)]
 mod item_generation_tests;
 #[cfg(test)]
+mod lint_wiki_tests;
+#[cfg(test)]
+mod machine_generation_tests;
+#[cfg(test)]
+mod matchup_tests;
+#[cfg(test)]
 mod migration_tests;
 #[cfg(test)]
+mod mkdocs_config_tests;
+#[cfg(test)]
+mod mkdocs_process_tests;
+#[cfg(test)]
+mod move_generation_tests;
+#[cfg(test)]
 mod nature_generation_tests;
 #[cfg(test)]
 mod pokemon_generation_tests;
 #[cfg(test)]
+mod randomizer_log_tests;
+#[cfg(test)]
+mod reconcile_wiki_tests;
+#[cfg(test)]
+mod regenerate_wiki_tests;
+#[cfg(test)]
 mod route_generation_tests;
+#[cfg(test)]
+mod static_site_tests;
+#[cfg(test)]
+mod table_transfer_tests;
+#[cfg(test)]
+mod type_chart_generation_tests;
+#[cfg(test)]
+mod wiki_bundle_tests;
+#[cfg(test)]
+mod wiki_pages_tests;
+#[cfg(test)]
+mod wiki_plugins_tests;
+#[cfg(test)]
+mod wiki_theme_tests;
@@ -0,0 +1,108 @@
+use std::fs::read_to_string;
+
+use serde_yaml::Value;
+
+use crate::{
+    database::get_mkdocs_config, page_generators::move_page::generate_move_page,
+    structs::page_models::Move,
+};
+
+#[test]
+// Move Page is created and present in the mkdocs.yml file
+fn test_generate_move_page_created() {
+    let base_path =
+        std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
+
+    let moves = vec![
+        Move {
+            name: "tackle".to_string(),
+            power: Some(50),
+            accuracy: Some(100),
+            pp: Some(35),
+            move_type: "normal".to_string(),
+            damage_class: "physical".to_string(),
+            is_modified: 1,
+            is_new: 0,
+        },
+        Move {
+            name: "sonic-boom".to_string(),
+            power: Some(70),
+            accuracy: Some(90),
+            pp: Some(15),
+            move_type: "sound".to_string(),
+            damage_class: "special".to_string(),
+            is_modified: 0,
+            is_new: 1,
+        },
+    ];
+    let original_moves = vec![Move {
+        name: "tackle".to_string(),
+        power: Some(40),
+        accuracy: Some(100),
+        pp: Some(35),
+        move_type: "normal".to_string(),
+        damage_class: "physical".to_string(),
+        is_modified: 0,
+        is_new: 0,
+    }];
+
+    let result = generate_move_page("testing", &moves, &original_moves, &base_path);
+
+    assert!(result.unwrap() == "Moves Page Generated".to_string());
+    let generated_path = base_path
+        .join("testing")
+        .join("dist")
+        .join("docs")
+        .join("move_changes.md");
+    assert!(generated_path.exists());
+
+    let generated_file = match read_to_string(&generated_path) {
+        Ok(file) => file,
+        Err(err) => panic!("Failed to read generated file: {}", err),
+    };
+
+    assert!(generated_file.contains("| Tackle | 40 → 50 | 100 | 35 |"));
+    assert!(generated_file.contains("| Sonic Boom | 70 | 90 | 15 |"));
+    // The page sits at the docs root
+    assert!(generated_file
+        .contains("| ![normal](img/types/normal.png) | ![physical](img/types/physical.png) |"));
+
+    let mkdocs_yaml_file_path = base_path.join("testing").join("dist").join("mkdocs.yml");
+    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+        Ok(config) => config,
+        Err(err) => {
+            panic!("Failed to get mkdocs config: {}", err);
+        }
+    };
+
+    let nav_entries = mkdocs_config.nav.as_sequence_mut().unwrap();
+
+    let mut page_entry_exists = false;
+    let mut page_index = 0;
+    for (index, entry) in nav_entries.iter_mut().enumerate() {
+        let map_entries = entry.as_mapping_mut().unwrap();
+        if let Some(_) = map_entries.get_mut(Value::String("Move Changes".to_string())) {
+            page_entry_exists = true;
+            page_index = index;
+            break;
+        }
+    }
+
+    assert!(page_entry_exists);
+
+    // Clean up
+    mkdocs_config
+        .nav
+        .as_sequence_mut()
+        .unwrap()
+        .remove(page_index);
+    std::fs::remove_file(generated_path).unwrap();
+
+    match std::fs::write(
+        &mkdocs_yaml_file_path,
+        serde_yaml::to_string(&mut mkdocs_config).unwrap(),
+    ) {
+        Ok(_) => {}
+        Err(err) => panic!("Failed to update mkdocs yaml: {}", err),
+    };
+}
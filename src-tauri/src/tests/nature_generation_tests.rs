@@ -1,13 +1,14 @@
 use serde_yaml::Value;
 
 use crate::{
-    database::get_mkdocs_config,
-    page_generators::nature_page::{generate_nature_page, Nature},
+    database::{get_mkdocs_config, session::WikiSession},
+    page_generators::nature_page::generate_nature_page,
+    structs::page_models::Nature,
 };
 
-#[test]
+#[tokio::test]
 // Ability Page is created and present in the mkdocs.yml file
-fn test_generate_nature_page_created() {
+async fn test_generate_nature_page_created() {
     let base_path =
         std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
 
@@ -28,7 +29,8 @@ fn test_generate_nature_page_created() {
         },
     ];
 
-    let result = generate_nature_page("testing", &natures, &base_path);
+    let session = WikiSession::open("testing", &base_path).await.unwrap();
+    let result = generate_nature_page(&session, &natures);
 
     assert!(result.unwrap() == "Natures Page Generated".to_string());
     let generated_path = base_path
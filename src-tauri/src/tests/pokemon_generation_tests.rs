@@ -3,25 +3,26 @@ use std::fs::read_to_string;
 use serde_yaml::Value;
 
 use crate::{
-    database::get_mkdocs_config,
+    database::{get_mkdocs_config, session::WikiSession},
     page_generators::pokemon_pages::{
         generate_pokemon_pages, update_pokemon_pages_with_stripped_name,
     },
     structs::pokemon_structs::{DBAbility, DBPokemon, PokemonMove},
 };
 
-#[test]
-fn test_updating_pages_with_stripped_name() {
+#[tokio::test]
+async fn test_updating_pages_with_stripped_name() {
     let base_path =
         std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
     let wiki_name = "home-page-editing";
-    let result = update_pokemon_pages_with_stripped_name(wiki_name, &base_path).unwrap();
+    let session = WikiSession::open(wiki_name, &base_path).await.unwrap();
+    let result = update_pokemon_pages_with_stripped_name(&session).unwrap();
     assert_eq!(result, ());
 }
 
-#[test]
+#[tokio::test]
 // Pokemon Page is created and present in the mkdocs.yml file
-fn test_generate_pokemon_page() {
+async fn test_generate_pokemon_page() {
     let base_path =
         std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
     let resource_path = std::path::PathBuf::from("/Applications/WikiGen.app/Contents/Resources");
@@ -80,12 +81,12 @@ fn test_generate_pokemon_page() {
             is_modified: 0,
         },
     ];
+    let session = WikiSession::open("testing", &base_path).await.unwrap();
     let result = generate_pokemon_pages(
-        "testing",
+        &session,
         &pokemon_list,
         &abilities_list,
         &moveset,
-        &base_path,
         &resource_path,
     );
     assert!(result.unwrap() == "Pokemon Pages Generated".to_string());
@@ -102,6 +103,14 @@ fn test_generate_pokemon_page() {
         Err(err) => panic!("Failed to read generated file: {}", err),
     };
 
+    // Fairy/Ice defenses come from the wiki's matchup map, placed after the stats
+    let stats_end = generated_file.find("</table>").unwrap();
+    let type_defenses = generated_file
+        .find("## Type Defenses\n| Damage Taken | Types |")
+        .unwrap();
+    assert!(stats_end < type_defenses);
+    assert!(generated_file.contains("\t| 4x | <img src='../../img/types/steel.png'"));
+
     let snapshot = match read_to_string(
         base_path
             .join("testing")
@@ -0,0 +1,225 @@
+use std::collections::{HashMap, HashSet};
+
+use indexmap::IndexMap;
+
+use crate::{
+    data_transfer::randomizer_log::{
+        apply_log_to_routes, normalize_name, parse_randomizer_log, resolve_species_updates,
+        RandomizerImportReport, WikiLookup,
+    },
+    page_generators::game_routes::{RouteProperties, Routes, TrainerInfo, TrainerPokemon},
+};
+
+const RANDOMIZER_LOG: &str = "Randomization of Pokemon Red (U) completed.
+
+--Pokemon Base Stats & Types--
+NUM|NAME      |TYPE            |  HP| ATK| DEF|SATK|SDEF| SPD|ABILITY1    |ABILITY2    |ITEM
+  1|BULBASAUR |FIRE/POISON     |  45|  49|  49|  65|  65|  45|Blaze       |--          |
+  4|CHARMANDER|WATER           |  39|  52|  43|  60|  50|  65|Torrent     |Made Up     |
+  7|MISSINGNO |NORMAL          |  33| 136|   0|   6|   6|  29|--          |--          |
+
+--Pokemon Movesets--
+001 BULBASAUR -> IVYSAUR
+Level 1 : Tackle
+Level 3 : Ember
+Level 7 : Splash Dance
+004 CHARMANDER -> Scratch, Water Gun at level 5
+
+--TM Moves--
+TM01 Ember
+TM02 Water Gun
+
+--TM Compatibility--
+  1 BULBASAUR |TM01|  -  |
+  4 CHARMANDER|  -  |TM02|
+
+--Trainers Pokemon--
+#1 (YOUNGSTER BEN => YOUNGSTER BEN) - CHARMANDER Lv5, BULBASAUR@Potion Lv6
+#2 (LEADER BROCK) - MISSINGNO Lv12
+#3 (LASS ROBIN) - BULBASAUR Lv3
+
+--Wild Pokemon--
+Set #1 - ROUTE 1 Grass/Cave (rate=25) - BULBASAUR Lv2-4, BULBASAUR Lv3, CHARMANDER Lv5
+Set #2 - Route 1 Surfing (rate=10)
+CHARMANDER  Lv20    HP 39 ATK 52
+Set #3 - Viridian Forest Grass/Cave (rate=8) - BULBASAUR Lv3
+";
+
+fn wiki_lookup() -> WikiLookup {
+    WikiLookup {
+        pokemon: HashMap::from([
+            ("bulbasaur".to_string(), (1, 1, "grass,poison".to_string())),
+            ("charmander".to_string(), (4, 4, "fire".to_string())),
+        ]),
+        moves: HashMap::from([
+            ("tackle".to_string(), 33),
+            ("ember".to_string(), 52),
+            ("scratch".to_string(), 10),
+            ("water-gun".to_string(), 55),
+        ]),
+        abilities: HashSet::from(["blaze".to_string(), "torrent".to_string()]),
+        items: HashSet::from(["potion".to_string()]),
+    }
+}
+
+fn wiki_routes() -> Routes {
+    let trainer = |position| TrainerInfo {
+        position,
+        pokemon_team: Vec::new(),
+        sprite: String::new(),
+        versions: Vec::new(),
+    };
+    let route = |trainers: IndexMap<String, TrainerInfo>| RouteProperties {
+        render: true,
+        position: 0,
+        trainers,
+        wild_encounters: Vec::new(),
+        variants: vec!["default".to_string()],
+        wild_encounter_area_levels: IndexMap::new(),
+    };
+
+    Routes {
+        routes: IndexMap::from([
+            (
+                "Route 1".to_string(),
+                route(IndexMap::from([
+                    ("Youngster Ben".to_string(), trainer(0)),
+                    ("Brock".to_string(), trainer(1)),
+                ])),
+            ),
+            ("Pewter City".to_string(), route(IndexMap::new())),
+        ]),
+        encounter_areas: vec!["grass".to_string()],
+    }
+}
+
+#[test]
+fn test_normalize_randomizer_names() {
+    assert_eq!(normalize_name("MR. MIME"), "mr-mime");
+    assert_eq!(normalize_name("NIDORAN♀"), "nidoran-f");
+    assert_eq!(normalize_name("Farfetch'd"), "farfetchd");
+    assert_eq!(normalize_name(" Vine Whip "), "vine-whip");
+}
+
+#[test]
+fn test_parse_randomizer_log() {
+    let log = parse_randomizer_log(RANDOMIZER_LOG);
+
+    assert_eq!(log.pokemon.len(), 3);
+    assert_eq!(log.pokemon[0].types, vec!["fire", "poison"]);
+    assert_eq!(log.pokemon[1].stats.as_ref().unwrap().speed, 65);
+    assert_eq!(log.pokemon[1].abilities, vec!["torrent", "made-up"]);
+
+    assert_eq!(log.movesets.len(), 2);
+    assert_eq!(log.movesets[0].moves[1], (3, "ember".to_string()));
+    assert_eq!(log.movesets[1].moves[1], (5, "water-gun".to_string()));
+
+    assert_eq!(
+        log.machines[1],
+        ("tm02".to_string(), "water-gun".to_string())
+    );
+    assert_eq!(log.machine_compatibility[0].1, vec!["tm01"]);
+
+    assert_eq!(log.trainers[0].team[1].item, "potion");
+    assert_eq!(log.trainers[0].team[1].level, 6);
+
+    assert_eq!(log.encounter_sets.len(), 3);
+    assert_eq!(log.encounter_sets[0].location, "ROUTE 1");
+    assert_eq!(log.encounter_sets[0].encounters[0].max_level, 4);
+    assert_eq!(log.encounter_sets[1].area.as_deref(), Some("surfing"));
+    assert_eq!(log.encounter_sets[1].encounters[0].min_level, 20);
+}
+
+#[test]
+fn test_resolve_randomizer_species_updates() {
+    let log = parse_randomizer_log(RANDOMIZER_LOG);
+    let mut report = RandomizerImportReport::default();
+
+    let (updates, machines) = resolve_species_updates(&log, &wiki_lookup(), &mut report);
+
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[0].types.as_deref(), Some("fire,poison"));
+    assert_eq!(updates[0].abilities.as_deref(), Some("blaze"));
+    assert_eq!(updates[0].level_up_moves, Some(vec![(33, 1), (52, 3)]));
+    assert_eq!(updates[0].machine_moves, Some(vec![52]));
+    assert_eq!(updates[1].machine_moves, Some(vec![55]));
+    assert_eq!(
+        machines,
+        vec![("tm01".to_string(), 52), ("tm02".to_string(), 55)]
+    );
+
+    let unmatched: Vec<&str> = report
+        .unmatched
+        .iter()
+        .map(|entry| entry.entry.as_str())
+        .collect();
+    assert_eq!(unmatched, vec!["made-up", "missingno", "splash-dance"]);
+}
+
+#[test]
+fn test_apply_randomizer_log_to_routes() {
+    let log = parse_randomizer_log(RANDOMIZER_LOG);
+    let lookup = wiki_lookup();
+    let mut report = RandomizerImportReport::default();
+    let (updates, _) = resolve_species_updates(&log, &lookup, &mut report);
+    report.unmatched.clear();
+
+    let mut routes = wiki_routes();
+    let previous_pokemon = |name: &str| TrainerPokemon {
+        id: 0,
+        unique_id: String::new(),
+        types: Vec::new(),
+        name: name.to_string(),
+        level: 1,
+        moves: vec!["growl".to_string()],
+        item: String::new(),
+        nature: "adamant".to_string(),
+        ability: "blaze".to_string(),
+        trainer_versions: vec!["Fire Starter".to_string()],
+    };
+    routes.routes["Route 1"].trainers["Youngster Ben"].pokemon_team =
+        vec![previous_pokemon("charmander"), previous_pokemon("pidgey")];
+    apply_log_to_routes(&log, &lookup, &updates, &mut routes, &mut report);
+
+    let route = &routes.routes["Route 1"];
+    let youngster = &route.trainers["Youngster Ben"];
+    assert_eq!(youngster.pokemon_team.len(), 2);
+    assert_eq!(youngster.pokemon_team[0].types, vec!["water"]);
+    assert_eq!(youngster.pokemon_team[1].item, "potion");
+    // What the log leaves out is kept from the pokemon in the same slot
+    assert_eq!(youngster.pokemon_team[0].moves, vec!["growl"]);
+    assert_eq!(youngster.pokemon_team[0].ability, "blaze");
+    assert!(youngster.pokemon_team[1].moves.is_empty());
+    assert!(youngster.pokemon_team[1].ability.is_empty());
+    for pokemon in &youngster.pokemon_team {
+        assert_eq!(pokemon.nature, "adamant");
+        assert_eq!(pokemon.trainer_versions, vec!["Fire Starter"]);
+    }
+    // Brock is matched from "LEADER BROCK" but his only pokemon is unknown
+    assert!(route.trainers["Brock"].pokemon_team.is_empty());
+    assert_eq!(report.trainers_updated, 2);
+
+    let grass_encounters: Vec<(&str, f32)> = route
+        .wild_encounters
+        .iter()
+        .filter(|encounter| encounter.encounter_area == "grass")
+        .map(|encounter| (encounter.name.as_str(), encounter.encounter_rate))
+        .collect();
+    assert_eq!(
+        grass_encounters,
+        vec![("bulbasaur", 66.0), ("charmander", 33.0)]
+    );
+    assert_eq!(route.wild_encounter_area_levels["grass"], "2-5");
+    assert_eq!(route.wild_encounter_area_levels["surfing"], "20");
+    assert!(routes.encounter_areas.contains(&"surfing".to_string()));
+
+    let unmatched: Vec<&str> = report
+        .unmatched
+        .iter()
+        .map(|entry| entry.entry.as_str())
+        .collect();
+    assert_eq!(
+        unmatched,
+        vec!["missingno", "LASS ROBIN", "Viridian Forest"]
+    );
+}
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use serde_yaml::{Mapping, Value};
+
+use crate::{
+    page_generators::game_routes::{RouteProperties, Routes},
+    structs::{
+        mkdocs_structs::{nav_entry_file, nav_entry_title, MKDocsConfig},
+        pokemon_structs::DBPokemon,
+    },
+    wiki_preparation::{
+        reconcile_wiki::reconcile_nav, wiki_pages::DEFAULT_NAV_ORDER, yaml_declaration::get_yaml,
+    },
+};
+
+fn pokemon(dex_number: u32, name: &str, render: &str) -> DBPokemon {
+    DBPokemon {
+        id: dex_number,
+        dex_number,
+        name: name.to_string(),
+        types: "normal".to_string(),
+        abilities: "run-away".to_string(),
+        hp: 50,
+        attack: 50,
+        defense: 50,
+        sp_attack: 50,
+        sp_defense: 50,
+        speed: 50,
+        evolution_method: "no_change".to_string(),
+        evolution_level: None,
+        evolution_item: None,
+        evolution_other: None,
+        evolves_into: None,
+        render: render.to_string(),
+    }
+}
+
+fn route(position: i32, render: bool) -> RouteProperties {
+    RouteProperties {
+        render,
+        position,
+        trainers: IndexMap::new(),
+        wild_encounters: Vec::new(),
+        variants: vec!["default".to_string()],
+        wild_encounter_area_levels: IndexMap::new(),
+    }
+}
+
+fn page(title: &str, file: &str) -> Value {
+    let mut page = Mapping::new();
+    page.insert(
+        Value::String(title.to_string()),
+        Value::String(file.to_string()),
+    );
+    Value::Mapping(page)
+}
+
+fn section_titles(mkdocs_config: &mut MKDocsConfig, section_title: &str) -> Vec<String> {
+    mkdocs_config
+        .nav_section_mut(section_title)
+        .unwrap()
+        .iter()
+        .filter_map(nav_entry_title)
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_reconcile_nav_rebuilds_sections_and_finds_orphans() {
+    let mut mkdocs_config = get_yaml("test", "", "", "", "");
+    // The nav as it drifted: a stale dex number, a route that was deleted,
+    // a change page whose file is gone and a route that was never added
+    mkdocs_config.nav_section_mut("Pokemon").unwrap().extend([
+        page("010 - Pikachu", "pokemon/010-pikachu.md"),
+        page("001 - Bulbasaur", "pokemon/001-bulbasaur.md"),
+    ]);
+    mkdocs_config
+        .nav_section_mut("Routes")
+        .unwrap()
+        .push(page("Old Route", "routes/Old Route.md"));
+    mkdocs_config
+        .nav_entries_mut()
+        .unwrap()
+        .insert(1, page("Move Changes", "move_changes.md"));
+
+    let pokemon_list = vec![
+        pokemon(25, "pikachu", "true"),
+        pokemon(1, "bulbasaur", "true"),
+        pokemon(150, "mewtwo", "false"),
+        pokemon(151, "mew", "true"),
+    ];
+    let routes = Routes {
+        routes: IndexMap::from([
+            ("Route 2".to_string(), route(1, true)),
+            ("Route 1".to_string(), route(0, true)),
+            ("Hidden Grotto".to_string(), route(2, false)),
+        ]),
+        encounter_areas: Vec::new(),
+    };
+    let docs_files: HashSet<String> = [
+        "index.md",
+        "type_chart.md",
+        "pokemon/001-bulbasaur.md",
+        "pokemon/010-pikachu.md",
+        "pokemon/025-pikachu.md",
+        "pokemon/150-mewtwo.md",
+        "routes/Route 1.md",
+        "routes/Route 2.md",
+        "routes/Old Route.md",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect();
+
+    let nav_order: Vec<String> = DEFAULT_NAV_ORDER.map(str::to_string).to_vec();
+
+    let report = reconcile_nav(
+        &mut mkdocs_config,
+        &pokemon_list,
+        &routes,
+        &docs_files,
+        &nav_order,
+    )
+    .unwrap();
+
+    assert_eq!(
+        section_titles(&mut mkdocs_config, "Pokemon"),
+        vec!["001 - Bulbasaur", "025 - Pikachu"]
+    );
+    assert_eq!(
+        section_titles(&mut mkdocs_config, "Routes"),
+        vec!["Route 1", "Route 2"]
+    );
+    let top_level: Vec<(Option<&str>, Option<&str>)> = mkdocs_config
+        .nav_entries_mut()
+        .unwrap()
+        .iter()
+        .map(|entry| (nav_entry_title(entry), nav_entry_file(entry)))
+        .collect();
+    assert_eq!(
+        top_level,
+        vec![
+            (Some("Home"), Some("index.md")),
+            (Some("Type Chart"), Some("type_chart.md")),
+            (Some("Pokemon"), None),
+            (Some("Routes"), None),
+        ]
+    );
+
+    assert_eq!(
+        report.added_entries,
+        vec![
+            "Pokemon / 025 - Pikachu",
+            "Routes / Route 1",
+            "Routes / Route 2",
+            "Type Chart",
+        ]
+    );
+    assert_eq!(
+        report.removed_entries,
+        vec![
+            "Pokemon / 010 - Pikachu",
+            "Routes / Old Route",
+            "Move Changes"
+        ]
+    );
+    assert_eq!(
+        report.deleted_files,
+        vec![
+            "pokemon/010-pikachu.md",
+            "pokemon/150-mewtwo.md",
+            "routes/Old Route.md",
+        ]
+    );
+    assert_eq!(report.missing_pages, vec!["pokemon/151-mew.md"]);
+}
@@ -0,0 +1,214 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use crate::{
+    errors::WikiGenError,
+    wiki_preparation::regenerate_wiki::{
+        run_in_batches, run_regeneration, PhaseResult, RegenerationControl, RegenerationPhase,
+        RegenerationProgress, RegenerationSummary,
+    },
+};
+
+// A control for the wiki "test" that keeps every progress report
+fn recording_control() -> (
+    RegenerationControl,
+    Arc<AtomicBool>,
+    Arc<Mutex<Vec<RegenerationProgress>>>,
+) {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let recorded_reports = reports.clone();
+    let control = RegenerationControl::new("test", cancelled.clone(), move |progress| {
+        recorded_reports.lock().unwrap().push(progress);
+    });
+    (control, cancelled, reports)
+}
+
+#[test]
+fn test_regeneration_summary_records_failures() {
+    let mut summary = RegenerationSummary::default();
+
+    summary.record(
+        RegenerationPhase::PokemonPages,
+        &Ok("Pokemon Pages Generated".to_string()),
+    );
+    summary.record(
+        RegenerationPhase::TypeChart,
+        &Err(WikiGenError::io("Failed to read types", "types.json")),
+    );
+
+    assert_eq!(summary.failures, 1);
+    assert!(!summary.cancelled);
+    assert_eq!(
+        summary.results,
+        vec![
+            PhaseResult {
+                phase: RegenerationPhase::PokemonPages,
+                succeeded: true,
+                message: "Pokemon Pages Generated".to_string(),
+            },
+            PhaseResult {
+                phase: RegenerationPhase::TypeChart,
+                succeeded: false,
+                message: "Failed to read types (types.json)".to_string(),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+// Every phase runs in order after its progress is reported, and a failed
+// phase doesn't stop the ones after it
+async fn test_run_regeneration_runs_phases_in_order() {
+    let (control, _, reports) = recording_control();
+    let ran_phases = Arc::new(Mutex::new(Vec::new()));
+
+    let summary = run_regeneration(&control, |phase, _| {
+        let ran_phases = ran_phases.clone();
+        async move {
+            ran_phases.lock().unwrap().push(phase);
+            match phase {
+                RegenerationPhase::TypeChart | RegenerationPhase::Machines => {
+                    Err(WikiGenError::from(format!("{:?} failed", phase)))
+                }
+                _ => Ok(format!("{:?} done", phase)),
+            }
+        }
+    })
+    .await;
+
+    assert_eq!(*ran_phases.lock().unwrap(), RegenerationPhase::ALL.to_vec());
+    let reports = reports.lock().unwrap();
+    assert_eq!(
+        reports
+            .iter()
+            .map(|progress| (progress.phase, progress.completed, progress.total))
+            .collect::<Vec<_>>(),
+        RegenerationPhase::ALL
+            .iter()
+            .enumerate()
+            .map(|(completed, phase)| (*phase, completed, RegenerationPhase::ALL.len()))
+            .collect::<Vec<_>>()
+    );
+    assert!(reports.iter().all(|progress| progress.wiki_name == "test"));
+
+    assert!(!summary.cancelled);
+    assert_eq!(summary.failures, 2);
+    assert_eq!(summary.results.len(), RegenerationPhase::ALL.len());
+    assert_eq!(
+        summary
+            .results
+            .iter()
+            .filter(|result| !result.succeeded)
+            .map(|result| (result.phase, result.message.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (RegenerationPhase::TypeChart, "TypeChart failed"),
+            (RegenerationPhase::Machines, "Machines failed"),
+        ]
+    );
+}
+
+#[tokio::test]
+// A cancel during a phase lets it finish and skips every phase after it
+async fn test_run_regeneration_stops_when_cancelled() {
+    let (control, cancelled, _) = recording_control();
+
+    let summary = run_regeneration(&control, |phase, _| {
+        let cancelled = cancelled.clone();
+        async move {
+            if phase == RegenerationPhase::PokemonPages {
+                cancelled.store(true, Ordering::SeqCst);
+            }
+            Ok(String::new())
+        }
+    })
+    .await;
+
+    assert!(summary.cancelled);
+    assert_eq!(summary.failures, 0);
+    assert_eq!(
+        summary
+            .results
+            .iter()
+            .map(|result| result.phase)
+            .collect::<Vec<_>>(),
+        RegenerationPhase::ALL[..3].to_vec()
+    );
+}
+
+#[tokio::test]
+// Batches report their progress within the phase and stop at a cancel or
+// the first failure
+async fn test_run_in_batches() {
+    let (control, cancelled, reports) = recording_control();
+    let batches = Arc::new(Mutex::new(Vec::new()));
+
+    let generate = |batch: Vec<usize>| {
+        let batches = batches.clone();
+        async move {
+            batches.lock().unwrap().push(batch.clone());
+            match batch.contains(&3) {
+                true => Err(WikiGenError::from("Failed to generate page 3")),
+                false => Ok("Pokemon Pages Generated".to_string()),
+            }
+        }
+    };
+
+    let result = run_in_batches(
+        RegenerationPhase::PokemonPages,
+        vec![0, 1, 2],
+        2,
+        &control,
+        generate,
+    )
+    .await;
+    assert_eq!(result.unwrap(), "Pokemon Pages Generated");
+    assert_eq!(*batches.lock().unwrap(), vec![vec![0, 1], vec![2]]);
+    assert_eq!(
+        reports
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|progress| {
+                (
+                    progress.phase,
+                    progress.completed,
+                    progress.phase_completed,
+                    progress.phase_total,
+                )
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            (RegenerationPhase::PokemonPages, 2, 0, 2),
+            (RegenerationPhase::PokemonPages, 2, 1, 2),
+        ]
+    );
+
+    batches.lock().unwrap().clear();
+    let result = run_in_batches(
+        RegenerationPhase::PokemonPages,
+        vec![0, 1, 2, 3, 4, 5, 6],
+        2,
+        &control,
+        generate,
+    )
+    .await;
+    assert!(result.is_err());
+    assert_eq!(*batches.lock().unwrap(), vec![vec![0, 1], vec![2, 3]]);
+
+    batches.lock().unwrap().clear();
+    cancelled.store(true, Ordering::SeqCst);
+    let result = run_in_batches(
+        RegenerationPhase::PokemonPages,
+        vec![0, 1, 2],
+        2,
+        &control,
+        generate,
+    )
+    .await;
+    assert_eq!(result.unwrap(), "Cancelled after 0 of 2 batches");
+    assert!(batches.lock().unwrap().is_empty());
+}
@@ -1,84 +1,291 @@
-// use serde_yaml::Value;
-
-// use crate::database::get_mkdocs_config;
-
-// #[test]
-// // Route Page is created and present in the mkdocs.yml file
-// fn test_generate_route_page_created() {
-//     let base_path =
-//         std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
-//     let resource_path = std::path::PathBuf::from("/Applications/WikiGen.app/Contents/Resources");
-
-//     let result = generate_route_pages("testing", &base_path, &resource_path, &["Route 1"]);
-
-//     assert!(result.unwrap() == "Route Page Generated".to_string());
-//     let generated_path = base_path
-//         .join("testing")
-//         .join("dist")
-//         .join("docs")
-//         .join("routes")
-//         .join("Route 1.md");
-//     assert!(generated_path.exists());
-
-//     // let generated_file = match read_to_string(&generated_path) {
-//     //     Ok(file) => file,
-//     //     Err(err) => panic!("Failed to read generated file: {}", err),
-//     // };
-
-//     // let snapshot = match read_to_string(
-//     //     base_path
-//     //         .join("testing")
-//     //         .join("snapshots")
-//     //         .join("generated_route-Route_1.md"),
-//     // ) {
-//     //     Ok(snapshot) => snapshot,
-//     //     Err(err) => panic!("Failed to read snapshot file: {}", err),
-//     // };
-
-//     // assert_eq!(generated_file, snapshot);
-
-//     let mkdocs_yaml_file_path = base_path.join("testing").join("dist").join("mkdocs.yml");
-//     let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
-//         Ok(config) => config,
-//         Err(err) => {
-//             panic!("Failed to get mkdocs config: {}", err);
-//         }
-//     };
-
-//     let mut mkdocs_routes: &mut Vec<Value> = &mut Vec::new();
-
-//     let nav_entries = mkdocs_config.nav.as_sequence_mut().unwrap();
-//     for entry in nav_entries {
-//         let map_entries = entry.as_mapping_mut().unwrap();
-//         match map_entries.get_mut(Value::String("Routes".to_string())) {
-//             Some(map_entry) => {
-//                 mkdocs_routes = map_entry.as_sequence_mut().unwrap();
-//             }
-//             None => {}
-//         }
-//     }
-
-//     let mut page_entry_exists = false;
-//     let mut page_position = 0;
-//     for (index, page_entry) in mkdocs_routes.iter_mut().enumerate() {
-//         if page_entry.as_mapping().unwrap().contains_key("Route 1") {
-//             page_entry_exists = true;
-//             page_position = index;
-//             break;
-//         }
-//     }
-
-//     assert!(page_entry_exists);
-
-//     // Clean up
-//     mkdocs_routes.remove(page_position);
-//     std::fs::remove_file(generated_path).unwrap();
-
-//     match std::fs::write(
-//         &mkdocs_yaml_file_path,
-//         serde_yaml::to_string(&mut mkdocs_config).unwrap(),
-//     ) {
-//         Ok(_) => {}
-//         Err(err) => panic!("Failed to update mkdocs yaml: {}", err),
-//     };
-// }
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use indexmap::IndexMap;
+
+use crate::{
+    database::{
+        get_mkdocs_config, get_sqlite_connection, session::WikiSession, update_mkdocs_yaml,
+        update_routes, update_types,
+    },
+    helpers::matchups::{preset_matchup_map, update_matchup_map, MatchupTable},
+    page_generators::game_routes::{
+        generate_route_pages, get_type_coverage, RouteProperties, Routes, TrainerInfo,
+        TrainerPokemon, TypeCoverage,
+    },
+    structs::{
+        matchup_models::{TypeChartPreset, WikiTypes, DEFAULT_TYPES},
+        mkdocs_structs::nav_entry_title,
+        page_models::MoveType,
+    },
+    wiki_preparation::yaml_declaration::get_yaml,
+};
+
+fn default_types() -> Vec<String> {
+    DEFAULT_TYPES
+        .iter()
+        .map(|_type| _type.to_string())
+        .collect()
+}
+
+fn move_type(name: &str, move_type: &str, damage_class: &str) -> (String, MoveType) {
+    (
+        name.to_string(),
+        MoveType {
+            name: name.to_string(),
+            move_type: move_type.to_string(),
+            damage_class: damage_class.to_string(),
+        },
+    )
+}
+
+pub(super) fn type_coverage() -> TypeCoverage {
+    TypeCoverage {
+        move_types: HashMap::from([
+            move_type("vine-whip", "grass", "physical"),
+            move_type("growl", "normal", "status"),
+            move_type("ember", "fire", "special"),
+            // A type the wiki removed
+            move_type("echo", "sound", "special"),
+        ]),
+        matchup_table: Some(Arc::new(MatchupTable::new(
+            default_types(),
+            preset_matchup_map(&TypeChartPreset::GenSixOnwards),
+        ))),
+    }
+}
+
+fn trainer_pokemon(
+    id: usize,
+    name: &str,
+    types: &[&str],
+    moves: &[&str],
+    trainer_versions: &[&str],
+) -> TrainerPokemon {
+    TrainerPokemon {
+        id,
+        unique_id: format!("{name}-{id}"),
+        types: types.iter().map(|_type| _type.to_string()).collect(),
+        name: name.to_string(),
+        level: 5,
+        moves: moves.iter().map(|_move| _move.to_string()).collect(),
+        item: String::new(),
+        nature: String::new(),
+        ability: String::new(),
+        trainer_versions: trainer_versions
+            .iter()
+            .map(|version| version.to_string())
+            .collect(),
+    }
+}
+
+pub(super) fn trainer() -> TrainerInfo {
+    TrainerInfo {
+        position: 0,
+        pokemon_team: vec![
+            trainer_pokemon(
+                1,
+                "bulbasaur",
+                &["grass", "poison"],
+                &["vine-whip", "growl"],
+                &["Normal"],
+            ),
+            trainer_pokemon(43, "oddish", &["grass", "poison"], &["echo"], &["Normal"]),
+            trainer_pokemon(4, "charmander", &["fire"], &["ember"], &["Normal", "Hard"]),
+        ],
+        sprite: String::new(),
+        versions: vec!["Normal".to_string(), "Hard".to_string()],
+    }
+}
+
+fn type_images(types: &[&str]) -> String {
+    types
+        .iter()
+        .map(|_type| {
+            format!(
+                "<img src=\"../../img/types/{_type}.png\" alt=\"{_type}\" style=\"width: 50px;\"/>"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+// Status moves and moves of removed types don't count towards the coverage,
+// and counters the team can't hit back are suggested first
+fn test_generate_team_summary() {
+    let team_summary = trainer()
+        .generate_team_summary("Normal", &type_coverage(), "\t")
+        .unwrap();
+
+    let shared_weaknesses = ["fire", "ice", "flying", "psychic"]
+        .iter()
+        .map(|_type| format!("{} x2", type_images(&[_type])))
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert_eq!(
+        team_summary,
+        format!(
+            "\n\t<div class=\"trainer-team-summary\"><p><strong>Hits super effectively:</strong> {}</p><p><strong>Shared weaknesses:</strong> {}</p><p><strong>Suggested counters:</strong> {}</p></div>",
+            type_images(&["water", "grass", "ice", "ground", "bug", "rock", "steel"]),
+            shared_weaknesses,
+            type_images(&["fire", "flying", "psychic"])
+        )
+    );
+}
+
+#[test]
+// Only the version's team is summarized, and nothing is summarized without matchups
+fn test_generate_team_summary_for_version() {
+    let team_summary = trainer()
+        .generate_team_summary("Hard", &type_coverage(), "")
+        .unwrap();
+    assert!(team_summary.contains(&format!(
+        "<strong>Shared weaknesses:</strong> -</p><p><strong>Suggested counters:</strong> {}</p>",
+        type_images(&["water", "ground", "rock"])
+    )));
+
+    let team_summary = trainer()
+        .generate_team_summary("Hard", &TypeCoverage::default(), "")
+        .unwrap();
+    assert_eq!(team_summary, "");
+
+    let team_summary = trainer()
+        .generate_team_summary("Elite", &type_coverage(), "")
+        .unwrap();
+    assert_eq!(team_summary, "");
+}
+
+// A wiki with the files route generation reads and no database tables
+pub(super) fn create_route_wiki(base_path: &PathBuf, wiki_name: &str) {
+    let _ = fs::remove_dir_all(base_path);
+    let wiki_path = base_path.join(wiki_name);
+    fs::create_dir_all(wiki_path.join("data")).unwrap();
+    fs::create_dir_all(wiki_path.join("dist").join("docs").join("routes")).unwrap();
+    File::create(wiki_path.join(format!("{}.db", wiki_name))).unwrap();
+
+    update_mkdocs_yaml(wiki_name, base_path, &get_yaml(wiki_name, "", "", "", "")).unwrap();
+    update_types(
+        wiki_name,
+        base_path,
+        &WikiTypes {
+            types: default_types(),
+        },
+    )
+    .unwrap();
+    update_matchup_map(
+        wiki_name,
+        base_path,
+        &preset_matchup_map(&TypeChartPreset::GenSixOnwards),
+    )
+    .unwrap();
+}
+
+#[tokio::test]
+// Coverage is built from the wiki's moves and matchups, and left empty
+// when the moves can't be read
+async fn test_get_type_coverage() {
+    let base_path = std::env::temp_dir().join("wikigen_type_coverage_test");
+    let wiki_name = "coverage";
+    create_route_wiki(&base_path, wiki_name);
+
+    let session = WikiSession::open(wiki_name, &base_path).await.unwrap();
+    let type_coverage = get_type_coverage(&session).await;
+    assert!(type_coverage.matchup_table.is_none());
+    assert!(type_coverage.move_types.is_empty());
+
+    let conn = get_sqlite_connection(base_path.join(wiki_name).join(format!("{}.db", wiki_name)))
+        .await
+        .unwrap();
+    sqlx::query(
+        "CREATE TABLE moves (id INTEGER PRIMARY KEY, name TEXT, type TEXT, damage_class TEXT);
+        INSERT INTO moves (id, name, type, damage_class) VALUES (1, 'ember', 'fire', 'special');",
+    )
+    .execute(&conn)
+    .await
+    .unwrap();
+
+    let type_coverage = get_type_coverage(&session).await;
+    let matchup_table = type_coverage.matchup_table.unwrap();
+    assert_eq!(matchup_table.types(), default_types().as_slice());
+    assert_eq!(matchup_table.effectiveness("fire", "grass").unwrap(), 2.0);
+    let ember = type_coverage.move_types.get("ember").unwrap();
+    assert_eq!(
+        (ember.move_type.as_str(), ember.damage_class.as_str()),
+        ("fire", "special")
+    );
+}
+
+#[tokio::test]
+// Route Page is created and present in the mkdocs.yml file
+async fn test_generate_route_page() {
+    let base_path = std::env::temp_dir().join("wikigen_route_page_test");
+    let resources_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let wiki_name = "routes";
+    create_route_wiki(&base_path, wiki_name);
+
+    let mut trainer = trainer();
+    trainer.pokemon_team[0].item = "oran-berry".to_string();
+    update_routes(
+        wiki_name,
+        &base_path,
+        &Routes {
+            routes: IndexMap::from([(
+                "Route 1".to_string(),
+                RouteProperties {
+                    render: true,
+                    position: 0,
+                    trainers: IndexMap::from([("Youngster Joey".to_string(), trainer)]),
+                    wild_encounters: Vec::new(),
+                    variants: vec!["default".to_string()],
+                    wild_encounter_area_levels: IndexMap::new(),
+                },
+            )]),
+            encounter_areas: Vec::new(),
+        },
+    )
+    .unwrap();
+
+    let session = WikiSession::open(wiki_name, &base_path).await.unwrap();
+    let result = generate_route_pages(&session, &resources_path, vec!["Route 1"], type_coverage());
+    assert_eq!(result.unwrap(), "Route Page Generated");
+
+    let generated_file = fs::read_to_string(
+        base_path
+            .join(wiki_name)
+            .join("dist")
+            .join("docs")
+            .join("routes")
+            .join("Route 1.md"),
+    )
+    .unwrap();
+    assert!(generated_file.contains("=== \"Trainer Encounters\""));
+    assert!(generated_file.contains("???+ note \"Youngster Joey\""));
+    assert!(generated_file.contains("=== \"Normal\""));
+    assert!(generated_file.contains("=== \"Hard\""));
+    assert!(
+        generated_file.contains("<img src=\"../../img/pokemon/bulbasaur.png\" alt=\"bulbasaur\"")
+    );
+    assert!(generated_file.contains(
+        "<img src=\"../../img/items/oran-berry.png\" alt=\"oran-berry\" style=\"width: 25px;\"/>"
+    ));
+    assert!(generated_file.contains(&type_images(&["poison"])));
+    // Both versions get a summary under their cards
+    assert_eq!(
+        generated_file
+            .matches("<div class=\"trainer-team-summary\">")
+            .count(),
+        2
+    );
+
+    let mut mkdocs_config =
+        get_mkdocs_config(&base_path.join(wiki_name).join("dist").join("mkdocs.yml")).unwrap();
+    let mkdocs_routes = mkdocs_config.nav_section_mut("Routes").unwrap();
+    assert!(mkdocs_routes
+        .iter()
+        .any(|entry| nav_entry_title(entry) == Some("Route 1")));
+}
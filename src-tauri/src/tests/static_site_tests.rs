@@ -0,0 +1,362 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use indexmap::IndexMap;
+
+use super::route_generation_tests::{create_route_wiki, trainer, type_coverage};
+use crate::{
+    database::{read_template, session::WikiSession, update_routes},
+    helpers::matchups::preset_matchup_map,
+    page_generators::{
+        game_routes::{generate_route_pages, RouteProperties, Routes, WildEncounter},
+        move_page::generate_move_page,
+        pokemon_pages::generate_page_from_template,
+        type_chart_page::generate_type_chart_page,
+    },
+    static_site::{
+        markdown::render_markdown,
+        preview_server::{build_response, resolve_request, PreviewResponse},
+        site::page_url,
+    },
+    structs::{
+        matchup_models::{TypeChartPreset, TypeEffectiveness, DEFAULT_TYPES},
+        page_models::Move,
+        pokemon_structs::{DBAbility, DBPokemon, PokemonMove},
+    },
+};
+
+#[test]
+fn test_render_markdown_handles_route_page_extensions() {
+    // Trimmed down from a generated route page: the trainer card HTML comes
+    // from a template and starts at column 0 inside the indented note
+    let markdown = "*[Lv.]: Level\n\
+=== \"Wild Encounters\"\n\
+\t???+ note \"Grass\"\n\
+\t\t<div class=\"wild-encounters\">\n\
+![pidgey](../../img/pokemon/pidgey.png) [Pidgey](/test/pokemon/016-pidgey) 40%\n\
+\t\t</div>\n\
+=== \"Trainer Encounters\"\n\
+\t!!! warning \"\"\n\
+\t\tLv. 5 [Bulbasaur](../pokemon/001-bulbasaur.md)\n";
+
+    let html = render_markdown(markdown, "routes/Route 1.md");
+
+    assert_eq!(html.matches("<input type=\"radio\"").count(), 2);
+    assert!(html.contains("<details class=\"note\" open>\n<summary>Grass</summary>"));
+    assert!(html.contains(
+        "<img src=\"../../img/pokemon/pidgey.png\" alt=\"pidgey\"> <a href=\"/test/pokemon/016-pidgey\">Pidgey</a> 40%"
+    ));
+    assert!(html.contains("<div class=\"admonition warning\">\n<p><abbr title=\"Level\">Lv.</abbr> 5 <a href=\"../../pokemon/001-bulbasaur/\">Bulbasaur</a></p>"));
+}
+
+fn pokemon_move(name: &str, learn_method: &str, machine_name: Option<&str>) -> PokemonMove {
+    PokemonMove {
+        pokemon: 1,
+        move_id: 1,
+        learn_method: learn_method.to_string(),
+        level_learned: Some(1),
+        move_name: name.to_string(),
+        move_type: Some("normal".to_string()),
+        power: Some(40),
+        accuracy: Some(100),
+        pp: Some(35),
+        damage_class: "physical".to_string(),
+        machine_name: machine_name.map(str::to_string),
+    }
+}
+
+#[test]
+// The generated pokemon page keeps its headings, tables and template HTML,
+// and the move images are rewritten for the page's folder
+fn test_render_generated_pokemon_page() {
+    let resources_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let template = read_template(&resources_path, "pokemon_page_template.md").unwrap();
+    let pokemon = DBPokemon {
+        id: 1,
+        name: "bulbasaur".to_string(),
+        dex_number: 1,
+        types: "grass,poison".to_string(),
+        abilities: "overgrow,none,chlorophyll".to_string(),
+        hp: 45,
+        attack: 49,
+        defense: 49,
+        sp_attack: 65,
+        sp_defense: 65,
+        speed: 45,
+        evolution_method: "level_up".to_string(),
+        evolution_item: None,
+        evolution_level: Some(16),
+        evolution_other: None,
+        evolves_into: Some("ivysaur".to_string()),
+        render: "true".to_string(),
+    };
+    let abilities = vec![DBAbility {
+        id: 1,
+        name: "overgrow".to_string(),
+        effect: "Powers up Grass-type moves in a pinch.".to_string(),
+        is_new: 0,
+        is_modified: 0,
+    }];
+    let movesets = vec![
+        pokemon_move("tackle", "level-up", None),
+        pokemon_move("headbutt", "machine", Some("tm01")),
+    ];
+    let locations = vec![WildEncounter {
+        id: 1,
+        name: "bulbasaur".to_string(),
+        encounter_rate: 40.0,
+        encounter_area: "grass".to_string(),
+        route: "Route 1".to_string(),
+        route_variant: "default".to_string(),
+        special_note: String::new(),
+    }];
+    let type_defenses =
+        TypeEffectiveness(HashMap::from([("2".to_string(), vec!["fire".to_string()])]));
+
+    let markdown = generate_page_from_template(
+        &template,
+        &pokemon,
+        &abilities,
+        &movesets,
+        &locations,
+        Some(&type_defenses),
+    );
+    let html = render_markdown(&markdown, "pokemon/001-bulbasaur.md");
+
+    for heading in [
+        "<h2 id=\"type-defenses\">Type Defenses</h2>",
+        "<h2 id=\"evolution-change\">Evolution Change</h2>",
+        "<h2 id=\"locations\">Locations</h2>",
+    ] {
+        assert!(html.contains(heading), "missing {heading}");
+    }
+    assert!(html.contains("<tr><td style=\"text-align: center\">Level Up</td><td style=\"text-align: center\">16</td><td style=\"text-align: center\">ivysaur</td></tr>"));
+    assert!(html.contains("<tr><td>Route 1</td><td>Grass</td><td>40</td><td></td></tr>"));
+    assert!(html.contains("<td>Tackle</td>"));
+    assert!(html.contains("<td>TM01</td><td>Headbutt</td>"));
+    assert!(html.contains("<img src=\"../../img/types/normal.png\" alt=\"normal\">"));
+    assert!(!html.contains("src=\"../img/types/"));
+    assert!(html.contains("<img src='../../img/types/fire.png' style='width: 60px;'/>"));
+    // Nothing is left as unrendered markdown
+    assert!(!html.contains("| --"));
+    assert!(!html.contains("##"));
+}
+
+#[tokio::test]
+// Tabs, notes, trainer cards and team summaries come through from a generated route page
+async fn test_render_generated_route_page() {
+    let base_path = std::env::temp_dir().join("wikigen_static_site_route_test");
+    let resources_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let wiki_name = "render";
+    create_route_wiki(&base_path, wiki_name);
+    update_routes(
+        wiki_name,
+        &base_path,
+        &Routes {
+            routes: IndexMap::from([(
+                "Route 1".to_string(),
+                RouteProperties {
+                    render: true,
+                    position: 0,
+                    trainers: IndexMap::from([("Youngster Joey".to_string(), trainer())]),
+                    wild_encounters: Vec::new(),
+                    variants: vec!["default".to_string()],
+                    wild_encounter_area_levels: IndexMap::new(),
+                },
+            )]),
+            encounter_areas: Vec::new(),
+        },
+    )
+    .unwrap();
+    let session = WikiSession::open(wiki_name, &base_path).await.unwrap();
+    generate_route_pages(&session, &resources_path, vec!["Route 1"], type_coverage()).unwrap();
+
+    let markdown = fs::read_to_string(
+        base_path
+            .join(wiki_name)
+            .join("dist")
+            .join("docs")
+            .join("routes")
+            .join("Route 1.md"),
+    )
+    .unwrap();
+    let html = render_markdown(&markdown, "routes/Route 1.md");
+
+    assert!(html.contains("<label for=\"__tabbed_1_1\">Trainer Encounters</label>"));
+    assert!(html.contains("<details class=\"note\" open>\n<summary>Youngster Joey</summary>"));
+    assert!(html.contains("<label for=\"__tabbed_2_1\">Normal</label>"));
+    assert!(html.contains("<label for=\"__tabbed_2_2\">Hard</label>"));
+    assert_eq!(
+        html.matches("<div class=\"trainer-pokemon-card\">").count(),
+        4
+    );
+    assert_eq!(
+        html.matches("<div class=\"trainer-team-summary\">").count(),
+        2
+    );
+    // The cards are raw HTML, so mkdocs leaves their paths alone
+    assert!(html.contains("<img src=\"../../img/pokemon/bulbasaur.png\" alt=\"bulbasaur\""));
+    assert!(!html.contains("<p><div"));
+    assert!(!html.contains("==="));
+    assert!(!html.contains("???+"));
+
+    fs::remove_dir_all(&base_path).unwrap();
+}
+
+#[tokio::test]
+// Both charts stay raw HTML tables inside their tabs
+async fn test_render_generated_type_chart_page() {
+    let base_path = std::env::temp_dir().join("wikigen_static_site_type_chart_test");
+    let wiki_name = "render";
+    create_route_wiki(&base_path, wiki_name);
+    let types = DEFAULT_TYPES
+        .iter()
+        .map(|_type| _type.to_string())
+        .collect::<Vec<String>>();
+    let standard_matchup_map = preset_matchup_map(&TypeChartPreset::GenSixOnwards);
+    let session = WikiSession::open(wiki_name, &base_path).await.unwrap();
+    generate_type_chart_page(
+        &session,
+        &types,
+        &standard_matchup_map,
+        &standard_matchup_map,
+    )
+    .unwrap();
+
+    let markdown = fs::read_to_string(
+        base_path
+            .join(wiki_name)
+            .join("dist")
+            .join("docs")
+            .join("type_chart.md"),
+    )
+    .unwrap();
+    let html = render_markdown(&markdown, "type_chart.md");
+
+    assert!(html.starts_with("<p>Highlighted cells differ from the standard type chart.</p>"));
+    assert!(html.contains("<label for=\"__tabbed_1_1\">Offense</label>"));
+    assert!(html.contains("<label for=\"__tabbed_1_2\">Defense</label>"));
+    assert!(html.contains(
+        "<p>Rows are the attacking type, columns are the defending type.</p>\n<table class=\"type-chart\">"
+    ));
+    assert_eq!(html.matches("<table class=\"type-chart\">").count(), 2);
+    assert!(!html.contains("<p><table"));
+
+    fs::remove_dir_all(&base_path).unwrap();
+}
+
+fn wiki_move(name: &str, power: u32, move_type: &str, is_new: i32, is_modified: i32) -> Move {
+    Move {
+        name: name.to_string(),
+        power: Some(power),
+        accuracy: Some(100),
+        pp: Some(35),
+        move_type: move_type.to_string(),
+        damage_class: "physical".to_string(),
+        is_modified,
+        is_new,
+    }
+}
+
+#[tokio::test]
+// The indented rows of both move tables stay in their tables, and the
+// type images are rewritten for the page's folder
+async fn test_render_generated_move_page() {
+    let base_path = std::env::temp_dir().join("wikigen_static_site_move_test");
+    let wiki_name = "render";
+    create_route_wiki(&base_path, wiki_name);
+    let moves = vec![
+        wiki_move("laser-blast", 90, "electric", 1, 0),
+        wiki_move("tackle", 50, "fighting", 0, 1),
+        wiki_move("growl", 0, "normal", 0, 0),
+    ];
+    let original_moves = vec![wiki_move("tackle", 40, "normal", 0, 0)];
+    let session = WikiSession::open(wiki_name, &base_path).await.unwrap();
+    generate_move_page(&session, &moves, &original_moves).unwrap();
+
+    let markdown = fs::read_to_string(
+        base_path
+            .join(wiki_name)
+            .join("dist")
+            .join("docs")
+            .join("move_changes.md"),
+    )
+    .unwrap();
+    let html = render_markdown(&markdown, "move_changes.md");
+
+    assert_eq!(html.matches("<table>").count(), 2);
+    assert!(html.contains("<th style=\"text-align: center\">New Moves</th>"));
+    assert!(html.contains("<th style=\"text-align: center\">Modified Moves</th>"));
+    assert!(html.contains("<tr><td style=\"text-align: center\">Laser Blast</td><td style=\"text-align: center\">90</td>"));
+    assert!(html.contains("<td style=\"text-align: center\">40 → 50</td>"));
+    assert!(html.contains(
+        "<td style=\"text-align: center\"><img src=\"../img/types/normal.png\" alt=\"normal\"> → <img src=\"../img/types/fighting.png\" alt=\"fighting\"></td>"
+    ));
+    assert!(html.contains("<img src=\"../img/types/physical.png\" alt=\"physical\">"));
+    assert!(!html.contains("Growl"));
+    // Nothing is left as unrendered markdown
+    assert!(!html.contains("| :--:"));
+    assert!(!html.contains("<p>"));
+
+    fs::remove_dir_all(&base_path).unwrap();
+}
+
+#[test]
+fn test_page_url_uses_directory_urls() {
+    assert_eq!(page_url("index.md"), "");
+    assert_eq!(page_url("routes/Route 1.md"), "routes/Route 1/");
+    assert_eq!(page_url("guides/index.md"), "guides/");
+}
+
+#[test]
+fn test_resolve_request_stays_in_site_folder() {
+    let base_path = std::env::temp_dir().join("wikigen_static_site_test");
+    let page_folder = base_path
+        .join("test")
+        .join("dist")
+        .join("site")
+        .join("routes")
+        .join("Route 1");
+    fs::create_dir_all(&page_folder).unwrap();
+    fs::write(page_folder.join("index.html"), "<h1>Route 1</h1>").unwrap();
+
+    assert_eq!(
+        resolve_request(&base_path, "/test/routes/Route%201/"),
+        PreviewResponse::File(page_folder.join("index.html"))
+    );
+    assert_eq!(
+        resolve_request(&base_path, "/test/routes/Route%201?q=1"),
+        PreviewResponse::Redirect("/test/routes/Route%201/".to_string())
+    );
+    assert_eq!(
+        resolve_request(&base_path, "/test/../test/routes/Route%201/"),
+        PreviewResponse::NotFound
+    );
+    assert_eq!(
+        resolve_request(&base_path, "/test/routes/Route%202/"),
+        PreviewResponse::NotFound
+    );
+
+    fs::remove_dir_all(&base_path).unwrap();
+}
+
+#[test]
+// HEAD answers with GET's headers and no body
+fn test_build_response_head_has_no_body() {
+    let base_path = std::env::temp_dir().join("wikigen_preview_head_test");
+    let site_folder = base_path.join("test").join("dist").join("site");
+    fs::create_dir_all(&site_folder).unwrap();
+    fs::write(site_folder.join("index.html"), "<h1>Home</h1>").unwrap();
+
+    let get_response = String::from_utf8(build_response(&base_path, "GET", "/test/")).unwrap();
+    let head_response = String::from_utf8(build_response(&base_path, "HEAD", "/test/")).unwrap();
+    assert!(get_response.ends_with("\r\n\r\n<h1>Home</h1>"));
+    assert!(head_response.contains("Content-Length: 13\r\n"));
+    assert_eq!(head_response, get_response.replace("<h1>Home</h1>", ""));
+
+    let head_not_found =
+        String::from_utf8(build_response(&base_path, "HEAD", "/test/missing/")).unwrap();
+    assert!(head_not_found.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    assert!(head_not_found.ends_with("\r\n\r\n"));
+
+    fs::remove_dir_all(&base_path).unwrap();
+}
@@ -0,0 +1,137 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{json, Value};
+
+use crate::{
+    data_transfer::{
+        csv::parse_csv,
+        tabular::{diff_table_rows, read_table_file, table_rows_to_csv},
+    },
+    structs::table_models::{table_spec, RowChangeKind, TableFormat, TableRow},
+};
+
+fn table_row(value: Value) -> TableRow {
+    value.as_object().unwrap().clone()
+}
+
+#[test]
+fn test_parse_csv_quoted_fields() {
+    let records =
+        parse_csv("name,effect\n\"Leftovers\",\"Heals, slowly\nevery \"\"turn\"\"\"\nPotion,\n")
+            .unwrap();
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[1].fields[1], "Heals, slowly\nevery \"turn\"");
+    assert_eq!(records[2].line, 4);
+    assert_eq!(records[2].fields, vec!["Potion", ""]);
+}
+
+#[test]
+fn test_table_csv_round_trip() {
+    let spec = table_spec("items").unwrap();
+    let rows = vec![table_row(
+        json!({"id": 1, "name": "potion", "effect": "Heals 20, HP", "is_modified": 0, "is_new": 0}),
+    )];
+
+    let content = table_rows_to_csv(spec, &rows);
+    let imported_table = read_table_file(spec, TableFormat::Csv, &content);
+
+    assert!(imported_table.errors.is_empty());
+    assert_eq!(imported_table.rows[0].values, rows[0]);
+}
+
+#[test]
+fn test_table_import_reports_line_errors() {
+    let spec = table_spec("moves").unwrap();
+    let content = "id,name,power,accuracy,pp,type,damage_class,machine_name,is_modified,is_new\n\
+        1,tackle,40,100,35,normal,physical,,0,0\n\
+        2,,strong,100,10,fire,special,,0,0\n\
+        3,ember,40,100,25,fire\n";
+
+    let imported_table = read_table_file(spec, TableFormat::Csv, content);
+
+    assert_eq!(imported_table.total_rows, 3);
+    assert_eq!(imported_table.rows.len(), 1);
+    assert_eq!(imported_table.rows[0].values["machine_name"], Value::Null);
+
+    let error_locations: Vec<(usize, Option<&str>)> = imported_table
+        .errors
+        .iter()
+        .map(|error| (error.line, error.column.as_deref()))
+        .collect();
+    assert_eq!(
+        error_locations,
+        vec![(3, Some("name")), (3, Some("power")), (4, None)]
+    );
+}
+
+#[test]
+fn test_table_import_rejects_unknown_columns() {
+    let spec = table_spec("natures").unwrap();
+    let imported_table =
+        read_table_file(spec, TableFormat::Json, r#"[{"name": "bold", "mood": 1}]"#);
+
+    assert!(imported_table.rows.is_empty());
+    assert_eq!(imported_table.errors[0].column.as_deref(), Some("mood"));
+}
+
+#[test]
+fn test_table_import_preview_diff() {
+    let spec = table_spec("abilities").unwrap();
+    let existing_rows = vec![
+        table_row(
+            json!({"id": 1, "name": "overgrow", "effect": "Boosts grass", "is_modified": 0, "is_new": 0}),
+        ),
+        table_row(
+            json!({"id": 2, "name": "blaze", "effect": "Boosts fire", "is_modified": 0, "is_new": 0}),
+        ),
+        table_row(
+            json!({"id": 3, "name": "torrent", "effect": "Boosts water", "is_modified": 0, "is_new": 0}),
+        ),
+    ];
+    let content = r#"[
+        {"id": 1, "name": "overgrow", "effect": "Boosts grass", "is_modified": 0, "is_new": 0},
+        {"id": 2, "name": "blaze", "effect": "Boosts fire moves", "is_modified": "1", "is_new": 0},
+        {"name": "levitate", "effect": "Immune to ground", "is_modified": 0, "is_new": 1},
+        {"id": 2, "name": "blaze", "effect": "Duplicate", "is_modified": 0, "is_new": 0}
+    ]"#;
+
+    let imported_table = read_table_file(spec, TableFormat::Json, content);
+    let preview = diff_table_rows(spec, imported_table, &existing_rows, &HashMap::new());
+
+    assert_eq!(preview.unchanged, 1);
+    assert_eq!(preview.missing, 1);
+    assert_eq!(preview.errors.len(), 1);
+    // JSON rows are reported by the line they start on, not their position
+    assert_eq!(preview.errors[0].line, 5);
+    assert_eq!(preview.errors[0].message, "Duplicate of the row on line 3");
+
+    assert_eq!(preview.changes.len(), 2);
+    assert_eq!(preview.changes[0].kind, RowChangeKind::Modified);
+    let changed_columns: Vec<&str> = preview.changes[0]
+        .fields
+        .iter()
+        .map(|field| field.column.as_str())
+        .collect();
+    assert_eq!(changed_columns, vec!["effect", "is_modified"]);
+    assert_eq!(preview.changes[1].kind, RowChangeKind::Added);
+    assert!(preview.changes[1].key.is_empty());
+}
+
+#[test]
+fn test_table_import_checks_references() {
+    let spec = table_spec("pokemon_movesets").unwrap();
+    let content = "pokemon,move,learn_method,level_learned\n1,33,level-up,1\n1,999,machine,\n";
+    let referenced_ids = HashMap::from([
+        ("pokemon", HashSet::from([1])),
+        ("move", HashSet::from([33])),
+    ]);
+
+    let imported_table = read_table_file(spec, TableFormat::Csv, content);
+    let preview = diff_table_rows(spec, imported_table, &[], &referenced_ids);
+
+    assert_eq!(preview.changes.len(), 1);
+    assert_eq!(preview.errors.len(), 1);
+    assert_eq!(preview.errors[0].line, 3);
+    assert_eq!(preview.errors[0].column.as_deref(), Some("move"));
+}
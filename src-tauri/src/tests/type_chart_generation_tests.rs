@@ -0,0 +1,266 @@
+use std::{
+    fs::{self, read_to_string, File},
+    path::PathBuf,
+};
+
+use crate::{
+    database::{
+        get_mkdocs_config, get_sqlite_connection, get_types, page_exists_in_mkdocs,
+        repository::get_pokemon_types, session::WikiSession,
+    },
+    helpers::{
+        copy_recursively,
+        matchups::{
+            get_calculated_defenses, get_matchup_map, get_type_chart, matchup_key,
+            preset_matchup_map,
+        },
+        types::{
+            apply_type_chart_preset, changed_pokemon_ids, current_defenses, get_type_combinations,
+            save_types_and_matchups, FileChanges,
+        },
+    },
+    page_generators::type_chart_page::generate_type_chart_page,
+    structs::matchup_models::TypeChartPreset,
+};
+
+#[tokio::test]
+// Type Chart Page is created, highlights changed matchups and is present in the mkdocs.yml file
+async fn test_generate_type_chart_page_created() {
+    let base_path =
+        std::path::PathBuf::from("/Users/akeemallen/Library/Application Support/com.wikigen.dev");
+
+    let types = TypeChartPreset::GenSixOnwards
+        .types()
+        .iter()
+        .map(|_type| _type.to_string())
+        .collect::<Vec<String>>();
+    let standard_matchup_map = preset_matchup_map(&TypeChartPreset::GenSixOnwards);
+    let mut matchup_map = standard_matchup_map.clone();
+    matchup_map.insert(matchup_key("normal", "ghost"), 1.0);
+
+    let session = WikiSession::open("testing", &base_path).await.unwrap();
+    let result = generate_type_chart_page(&session, &types, &matchup_map, &standard_matchup_map);
+
+    assert!(result.unwrap() == "Type Chart Page Generated".to_string());
+    let generated_path = base_path
+        .join("testing")
+        .join("dist")
+        .join("docs")
+        .join("type_chart.md");
+    assert!(generated_path.exists());
+
+    let generated_file = match read_to_string(&generated_path) {
+        Ok(file) => file,
+        Err(err) => panic!("Failed to read generated file: {}", err),
+    };
+
+    assert!(generated_file.contains("title=\"Standard: 0x\""));
+
+    let mkdocs_yaml_file_path = base_path.join("testing").join("dist").join("mkdocs.yml");
+    let mut mkdocs_config = match get_mkdocs_config(&mkdocs_yaml_file_path) {
+        Ok(config) => config,
+        Err(err) => {
+            panic!("Failed to get mkdocs config: {}", err);
+        }
+    };
+
+    let (page_entry_exists, page_index) =
+        page_exists_in_mkdocs(mkdocs_config.clone(), "Type Chart").unwrap();
+    assert!(page_entry_exists);
+
+    // Clean up
+    mkdocs_config
+        .nav
+        .as_sequence_mut()
+        .unwrap()
+        .remove(page_index);
+    std::fs::remove_file(generated_path).unwrap();
+
+    match std::fs::write(
+        &mkdocs_yaml_file_path,
+        serde_yaml::to_string(&mut mkdocs_config).unwrap(),
+    ) {
+        Ok(_) => {}
+        Err(err) => panic!("Failed to update mkdocs yaml: {}", err),
+    };
+}
+
+#[tokio::test]
+// A new wiki starts from the modern starting data, types its pokemon and moves
+// use are kept whichever preset it picks
+async fn test_create_wiki_with_each_type_chart_preset() {
+    let data_dir = std::env::temp_dir().join("wikigen_type_chart_preset_test");
+    let starting_data_folder = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join("generator_assets")
+        .join("starting_data");
+
+    for preset in [
+        TypeChartPreset::GenOne,
+        TypeChartPreset::GenTwoToFive,
+        TypeChartPreset::GenSixOnwards,
+    ] {
+        let wiki_name = "preset_wiki";
+        let wiki_path = data_dir.join(wiki_name);
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&wiki_path).unwrap();
+        copy_recursively(&starting_data_folder, wiki_path.join("data")).unwrap();
+
+        let sqlite_path = wiki_path.join(format!("{}.db", wiki_name));
+        File::create(&sqlite_path).unwrap();
+        let conn = get_sqlite_connection(sqlite_path).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE pokemon (id INTEGER PRIMARY KEY, name TEXT, types TEXT);
+            CREATE TABLE moves (id INTEGER PRIMARY KEY, name TEXT, type TEXT);
+            INSERT INTO pokemon (name, types) VALUES
+                ('bulbasaur', 'grass,poison'), ('magnemite', 'electric,steel'),
+                ('clefairy', 'fairy'), ('abra', 'psychic');
+            INSERT INTO moves (name, type) VALUES ('tackle', 'normal'), ('ember', 'fire');",
+        )
+        .execute(&conn)
+        .await
+        .unwrap();
+
+        let calculated_defenses = apply_type_chart_preset(wiki_name, &data_dir, &preset, &conn)
+            .await
+            .unwrap();
+        conn.close().await;
+
+        for key in ["grass-poison", "electric-steel", "fairy"] {
+            assert!(
+                calculated_defenses.contains_key(key),
+                "{:?}: {}",
+                preset,
+                key
+            );
+        }
+        assert_eq!(
+            get_calculated_defenses(wiki_name, &data_dir).unwrap(),
+            calculated_defenses
+        );
+        assert_eq!(get_type_chart(wiki_name, &data_dir).unwrap().preset, preset);
+
+        let types = get_types(&wiki_path.join("data").join("types.json"))
+            .unwrap()
+            .types;
+        for used_type in ["steel", "fairy"] {
+            assert!(types.contains(&used_type.to_string()), "{:?}", preset);
+        }
+        // Nothing uses dark, so it follows the preset
+        assert_eq!(
+            types.contains(&"dark".to_string()),
+            preset != TypeChartPreset::GenOne
+        );
+
+        let ghost_vs_psychic = if preset == TypeChartPreset::GenOne {
+            "0"
+        } else {
+            "2"
+        };
+        assert!(calculated_defenses["psychic"].0[ghost_vs_psychic].contains(&"Ghost".to_string()));
+    }
+
+    let _ = fs::remove_dir_all(&data_dir);
+}
+
+#[tokio::test]
+// Editing a matchup rewrites calculated_defenses.json and only flags the
+// pokemon whose defenses it changes
+async fn test_matchup_edit_recalculates_defenses() {
+    let data_dir = std::env::temp_dir().join("wikigen_matchup_edit_test");
+    let starting_data_folder = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join("generator_assets")
+        .join("starting_data");
+    let wiki_name = "matchup_wiki";
+    let wiki_path = data_dir.join(wiki_name);
+    let _ = fs::remove_dir_all(&data_dir);
+    fs::create_dir_all(&wiki_path).unwrap();
+    copy_recursively(&starting_data_folder, wiki_path.join("data")).unwrap();
+
+    let sqlite_path = wiki_path.join(format!("{}.db", wiki_name));
+    File::create(&sqlite_path).unwrap();
+    let conn = get_sqlite_connection(sqlite_path).await.unwrap();
+    sqlx::query(
+        "CREATE TABLE pokemon (id INTEGER PRIMARY KEY, name TEXT, types TEXT);
+        CREATE TABLE moves (id INTEGER PRIMARY KEY, name TEXT, type TEXT);
+        INSERT INTO pokemon (id, name, types) VALUES
+            (1, 'bulbasaur', 'grass,poison'), (81, 'magnemite', 'electric,steel'),
+            (35, 'clefairy', 'fairy');",
+    )
+    .execute(&conn)
+    .await
+    .unwrap();
+    apply_type_chart_preset(wiki_name, &data_dir, &TypeChartPreset::GenSixOnwards, &conn)
+        .await
+        .unwrap();
+
+    let type_combinations = get_type_combinations(&conn).await.unwrap();
+    let previous_defenses = current_defenses(wiki_name, &data_dir, &type_combinations);
+    let wiki_types = get_types(&wiki_path.join("data").join("types.json")).unwrap();
+    let mut matchup_map = get_matchup_map(wiki_name, &data_dir).unwrap();
+    matchup_map.insert(matchup_key("fire", "grass"), 1.0);
+
+    let calculated_defenses = save_types_and_matchups(
+        wiki_name,
+        &data_dir,
+        &wiki_types,
+        &matchup_map,
+        &type_combinations,
+    )
+    .unwrap();
+    assert_eq!(
+        get_calculated_defenses(wiki_name, &data_dir).unwrap(),
+        calculated_defenses
+    );
+    assert!(calculated_defenses["grass-poison"].0["1"].contains(&"Fire".to_string()));
+    assert!(previous_defenses["grass-poison"].0["2"].contains(&"Fire".to_string()));
+
+    let pokemon_types = get_pokemon_types(&conn).await.unwrap();
+    assert_eq!(
+        changed_pokemon_ids(&pokemon_types, &previous_defenses, &calculated_defenses),
+        vec![1]
+    );
+    // The file written by the edit matches what the pages are generated from
+    assert_eq!(
+        current_defenses(wiki_name, &data_dir, &type_combinations),
+        calculated_defenses
+    );
+
+    conn.close().await;
+    let _ = fs::remove_dir_all(&data_dir);
+}
+
+#[test]
+// A rename that fails partway puts back every file it had already changed
+fn test_file_changes_undo() {
+    let base_path = std::env::temp_dir().join("wikigen_file_changes_test");
+    let wiki_name = "undo_wiki";
+    let data_path = base_path.join(wiki_name).join("data");
+    let _ = fs::remove_dir_all(&base_path);
+    fs::create_dir_all(&data_path).unwrap();
+    fs::write(data_path.join("types.json"), "original").unwrap();
+    fs::write(data_path.join("fire.png"), "image").unwrap();
+
+    let mut file_changes = FileChanges::default();
+    file_changes.snapshot(data_path.join("types.json")).unwrap();
+    file_changes
+        .snapshot(data_path.join("calculated_defenses.json"))
+        .unwrap();
+    file_changes
+        .rename(data_path.join("fire.png"), data_path.join("flame.png"))
+        .unwrap();
+    fs::write(data_path.join("types.json"), "renamed").unwrap();
+    fs::write(data_path.join("calculated_defenses.json"), "{}").unwrap();
+
+    file_changes.undo(wiki_name, &base_path);
+    assert_eq!(
+        read_to_string(data_path.join("types.json")).unwrap(),
+        "original"
+    );
+    assert!(!data_path.join("calculated_defenses.json").exists());
+    assert!(data_path.join("fire.png").exists());
+    assert!(!data_path.join("flame.png").exists());
+
+    let _ = fs::remove_dir_all(&base_path);
+}
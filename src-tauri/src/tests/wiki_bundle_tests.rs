@@ -0,0 +1,130 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+};
+
+use serde_json::json;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::wiki_preparation::wiki_bundle::{
+    add_wiki_entry, check_wiki_name, extract_wiki_bundle, read_wiki_bundle_manifest,
+    write_wiki_bundle, WikiBundleManifest, BUNDLE_FORMAT_VERSION,
+};
+
+#[test]
+fn test_wiki_bundle_round_trip() {
+    let base_path = std::env::temp_dir().join("wikigen_bundle_test");
+    let _ = fs::remove_dir_all(&base_path);
+
+    let wiki_path = base_path.join("test-wiki");
+    fs::create_dir_all(wiki_path.join("data")).unwrap();
+    fs::create_dir_all(wiki_path.join("dist/docs/pokemon")).unwrap();
+    fs::create_dir_all(wiki_path.join("dist/.git")).unwrap();
+    fs::write(wiki_path.join("test-wiki.db"), "database").unwrap();
+    fs::write(wiki_path.join("data/routes.json"), "{}").unwrap();
+    fs::write(wiki_path.join("dist/mkdocs.yml"), "site_name: test").unwrap();
+    fs::write(wiki_path.join("dist/docs/pokemon/001.md"), "# Bulbasaur").unwrap();
+    fs::write(wiki_path.join("dist/.git/HEAD"), "ref: refs/heads/main").unwrap();
+
+    let mut manifest = WikiBundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        app_version: "1.0.0".to_string(),
+        exported_at: "2024-01-01T00:00:00+00:00".to_string(),
+        wiki_name: "test-wiki".to_string(),
+        wiki: json!({"name": "test-wiki", "site_name": "Test Wiki"}),
+        migrations: vec!["initial".to_string()],
+        files: Vec::new(),
+    };
+    let export_path = base_path.join("exports/test-wiki.wikigen");
+    write_wiki_bundle(
+        &wiki_path,
+        &wiki_path.join("test-wiki.db"),
+        &mut manifest,
+        &export_path,
+    )
+    .unwrap();
+
+    assert!(manifest
+        .files
+        .contains(&"dist/docs/pokemon/001.md".to_string()));
+    assert!(!manifest
+        .files
+        .iter()
+        .any(|file| file.starts_with("dist/.git")));
+
+    let read_manifest = read_wiki_bundle_manifest(&export_path).unwrap();
+    assert_eq!(read_manifest.wiki_name, "test-wiki");
+    assert_eq!(read_manifest.files, manifest.files);
+
+    extract_wiki_bundle(&export_path, "copied-wiki", &base_path, false).unwrap();
+    let copied_path = base_path.join("copied-wiki");
+    assert!(copied_path.join("copied-wiki.db").exists());
+    assert!(copied_path.join("dist/docs/pokemon/001.md").exists());
+    assert!(!copied_path.join("dist/.git").exists());
+
+    // Extracting over an existing wiki is refused
+    assert!(extract_wiki_bundle(&export_path, "copied-wiki", &base_path, false).is_err());
+
+    let wiki = add_wiki_entry(&base_path, "copied-wiki", &read_manifest.wiki).unwrap();
+    assert_eq!(wiki["name"], "copied-wiki");
+    assert_eq!(wiki["site_name"], "Test Wiki");
+
+    // Overwriting swaps the extracted copy in for the existing wiki
+    fs::write(copied_path.join("dist/docs/pokemon/001.md"), "# Edited").unwrap();
+    fs::write(copied_path.join("data/notes.txt"), "not in the bundle").unwrap();
+    extract_wiki_bundle(&export_path, "copied-wiki", &base_path, true).unwrap();
+    assert_eq!(
+        fs::read_to_string(copied_path.join("dist/docs/pokemon/001.md")).unwrap(),
+        "# Bulbasaur"
+    );
+    assert!(!copied_path.join("data/notes.txt").exists());
+    assert!(!base_path.join(".copied-wiki.import").exists());
+    assert!(!base_path.join(".copied-wiki.replaced").exists());
+
+    // A bundle that fails partway through leaves the existing wiki alone
+    let broken_path = base_path.join("exports/broken.wikigen");
+    let mut writer = ZipWriter::new(File::create(&broken_path).unwrap());
+    writer
+        .start_file("manifest.json", SimpleFileOptions::default())
+        .unwrap();
+    writer
+        .write_all(serde_json::to_string(&manifest).unwrap().as_bytes())
+        .unwrap();
+    writer
+        .start_file("../escaped.txt", SimpleFileOptions::default())
+        .unwrap();
+    writer.write_all(b"outside").unwrap();
+    writer.finish().unwrap();
+
+    assert!(extract_wiki_bundle(&broken_path, "copied-wiki", &base_path, true).is_err());
+    assert!(copied_path.join("copied-wiki.db").exists());
+    assert!(copied_path.join("dist/docs/pokemon/001.md").exists());
+    assert!(!base_path.join(".copied-wiki.import").exists());
+
+    let _ = fs::remove_dir_all(&base_path);
+}
+
+#[test]
+fn test_check_wiki_name() {
+    assert!(check_wiki_name("emerald-plus").is_ok());
+    assert!(check_wiki_name("Emerald Plus 2").is_ok());
+
+    for wiki_name in [
+        "",
+        "  ",
+        ".",
+        "..",
+        "../other",
+        "wikis/emerald",
+        "wikis\\emerald",
+        "/emerald",
+        "C:emerald",
+        "emerald..plus",
+    ] {
+        assert!(
+            check_wiki_name(wiki_name).is_err(),
+            "{:?} should be rejected",
+            wiki_name
+        );
+    }
+}
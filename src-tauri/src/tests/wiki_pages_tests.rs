@@ -0,0 +1,200 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::{
+    structs::mkdocs_structs::{nav_entry_title, MKDocsConfig},
+    wiki_preparation::{
+        wiki_pages::{
+            add_custom_page_entry, custom_page_entries, merge_nav_order, remove_custom_page_entry,
+            reorder_nav, update_custom_page_entry, DEFAULT_NAV_ORDER,
+        },
+        yaml_declaration::get_yaml,
+    },
+};
+
+fn page(title: &str, file: &str) -> Value {
+    let mut page = Mapping::new();
+    page.insert(
+        Value::String(title.to_string()),
+        Value::String(file.to_string()),
+    );
+    Value::Mapping(page)
+}
+
+fn top_level_titles(mkdocs_config: &mut MKDocsConfig) -> Vec<String> {
+    mkdocs_config
+        .nav_entries_mut()
+        .unwrap()
+        .iter()
+        .filter_map(nav_entry_title)
+        .map(str::to_string)
+        .collect()
+}
+
+fn titles(titles: &[&str]) -> Vec<String> {
+    titles.iter().map(|title| title.to_string()).collect()
+}
+
+#[test]
+fn test_generated_pages_follow_the_saved_nav_order() {
+    let mut mkdocs_config = get_yaml("test", "", "", "", "");
+    // Saved while the wiki had no change pages, which slot in after Home
+    let nav_order = merge_nav_order(&titles(&["Home", "Routes", "FAQ", "Pokemon"]));
+    assert_eq!(
+        nav_order,
+        titles(&[
+            "Home",
+            "Changelog",
+            "Type Chart",
+            "Move Changes",
+            "TMs & HMs",
+            "Ability Changes",
+            "Item Changes",
+            "Item Locations",
+            "Nature Changes",
+            "Routes",
+            "FAQ",
+            "Pokemon",
+        ])
+    );
+
+    let nav_order = titles(&[
+        "Home",
+        "Routes",
+        "Pokemon",
+        "Nature Changes",
+        "Move Changes",
+    ]);
+    mkdocs_config
+        .insert_nav_entry(page("Nature Changes", "nature_changes.md"), &nav_order)
+        .unwrap();
+    mkdocs_config
+        .insert_nav_entry(page("Move Changes", "move_changes.md"), &nav_order)
+        .unwrap();
+    mkdocs_config
+        .insert_nav_entry(page("Downloads", "pages/downloads.md"), &nav_order)
+        .unwrap();
+    assert_eq!(
+        top_level_titles(&mut mkdocs_config),
+        titles(&[
+            "Home",
+            "Pokemon",
+            "Routes",
+            "Nature Changes",
+            "Move Changes",
+            "Downloads"
+        ])
+    );
+
+    // Without a saved order they land after Home like they used to
+    let mut mkdocs_config = get_yaml("test", "", "", "", "");
+    let default_order: Vec<String> = DEFAULT_NAV_ORDER.map(str::to_string).to_vec();
+    mkdocs_config
+        .insert_nav_entry(page("Type Chart", "type_chart.md"), &default_order)
+        .unwrap();
+    assert_eq!(
+        top_level_titles(&mut mkdocs_config),
+        titles(&["Home", "Type Chart", "Pokemon", "Routes"])
+    );
+}
+
+#[test]
+fn test_custom_pages_nest_rename_move_and_reorder() {
+    let mut mkdocs_config = get_yaml("test", "", "", "", "");
+    let nav_order: Vec<String> = DEFAULT_NAV_ORDER.map(str::to_string).to_vec();
+
+    add_custom_page_entry(&mut mkdocs_config, "FAQ", "pages/faq.md", None, &nav_order).unwrap();
+    add_custom_page_entry(
+        &mut mkdocs_config,
+        "Physical Special Split",
+        "pages/physical-special-split.md",
+        Some("Mechanics"),
+        &nav_order,
+    )
+    .unwrap();
+    add_custom_page_entry(
+        &mut mkdocs_config,
+        "Weather",
+        "pages/weather.md",
+        Some("Mechanics"),
+        &nav_order,
+    )
+    .unwrap();
+
+    assert!(add_custom_page_entry(
+        &mut mkdocs_config,
+        "FAQ",
+        "pages/faq-2.md",
+        None,
+        &nav_order
+    )
+    .is_err());
+    assert!(add_custom_page_entry(
+        &mut mkdocs_config,
+        "Starters",
+        "pages/starters.md",
+        Some("Pokemon"),
+        &nav_order,
+    )
+    .is_err());
+
+    update_custom_page_entry(
+        &mut mkdocs_config,
+        "pages/faq.md",
+        "Questions",
+        None,
+        &nav_order,
+    )
+    .unwrap();
+    reorder_nav(
+        &mut mkdocs_config,
+        Some("Mechanics"),
+        &titles(&["Weather", "Physical Special Split"]),
+    )
+    .unwrap();
+    assert!(reorder_nav(&mut mkdocs_config, None, &titles(&["Home", "Pokemon"])).is_err());
+    reorder_nav(
+        &mut mkdocs_config,
+        None,
+        &titles(&["Home", "Mechanics", "Questions", "Pokemon", "Routes"]),
+    )
+    .unwrap();
+
+    let pages: Vec<(String, String, Option<String>)> = custom_page_entries(&mkdocs_config)
+        .into_iter()
+        .map(|page| (page.title, page.file, page.section))
+        .collect();
+    assert_eq!(
+        pages,
+        vec![
+            (
+                "Weather".to_string(),
+                "pages/weather.md".to_string(),
+                Some("Mechanics".to_string())
+            ),
+            (
+                "Physical Special Split".to_string(),
+                "pages/physical-special-split.md".to_string(),
+                Some("Mechanics".to_string())
+            ),
+            ("Questions".to_string(), "pages/faq.md".to_string(), None),
+        ]
+    );
+
+    // Moving the last pages out of a section removes it
+    update_custom_page_entry(
+        &mut mkdocs_config,
+        "pages/weather.md",
+        "Weather",
+        None,
+        &nav_order,
+    )
+    .unwrap();
+    assert!(
+        remove_custom_page_entry(&mut mkdocs_config, "pages/physical-special-split.md").unwrap()
+    );
+    assert!(!remove_custom_page_entry(&mut mkdocs_config, "index.md").unwrap());
+    assert_eq!(
+        top_level_titles(&mut mkdocs_config),
+        titles(&["Home", "Questions", "Pokemon", "Routes", "Weather"])
+    );
+}
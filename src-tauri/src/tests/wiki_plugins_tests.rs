@@ -0,0 +1,101 @@
+use crate::{
+    structs::mkdocs_structs::Plugin,
+    wiki_preparation::{
+        wiki_plugins::{
+            add_page_redirect, apply_plugin_settings, page_redirects, plugin_packages,
+            plugin_settings, PageRedirect,
+        },
+        yaml_declaration::get_yaml,
+    },
+};
+
+#[test]
+fn test_apply_plugin_settings_keeps_unknown_plugins_and_options() {
+    let mut mkdocs_config = get_yaml("test", "", "", "", "");
+    mkdocs_config
+        .plugins
+        .push(Plugin::with_option("minify", "minify_html", true));
+    mkdocs_config
+        .plugins
+        .push(Plugin::with_option("tags", "tags_file", "tags.md"));
+
+    let mut settings = plugin_settings(&mkdocs_config);
+    assert_eq!(settings.search_languages, vec!["en"]);
+    assert!(settings.tags);
+
+    settings.search_languages = vec!["en".to_string(), " DE ".to_string()];
+    settings.tags = true;
+    settings.glightbox = true;
+    settings.redirects = true;
+    settings.redirect_maps = vec![PageRedirect {
+        from: "old_page.md".to_string(),
+        to: "new_page.md".to_string(),
+    }];
+    apply_plugin_settings(&mut mkdocs_config, &settings).unwrap();
+
+    let plugin_names: Vec<&str> = mkdocs_config
+        .plugins
+        .iter()
+        .filter_map(|plugin| plugin.name())
+        .collect();
+    assert_eq!(
+        plugin_names,
+        vec!["search", "minify", "tags", "glightbox", "redirects"]
+    );
+    assert_eq!(
+        mkdocs_config.plugins[2],
+        Plugin::with_option("tags", "tags_file", "tags.md")
+    );
+    assert_eq!(
+        plugin_settings(&mkdocs_config).search_languages,
+        vec!["en", "de"]
+    );
+    assert_eq!(
+        plugin_packages(&mkdocs_config),
+        vec!["mkdocs-glightbox", "mkdocs-redirects"]
+    );
+
+    settings.glightbox = false;
+    settings.search_languages = vec!["en; rm".to_string()];
+    assert!(apply_plugin_settings(&mut mkdocs_config, &settings).is_err());
+}
+
+#[test]
+fn test_add_page_redirect_follows_moved_pages() {
+    let mut mkdocs_config = get_yaml("test", "", "", "", "");
+    // Nothing is recorded until the redirects plugin is enabled
+    assert!(!add_page_redirect(
+        &mut mkdocs_config,
+        "pokemon/004-charmander.md",
+        "pokemon/005-charmander.md"
+    ));
+
+    let mut settings = plugin_settings(&mkdocs_config);
+    settings.redirects = true;
+    apply_plugin_settings(&mut mkdocs_config, &settings).unwrap();
+
+    assert!(add_page_redirect(
+        &mut mkdocs_config,
+        "pokemon/004-charmander.md",
+        "pokemon/005-charmander.md"
+    ));
+    assert!(add_page_redirect(
+        &mut mkdocs_config,
+        "pokemon/005-charmander.md",
+        "pokemon/006-charmander.md"
+    ));
+
+    assert_eq!(
+        page_redirects(&mkdocs_config),
+        vec![
+            PageRedirect {
+                from: "pokemon/004-charmander.md".to_string(),
+                to: "pokemon/006-charmander.md".to_string(),
+            },
+            PageRedirect {
+                from: "pokemon/005-charmander.md".to_string(),
+                to: "pokemon/006-charmander.md".to_string(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,95 @@
+use crate::{
+    structs::mkdocs_structs::ThemeFont,
+    wiki_preparation::{
+        wiki_theme::{
+            apply_theme_settings, css_snippet_contents, read_css_snippet, theme_settings,
+            CssSnippet,
+        },
+        yaml_declaration::get_yaml,
+    },
+};
+
+#[test]
+fn test_apply_theme_settings_updates_palettes_fonts_and_snippets() {
+    let mut mkdocs_config = get_yaml("test", "", "", "", "");
+    mkdocs_config
+        .extra_css
+        .push("stylesheets/snippets/old.css".to_string());
+
+    let mut settings = theme_settings(&mkdocs_config);
+    settings.primary = "teal".to_string();
+    settings.accent = "amber".to_string();
+    settings.dark_scheme = true;
+    settings.text_font = "Lato".to_string();
+    settings.features = vec![
+        "navigation.tabs".to_string(),
+        " navigation.tabs ".to_string(),
+    ];
+    settings.css_snippets = vec![CssSnippet {
+        name: "Type Badges".to_string(),
+        css: ".type { border-radius: 4px; }".to_string(),
+    }];
+
+    apply_theme_settings(&mut mkdocs_config, &settings).unwrap();
+
+    let theme = &mkdocs_config.theme;
+    let schemes: Vec<(&str, &str, &str)> = theme
+        .palette
+        .palettes()
+        .iter()
+        .map(|palette| {
+            (
+                palette.primary.as_str(),
+                palette.accent.as_str(),
+                palette.scheme.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        schemes,
+        vec![("teal", "amber", "default"), ("teal", "amber", "slate")]
+    );
+    assert_eq!(
+        theme.font,
+        Some(ThemeFont {
+            text: "Lato".to_string(),
+            code: String::new(),
+        })
+    );
+    assert_eq!(theme.features, vec!["navigation.tabs"]);
+    assert_eq!(
+        mkdocs_config.extra_css,
+        vec![
+            "stylesheets/extra.css",
+            "stylesheets/snippets/type-badges.css"
+        ]
+    );
+
+    settings.primary = "not-a-color".to_string();
+    assert!(apply_theme_settings(&mut mkdocs_config, &settings).is_err());
+}
+
+#[test]
+// Snippet names come back as typed rather than as their slugged file name
+fn test_css_snippet_keeps_its_name() {
+    let snippet = CssSnippet {
+        name: "Type Badges (Gen 9)".to_string(),
+        css: ".type {\n    border-radius: 4px;\n}\n".to_string(),
+    };
+    let snippet_contents = css_snippet_contents(&snippet);
+    assert!(snippet_contents.starts_with("/* snippet: Type Badges (Gen 9) */\n"));
+    assert_eq!(
+        read_css_snippet("type-badges-gen-9", &snippet_contents),
+        snippet
+    );
+
+    // Files edited by hand without the header keep all their CSS
+    let hand_written = "/* badges */\n.type { color: red; }";
+    assert_eq!(
+        read_css_snippet("type-badges", hand_written),
+        CssSnippet {
+            name: "type-badges".to_string(),
+            css: hand_written.to_string(),
+        }
+    );
+}
@@ -1,12 +1,21 @@
 extern crate chrono;
-use tauri::{AppHandle, Manager};
+use std::{io, path::PathBuf};
 
+use tauri::AppHandle;
+
+use crate::database::get_app_data_dir;
+use crate::errors::WikiGenResult;
 use crate::helpers::copy_recursively;
 use chrono::Local;
 
 #[tauri::command]
-pub async fn backup_wiki(wiki_name: &str, app_handle: AppHandle) -> Result<String, String> {
-    let data_dir = app_handle.path().app_data_dir().unwrap();
+pub async fn backup_wiki(wiki_name: &str, app_handle: AppHandle) -> WikiGenResult<String> {
+    let data_dir = get_app_data_dir(&app_handle)?;
+    let _ = create_wiki_backup(&data_dir, wiki_name);
+    Ok("Wiki Backed Up".to_string())
+}
+
+pub fn create_wiki_backup(data_dir: &PathBuf, wiki_name: &str) -> io::Result<PathBuf> {
     let base_path = data_dir.join(wiki_name);
 
     let date = Local::now();
@@ -16,6 +25,6 @@ pub async fn backup_wiki(wiki_name: &str, app_handle: AppHandle) -> Result<Strin
         wiki_name,
         date.format("%Y_%m_%d_%H_%M_%S").to_string()
     ));
-    let _ = copy_recursively(base_path, backup_dir);
-    Ok("Wiki Backed Up".to_string())
+    copy_recursively(base_path, &backup_dir)?;
+    Ok(backup_dir)
 }
@@ -1,22 +1,30 @@
 use std::{env, process::Command};
 
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
-use crate::logger::{self, LogLevel};
+use crate::{
+    database::{get_app_data_dir, session::get_wiki_session},
+    errors::{WikiGenError, WikiGenResult},
+    logger::{self, LogLevel},
+    page_generators::changelog_page::{publish_changelog, unpublish_changelog},
+};
 
 #[tauri::command]
 pub async fn commit_wiki_changes(
     wiki_name: &str,
     ssh_url: &str,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let data_dir = app_handle.path().app_data_dir().unwrap();
+) -> WikiGenResult<String> {
+    let data_dir = get_app_data_dir(&app_handle)?;
     let base_path = data_dir.join(wiki_name);
     let dist_directory = base_path.join("dist");
 
     // Check if git is initialized
     if let Err(err) = env::set_current_dir(&dist_directory) {
-        let error = format!("Error while initializing repo: {}", err);
+        let error = WikiGenError::io(
+            format!("Error while initializing repo: {}", err),
+            dist_directory.display(),
+        );
         logger::write_log(&base_path, LogLevel::Error, &error);
         return Err(error);
     }
@@ -27,40 +35,34 @@ pub async fn commit_wiki_changes(
             Err(err) => {
                 let error = format!("Error while initializing repo: {}", err);
                 logger::write_log(&base_path, LogLevel::Error, &error);
-                return Err(error);
+                return Err(error.into());
             }
         };
     }
-    // Run git add
-    match Command::new("git").arg("add").arg(".").output() {
-        Ok(_) => {}
+    // Edits recorded since the last commit go out with this one
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let published_entries = match publish_changelog(&session).await {
+        Ok(published_entries) => published_entries,
         Err(err) => {
-            let error = format!("Error while adding to repo: {}", err);
-            logger::write_log(&base_path, LogLevel::Error, &error);
-            return Err(error);
+            logger::write_log(&base_path, LogLevel::Error, &err);
+            return Err(err);
         }
     };
-    // Run git commit
-    match Command::new("git")
-        .arg("commit")
-        .arg("-m")
-        .arg("Wiki Updates")
-        .output()
-    {
-        Ok(_) => {}
-        Err(err) => {
-            let error = format!("Error while adding to repo: {}", err);
-            logger::write_log(&base_path, LogLevel::Error, &error);
-            return Err(error);
+    if let Err(error) = commit_dist_changes() {
+        // The edits weren't committed, so they stay unpublished
+        if let Err(err) = unpublish_changelog(&session, &published_entries).await {
+            logger::write_log(&base_path, LogLevel::Error, &err);
         }
-    };
+        logger::write_log(&base_path, LogLevel::Error, &error);
+        return Err(error.into());
+    }
 
     let output = match Command::new("git").arg("remote").arg("-v").output() {
         Ok(output) => output,
         Err(err) => {
             let error = format!("Unable to check remote: {}", err);
             logger::write_log(&base_path, LogLevel::Error, &error);
-            return Err(error);
+            return Err(error.into());
         }
     };
 
@@ -69,7 +71,7 @@ pub async fn commit_wiki_changes(
         Err(err) => {
             let error = format!("Error while reading remote: {}", err);
             logger::write_log(&base_path, LogLevel::Error, &error);
-            return Err(error);
+            return Err(error.into());
         }
     };
 
@@ -85,10 +87,49 @@ pub async fn commit_wiki_changes(
             Err(err) => {
                 let error = format!("Error while adding to repo: {}", err);
                 logger::write_log(&base_path, LogLevel::Error, &error);
-                return Err(error);
+                return Err(error.into());
             }
         };
     }
 
     Ok("Wiki Changes Commited and Ready for Deployment".to_string())
 }
+
+// Runs git add and git commit in the dist folder. A clean tree isn't an
+// error, the wiki just has nothing new to deploy.
+fn commit_dist_changes() -> Result<(), String> {
+    match Command::new("git").arg("add").arg(".").output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => {
+            return Err(format!(
+                "Error while adding to repo: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+        Err(err) => return Err(format!("Error while adding to repo: {}", err)),
+    };
+
+    match Command::new("git")
+        .arg("commit")
+        .arg("-m")
+        .arg("Wiki Updates")
+        .output()
+    {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if stdout.contains("nothing to commit") {
+                return Ok(());
+            }
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(format!(
+                "Error while committing to repo: {}",
+                match stderr.trim().is_empty() {
+                    true => stdout.trim(),
+                    false => stderr.trim(),
+                }
+            ))
+        }
+        Err(err) => Err(format!("Error while committing to repo: {}", err)),
+    }
+}
@@ -1,8 +1,15 @@
+use crate::database::{
+    get_app_data_dir, get_resource_dir, get_sqlite_connection, update_mkdocs_yaml,
+};
+use crate::errors::{WikiGenError, WikiGenResult};
+use crate::helpers::types::apply_type_chart_preset;
 use crate::helpers::{capitalize, copy_recursively};
+use crate::page_generators::home_page::{home_page_markdown, HomePage, HOME_PAGE_FILE};
+use crate::structs::matchup_models::TypeChartPreset;
 use crate::wiki_preparation::yaml_declaration;
 use serde::{Deserialize, Serialize};
 use std::fs::{self};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,23 +26,24 @@ pub async fn create_wiki(
     wiki_description: &str,
     wiki_author: &str,
     site_name: &str,
+    type_chart: Option<TypeChartPreset>,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let data_dir = app_handle.path().app_data_dir().unwrap();
+) -> WikiGenResult<String> {
+    let data_dir = get_app_data_dir(&app_handle)?;
     let base_path = data_dir.join(wiki_name);
     if base_path.exists() {
-        return Err(format!("{} already exists", wiki_name));
+        return Err(format!("{} already exists", wiki_name).into());
     }
 
-    let resource_dir = app_handle.path().resource_dir().unwrap();
+    let resource_dir = get_resource_dir(&app_handle)?;
     let resource_path = resource_dir.join("resources");
 
     match fs::create_dir_all(&base_path) {
         Ok(_) => {}
         Err(err) => {
-            return Err(format!(
-                "Failed to create {} wiki's base path: {:?}",
-                wiki_name, err
+            return Err(WikiGenError::io(
+                format!("Failed to create {} wiki's base path: {:?}", wiki_name, err),
+                wiki_name,
             ));
         }
     }
@@ -47,7 +55,10 @@ pub async fn create_wiki(
     match fs::create_dir_all(&docs_folder) {
         Ok(_) => {}
         Err(err) => {
-            return Err(format!("Failed to create dist directory path: {:?}", err));
+            return Err(WikiGenError::io(
+                format!("Failed to create dist directory path: {:?}", err),
+                wiki_name,
+            ));
         }
     }
 
@@ -55,9 +66,9 @@ pub async fn create_wiki(
     match fs::create_dir_all(&pokemon_data_folder) {
         Ok(_) => {}
         Err(err) => {
-            return Err(format!(
-                "Failed to create pokemon directory path: {:?}",
-                err
+            return Err(WikiGenError::io(
+                format!("Failed to create pokemon directory path: {:?}", err),
+                wiki_name,
             ));
         }
     }
@@ -66,7 +77,10 @@ pub async fn create_wiki(
     match fs::create_dir_all(&routes_folder) {
         Ok(_) => {}
         Err(err) => {
-            return Err(format!("Failed to create routes directory path: {:?}", err));
+            return Err(WikiGenError::io(
+                format!("Failed to create routes directory path: {:?}", err),
+                wiki_name,
+            ));
         }
     }
 
@@ -81,10 +95,21 @@ pub async fn create_wiki(
     match fs::copy(sqlite_db_path, base_path.join(format!("{}.db", wiki_name))) {
         Ok(_) => {}
         Err(err) => {
-            return Err(format!("Failed to copy initial database: {:?}", err));
+            return Err(WikiGenError::io(
+                format!("Failed to copy initial database: {:?}", err),
+                wiki_name,
+            ));
         }
     }
 
+    // The starting data is built from the modern type chart
+    if let Some(preset) = type_chart {
+        let conn = get_sqlite_connection(base_path.join(format!("{}.db", wiki_name))).await?;
+        let applied_preset = apply_type_chart_preset(wiki_name, &data_dir, &preset, &conn).await;
+        conn.close().await;
+        applied_preset?;
+    }
+
     let items_folder = generator_assets_path.join("items");
     let dist_items_folder = docs_folder.join("img").join("items");
     let _ = copy_recursively(items_folder, dist_items_folder);
@@ -105,18 +130,27 @@ pub async fn create_wiki(
     match fs::create_dir_all(&route_image_folder) {
         Ok(_) => {}
         Err(err) => {
-            return Err(format!(
-                "Failed to create route image directory path: {:?}",
-                err
+            return Err(WikiGenError::io(
+                format!("Failed to create route image directory path: {:?}", err),
+                wiki_name,
             ));
         }
     }
 
-    let index_file_path = docs_folder.join("index.md");
-    match fs::write(index_file_path, "# Index") {
+    // Filled in with the wiki's data the first time the home page is generated
+    let home_page = HomePage {
+        site_name: site_name.to_string(),
+        site_description: wiki_description.to_string(),
+        ..Default::default()
+    };
+    let index_file_path = docs_folder.join(HOME_PAGE_FILE);
+    match fs::write(index_file_path, home_page_markdown(&home_page, None)?) {
         Ok(_) => {}
         Err(err) => {
-            return Err(format!("Failed to create index/Homepage file: {:?}", err));
+            return Err(WikiGenError::io(
+                format!("Failed to create index/Homepage file: {:?}", err),
+                wiki_name,
+            ));
         }
     }
 
@@ -127,14 +161,18 @@ pub async fn create_wiki(
         title: wiki_name.to_string(),
     };
 
+    let wiki_config_json = match serde_json::to_string(&wiki_config) {
+        Ok(wiki_config_json) => wiki_config_json,
+        Err(err) => return Err(format!("Failed to serialize wiki config: {}", err).into()),
+    };
     let config_file_path = docs_folder.join("config.json");
-    match fs::write(
-        config_file_path,
-        serde_json::to_string(&wiki_config).unwrap(),
-    ) {
+    match fs::write(config_file_path, wiki_config_json) {
         Ok(_) => {}
         Err(err) => {
-            return Err(format!("Failed to create mkdocs config file: {:?}", err));
+            return Err(WikiGenError::io(
+                format!("Failed to create mkdocs config file: {:?}", err),
+                wiki_name,
+            ));
         }
     }
 
@@ -153,16 +191,7 @@ pub async fn create_wiki(
         &repo_url,
     );
 
-    let mkdocs_file_path = dist_folder.join("mkdocs.yml");
-    match fs::write(
-        mkdocs_file_path,
-        serde_yaml::to_string(&mkdocs_config).unwrap(),
-    ) {
-        Ok(_) => {}
-        Err(err) => {
-            return Err(format!("Failed to create mkdocs yaml file: {:?}", err));
-        }
-    }
+    update_mkdocs_yaml(wiki_name, &data_dir, &mkdocs_config)?;
 
     return Ok(format!(
         "{} Wiki created and initialized",
@@ -0,0 +1,253 @@
+// Checks that every name the wiki's data points at exists, so broken links
+// and empty sections are found before the pages are generated
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use tauri::AppHandle;
+
+use crate::{
+    database::{
+        get_routes,
+        repository::{
+            get_all_pokemon, get_db_abilities, get_item_locations, get_items, get_move_names,
+        },
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::WikiGenResult,
+    logger::{self, LogLevel},
+    page_generators::game_routes::Routes,
+    structs::{
+        lint_models::{LintCategory, LintReport, LintSeverity},
+        page_models::ItemLocation,
+        pokemon_structs::DBPokemon,
+    },
+};
+
+pub struct WikiLintData {
+    pub pokemon: Vec<DBPokemon>,
+    pub move_names: HashSet<String>,
+    pub ability_names: HashSet<String>,
+    pub item_names: HashSet<String>,
+    pub item_locations: Vec<ItemLocation>,
+    pub routes: Routes,
+    // Sprite names in docs/img/pokemon, None when the folder is missing
+    pub sprites: Option<HashSet<String>>,
+}
+
+#[tauri::command]
+pub async fn lint_wiki(wiki_name: &str, app_handle: AppHandle) -> WikiGenResult<LintReport> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match load_lint_data(&session).await {
+        Ok(lint_data) => Ok(lint_wiki_data(&lint_data)),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+async fn load_lint_data(session: &WikiSession) -> WikiGenResult<WikiLintData> {
+    let pokemon = get_all_pokemon(&session.pool).await?;
+    let move_names = get_move_names(&session.pool)
+        .await?
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect();
+    let ability_names = get_db_abilities(&session.pool)
+        .await?
+        .into_iter()
+        .map(|ability| ability.name)
+        .collect();
+    let item_names = get_items(&session.pool)
+        .await?
+        .into_iter()
+        .map(|item| item.name)
+        .collect();
+    let item_locations = get_item_locations(&session.pool).await?;
+    let routes = get_routes(&session.wiki_path.join("data").join("routes.json"))?;
+
+    let sprites = fs::read_dir(session.docs_path.join("img").join("pokemon"))
+        .ok()
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|extension| extension == "png"))
+                .filter_map(|path| {
+                    path.file_stem()
+                        .map(|file_stem| file_stem.to_string_lossy().to_string())
+                })
+                .collect()
+        });
+
+    Ok(WikiLintData {
+        pokemon,
+        move_names,
+        ability_names,
+        item_names,
+        item_locations,
+        routes,
+        sprites,
+    })
+}
+
+pub fn lint_wiki_data(lint_data: &WikiLintData) -> LintReport {
+    let mut report = LintReport::default();
+    let dex_numbers: HashMap<&str, usize> = lint_data
+        .pokemon
+        .iter()
+        .map(|pokemon| (pokemon.name.as_str(), pokemon.dex_number as usize))
+        .collect();
+
+    for pokemon in &lint_data.pokemon {
+        for ability in pokemon.abilities.split(',').map(str::trim) {
+            if !ability.is_empty() && !lint_data.ability_names.contains(ability) {
+                report.add(
+                    LintSeverity::Error,
+                    LintCategory::Abilities,
+                    &pokemon.name,
+                    format!("Ability {} is not in the abilities table", ability),
+                );
+            }
+        }
+
+        if pokemon.evolution_method != "no_change" {
+            let evolves_into = pokemon
+                .evolves_into
+                .as_deref()
+                .map(str::trim)
+                .unwrap_or_default();
+            if evolves_into.is_empty() {
+                report.add(
+                    LintSeverity::Warning,
+                    LintCategory::Evolutions,
+                    &pokemon.name,
+                    "Has an evolution method but no pokemon it evolves into",
+                );
+            }
+            // Branching evolutions are stored as a comma separated list
+            for evolution in evolves_into.split(',').map(str::trim) {
+                if !evolution.is_empty() && !dex_numbers.contains_key(evolution) {
+                    report.add(
+                        LintSeverity::Error,
+                        LintCategory::Evolutions,
+                        &pokemon.name,
+                        format!(
+                            "Evolves into {}, which is not a pokemon in the wiki",
+                            evolution
+                        ),
+                    );
+                }
+            }
+        }
+
+        if let Some(sprites) = &lint_data.sprites {
+            if !sprites.contains(&pokemon.name) {
+                report.add(
+                    LintSeverity::Warning,
+                    LintCategory::Sprites,
+                    &pokemon.name,
+                    format!("No sprite at img/pokemon/{}.png", pokemon.name),
+                );
+            }
+        }
+    }
+    if lint_data.sprites.is_none() {
+        report.add(
+            LintSeverity::Error,
+            LintCategory::Sprites,
+            "img/pokemon",
+            "The pokemon sprite folder is missing from docs",
+        );
+    }
+
+    for (route_name, route_properties) in &lint_data.routes.routes {
+        for (trainer_name, trainer_info) in &route_properties.trainers {
+            let subject = format!("{} / {}", route_name, trainer_name);
+            for trainer_pokemon in &trainer_info.pokemon_team {
+                if let Some(message) =
+                    pokemon_reference_issue(&dex_numbers, &trainer_pokemon.name, trainer_pokemon.id)
+                {
+                    report.add(
+                        LintSeverity::Error,
+                        LintCategory::TrainerPokemon,
+                        &subject,
+                        message,
+                    );
+                }
+                for move_name in &trainer_pokemon.moves {
+                    if !move_name.is_empty() && !lint_data.move_names.contains(move_name) {
+                        report.add(
+                            LintSeverity::Error,
+                            LintCategory::TrainerMoves,
+                            &subject,
+                            format!(
+                                "{} knows {}, which is not in the moves table",
+                                trainer_pokemon.name, move_name
+                            ),
+                        );
+                    }
+                }
+                if !trainer_pokemon.ability.is_empty()
+                    && !lint_data.ability_names.contains(&trainer_pokemon.ability)
+                {
+                    report.add(
+                        LintSeverity::Error,
+                        LintCategory::Abilities,
+                        &subject,
+                        format!(
+                            "{} has ability {}, which is not in the abilities table",
+                            trainer_pokemon.name, trainer_pokemon.ability
+                        ),
+                    );
+                }
+            }
+        }
+
+        for wild_encounter in &route_properties.wild_encounters {
+            if let Some(message) =
+                pokemon_reference_issue(&dex_numbers, &wild_encounter.name, wild_encounter.id)
+            {
+                report.add(
+                    LintSeverity::Error,
+                    LintCategory::WildEncounters,
+                    format!("{} / {}", route_name, wild_encounter.encounter_area),
+                    message,
+                );
+            }
+        }
+    }
+
+    for item_location in &lint_data.item_locations {
+        if !lint_data.item_names.contains(&item_location.item_name) {
+            report.add(
+                LintSeverity::Error,
+                LintCategory::ItemLocations,
+                &item_location.route,
+                format!("{} is not in the items table", item_location.item_name),
+            );
+        }
+    }
+
+    report
+}
+
+// Route pokemon link to their page through the dex number, so it has to be
+// the one of the pokemon with that name
+fn pokemon_reference_issue(
+    dex_numbers: &HashMap<&str, usize>,
+    name: &str,
+    dex_number: usize,
+) -> Option<String> {
+    match dex_numbers.get(name) {
+        None => Some(format!("{} is not a pokemon in the wiki", name)),
+        Some(expected) if *expected != dex_number => Some(format!(
+            "{} has dex number {}, but the wiki has it as {}",
+            name, dex_number, expected
+        )),
+        Some(_) => None,
+    }
+}
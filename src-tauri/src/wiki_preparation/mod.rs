@@ -1,4 +1,11 @@
 pub mod backup_wiki;
 pub mod commit_wiki_changes;
 pub mod create_wiki;
+pub mod lint_wiki;
+pub mod reconcile_wiki;
+pub mod regenerate_wiki;
+pub mod wiki_bundle;
+pub mod wiki_pages;
+pub mod wiki_plugins;
+pub mod wiki_theme;
 pub mod yaml_declaration;
@@ -0,0 +1,298 @@
+// Rebuilds the mkdocs nav from the database and routes.json instead of the
+// incremental patches each generator makes, and removes pages nothing links to
+use std::{collections::HashSet, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use tauri::AppHandle;
+
+use crate::{
+    database::{
+        get_routes,
+        repository::get_all_pokemon,
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::{capitalize_and_remove_hyphens, get_pokemon_dex_formatted_name},
+    logger::{self, LogLevel},
+    page_generators::game_routes::Routes,
+    structs::{
+        mkdocs_structs::{nav_entry_file, nav_entry_title, MKDocsConfig},
+        pokemon_structs::DBPokemon,
+    },
+    wiki_preparation::{wiki_pages::get_nav_order, wiki_plugins::add_page_redirect},
+};
+
+// Top level pages the generators add, kept in the nav only while their file exists
+const GENERATED_PAGES: [(&str, &str); 8] = [
+    ("Type Chart", "type_chart.md"),
+    ("Ability Changes", "ability_changes.md"),
+    ("Item Changes", "item_changes.md"),
+    ("Item Locations", "item_locations.md"),
+    ("TMs & HMs", "machines.md"),
+    ("Move Changes", "move_changes.md"),
+    ("Nature Changes", "nature_changes.md"),
+    ("Changelog", "changelog.md"),
+];
+
+// Folders in docs whose pages all come from the database or routes.json
+const GENERATED_FOLDERS: [&str; 2] = ["pokemon", "routes"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ReconcileReport {
+    pub added_entries: Vec<String>,
+    pub removed_entries: Vec<String>,
+    // Paths relative to docs, e.g. "pokemon/004-charmander.md"
+    pub deleted_files: Vec<String>,
+    // Pages that should be in the nav but haven't been generated yet
+    pub missing_pages: Vec<String>,
+    // "old -> new" for deleted Pokemon pages that moved to a new dex number
+    pub added_redirects: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn reconcile_wiki(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<ReconcileReport> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match reconcile_session(&session).await {
+        Ok(report) => Ok(report),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+async fn reconcile_session(session: &WikiSession) -> WikiGenResult<ReconcileReport> {
+    let pokemon = get_all_pokemon(&session.pool).await?;
+    let routes = get_routes(&session.wiki_path.join("data").join("routes.json"))?;
+    let docs_files = get_docs_files(&session.docs_path)?;
+    let mut mkdocs_config = session.mkdocs_config()?;
+
+    let nav_order = get_nav_order(&session.wiki_path)?;
+
+    let report = reconcile_nav(
+        &mut mkdocs_config,
+        &pokemon,
+        &routes,
+        &docs_files,
+        &nav_order,
+    )?;
+
+    for file_name in &report.deleted_files {
+        let file_path = session.docs_path.join(file_name);
+        if let Err(err) = fs::remove_file(&file_path) {
+            return Err(WikiGenError::io(
+                format!("Failed to remove orphaned page: {err}"),
+                file_path.display(),
+            ));
+        }
+    }
+    session.update_mkdocs_config(&mkdocs_config)?;
+
+    Ok(report)
+}
+
+// Markdown files at the top of docs and in the generated folders
+fn get_docs_files(docs_path: &Path) -> WikiGenResult<HashSet<String>> {
+    let mut docs_files = HashSet::new();
+    for file_name in markdown_files(docs_path)? {
+        docs_files.insert(file_name);
+    }
+    for folder in GENERATED_FOLDERS {
+        let folder_path = docs_path.join(folder);
+        if !folder_path.is_dir() {
+            continue;
+        }
+        for file_name in markdown_files(&folder_path)? {
+            docs_files.insert(format!("{folder}/{file_name}"));
+        }
+    }
+
+    Ok(docs_files)
+}
+
+fn markdown_files(folder_path: &Path) -> WikiGenResult<Vec<String>> {
+    let entries = match fs::read_dir(folder_path) {
+        Ok(entries) => entries,
+        Err(err) => {
+            return Err(WikiGenError::io(
+                format!("Failed to read docs folder: {err}"),
+                folder_path.display(),
+            ))
+        }
+    };
+
+    Ok(entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "md"))
+        .filter_map(|path| {
+            path.file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+        })
+        .collect())
+}
+
+// Rewrites the Pokemon and Routes sections and the generated top level pages
+// to match the data, and lists the files in the generated folders that the
+// rebuilt nav no longer points to as deleted
+pub fn reconcile_nav(
+    mkdocs_config: &mut MKDocsConfig,
+    pokemon: &[DBPokemon],
+    routes: &Routes,
+    docs_files: &HashSet<String>,
+    nav_order: &[String],
+) -> WikiGenResult<ReconcileReport> {
+    let mut report = ReconcileReport::default();
+
+    let mut rendered_pokemon: Vec<&DBPokemon> = pokemon
+        .iter()
+        .filter(|pokemon| pokemon.render == "true")
+        .collect();
+    rendered_pokemon.sort_by_key(|pokemon| pokemon.dex_number);
+    let pokemon_pages = rendered_pokemon
+        .into_iter()
+        .map(|pokemon| {
+            let dex_name = get_pokemon_dex_formatted_name(pokemon.dex_number);
+            (
+                format!(
+                    "{} - {}",
+                    dex_name,
+                    capitalize_and_remove_hyphens(&pokemon.name)
+                ),
+                format!("pokemon/{}-{}.md", dex_name, pokemon.name),
+            )
+        })
+        .collect();
+
+    let mut rendered_routes: Vec<(&String, i32)> = routes
+        .routes
+        .iter()
+        .filter(|(_, route_properties)| route_properties.render)
+        .map(|(route_name, route_properties)| (route_name, route_properties.position))
+        .collect();
+    rendered_routes.sort_by_key(|(_, position)| *position);
+    let route_pages = rendered_routes
+        .into_iter()
+        .map(|(route_name, _)| (route_name.to_string(), format!("routes/{route_name}.md")))
+        .collect();
+
+    let mut referenced_files = HashSet::new();
+    for (section_title, pages) in [("Pokemon", pokemon_pages), ("Routes", route_pages)] {
+        let section = mkdocs_config.nav_section_mut(section_title)?;
+        let old_titles: HashSet<String> = section
+            .iter()
+            .filter_map(nav_entry_title)
+            .map(str::to_string)
+            .collect();
+
+        let mut new_section = Vec::new();
+        for (title, file) in pages {
+            if !docs_files.contains(&file) {
+                report.missing_pages.push(file);
+                continue;
+            }
+            if !old_titles.contains(&title) {
+                report
+                    .added_entries
+                    .push(format!("{section_title} / {title}"));
+            }
+            new_section.push(nav_page(&title, &file));
+            referenced_files.insert(file);
+        }
+
+        let new_titles: HashSet<&str> = new_section.iter().filter_map(nav_entry_title).collect();
+        for entry in section.iter() {
+            match nav_entry_title(entry) {
+                Some(title) if !new_titles.contains(title) => {
+                    report
+                        .removed_entries
+                        .push(format!("{section_title} / {title}"));
+                }
+                Some(_) => {}
+                None => report
+                    .removed_entries
+                    .push(format!("{section_title} / untitled entry")),
+            }
+        }
+        *section = new_section;
+    }
+
+    for (title, file) in GENERATED_PAGES {
+        let nav_entries = mkdocs_config.nav_entries_mut()?;
+        let page_position = nav_entries
+            .iter()
+            .position(|entry| nav_entry_title(entry) == Some(title));
+        let file_exists = docs_files.contains(file);
+        match page_position {
+            Some(index) if !file_exists => {
+                nav_entries.remove(index);
+                report.removed_entries.push(title.to_string());
+            }
+            None if file_exists => {
+                // Same spot the generators put their pages
+                mkdocs_config.insert_nav_entry(nav_page(title, file), nav_order)?;
+                report.added_entries.push(title.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    // Pages the user linked by hand outside the generated sections stay
+    for entry in mkdocs_config.nav_entries_mut()?.iter() {
+        if let Some(file) = nav_entry_file(entry) {
+            referenced_files.insert(file.to_string());
+        }
+    }
+
+    let mut orphaned_files: Vec<String> = docs_files
+        .iter()
+        .filter(|file| {
+            file.split_once('/')
+                .is_some_and(|(folder, _)| GENERATED_FOLDERS.contains(&folder))
+        })
+        .filter(|file| !referenced_files.contains(*file))
+        .cloned()
+        .collect();
+    orphaned_files.sort();
+
+    // A Pokemon whose dex number changed keeps its old links working when
+    // the redirects plugin is on
+    for file in &orphaned_files {
+        let Some(page_name) = file
+            .strip_prefix("pokemon/")
+            .and_then(|page| page.split_once('-'))
+            .map(|(_, page_name)| page_name)
+        else {
+            continue;
+        };
+        let new_file = referenced_files.iter().find(|referenced_file| {
+            referenced_file
+                .strip_prefix("pokemon/")
+                .and_then(|page| page.split_once('-'))
+                .is_some_and(|(_, referenced_name)| referenced_name == page_name)
+        });
+        if let Some(new_file) = new_file {
+            if add_page_redirect(mkdocs_config, file, new_file) {
+                report.added_redirects.push(format!("{file} -> {new_file}"));
+            }
+        }
+    }
+    report.deleted_files = orphaned_files;
+
+    Ok(report)
+}
+
+fn nav_page(title: &str, file: &str) -> Value {
+    let mut page = Mapping::new();
+    page.insert(
+        Value::String(title.to_string()),
+        Value::String(file.to_string()),
+    );
+    Value::Mapping(page)
+}
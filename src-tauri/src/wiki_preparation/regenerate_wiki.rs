@@ -0,0 +1,347 @@
+// Runs every page generator for a wiki in one go, reporting progress to the
+// frontend as it goes and stopping early when asked to
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    database::{
+        get_routes,
+        repository::get_all_pokemon,
+        session::{get_wiki_session, WikiSession},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    logger::{self, LogLevel},
+    page_generators::{
+        ability_page::generate_ability_page_with_handle,
+        changelog_page::generate_changelog_page_with_handle,
+        game_routes::generate_route_pages_with_handle,
+        home_page::generate_home_page_with_handle,
+        item_page::{
+            generate_item_changes_page_with_handle, generate_item_location_page_with_handle,
+        },
+        machine_page::generate_machine_page_with_handle,
+        move_page::generate_move_page_with_handle,
+        nature_page::generate_nature_page_with_handle,
+        pokemon_pages::generate_pokemon_pages_from_list,
+        type_chart_page::generate_type_chart_page_with_handle,
+    },
+    wiki_preparation::yaml_declaration::update_yaml,
+};
+
+pub const REGENERATION_PROGRESS_EVENT: &str = "wiki-regeneration-progress";
+pub const POKEMON_PAGE_BATCH_SIZE: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RegenerationPhase {
+    MkdocsConfig,
+    TypeChart,
+    PokemonPages,
+    RoutePages,
+    AbilityChanges,
+    MoveChanges,
+    Machines,
+    ItemChanges,
+    ItemLocations,
+    NatureChanges,
+    Changelog,
+    HomePage,
+}
+
+impl RegenerationPhase {
+    // The markdown extensions are brought up to date first since every page
+    // relies on them, the rest only touch their own pages and nav entries. The
+    // home page goes last since it links to and counts what the others wrote.
+    pub const ALL: [RegenerationPhase; 12] = [
+        RegenerationPhase::MkdocsConfig,
+        RegenerationPhase::TypeChart,
+        RegenerationPhase::PokemonPages,
+        RegenerationPhase::RoutePages,
+        RegenerationPhase::AbilityChanges,
+        RegenerationPhase::MoveChanges,
+        RegenerationPhase::Machines,
+        RegenerationPhase::ItemChanges,
+        RegenerationPhase::ItemLocations,
+        RegenerationPhase::NatureChanges,
+        RegenerationPhase::Changelog,
+        RegenerationPhase::HomePage,
+    ];
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegenerationProgress {
+    pub wiki_name: String,
+    pub phase: RegenerationPhase,
+    // Phases finished before this one started
+    pub completed: usize,
+    pub total: usize,
+    // Batches of this phase finished so far, both zero for phases that
+    // aren't run in batches
+    pub phase_completed: usize,
+    pub phase_total: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PhaseResult {
+    pub phase: RegenerationPhase,
+    pub succeeded: bool,
+    // What the generator returned, or the error when it failed
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RegenerationSummary {
+    pub results: Vec<PhaseResult>,
+    pub failures: usize,
+    // Set when the regeneration was cancelled before every phase ran
+    pub cancelled: bool,
+}
+
+impl RegenerationSummary {
+    pub fn record(&mut self, phase: RegenerationPhase, result: &WikiGenResult<String>) {
+        let (succeeded, message) = match result {
+            Ok(message) => (true, message.clone()),
+            Err(err) => {
+                self.failures += 1;
+                (false, err.to_string())
+            }
+        };
+        self.results.push(PhaseResult {
+            phase,
+            succeeded,
+            message,
+        });
+    }
+}
+
+// Cancel flags of the regenerations running, by wiki name
+#[derive(Default)]
+pub struct RegenerationState(Mutex<HashMap<String, Arc<AtomicBool>>>);
+
+// Handed to each phase so it can report progress and notice a cancel
+// part way through
+#[derive(Clone)]
+pub struct RegenerationControl {
+    wiki_name: String,
+    cancelled: Arc<AtomicBool>,
+    on_progress: Arc<dyn Fn(RegenerationProgress) + Send + Sync>,
+}
+
+impl RegenerationControl {
+    pub fn new(
+        wiki_name: &str,
+        cancelled: Arc<AtomicBool>,
+        on_progress: impl Fn(RegenerationProgress) + Send + Sync + 'static,
+    ) -> Self {
+        RegenerationControl {
+            wiki_name: wiki_name.to_string(),
+            cancelled,
+            on_progress: Arc::new(on_progress),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn report(&self, phase: RegenerationPhase, phase_completed: usize, phase_total: usize) {
+        (self.on_progress)(RegenerationProgress {
+            wiki_name: self.wiki_name.clone(),
+            phase,
+            completed: RegenerationPhase::ALL
+                .iter()
+                .position(|ordered| *ordered == phase)
+                .unwrap_or_default(),
+            total: RegenerationPhase::ALL.len(),
+            phase_completed,
+            phase_total,
+        });
+    }
+}
+
+// Runs the phases in order, checking for a cancel before each one. A failed
+// phase is recorded and the rest still run.
+pub async fn run_regeneration<F, Fut>(
+    control: &RegenerationControl,
+    mut run_phase: F,
+) -> RegenerationSummary
+where
+    F: FnMut(RegenerationPhase, RegenerationControl) -> Fut,
+    Fut: Future<Output = WikiGenResult<String>>,
+{
+    let mut summary = RegenerationSummary::default();
+    for phase in RegenerationPhase::ALL {
+        if control.is_cancelled() {
+            summary.cancelled = true;
+            break;
+        }
+
+        control.report(phase, 0, 0);
+        let result = run_phase(phase, control.clone()).await;
+        summary.record(phase, &result);
+    }
+
+    summary
+}
+
+// Splits a phase's work into batches, reporting progress and checking for a
+// cancel before each one. Stops at the first batch that fails.
+pub async fn run_in_batches<F, Fut>(
+    phase: RegenerationPhase,
+    ids: Vec<usize>,
+    batch_size: usize,
+    control: &RegenerationControl,
+    mut generate: F,
+) -> WikiGenResult<String>
+where
+    F: FnMut(Vec<usize>) -> Fut,
+    Fut: Future<Output = WikiGenResult<String>>,
+{
+    let batches = ids
+        .chunks(batch_size.max(1))
+        .map(<[usize]>::to_vec)
+        .collect::<Vec<_>>();
+    let total = batches.len();
+
+    let mut message = String::new();
+    for (completed, batch) in batches.into_iter().enumerate() {
+        if control.is_cancelled() {
+            return Ok(format!("Cancelled after {completed} of {total} batches"));
+        }
+        control.report(phase, completed, total);
+        message = generate(batch).await?;
+    }
+
+    Ok(message)
+}
+
+#[tauri::command]
+pub async fn regenerate_wiki(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<RegenerationSummary> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let regeneration_state = app_handle.state::<RegenerationState>();
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    {
+        let mut running = regeneration_state.0.lock().map_err(|err| {
+            WikiGenError::from(format!("Failed to access regeneration state: {}", err))
+        })?;
+        if running.contains_key(wiki_name) {
+            return Err(WikiGenError::from(format!(
+                "{wiki_name} is already being regenerated"
+            )));
+        }
+        running.insert(wiki_name.to_string(), cancelled.clone());
+    }
+
+    let progress_handle = app_handle.clone();
+    let wiki_path = session.wiki_path.clone();
+    let control = RegenerationControl::new(wiki_name, cancelled, move |progress| {
+        if let Err(err) = progress_handle.emit(REGENERATION_PROGRESS_EVENT, progress) {
+            let message = format!("Failed to emit regeneration progress: {err}");
+            logger::write_log(&wiki_path, LogLevel::Error, &message);
+        }
+    });
+
+    let summary = run_regeneration(&control, |phase, control| {
+        let session = session.clone();
+        let app_handle = app_handle.clone();
+        async move {
+            let result = run_phase(phase, &session, &app_handle, &control).await;
+            if let Err(err) = &result {
+                let message = format!("Regeneration phase {:?} failed: {err}", phase);
+                logger::write_log(&session.wiki_path, LogLevel::Error, &message);
+            }
+            result
+        }
+    })
+    .await;
+
+    if let Ok(mut running) = regeneration_state.0.lock() {
+        running.remove(wiki_name);
+    }
+
+    Ok(summary)
+}
+
+#[tauri::command]
+pub fn cancel_wiki_regeneration(wiki_name: &str, app_handle: AppHandle) -> WikiGenResult<()> {
+    let regeneration_state = app_handle.state::<RegenerationState>();
+    let running = regeneration_state.0.lock().map_err(|err| {
+        WikiGenError::from(format!("Failed to access regeneration state: {}", err))
+    })?;
+    if let Some(cancelled) = running.get(wiki_name) {
+        cancelled.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+async fn run_phase(
+    phase: RegenerationPhase,
+    session: &WikiSession,
+    app_handle: &AppHandle,
+    control: &RegenerationControl,
+) -> WikiGenResult<String> {
+    let wiki_name = session.wiki_name.as_str();
+    match phase {
+        RegenerationPhase::MkdocsConfig => update_yaml(wiki_name, app_handle.clone()).await,
+        RegenerationPhase::TypeChart => {
+            generate_type_chart_page_with_handle(wiki_name, app_handle.clone()).await
+        }
+        RegenerationPhase::PokemonPages => {
+            let pokemon_ids = get_all_pokemon(&session.pool)
+                .await?
+                .iter()
+                .map(|pokemon| pokemon.id as usize)
+                .collect();
+            run_in_batches(
+                phase,
+                pokemon_ids,
+                POKEMON_PAGE_BATCH_SIZE,
+                control,
+                |batch| generate_pokemon_pages_from_list(wiki_name, batch, app_handle.clone()),
+            )
+            .await
+        }
+        RegenerationPhase::RoutePages => {
+            let routes = get_routes(&session.wiki_path.join("data").join("routes.json"))?;
+            let route_names = routes.routes.keys().map(String::as_str).collect();
+            generate_route_pages_with_handle(wiki_name, route_names, app_handle.clone()).await
+        }
+        RegenerationPhase::AbilityChanges => {
+            generate_ability_page_with_handle(wiki_name, app_handle.clone()).await
+        }
+        RegenerationPhase::MoveChanges => {
+            generate_move_page_with_handle(wiki_name, app_handle.clone()).await
+        }
+        RegenerationPhase::Machines => {
+            generate_machine_page_with_handle(wiki_name, app_handle.clone()).await
+        }
+        RegenerationPhase::ItemChanges => {
+            generate_item_changes_page_with_handle(wiki_name, app_handle.clone()).await
+        }
+        RegenerationPhase::ItemLocations => {
+            generate_item_location_page_with_handle(wiki_name, app_handle.clone()).await
+        }
+        RegenerationPhase::NatureChanges => {
+            generate_nature_page_with_handle(wiki_name, app_handle.clone()).await
+        }
+        RegenerationPhase::Changelog => {
+            generate_changelog_page_with_handle(wiki_name, app_handle.clone()).await
+        }
+        RegenerationPhase::HomePage => {
+            generate_home_page_with_handle(wiki_name, app_handle.clone()).await
+        }
+    }
+}
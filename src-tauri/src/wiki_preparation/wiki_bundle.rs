@@ -0,0 +1,516 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    database::{
+        get_app_data_dir, get_resource_dir,
+        repository::{get_applied_migrations, snapshot_database},
+        session::{get_wiki_session, release_wiki_session},
+    },
+    errors::{WikiGenError, WikiGenResult},
+    helpers::matchups::invalidate_matchup_table,
+    logger::{self, LogLevel},
+    migrations::{gather_migrations, run_wiki_migrations},
+    wiki_preparation::backup_wiki::create_wiki_backup,
+};
+
+// Bumped whenever the layout of the bundle changes so older
+// versions of the app refuse bundles they can't read
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+pub const BUNDLE_EXTENSION: &str = "wikigen";
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+// The database is stored under a fixed name so the wiki can be renamed on import
+const BUNDLE_DATABASE_NAME: &str = "wiki.db";
+const BUNDLE_DIRECTORIES: [&str; 2] = ["data", "dist"];
+// Deployment state and build output are specific to the machine that exported the wiki
+const EXCLUDED_DIST_ENTRIES: [&str; 2] = [".git", "site"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WikiBundleManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+    pub wiki_name: String,
+    // The wiki's entry from wikis.json, kept as is so settings survive the round trip
+    pub wiki: Value,
+    pub migrations: Vec<String>,
+    pub files: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn export_wiki(
+    wiki_name: &str,
+    export_path: Option<String>,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let base_path = session.base_path.clone();
+
+    let wiki = match read_wikis(&base_path)?.remove(wiki_name) {
+        Some(wiki) => wiki,
+        None => return Err(format!("{} is not a known wiki", wiki_name).into()),
+    };
+
+    // Wikis created before migrations were tracked have no table to read
+    let migrations = match get_applied_migrations(&session.pool).await {
+        Ok(migrations) => migrations,
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Vec::new()
+        }
+    };
+
+    let export_path = match export_path {
+        Some(export_path) => PathBuf::from(export_path),
+        None => base_path.join("exports").join(format!(
+            "{}_{}.{}",
+            wiki_name,
+            chrono::Local::now().format("%Y_%m_%d_%H_%M_%S"),
+            BUNDLE_EXTENSION
+        )),
+    };
+
+    // A snapshot left behind by an interrupted export would make the next one fail
+    let snapshot_path = session.wiki_path.join(format!("{}.export.db", wiki_name));
+    let _ = fs::remove_file(&snapshot_path);
+    snapshot_database(&session.pool, &snapshot_path).await?;
+
+    let mut manifest = WikiBundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        app_version: app_handle.package_info().version.to_string(),
+        exported_at: chrono::Local::now().to_rfc3339(),
+        wiki_name: wiki_name.to_string(),
+        wiki,
+        migrations,
+        files: Vec::new(),
+    };
+    let result = write_wiki_bundle(
+        &session.wiki_path,
+        &snapshot_path,
+        &mut manifest,
+        &export_path,
+    );
+    let _ = fs::remove_file(&snapshot_path);
+
+    if let Err(err) = result {
+        logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+        return Err(err);
+    }
+
+    Ok(export_path.display().to_string())
+}
+
+#[tauri::command]
+pub async fn import_wiki(
+    archive_path: &str,
+    wiki_name: Option<String>,
+    overwrite: bool,
+    app_handle: AppHandle,
+) -> WikiGenResult<Value> {
+    let base_path = get_app_data_dir(&app_handle)?;
+    let resources_path = get_resource_dir(&app_handle)?;
+    let archive_path = PathBuf::from(archive_path);
+
+    let manifest = read_wiki_bundle_manifest(&archive_path)?;
+    let wiki_name = match wiki_name {
+        Some(wiki_name) if !wiki_name.trim().is_empty() => wiki_name.trim().to_string(),
+        _ => manifest.wiki_name.clone(),
+    };
+
+    check_wiki_name(&wiki_name)?;
+
+    let wiki_path = base_path.join(&wiki_name);
+    let replacing = wiki_path.exists();
+    if replacing {
+        if !overwrite {
+            return Err(format!("{} already exists", wiki_name).into());
+        }
+
+        release_wiki_session(&wiki_name, &app_handle).await;
+        if let Err(err) = create_wiki_backup(&base_path, &wiki_name) {
+            return Err(WikiGenError::io(
+                format!(
+                    "Failed to back up {} before replacing it: {}",
+                    wiki_name, err
+                ),
+                wiki_path.display(),
+            ));
+        }
+    }
+
+    extract_wiki_bundle(&archive_path, &wiki_name, &base_path, replacing)?;
+    let wiki = add_wiki_entry(&base_path, &wiki_name, &manifest.wiki)?;
+    invalidate_matchup_table(&wiki_name, &base_path);
+
+    // Bundles from older versions of the app are brought up to date like any other wiki
+    match gather_migrations(&base_path, &resources_path) {
+        Ok(migrations) => {
+            let session = get_wiki_session(&wiki_name, &app_handle).await?;
+            run_wiki_migrations(
+                &wiki_name,
+                &session.pool,
+                &migrations,
+                &base_path,
+                &resources_path,
+            )
+            .await?
+        }
+        Err(err) => logger::write_log(&wiki_path, LogLevel::MigrationError, &err),
+    }
+
+    Ok(wiki)
+}
+
+pub fn write_wiki_bundle(
+    wiki_path: &PathBuf,
+    database_path: &PathBuf,
+    manifest: &mut WikiBundleManifest,
+    export_path: &PathBuf,
+) -> WikiGenResult<()> {
+    if let Some(export_directory) = export_path.parent() {
+        fs::create_dir_all(export_directory)?;
+    }
+
+    let mut files = vec![(BUNDLE_DATABASE_NAME.to_string(), database_path.clone())];
+    for directory in BUNDLE_DIRECTORIES {
+        collect_bundle_files(wiki_path, &wiki_path.join(directory), &mut files)?;
+    }
+    manifest.files = files.iter().map(|(name, _)| name.clone()).collect();
+
+    let archive_file = match File::create(export_path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(WikiGenError::io(
+                format!("Failed to create bundle file: {}", err),
+                export_path.display(),
+            ));
+        }
+    };
+
+    // Sprites and images are already compressed, so entries are stored as is
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+    let mut archive = ZipWriter::new(archive_file);
+
+    let manifest_json = match serde_json::to_string_pretty(manifest) {
+        Ok(manifest_json) => manifest_json,
+        Err(err) => return Err(format!("Failed to serialize bundle manifest: {}", err).into()),
+    };
+    archive
+        .start_file(MANIFEST_FILE_NAME, options)
+        .map_err(|err| bundle_error(err, MANIFEST_FILE_NAME))?;
+    archive
+        .write_all(manifest_json.as_bytes())
+        .map_err(|err| bundle_error(err, MANIFEST_FILE_NAME))?;
+
+    for (name, file_path) in &files {
+        archive
+            .start_file(name.as_str(), options)
+            .map_err(|err| bundle_error(err, name))?;
+        let mut file = File::open(file_path).map_err(|err| bundle_error(err, name))?;
+        io::copy(&mut file, &mut archive).map_err(|err| bundle_error(err, name))?;
+    }
+
+    archive
+        .finish()
+        .map_err(|err| bundle_error(err, export_path.display()))?;
+
+    Ok(())
+}
+
+fn collect_bundle_files(
+    wiki_path: &Path,
+    directory: &Path,
+    files: &mut Vec<(String, PathBuf)>,
+) -> WikiGenResult<()> {
+    if !directory.exists() {
+        return Ok(());
+    }
+
+    let entries = fs::read_dir(directory).map_err(|err| bundle_error(err, directory.display()))?;
+    for entry in entries {
+        let entry_path = entry
+            .map_err(|err| bundle_error(err, directory.display()))?
+            .path();
+        let Ok(relative_path) = entry_path.strip_prefix(wiki_path) else {
+            continue;
+        };
+        // Archive entries always use forward slashes
+        let name = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if EXCLUDED_DIST_ENTRIES
+            .iter()
+            .any(|excluded| name == format!("dist/{}", excluded))
+        {
+            continue;
+        }
+
+        if entry_path.is_dir() {
+            collect_bundle_files(wiki_path, &entry_path, files)?;
+        } else {
+            files.push((name, entry_path));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn read_wiki_bundle_manifest(archive_path: &PathBuf) -> WikiGenResult<WikiBundleManifest> {
+    let mut archive = open_wiki_bundle(archive_path)?;
+    read_manifest(&mut archive, archive_path)
+}
+
+// Unpacks the bundle next to the other wikis under the given name.
+// Files are extracted to a staging directory first so a bad bundle
+// never leaves a half written wiki behind, or costs the wiki it replaces.
+pub fn extract_wiki_bundle(
+    archive_path: &PathBuf,
+    wiki_name: &str,
+    base_path: &PathBuf,
+    overwrite: bool,
+) -> WikiGenResult<WikiBundleManifest> {
+    check_wiki_name(wiki_name)?;
+    let mut archive = open_wiki_bundle(archive_path)?;
+    let manifest = read_manifest(&mut archive, archive_path)?;
+
+    let wiki_path = base_path.join(wiki_name);
+    let replacing = wiki_path.exists();
+    if replacing && !overwrite {
+        return Err(format!("{} already exists", wiki_name).into());
+    }
+
+    let staging_path = base_path.join(format!(".{}.import", wiki_name));
+    if staging_path.exists() {
+        fs::remove_dir_all(&staging_path)?;
+    }
+    if let Err(err) = extract_entries(&mut archive, wiki_name, &staging_path) {
+        let _ = fs::remove_dir_all(&staging_path);
+        return Err(err);
+    }
+
+    let result = match replacing {
+        true => swap_in_wiki(&staging_path, &wiki_path, base_path, wiki_name),
+        false => fs::rename(&staging_path, &wiki_path).map_err(WikiGenError::from),
+    };
+    if let Err(err) = result {
+        let _ = fs::remove_dir_all(&staging_path);
+        return Err(err);
+    }
+
+    Ok(manifest)
+}
+
+// The old wiki is moved aside rather than deleted until the new one is in
+// place, and moved back if that fails
+fn swap_in_wiki(
+    staging_path: &Path,
+    wiki_path: &Path,
+    base_path: &Path,
+    wiki_name: &str,
+) -> WikiGenResult<()> {
+    let replaced_path = base_path.join(format!(".{}.replaced", wiki_name));
+    if replaced_path.exists() {
+        fs::remove_dir_all(&replaced_path)?;
+    }
+    if let Err(err) = fs::rename(wiki_path, &replaced_path) {
+        return Err(WikiGenError::io(
+            format!(
+                "Failed to move {} aside before replacing it: {}",
+                wiki_name, err
+            ),
+            wiki_path.display(),
+        ));
+    }
+    if let Err(err) = fs::rename(staging_path, wiki_path) {
+        let _ = fs::rename(&replaced_path, wiki_path);
+        return Err(WikiGenError::io(
+            format!("Failed to replace {}: {}", wiki_name, err),
+            wiki_path.display(),
+        ));
+    }
+
+    // The import already succeeded, a leftover copy is cleared on the next one
+    let _ = fs::remove_dir_all(&replaced_path);
+    Ok(())
+}
+
+// Wiki names become folder names in the app data folder, so anything other
+// than a single plain folder name could point an import somewhere else
+pub fn check_wiki_name(wiki_name: &str) -> WikiGenResult<()> {
+    let mut components = Path::new(wiki_name).components();
+    let is_folder_name = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    );
+    if !is_folder_name
+        || wiki_name.trim().is_empty()
+        || wiki_name.contains(['/', '\\', ':'])
+        || wiki_name.contains("..")
+    {
+        return Err(WikiGenError::from(format!(
+            "\"{}\" can't be used as a wiki name",
+            wiki_name
+        )));
+    }
+    Ok(())
+}
+
+fn extract_entries(
+    archive: &mut ZipArchive<File>,
+    wiki_name: &str,
+    staging_path: &PathBuf,
+) -> WikiGenResult<()> {
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|err| bundle_error(err, index))?;
+        let entry_name = entry.name().to_string();
+        // Entries that would land outside the wiki directory are rejected
+        let Some(relative_path) = entry.enclosed_name() else {
+            return Err(WikiGenError::io(
+                "Bundle entry has an unsafe path",
+                entry_name,
+            ));
+        };
+
+        let destination = if entry_name == BUNDLE_DATABASE_NAME {
+            staging_path.join(format!("{}.db", wiki_name))
+        } else if BUNDLE_DIRECTORIES
+            .iter()
+            .any(|directory| relative_path.starts_with(directory))
+        {
+            staging_path.join(&relative_path)
+        } else {
+            continue;
+        };
+
+        if entry.is_dir() {
+            fs::create_dir_all(&destination).map_err(|err| bundle_error(err, &entry_name))?;
+            continue;
+        }
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).map_err(|err| bundle_error(err, &entry_name))?;
+        }
+        let mut file = File::create(&destination).map_err(|err| bundle_error(err, &entry_name))?;
+        io::copy(&mut entry, &mut file).map_err(|err| bundle_error(err, &entry_name))?;
+    }
+
+    if !staging_path.join(format!("{}.db", wiki_name)).exists() {
+        return Err(WikiGenError::io(
+            "Bundle does not contain a wiki database",
+            BUNDLE_DATABASE_NAME,
+        ));
+    }
+
+    Ok(())
+}
+
+fn open_wiki_bundle(archive_path: &PathBuf) -> WikiGenResult<ZipArchive<File>> {
+    let archive_file = match File::open(archive_path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(WikiGenError::io(
+                format!("Failed to open bundle file: {}", err),
+                archive_path.display(),
+            ));
+        }
+    };
+
+    ZipArchive::new(archive_file).map_err(|err| {
+        WikiGenError::io(
+            format!("File is not a wiki bundle: {}", err),
+            archive_path.display(),
+        )
+    })
+}
+
+fn read_manifest(
+    archive: &mut ZipArchive<File>,
+    archive_path: &PathBuf,
+) -> WikiGenResult<WikiBundleManifest> {
+    let manifest_file = archive.by_name(MANIFEST_FILE_NAME).map_err(|err| {
+        WikiGenError::io(
+            format!("Bundle is missing its manifest: {}", err),
+            archive_path.display(),
+        )
+    })?;
+    let manifest: WikiBundleManifest = match serde_json::from_reader(manifest_file) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            return Err(WikiGenError::io(
+                format!("Failed to parse bundle manifest: {}", err),
+                archive_path.display(),
+            ));
+        }
+    };
+
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(WikiGenError::io(
+            format!(
+                "Bundle was exported by a newer version of WikiGen ({})",
+                manifest.app_version
+            ),
+            archive_path.display(),
+        ));
+    }
+
+    Ok(manifest)
+}
+
+fn read_wikis(base_path: &PathBuf) -> WikiGenResult<Map<String, Value>> {
+    let wikis_file_path = base_path.join("wikis.json");
+    if !wikis_file_path.exists() {
+        return Ok(Map::new());
+    }
+
+    let wikis_file = File::open(&wikis_file_path)?;
+    match serde_json::from_reader(wikis_file) {
+        Ok(wikis) => Ok(wikis),
+        Err(err) => Err(WikiGenError::io(
+            format!("Failed to parse wikis file: {}", err),
+            wikis_file_path.display(),
+        )),
+    }
+}
+
+// Registers the imported wiki in wikis.json under its new name
+pub fn add_wiki_entry(base_path: &PathBuf, wiki_name: &str, wiki: &Value) -> WikiGenResult<Value> {
+    let mut wikis = read_wikis(base_path)?;
+
+    let mut wiki = wiki.clone();
+    if let Some(wiki_properties) = wiki.as_object_mut() {
+        wiki_properties.insert("name".to_string(), Value::String(wiki_name.to_string()));
+    }
+    wikis.insert(wiki_name.to_string(), wiki.clone());
+
+    let wikis_file_path = base_path.join("wikis.json");
+    let wikis_json = match serde_json::to_string(&wikis) {
+        Ok(wikis_json) => wikis_json,
+        Err(err) => return Err(format!("Failed to serialize wikis file: {}", err).into()),
+    };
+    if let Err(err) = fs::write(&wikis_file_path, wikis_json) {
+        return Err(WikiGenError::io(
+            format!("Failed to update wikis file: {}", err),
+            wikis_file_path.display(),
+        ));
+    }
+
+    Ok(wiki)
+}
+
+fn bundle_error(err: impl std::fmt::Display, context: impl std::fmt::Display) -> WikiGenError {
+    WikiGenError::io(format!("Failed to process wiki bundle: {}", err), context)
+}
@@ -0,0 +1,638 @@
+// Free-form markdown pages the user writes, like a FAQ or mechanics changes,
+// kept in docs/pages and placed anywhere in the nav. Also saves the order of
+// the top level nav so generators put their pages back where the user wants.
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use tauri::AppHandle;
+
+use crate::{
+    database::session::{get_wiki_session, WikiSession},
+    errors::{WikiGenError, WikiGenResult},
+    logger::{self, LogLevel},
+    static_site::markdown::slugify,
+    structs::mkdocs_structs::{nav_entry_file, nav_entry_title, MKDocsConfig},
+};
+
+pub const CUSTOM_PAGES_FOLDER: &str = "pages";
+const NAV_ORDER_FILE: &str = "nav_order.json";
+
+// Where generated pages go until the user orders the nav themselves, the
+// change pages between Home and the Pokemon
+pub const DEFAULT_NAV_ORDER: [&str; 11] = [
+    "Home",
+    "Changelog",
+    "Type Chart",
+    "Move Changes",
+    "TMs & HMs",
+    "Ability Changes",
+    "Item Changes",
+    "Item Locations",
+    "Nature Changes",
+    "Pokemon",
+    "Routes",
+];
+
+// Sections reconcile rebuilds from the database and routes.json, so pages
+// can't be nested in them
+const GENERATED_SECTIONS: [&str; 2] = ["Pokemon", "Routes"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CustomPage {
+    pub title: String,
+    // Relative to docs, e.g. "pages/faq.md"
+    pub file: String,
+    // Top level section the page is nested in, None at the top level
+    pub section: Option<String>,
+    pub markdown: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NavItem {
+    pub title: String,
+    // Titles of the pages in a custom section. Empty for pages and for the
+    // generated sections, which keep their own order.
+    pub children: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn get_custom_pages(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<Vec<CustomPage>> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let mkdocs_config = session.mkdocs_config()?;
+
+    let mut custom_pages = custom_page_entries(&mkdocs_config);
+    for page in custom_pages.iter_mut() {
+        // A page deleted by hand shows up empty and is written again on save
+        page.markdown = fs::read_to_string(session.docs_path.join(&page.file)).unwrap_or_default();
+    }
+
+    Ok(custom_pages)
+}
+
+#[tauri::command]
+pub async fn create_custom_page(
+    wiki_name: &str,
+    title: &str,
+    section: Option<String>,
+    markdown: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<CustomPage> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match create_session_custom_page(&session, title, section.as_deref(), markdown) {
+        Ok(page) => Ok(page),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn update_custom_page(
+    wiki_name: &str,
+    file: &str,
+    title: &str,
+    section: Option<String>,
+    markdown: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<CustomPage> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match update_session_custom_page(&session, file, title, section.as_deref(), markdown) {
+        Ok(page) => Ok(page),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn delete_custom_page(
+    wiki_name: &str,
+    file: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match delete_session_custom_page(&session, file) {
+        Ok(()) => Ok("Page deleted".to_string()),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_nav_layout(wiki_name: &str, app_handle: AppHandle) -> WikiGenResult<Vec<NavItem>> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let mut mkdocs_config = session.mkdocs_config()?;
+
+    let nav_items = mkdocs_config
+        .nav_entries_mut()?
+        .iter()
+        .filter_map(|entry| {
+            let title = nav_entry_title(entry)?;
+            let children = match custom_section_pages(entry) {
+                Some(pages) => pages
+                    .iter()
+                    .filter_map(nav_entry_title)
+                    .map(str::to_string)
+                    .collect(),
+                None => Vec::new(),
+            };
+            Some(NavItem {
+                title: title.to_string(),
+                children,
+            })
+        })
+        .collect();
+
+    Ok(nav_items)
+}
+
+// Reorders the top level of the nav when `section` is None, or the pages of
+// a custom section. `titles` has to list every entry once.
+#[tauri::command]
+pub async fn update_nav_order(
+    wiki_name: &str,
+    section: Option<String>,
+    titles: Vec<String>,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match update_session_nav_order(&session, section.as_deref(), &titles) {
+        Ok(()) => Ok("Nav order updated".to_string()),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+fn create_session_custom_page(
+    session: &WikiSession,
+    title: &str,
+    section: Option<&str>,
+    markdown: &str,
+) -> WikiGenResult<CustomPage> {
+    let mut mkdocs_config = session.mkdocs_config()?;
+    let title = page_title(title)?;
+    let section = section_title(section);
+
+    let slug = slugify(&title);
+    if slug.is_empty() {
+        return Err(WikiGenError::from(
+            "Page titles need at least one letter or number",
+        ));
+    }
+    let mut file = format!("{CUSTOM_PAGES_FOLDER}/{slug}.md");
+    let mut suffix = 2;
+    while session.docs_path.join(&file).exists() {
+        file = format!("{CUSTOM_PAGES_FOLDER}/{slug}-{suffix}.md");
+        suffix += 1;
+    }
+
+    let nav_order = get_nav_order(&session.wiki_path)?;
+    add_custom_page_entry(
+        &mut mkdocs_config,
+        &title,
+        &file,
+        section.as_deref(),
+        &nav_order,
+    )?;
+    write_page(&session.docs_path.join(&file), markdown)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
+
+    Ok(CustomPage {
+        title,
+        file,
+        section,
+        markdown: markdown.to_string(),
+    })
+}
+
+// The file keeps its name when the page is renamed so links to it still work
+fn update_session_custom_page(
+    session: &WikiSession,
+    file: &str,
+    title: &str,
+    section: Option<&str>,
+    markdown: &str,
+) -> WikiGenResult<CustomPage> {
+    let mut mkdocs_config = session.mkdocs_config()?;
+    let title = page_title(title)?;
+    let section = section_title(section);
+    let nav_order = get_nav_order(&session.wiki_path)?;
+
+    update_custom_page_entry(
+        &mut mkdocs_config,
+        file,
+        &title,
+        section.as_deref(),
+        &nav_order,
+    )?;
+    write_page(&session.docs_path.join(file), markdown)?;
+    session.update_mkdocs_config(&mkdocs_config)?;
+
+    Ok(CustomPage {
+        title,
+        file: file.to_string(),
+        section,
+        markdown: markdown.to_string(),
+    })
+}
+
+fn delete_session_custom_page(session: &WikiSession, file: &str) -> WikiGenResult<()> {
+    let mut mkdocs_config = session.mkdocs_config()?;
+    if !remove_custom_page_entry(&mut mkdocs_config, file)? {
+        return Err(WikiGenError::invalid_nav(
+            format!("{file} is not a custom page in the nav"),
+            file,
+        ));
+    }
+
+    let page_path = session.docs_path.join(file);
+    if page_path.try_exists().unwrap_or(false) {
+        fs::remove_file(&page_path).map_err(|err| {
+            WikiGenError::io(
+                format!("Failed to remove page: {}", err),
+                page_path.display(),
+            )
+        })?;
+    }
+
+    session.update_mkdocs_config(&mkdocs_config)
+}
+
+fn update_session_nav_order(
+    session: &WikiSession,
+    section: Option<&str>,
+    titles: &[String],
+) -> WikiGenResult<()> {
+    let mut mkdocs_config = session.mkdocs_config()?;
+    reorder_nav(&mut mkdocs_config, section, titles)?;
+    if section.is_none() {
+        save_nav_order(&session.wiki_path, &merge_nav_order(titles))?;
+    }
+    session.update_mkdocs_config(&mkdocs_config)
+}
+
+// The saved top level order, or the default for wikis that never set one
+pub fn get_nav_order(wiki_path: &Path) -> WikiGenResult<Vec<String>> {
+    let nav_order_path = wiki_path.join("data").join(NAV_ORDER_FILE);
+    if !nav_order_path.try_exists().unwrap_or(false) {
+        return Ok(DEFAULT_NAV_ORDER
+            .iter()
+            .map(|title| title.to_string())
+            .collect());
+    }
+
+    let nav_order_json = fs::read_to_string(&nav_order_path).map_err(|err| {
+        WikiGenError::io(
+            format!("Failed to read nav order: {}", err),
+            nav_order_path.display(),
+        )
+    })?;
+    match serde_json::from_str::<Vec<String>>(&nav_order_json) {
+        Ok(titles) => Ok(merge_nav_order(&titles)),
+        Err(err) => Err(WikiGenError::io(
+            format!("Failed to parse nav order: {}", err),
+            nav_order_path.display(),
+        )),
+    }
+}
+
+fn save_nav_order(wiki_path: &Path, nav_order: &[String]) -> WikiGenResult<()> {
+    let nav_order_path = wiki_path.join("data").join(NAV_ORDER_FILE);
+    let nav_order_json = serde_json::to_string_pretty(nav_order)
+        .map_err(|err| WikiGenError::from(format!("Failed to save nav order: {}", err)))?;
+    fs::write(&nav_order_path, nav_order_json).map_err(|err| {
+        WikiGenError::io(
+            format!("Failed to write nav order: {}", err),
+            nav_order_path.display(),
+        )
+    })
+}
+
+// Adds the generated pages the user's order leaves out, e.g. because they
+// had no changes when it was saved, after the default page before them
+pub fn merge_nav_order(titles: &[String]) -> Vec<String> {
+    let mut nav_order = titles.to_vec();
+    for (default_index, default_title) in DEFAULT_NAV_ORDER.iter().enumerate() {
+        if nav_order.iter().any(|title| title == default_title) {
+            continue;
+        }
+        let index = DEFAULT_NAV_ORDER[..default_index]
+            .iter()
+            .rev()
+            .find_map(|previous| nav_order.iter().position(|title| title == previous))
+            .map_or(0, |index| index + 1);
+        nav_order.insert(index, default_title.to_string());
+    }
+    nav_order
+}
+
+pub fn custom_page_entries(mkdocs_config: &MKDocsConfig) -> Vec<CustomPage> {
+    let mut custom_pages = Vec::new();
+    let Some(nav_entries) = mkdocs_config.nav.as_sequence() else {
+        return custom_pages;
+    };
+    for entry in nav_entries {
+        let Some(entry_title) = nav_entry_title(entry) else {
+            continue;
+        };
+        let (section, pages) = match custom_section_pages(entry) {
+            Some(pages) => (Some(entry_title.to_string()), pages.as_slice()),
+            None => (None, std::slice::from_ref(entry)),
+        };
+        for page in pages {
+            let (Some(title), Some(file)) = (nav_entry_title(page), nav_entry_file(page)) else {
+                continue;
+            };
+            if is_custom_page_file(file) {
+                custom_pages.push(CustomPage {
+                    title: title.to_string(),
+                    file: file.to_string(),
+                    section: section.clone(),
+                    markdown: String::new(),
+                });
+            }
+        }
+    }
+    custom_pages
+}
+
+pub fn add_custom_page_entry(
+    mkdocs_config: &mut MKDocsConfig,
+    title: &str,
+    file: &str,
+    section: Option<&str>,
+    nav_order: &[String],
+) -> WikiGenResult<()> {
+    let page = nav_page(title, file);
+    let Some(section) = section else {
+        if top_level_titles(mkdocs_config)?.contains(&title.to_string()) {
+            return Err(title_taken(title));
+        }
+        return mkdocs_config.insert_nav_entry(page, nav_order);
+    };
+
+    if GENERATED_SECTIONS.contains(&section) {
+        return Err(WikiGenError::invalid_nav(
+            format!("Pages can't be added to the generated {section} section"),
+            section,
+        ));
+    }
+    let nav_entries = mkdocs_config.nav_entries_mut()?;
+    let Some(section_entry) = nav_entries
+        .iter_mut()
+        .find(|entry| nav_entry_title(entry) == Some(section))
+    else {
+        let mut new_section = Mapping::new();
+        new_section.insert(
+            Value::String(section.to_string()),
+            Value::Sequence(vec![page]),
+        );
+        return mkdocs_config.insert_nav_entry(Value::Mapping(new_section), nav_order);
+    };
+
+    let Some(section_pages) = section_entry
+        .as_mapping_mut()
+        .and_then(|mapping| mapping.values_mut().next())
+        .and_then(Value::as_sequence_mut)
+    else {
+        return Err(WikiGenError::invalid_nav(
+            format!("{section} is a page, not a section"),
+            section,
+        ));
+    };
+    if section_pages
+        .iter()
+        .any(|existing| nav_entry_title(existing) == Some(title))
+    {
+        return Err(title_taken(title));
+    }
+    section_pages.push(page);
+    Ok(())
+}
+
+// Renames the page in place, or moves it to the end of its new section
+pub fn update_custom_page_entry(
+    mkdocs_config: &mut MKDocsConfig,
+    file: &str,
+    title: &str,
+    section: Option<&str>,
+    nav_order: &[String],
+) -> WikiGenResult<()> {
+    let Some(current_page) = custom_page_entries(mkdocs_config)
+        .into_iter()
+        .find(|page| page.file == file)
+    else {
+        return Err(WikiGenError::invalid_nav(
+            format!("{file} is not a custom page in the nav"),
+            file,
+        ));
+    };
+
+    if current_page.section.as_deref() != section {
+        remove_custom_page_entry(mkdocs_config, file)?;
+        return add_custom_page_entry(mkdocs_config, title, file, section, nav_order);
+    }
+    if current_page.title == title {
+        return Ok(());
+    }
+
+    let nav_entries = mkdocs_config.nav_entries_mut()?;
+    let level = match section {
+        Some(section) => nav_entries
+            .iter_mut()
+            .find(|entry| nav_entry_title(entry) == Some(section))
+            .and_then(Value::as_mapping_mut)
+            .and_then(|mapping| mapping.values_mut().next())
+            .and_then(Value::as_sequence_mut)
+            .ok_or_else(|| {
+                WikiGenError::invalid_nav(format!("Nav section {section} is missing"), section)
+            })?,
+        None => nav_entries,
+    };
+    if level
+        .iter()
+        .any(|existing| nav_entry_title(existing) == Some(title))
+    {
+        return Err(title_taken(title));
+    }
+    if let Some(entry) = level
+        .iter_mut()
+        .find(|entry| nav_entry_file(entry) == Some(file))
+    {
+        *entry = nav_page(title, file);
+    }
+    Ok(())
+}
+
+// Whether the page was in the nav. A custom section left empty goes too.
+pub fn remove_custom_page_entry(
+    mkdocs_config: &mut MKDocsConfig,
+    file: &str,
+) -> WikiGenResult<bool> {
+    if !is_custom_page_file(file) {
+        return Ok(false);
+    }
+    let nav_entries = mkdocs_config.nav_entries_mut()?;
+    if let Some(index) = nav_entries
+        .iter()
+        .position(|entry| nav_entry_file(entry) == Some(file))
+    {
+        nav_entries.remove(index);
+        return Ok(true);
+    }
+
+    for index in 0..nav_entries.len() {
+        if custom_section_pages(&nav_entries[index]).is_none() {
+            continue;
+        }
+        let Some(section_pages) = nav_entries[index]
+            .as_mapping_mut()
+            .and_then(|mapping| mapping.values_mut().next())
+            .and_then(Value::as_sequence_mut)
+        else {
+            continue;
+        };
+        let Some(page_index) = section_pages
+            .iter()
+            .position(|page| nav_entry_file(page) == Some(file))
+        else {
+            continue;
+        };
+        section_pages.remove(page_index);
+        if section_pages.is_empty() {
+            nav_entries.remove(index);
+        }
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+pub fn reorder_nav(
+    mkdocs_config: &mut MKDocsConfig,
+    section: Option<&str>,
+    titles: &[String],
+) -> WikiGenResult<()> {
+    let nav_entries = mkdocs_config.nav_entries_mut()?;
+    let entries = match section {
+        Some(section) => {
+            let section_entry = nav_entries
+                .iter_mut()
+                .find(|entry| nav_entry_title(entry) == Some(section))
+                .filter(|entry| custom_section_pages(entry).is_some())
+                .ok_or_else(|| {
+                    WikiGenError::invalid_nav(
+                        format!("{section} is not a custom nav section"),
+                        section,
+                    )
+                })?;
+            section_entry
+                .as_mapping_mut()
+                .and_then(|mapping| mapping.values_mut().next())
+                .and_then(Value::as_sequence_mut)
+                .ok_or_else(|| {
+                    WikiGenError::invalid_nav(
+                        format!("Nav section {section} is not a list of pages"),
+                        section,
+                    )
+                })?
+        }
+        None => nav_entries,
+    };
+
+    let mut current_titles: Vec<&str> = entries.iter().filter_map(nav_entry_title).collect();
+    let mut new_titles: Vec<&str> = titles.iter().map(String::as_str).collect();
+    current_titles.sort_unstable();
+    new_titles.sort_unstable();
+    if current_titles.len() != entries.len() || current_titles != new_titles {
+        return Err(WikiGenError::invalid_nav(
+            "The new order has to list every nav entry once",
+            section.unwrap_or("nav"),
+        ));
+    }
+
+    entries.sort_by_key(|entry| {
+        nav_entry_title(entry).and_then(|title| titles.iter().position(|ordered| ordered == title))
+    });
+    Ok(())
+}
+
+// The pages of a top level section the user made, None for pages and the
+// generated sections
+fn custom_section_pages(entry: &Value) -> Option<&Vec<Value>> {
+    let (title, pages) = entry.as_mapping()?.iter().next()?;
+    if GENERATED_SECTIONS.contains(&title.as_str()?) {
+        return None;
+    }
+    pages.as_sequence()
+}
+
+fn is_custom_page_file(file: &str) -> bool {
+    file.strip_prefix(CUSTOM_PAGES_FOLDER)
+        .and_then(|page| page.strip_prefix('/'))
+        .is_some_and(|page| page.ends_with(".md") && !page.split('/').any(|part| part == ".."))
+}
+
+fn top_level_titles(mkdocs_config: &mut MKDocsConfig) -> WikiGenResult<Vec<String>> {
+    Ok(mkdocs_config
+        .nav_entries_mut()?
+        .iter()
+        .filter_map(nav_entry_title)
+        .map(str::to_string)
+        .collect())
+}
+
+fn page_title(title: &str) -> WikiGenResult<String> {
+    match title.trim() {
+        "" => Err(WikiGenError::from("Pages need a title")),
+        title => Ok(title.to_string()),
+    }
+}
+
+fn section_title(section: Option<&str>) -> Option<String> {
+    section
+        .map(str::trim)
+        .filter(|section| !section.is_empty())
+        .map(str::to_string)
+}
+
+fn title_taken(title: &str) -> WikiGenError {
+    WikiGenError::invalid_nav(
+        format!("The nav already has an entry called {title}"),
+        title,
+    )
+}
+
+fn nav_page(title: &str, file: &str) -> Value {
+    let mut page = Mapping::new();
+    page.insert(
+        Value::String(title.to_string()),
+        Value::String(file.to_string()),
+    );
+    Value::Mapping(page)
+}
+
+fn write_page(page_path: &Path, markdown: &str) -> WikiGenResult<()> {
+    if let Some(parent) = page_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(page_path, markdown).map_err(|err| {
+        WikiGenError::io(
+            format!("Failed to write page: {}", err),
+            page_path.display(),
+        )
+    })
+}
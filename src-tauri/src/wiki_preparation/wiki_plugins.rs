@@ -0,0 +1,346 @@
+// Enables and configures the mkdocs plugins the wiki supports: search
+// languages, offline, tags, glightbox, git revision dates and redirects
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use tauri::AppHandle;
+
+use crate::{
+    database::session::get_wiki_session,
+    errors::{WikiGenError, WikiGenResult},
+    logger::{self, LogLevel},
+    structs::mkdocs_structs::{MKDocsConfig, Plugin},
+};
+
+const SEARCH_PLUGIN: &str = "search";
+const OFFLINE_PLUGIN: &str = "offline";
+const TAGS_PLUGIN: &str = "tags";
+const GLIGHTBOX_PLUGIN: &str = "glightbox";
+const GIT_REVISION_DATE_PLUGIN: &str = "git-revision-date-localized";
+const REDIRECTS_PLUGIN: &str = "redirects";
+
+// Plugins that don't ship with mkdocs or material and the pip package for each
+const PLUGIN_PACKAGES: [(&str, &str); 3] = [
+    (GLIGHTBOX_PLUGIN, "mkdocs-glightbox"),
+    (
+        GIT_REVISION_DATE_PLUGIN,
+        "mkdocs-git-revision-date-localized-plugin",
+    ),
+    (REDIRECTS_PLUGIN, "mkdocs-redirects"),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PluginSettings {
+    // Empty leaves search on mkdocs' default language
+    pub search_languages: Vec<String>,
+    pub offline: bool,
+    pub tags: bool,
+    pub glightbox: bool,
+    pub git_revision_date: bool,
+    pub redirects: bool,
+    pub redirect_maps: Vec<PageRedirect>,
+}
+
+// Both paths are relative to docs, e.g. "pokemon/004-charmander.md"
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PageRedirect {
+    pub from: String,
+    pub to: String,
+}
+
+#[tauri::command]
+pub async fn get_wiki_plugins(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<PluginSettings> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let mkdocs_config = session.mkdocs_config()?;
+
+    Ok(plugin_settings(&mkdocs_config))
+}
+
+#[tauri::command]
+pub async fn update_wiki_plugins(
+    wiki_name: &str,
+    plugin_settings: PluginSettings,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    let result = session.mkdocs_config().and_then(|mut mkdocs_config| {
+        apply_plugin_settings(&mut mkdocs_config, &plugin_settings)?;
+        session.update_mkdocs_config(&mkdocs_config)
+    });
+    match result {
+        Ok(()) => Ok("Plugins updated".to_string()),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+// The pip packages mkdocs needs to serve the wiki with its current plugins
+#[tauri::command]
+pub async fn get_plugin_packages(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<Vec<String>> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let mkdocs_config = session.mkdocs_config()?;
+
+    Ok(plugin_packages(&mkdocs_config))
+}
+
+pub fn plugin_packages(mkdocs_config: &MKDocsConfig) -> Vec<String> {
+    PLUGIN_PACKAGES
+        .iter()
+        .filter(|(plugin_name, _)| find_plugin(mkdocs_config, plugin_name).is_some())
+        .map(|(_, package)| package.to_string())
+        .collect()
+}
+
+pub fn plugin_settings(mkdocs_config: &MKDocsConfig) -> PluginSettings {
+    let search_languages = match plugin_option(mkdocs_config, SEARCH_PLUGIN, "lang") {
+        Some(Value::String(language)) => vec![language.clone()],
+        Some(Value::Sequence(languages)) => languages
+            .iter()
+            .filter_map(|language| language.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    PluginSettings {
+        search_languages,
+        offline: find_plugin(mkdocs_config, OFFLINE_PLUGIN).is_some(),
+        tags: find_plugin(mkdocs_config, TAGS_PLUGIN).is_some(),
+        glightbox: find_plugin(mkdocs_config, GLIGHTBOX_PLUGIN).is_some(),
+        git_revision_date: find_plugin(mkdocs_config, GIT_REVISION_DATE_PLUGIN).is_some(),
+        redirects: find_plugin(mkdocs_config, REDIRECTS_PLUGIN).is_some(),
+        redirect_maps: page_redirects(mkdocs_config),
+    }
+}
+
+// Adds, updates or removes each supported plugin. Options the form doesn't
+// cover and plugins it doesn't know about are left as they are.
+pub fn apply_plugin_settings(
+    mkdocs_config: &mut MKDocsConfig,
+    plugin_settings: &PluginSettings,
+) -> WikiGenResult<()> {
+    let mut search_languages: Vec<Value> = Vec::new();
+    for language in &plugin_settings.search_languages {
+        let language = language.trim().to_lowercase();
+        if language.is_empty() {
+            continue;
+        }
+        if !language
+            .chars()
+            .all(|c| c.is_ascii_alphabetic() || c == '-')
+        {
+            return Err(WikiGenError::from(format!(
+                "Unknown search language: {}",
+                language
+            )));
+        }
+        let language = Value::String(language);
+        if !search_languages.contains(&language) {
+            search_languages.push(language);
+        }
+    }
+
+    let mut redirect_maps = Mapping::new();
+    for redirect in &plugin_settings.redirect_maps {
+        let from = redirect.from.trim();
+        let to = redirect.to.trim();
+        for page in [from, to] {
+            if page.is_empty() || page.starts_with('/') || page.split('/').any(|part| part == "..")
+            {
+                return Err(WikiGenError::from(format!(
+                    "Redirects must point between pages in docs: {}",
+                    page
+                )));
+            }
+        }
+        if !from.ends_with(".md") {
+            return Err(WikiGenError::from(format!(
+                "Redirects must start from a markdown page: {}",
+                from
+            )));
+        }
+        if from == to {
+            continue;
+        }
+        redirect_maps.insert(
+            Value::String(from.to_string()),
+            Value::String(to.to_string()),
+        );
+    }
+
+    // Search is always on, mkdocs adds it back if the list leaves it out
+    let search_plugin = find_or_add_plugin(mkdocs_config, SEARCH_PLUGIN);
+    match search_languages.len() {
+        0 => remove_plugin_option(search_plugin, "lang"),
+        1 => set_plugin_option(search_plugin, "lang", search_languages.remove(0)),
+        _ => set_plugin_option(search_plugin, "lang", Value::Sequence(search_languages)),
+    }
+
+    for (plugin_name, enabled) in [
+        (OFFLINE_PLUGIN, plugin_settings.offline),
+        (TAGS_PLUGIN, plugin_settings.tags),
+        (GLIGHTBOX_PLUGIN, plugin_settings.glightbox),
+        (GIT_REVISION_DATE_PLUGIN, plugin_settings.git_revision_date),
+        (REDIRECTS_PLUGIN, plugin_settings.redirects),
+    ] {
+        if !enabled {
+            mkdocs_config
+                .plugins
+                .retain(|plugin| plugin.name() != Some(plugin_name));
+            continue;
+        }
+        let is_new = find_plugin(mkdocs_config, plugin_name).is_none();
+        let plugin = find_or_add_plugin(mkdocs_config, plugin_name);
+        // Pages that aren't committed yet would otherwise fail the build
+        if is_new && plugin_name == GIT_REVISION_DATE_PLUGIN {
+            set_plugin_option(plugin, "fallback_to_build_date", Value::Bool(true));
+        }
+        if plugin_name == REDIRECTS_PLUGIN {
+            set_plugin_option(
+                plugin,
+                "redirect_maps",
+                Value::Mapping(redirect_maps.clone()),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn page_redirects(mkdocs_config: &MKDocsConfig) -> Vec<PageRedirect> {
+    match plugin_option(mkdocs_config, REDIRECTS_PLUGIN, "redirect_maps") {
+        Some(Value::Mapping(redirect_maps)) => redirect_maps
+            .iter()
+            .filter_map(|(from, to)| {
+                Some(PageRedirect {
+                    from: from.as_str()?.to_string(),
+                    to: to.as_str()?.to_string(),
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Records that a page moved so old links keep working. Does nothing unless
+// the redirects plugin is enabled. Redirects that pointed at the old page
+// are moved along to the new one. Returns whether a redirect was added.
+pub fn add_page_redirect(mkdocs_config: &mut MKDocsConfig, from: &str, to: &str) -> bool {
+    if from == to {
+        return false;
+    }
+    let Some(plugin) = mkdocs_config
+        .plugins
+        .iter_mut()
+        .find(|plugin| plugin.name() == Some(REDIRECTS_PLUGIN))
+    else {
+        return false;
+    };
+
+    let mut redirect_maps = match plugin_option_mut(plugin, "redirect_maps") {
+        Some(Value::Mapping(redirect_maps)) => redirect_maps.clone(),
+        _ => Mapping::new(),
+    };
+    // The new page may be where an old redirect started, it exists again now
+    redirect_maps.shift_remove(to);
+    for (_, target) in redirect_maps.iter_mut() {
+        if target.as_str() == Some(from) {
+            *target = Value::String(to.to_string());
+        }
+    }
+    redirect_maps.insert(
+        Value::String(from.to_string()),
+        Value::String(to.to_string()),
+    );
+    set_plugin_option(plugin, "redirect_maps", Value::Mapping(redirect_maps));
+
+    true
+}
+
+fn find_plugin<'a>(mkdocs_config: &'a MKDocsConfig, plugin_name: &str) -> Option<&'a Plugin> {
+    mkdocs_config
+        .plugins
+        .iter()
+        .find(|plugin| plugin.name() == Some(plugin_name))
+}
+
+fn find_or_add_plugin<'a>(
+    mkdocs_config: &'a mut MKDocsConfig,
+    plugin_name: &str,
+) -> &'a mut Plugin {
+    let position = match mkdocs_config
+        .plugins
+        .iter()
+        .position(|plugin| plugin.name() == Some(plugin_name))
+    {
+        Some(position) => position,
+        None => {
+            mkdocs_config
+                .plugins
+                .push(Plugin::String(plugin_name.to_string()));
+            mkdocs_config.plugins.len() - 1
+        }
+    };
+    &mut mkdocs_config.plugins[position]
+}
+
+fn plugin_option<'a>(
+    mkdocs_config: &'a MKDocsConfig,
+    plugin_name: &str,
+    option: &str,
+) -> Option<&'a Value> {
+    match find_plugin(mkdocs_config, plugin_name)? {
+        Plugin::String(_) => None,
+        Plugin::Map(entry) => entry.values().next()?.as_mapping()?.get(option),
+    }
+}
+
+fn plugin_option_mut<'a>(plugin: &'a mut Plugin, option: &str) -> Option<&'a mut Value> {
+    match plugin {
+        Plugin::String(_) => None,
+        Plugin::Map(entry) => entry.values_mut().next()?.as_mapping_mut()?.get_mut(option),
+    }
+}
+
+fn set_plugin_option(plugin: &mut Plugin, option: &str, value: Value) {
+    match plugin {
+        Plugin::String(plugin_name) => {
+            let plugin_name = plugin_name.clone();
+            *plugin = Plugin::with_option(&plugin_name, option, value);
+        }
+        Plugin::Map(entry) => {
+            if let Some(options) = entry.values_mut().next() {
+                if !options.is_mapping() {
+                    // "plugin:" with nothing after it parses as null
+                    *options = Value::Mapping(Mapping::new());
+                }
+                if let Some(options) = options.as_mapping_mut() {
+                    options.insert(Value::String(option.to_string()), value);
+                }
+            }
+        }
+    }
+}
+
+fn remove_plugin_option(plugin: &mut Plugin, option: &str) {
+    let Plugin::Map(entry) = plugin else {
+        return;
+    };
+    if let Some(options) = entry.values_mut().next().and_then(Value::as_mapping_mut) {
+        options.shift_remove(option);
+        if !options.is_empty() {
+            return;
+        }
+    }
+    // Nothing else configured, so back to the plain name
+    if let Some(plugin_name) = plugin.name().map(str::to_string) {
+        *plugin = Plugin::String(plugin_name);
+    }
+}
@@ -0,0 +1,392 @@
+// Edits the material theme in mkdocs.yml: colors, the dark scheme, logo,
+// favicon, fonts, features and custom CSS snippets
+use std::{collections::HashSet, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{
+    database::session::{get_wiki_session, WikiSession},
+    errors::{WikiGenError, WikiGenResult},
+    logger::{self, LogLevel},
+    static_site::markdown::slugify,
+    structs::mkdocs_structs::{MKDocsConfig, ThemeFont, ThemePalette},
+    wiki_preparation::yaml_declaration::light_and_dark_palettes,
+};
+
+// Colors material ships for primary, accent only supports the first 16
+pub const THEME_COLORS: [&str; 21] = [
+    "red",
+    "pink",
+    "purple",
+    "deep-purple",
+    "indigo",
+    "blue",
+    "light-blue",
+    "cyan",
+    "teal",
+    "green",
+    "light-green",
+    "lime",
+    "yellow",
+    "amber",
+    "orange",
+    "deep-orange",
+    "brown",
+    "grey",
+    "blue-grey",
+    "black",
+    "white",
+];
+const ACCENT_COLOR_COUNT: usize = 16;
+
+const THEME_IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "gif", "svg", "ico", "webp"];
+const CSS_SNIPPETS_FOLDER: &str = "stylesheets/snippets";
+// First line of a snippet file, keeping the name as it was typed since the
+// file name is slugged
+const SNIPPET_NAME_HEADER: (&str, &str) = ("/* snippet: ", " */");
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ThemeSettings {
+    pub primary: String,
+    // Empty to use material's default accent
+    pub accent: String,
+    // Whether the dark palette uses the slate scheme. Off keeps both light.
+    pub dark_scheme: bool,
+    // Paths relative to docs
+    pub logo: String,
+    pub favicon: String,
+    pub text_font: String,
+    pub code_font: String,
+    pub features: Vec<String>,
+    pub css_snippets: Vec<CssSnippet>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CssSnippet {
+    pub name: String,
+    pub css: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeImage {
+    Logo,
+    Favicon,
+}
+
+#[tauri::command]
+pub async fn get_wiki_theme(
+    wiki_name: &str,
+    app_handle: AppHandle,
+) -> WikiGenResult<ThemeSettings> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let mkdocs_config = session.mkdocs_config()?;
+
+    let mut theme_settings = theme_settings(&mkdocs_config);
+    for snippet in theme_settings.css_snippets.iter_mut() {
+        let snippet_path = session.docs_path.join(css_snippet_file(&snippet.name));
+        // A snippet deleted by hand shows up empty and is written again on save
+        let snippet_contents = fs::read_to_string(snippet_path).unwrap_or_default();
+        *snippet = read_css_snippet(&snippet.name, &snippet_contents);
+    }
+
+    Ok(theme_settings)
+}
+
+#[tauri::command]
+pub async fn update_wiki_theme(
+    wiki_name: &str,
+    theme_settings: ThemeSettings,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match update_session_theme(&session, &theme_settings) {
+        Ok(()) => Ok("Theme updated".to_string()),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+// Saves an uploaded logo or favicon into docs/img/theme and points the theme
+// at it, returning its path relative to docs
+#[tauri::command]
+pub async fn upload_theme_image(
+    wiki_name: &str,
+    image_kind: ThemeImage,
+    file_name: &str,
+    image_data: Vec<u8>,
+    app_handle: AppHandle,
+) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+
+    match save_theme_image(&session, image_kind, file_name, &image_data) {
+        Ok(image_path) => Ok(image_path),
+        Err(err) => {
+            logger::write_log(&session.wiki_path, LogLevel::Error, &err);
+            Err(err)
+        }
+    }
+}
+
+fn update_session_theme(
+    session: &WikiSession,
+    theme_settings: &ThemeSettings,
+) -> WikiGenResult<()> {
+    let mut mkdocs_config = session.mkdocs_config()?;
+    let previous_snippets = snippet_files(&mkdocs_config);
+    apply_theme_settings(&mut mkdocs_config, theme_settings)?;
+
+    for snippet in &theme_settings.css_snippets {
+        let snippet_path = session.docs_path.join(css_snippet_file(&snippet.name));
+        write_docs_file(&snippet_path, css_snippet_contents(snippet).as_bytes())?;
+    }
+    // Snippets that were removed are deleted along with their entry
+    let current_snippets = snippet_files(&mkdocs_config);
+    for snippet_file in previous_snippets.difference(&current_snippets) {
+        let snippet_path = session.docs_path.join(snippet_file);
+        if snippet_path.try_exists().unwrap_or(false) {
+            fs::remove_file(&snippet_path).map_err(|err| {
+                WikiGenError::io(
+                    format!("Failed to remove CSS snippet: {}", err),
+                    snippet_path.display(),
+                )
+            })?;
+        }
+    }
+
+    session.update_mkdocs_config(&mkdocs_config)
+}
+
+fn save_theme_image(
+    session: &WikiSession,
+    image_kind: ThemeImage,
+    file_name: &str,
+    image_data: &[u8],
+) -> WikiGenResult<String> {
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if !THEME_IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(WikiGenError::from(format!(
+            "Unsupported image type, use one of: {}",
+            THEME_IMAGE_EXTENSIONS.join(", ")
+        )));
+    }
+    if image_data.is_empty() {
+        return Err(WikiGenError::from("The image is empty"));
+    }
+
+    let image_name = match image_kind {
+        ThemeImage::Logo => "logo",
+        ThemeImage::Favicon => "favicon",
+    };
+    let image_path = format!("img/theme/{}.{}", image_name, extension);
+    write_docs_file(&session.docs_path.join(&image_path), image_data)?;
+
+    let mut mkdocs_config = session.mkdocs_config()?;
+    match image_kind {
+        ThemeImage::Logo => mkdocs_config.theme.logo = image_path.clone(),
+        ThemeImage::Favicon => mkdocs_config.theme.favicon = image_path.clone(),
+    }
+    session.update_mkdocs_config(&mkdocs_config)?;
+
+    Ok(image_path)
+}
+
+fn write_docs_file(file_path: &Path, contents: &[u8]) -> WikiGenResult<()> {
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file_path, contents).map_err(|err| {
+        WikiGenError::io(
+            format!("Failed to write theme file: {}", err),
+            file_path.display(),
+        )
+    })
+}
+
+pub fn css_snippet_file(snippet_name: &str) -> String {
+    format!("{}/{}.css", CSS_SNIPPETS_FOLDER, slugify(snippet_name))
+}
+
+pub fn css_snippet_contents(snippet: &CssSnippet) -> String {
+    let (header_start, header_end) = SNIPPET_NAME_HEADER;
+    // A name can't close the comment early
+    let name = snippet.name.replace("*/", "* /");
+    format!("{header_start}{name}{header_end}\n{}", snippet.css)
+}
+
+// Snippets written without the header, by hand or before it was added,
+// are named after their file
+pub fn read_css_snippet(file_name: &str, snippet_contents: &str) -> CssSnippet {
+    let (header_start, header_end) = SNIPPET_NAME_HEADER;
+    let (first_line, css) = snippet_contents
+        .split_once('\n')
+        .unwrap_or((snippet_contents, ""));
+    let name = first_line
+        .trim_end_matches('\r')
+        .strip_prefix(header_start)
+        .and_then(|header| header.strip_suffix(header_end));
+
+    match name {
+        Some(name) => CssSnippet {
+            name: name.to_string(),
+            css: css.to_string(),
+        },
+        None => CssSnippet {
+            name: file_name.to_string(),
+            css: snippet_contents.to_string(),
+        },
+    }
+}
+
+fn snippet_files(mkdocs_config: &MKDocsConfig) -> HashSet<String> {
+    mkdocs_config
+        .extra_css
+        .iter()
+        .filter(|stylesheet| stylesheet.starts_with(CSS_SNIPPETS_FOLDER))
+        .cloned()
+        .collect()
+}
+
+// The theme as the settings form shows it. Snippets are named after their
+// files and left empty until read_css_snippet reads them from docs.
+pub fn theme_settings(mkdocs_config: &MKDocsConfig) -> ThemeSettings {
+    let theme = &mkdocs_config.theme;
+    let first_palette = theme.palette.palettes().first();
+    let font = theme.font.clone().unwrap_or_default();
+
+    ThemeSettings {
+        primary: first_palette
+            .map(|palette| palette.primary.clone())
+            .unwrap_or_else(|| "indigo".to_string()),
+        accent: first_palette
+            .map(|palette| palette.accent.clone())
+            .unwrap_or_default(),
+        dark_scheme: theme
+            .palette
+            .palettes()
+            .iter()
+            .any(|palette| palette.scheme == "slate"),
+        logo: theme.logo.clone(),
+        favicon: theme.favicon.clone(),
+        text_font: font.text,
+        code_font: font.code,
+        features: theme.features.clone(),
+        css_snippets: mkdocs_config
+            .extra_css
+            .iter()
+            .filter_map(|stylesheet| {
+                let name = stylesheet
+                    .strip_prefix(CSS_SNIPPETS_FOLDER)?
+                    .strip_prefix('/')?
+                    .strip_suffix(".css")?;
+                Some(CssSnippet {
+                    name: name.to_string(),
+                    css: String::new(),
+                })
+            })
+            .collect(),
+    }
+}
+
+pub fn apply_theme_settings(
+    mkdocs_config: &mut MKDocsConfig,
+    theme_settings: &ThemeSettings,
+) -> WikiGenResult<()> {
+    if !THEME_COLORS.contains(&theme_settings.primary.as_str()) {
+        return Err(WikiGenError::from(format!(
+            "Unknown primary color: {}",
+            theme_settings.primary
+        )));
+    }
+    if !theme_settings.accent.is_empty()
+        && !THEME_COLORS[..ACCENT_COLOR_COUNT].contains(&theme_settings.accent.as_str())
+    {
+        return Err(WikiGenError::from(format!(
+            "Unknown accent color: {}",
+            theme_settings.accent
+        )));
+    }
+    for image_path in [&theme_settings.logo, &theme_settings.favicon] {
+        if image_path.starts_with('/') || image_path.split('/').any(|part| part == "..") {
+            return Err(WikiGenError::from(format!(
+                "Theme images must be inside the docs folder: {}",
+                image_path
+            )));
+        }
+    }
+
+    let mut snippet_files = Vec::new();
+    for snippet in &theme_settings.css_snippets {
+        if slugify(&snippet.name).is_empty() {
+            return Err(WikiGenError::from("CSS snippets need a name"));
+        }
+        let snippet_file = css_snippet_file(&snippet.name);
+        if snippet_files.contains(&snippet_file) {
+            return Err(WikiGenError::from(format!(
+                "Two CSS snippets are named {}",
+                snippet.name
+            )));
+        }
+        snippet_files.push(snippet_file);
+    }
+
+    let theme = &mut mkdocs_config.theme;
+    let has_dark_palette = theme
+        .palette
+        .palettes()
+        .iter()
+        .any(|palette| palette.media.contains("dark"));
+    // A palette shape the settings can't edit is replaced, the same as no palette
+    if theme.palette.palettes().is_empty() || (theme_settings.dark_scheme && !has_dark_palette) {
+        theme.palette = ThemePalette::List(light_and_dark_palettes(
+            &theme_settings.primary,
+            &theme_settings.accent,
+        ));
+    }
+    for palette in theme.palette.palettes_mut() {
+        palette.primary = theme_settings.primary.clone();
+        palette.accent = theme_settings.accent.clone();
+        palette.scheme = match theme_settings.dark_scheme && palette.media.contains("dark") {
+            true => "slate".to_string(),
+            false => "default".to_string(),
+        };
+    }
+
+    theme.logo = theme_settings.logo.clone();
+    theme.favicon = theme_settings.favicon.clone();
+    theme.font = match (
+        theme_settings.text_font.trim(),
+        theme_settings.code_font.trim(),
+    ) {
+        ("", "") => None,
+        (text, code) => Some(ThemeFont {
+            text: text.to_string(),
+            code: code.to_string(),
+        }),
+    };
+
+    let mut features = Vec::new();
+    for feature in &theme_settings.features {
+        let feature = feature.trim().to_string();
+        if !feature.is_empty() && !features.contains(&feature) {
+            features.push(feature);
+        }
+    }
+    theme.features = features;
+
+    // Snippets go after the stylesheets already listed so they take priority
+    mkdocs_config
+        .extra_css
+        .retain(|stylesheet| !stylesheet.starts_with(CSS_SNIPPETS_FOLDER));
+    mkdocs_config.extra_css.extend(snippet_files);
+
+    Ok(())
+}
@@ -1,14 +1,12 @@
-use std::{
-    collections::HashMap,
-    fs::{self, File},
-};
-
 use serde_yaml::{Mapping, Value};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
-use crate::structs::mkdocs_structs::{
-    MKDocsConfig, MarkdownExtension, Palette, Plugin, PymdownxTabbed, PymdownxTaskList, Search,
-    Theme, Toggle,
+use crate::{
+    database::session::get_wiki_session,
+    errors::WikiGenResult,
+    structs::mkdocs_structs::{
+        MKDocsConfig, MarkdownExtension, Palette, Plugin, Theme, ThemePalette, Toggle,
+    },
 };
 
 // Since I'm new to rust, almost everything in here is likely poorly done, but it works for now.
@@ -19,12 +17,6 @@ pub fn get_yaml(
     site_author: &str,
     repo_url: &str,
 ) -> MKDocsConfig {
-    let mut custom_checkbox = HashMap::new();
-    custom_checkbox.insert("custom_checkbox".to_string(), true);
-
-    let mut alternate_style = HashMap::new();
-    alternate_style.insert("alternate_style".to_string(), true);
-
     let mut home_map = Mapping::new();
     home_map.insert(
         Value::String("Home".to_string()),
@@ -49,112 +41,93 @@ pub fn get_yaml(
         theme: Theme {
             name: "material".to_string(),
             features: vec![
-                "content.tabls.links".to_string(),
+                "content.tabs.link".to_string(),
                 "content.tooltips".to_string(),
             ],
             favicon: "img/items/poke-ball.png".to_string(),
-            palette: [
-                Palette {
-                    media: "(prefers-color-scheme: light)".to_string(),
-                    primary: "black".to_string(),
-                    scheme: "default".to_string(),
-                    toggle: Toggle {
-                        icon: "material/eye-outline".to_string(),
-                        name: "Switch to dark mode".to_string(),
-                    },
-                },
-                Palette {
-                    media: "(prefers-color-scheme: dark)".to_string(),
-                    primary: "black".to_string(),
-                    scheme: "default".to_string(),
-                    toggle: Toggle {
-                        icon: "material/eye".to_string(),
-                        name: "Switch to light mode".to_string(),
-                    },
-                },
-            ],
+            logo: String::new(),
+            font: None,
+            palette: ThemePalette::List(light_and_dark_palettes("black", "")),
+            other_keys: Mapping::new(),
         },
         nav: Value::Sequence(vec![
             Value::Mapping(home_map),
             Value::Mapping(pokemon_map),
             Value::Mapping(routes_map),
         ]),
-        plugins: vec![Plugin {
-            search: Search {
-                lang: "en".to_string(),
-            },
-        }],
-        markdown_extensions: [
-            MarkdownExtension::String("admonition".to_string()),
-            MarkdownExtension::String("abbr".to_string()),
-            MarkdownExtension::String("attr_list".to_string()),
-            MarkdownExtension::String("pymdownx.snippets".to_string()),
-            MarkdownExtension::String("pymdownx.superfences".to_string()),
-            MarkdownExtension::String("pymdownx.details".to_string()),
-            MarkdownExtension::PymdownxTaskList(PymdownxTaskList {
-                custom_checkbox: true,
-            }),
-            MarkdownExtension::PymdownxTabbed(PymdownxTabbed {
-                alternate_style: true,
-            }),
-        ]
-        .to_vec(),
+        plugins: vec![Plugin::with_option("search", "lang", "en")],
+        markdown_extensions: default_markdown_extensions(),
         extra_css: vec!["stylesheets/extra.css".to_string()],
+        other_keys: Mapping::new(),
     };
 
     return mkdocs_config;
 }
 
+// Material picks between these with the system color scheme and shows a
+// toggle to switch by hand
+pub fn light_and_dark_palettes(primary: &str, accent: &str) -> Vec<Palette> {
+    vec![
+        Palette {
+            media: "(prefers-color-scheme: light)".to_string(),
+            primary: primary.to_string(),
+            accent: accent.to_string(),
+            scheme: "default".to_string(),
+            toggle: Some(Toggle {
+                icon: "material/eye-outline".to_string(),
+                name: "Switch to dark mode".to_string(),
+            }),
+            other_keys: Mapping::new(),
+        },
+        Palette {
+            media: "(prefers-color-scheme: dark)".to_string(),
+            primary: primary.to_string(),
+            accent: accent.to_string(),
+            scheme: "slate".to_string(),
+            toggle: Some(Toggle {
+                icon: "material/eye".to_string(),
+                name: "Switch to light mode".to_string(),
+            }),
+            other_keys: Mapping::new(),
+        },
+    ]
+}
+
 #[tauri::command]
-pub fn update_yaml(wiki_name: &str, app_handle: AppHandle) -> Result<String, String> {
-    let data_dir = app_handle.path().app_data_dir().unwrap();
-    let dist_folder = data_dir.join(wiki_name).join("dist");
+pub async fn update_yaml(wiki_name: &str, app_handle: AppHandle) -> WikiGenResult<String> {
+    let session = get_wiki_session(wiki_name, &app_handle).await?;
+    let mut mkdocs_config = session.mkdocs_config()?;
 
-    let mkdocs_yaml_file_path = dist_folder.join("mkdocs.yml");
-    let mkdocs_yaml_file = match File::open(&mkdocs_yaml_file_path) {
-        Ok(file) => file,
-        Err(err) => {
-            return Err(format!("Failed to open mkdocs yaml file: {}", err));
-        }
-    };
-    let mut mkdocs_config: MKDocsConfig = match serde_yaml::from_reader(mkdocs_yaml_file) {
-        Ok(mkdocs) => mkdocs,
-        Err(err) => {
-            return Err(format!("Failed to parse mkdocs yaml file: {}", err));
+    // Extensions the pages rely on are added next to any the user set up
+    let mut extensions_added = false;
+    for extension in default_markdown_extensions() {
+        if !mkdocs_config
+            .markdown_extensions
+            .iter()
+            .any(|existing| existing.name() == extension.name())
+        {
+            mkdocs_config.markdown_extensions.push(extension);
+            extensions_added = true;
         }
-    };
+    }
 
-    if !mkdocs_config
-        .markdown_extensions
-        .contains(&MarkdownExtension::String("pymdownx.details".to_string()))
-    {
-        mkdocs_config.markdown_extensions = [
-            MarkdownExtension::String("admonition".to_string()),
-            MarkdownExtension::String("abbr".to_string()),
-            MarkdownExtension::String("attr_list".to_string()),
-            MarkdownExtension::String("pymdownx.snippets".to_string()),
-            MarkdownExtension::String("pymdownx.superfences".to_string()),
-            MarkdownExtension::String("pymdownx.details".to_string()),
-            MarkdownExtension::PymdownxTaskList(PymdownxTaskList {
-                custom_checkbox: true,
-            }),
-            MarkdownExtension::PymdownxTabbed(PymdownxTabbed {
-                alternate_style: true,
-            }),
-        ]
-        .to_vec();
-        let mkdocs_file_path = dist_folder.join("mkdocs.yml");
-        match fs::write(
-            mkdocs_file_path,
-            serde_yaml::to_string(&mkdocs_config).unwrap(),
-        ) {
-            Ok(_) => {}
-            Err(err) => {
-                return Err(format!("Failed to create mkdocs yaml file: {:?}", err));
-            }
-        }
+    if extensions_added {
+        session.update_mkdocs_config(&mkdocs_config)?;
         return Ok("Mkdocs yml updated".to_string());
     }
 
     Ok("".to_string())
 }
+
+fn default_markdown_extensions() -> Vec<MarkdownExtension> {
+    vec![
+        MarkdownExtension::String("admonition".to_string()),
+        MarkdownExtension::String("abbr".to_string()),
+        MarkdownExtension::String("attr_list".to_string()),
+        MarkdownExtension::String("pymdownx.snippets".to_string()),
+        MarkdownExtension::String("pymdownx.superfences".to_string()),
+        MarkdownExtension::String("pymdownx.details".to_string()),
+        MarkdownExtension::with_option("pymdownx.tasklist", "custom_checkbox", true),
+        MarkdownExtension::with_option("pymdownx.tabbed", "alternate_style", true),
+    ]
+}
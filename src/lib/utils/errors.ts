@@ -0,0 +1,22 @@
+// Shape of the errors returned by the backend commands
+export type WikiGenError = {
+  code: string;
+  message: string;
+  context: string;
+};
+
+export function isWikiGenError(err: unknown): err is WikiGenError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    "code" in err &&
+    "message" in err
+  );
+}
+
+export function errorMessage(err: unknown): string {
+  if (isWikiGenError(err)) {
+    return err.context ? `${err.message} (${err.context})` : err.message;
+  }
+  return String(err);
+}
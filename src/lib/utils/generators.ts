@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import type { Routes } from "../../store/gameRoutes";
 import { BaseDirectory, writeTextFile } from "@tauri-apps/plugin-fs";
+import { errorMessage } from "./errors";
 
 export async function generatePokemonPages(
   pokemonIds: number[],
@@ -9,6 +10,8 @@ export async function generatePokemonPages(
   return await invoke("generate_pokemon_pages_from_list", {
     pokemonIds: pokemonIds,
     wikiName,
+  }).catch((err) => {
+    throw errorMessage(err);
   });
 }
 
@@ -21,6 +24,8 @@ export async function removePokemonPage(
     wikiName,
     oldDexNumber,
     pokemonName,
+  }).catch((err) => {
+    throw errorMessage(err);
   });
 }
 
@@ -31,6 +36,8 @@ export async function generateRoutePages(
   return await invoke("generate_route_pages_with_handle", {
     routeNames,
     wikiName,
+  }).catch((err) => {
+    throw errorMessage(err);
   });
 }
 
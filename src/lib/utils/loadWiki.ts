@@ -12,6 +12,8 @@ import { routes } from "../../store/gameRoutes";
 import { sortRoutesByPosition } from "$lib/utils";
 import { types } from "../../store/types";
 import { appDataDir } from "@tauri-apps/api/path";
+import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "./errors";
 
 async function loadRoutes(wikiName: string) {
   const routesFromFile = await readTextFile(`${wikiName}/data/routes.json`, {
@@ -100,6 +102,11 @@ export async function loadWikiData(wiki: Wiki, toast: any) {
       loadRoutes(wiki.name).catch((err) => {
         toast.error(`Error loading routes: ${err}`);
       });
+
+      // Open the session the backend commands share for this wiki
+      invoke("open_wiki_session", { wikiName: wiki.name }).catch((err) => {
+        toast.error(`Error opening wiki session: ${errorMessage(err)}`);
+      });
     })
     .catch((err) => {
       toast.error(`Error loading values from database: ${err}`);
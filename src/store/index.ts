@@ -32,4 +32,4 @@ export let user = writable<User>({
   avatarUrl: "",
   isConnected: false,
 });
-export let spawnedProcessID = writable<any>(null);
+export let spawnedProcessID = writable<number | null>(null);